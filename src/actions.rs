@@ -15,6 +15,7 @@ actions!(
         OpenOutputDir,
         OpenLogFolder,
         ToggleSimulateBurn,
+        ToggleVerifyBurn,
         ToggleEmbedAlbumArt,
         OpenDisplaySettings,
         SetVolumeLabel,
@@ -24,6 +25,42 @@ actions!(
         SaveProfile,
         // Mixtape action
         NewMixtape,
+        // Multi-disc burn
+        BurnAcrossDiscs,
+        // Several copies of the same ISO
+        BurnCopies,
+        // Red Book audio CD
+        BurnAudioCd,
+        // Blank a CD-RW ahead of a burn
+        QuickEraseDisc,
+        FullEraseDisc,
+        // Copy to a folder or USB drive instead of burning
+        ExportToFolder,
+        // Planned disc file tree, before the ISO is built
+        PreviewDiscLayout,
+        // Disc file name rules
+        IsoNamesLevel1,
+        IsoNamesLevel2,
+        IsoNamesJoliet,
+        IsoNamesRockRidge,
+        ToggleGaplessAudioCd,
+        // Car stereo compatibility profiles
+        CompatModern,
+        CompatGenericHeadUnit,
+        CompatStrict83,
+        // Playlists written onto data discs
+        PlaylistsNone,
+        PlaylistsM3u8,
+        PlaylistsPls,
+        PlaylistsBoth,
+        // Drive and write speed used for burns
+        ChooseBurnDrive,
+        // Bitrate mode and LAME tuning, saved with the burn profile
+        OpenEncodingOptions,
+        // History of burned discs
+        OpenDiscCatalog,
+        // Jewel-case insert and tracklists
+        PrintInsert,
     ]
 );
 
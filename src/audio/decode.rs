@@ -0,0 +1,156 @@
+//! Decoding audio files with symphonia
+//!
+//! Used wherever audio has to be decoded without ffmpeg: the built-in LAME
+//! encoder, and audio CD images when ffmpeg isn't there.
+
+use std::borrow::Cow;
+use std::fs::File;
+use std::path::Path;
+
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{CODEC_TYPE_NULL, DecoderOptions};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// A block of decoded audio
+pub struct AudioBlock<'a> {
+    /// Interleaved 16-bit samples, frame by frame
+    pub samples: &'a [i16],
+    pub channels: usize,
+    pub sample_rate: u32,
+    /// How far through the file decoding is, from 0.0 to 1.0
+    pub progress: f64,
+}
+
+/// Decode `path`, handing each block of audio to `on_block` as it's decoded
+///
+/// Damaged packets are skipped with a warning. An error from `on_block`
+/// stops decoding and is returned as-is.
+pub fn decode_file(
+    path: &Path,
+    on_block: &mut dyn FnMut(AudioBlock<'_>) -> Result<(), String>,
+) -> Result<(), String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let mut format = symphonia::default::get_probe()
+        .format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| format!("Failed to probe file: {}", e))?
+        .format;
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| "No audio track found".to_string())?;
+    let track_id = track.id;
+    let total_frames = track.codec_params.n_frames.unwrap_or(0);
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| format!("Failed to create decoder: {}", e))?;
+
+    let mut samples: Option<SampleBuffer<i16>> = None;
+    let mut frames_done: u64 = 0;
+    let mut decoded_any = false;
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break;
+            }
+            Err(e) => return Err(format!("Failed to read audio: {}", e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            Err(SymphoniaError::DecodeError(e)) => {
+                // A damaged packet only costs a moment of audio
+                log::warn!("Skipping undecodable packet in {:?}: {}", path, e);
+                continue;
+            }
+            Err(e) => return Err(format!("Failed to decode audio: {}", e)),
+        };
+        let spec = *decoded.spec();
+        let channels = spec.channels.count();
+        if channels == 0 {
+            return Err("Audio has no channels".to_string());
+        }
+
+        let buffer = match samples.take() {
+            Some(buffer) if buffer.capacity() >= decoded.capacity() * channels => {
+                samples.insert(buffer)
+            }
+            _ => samples.insert(SampleBuffer::new(decoded.capacity() as u64, spec)),
+        };
+        buffer.copy_interleaved_ref(decoded);
+
+        frames_done += (buffer.samples().len() / channels) as u64;
+        let progress = if total_frames > 0 {
+            (frames_done as f64 / total_frames as f64).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        on_block(AudioBlock {
+            samples: buffer.samples(),
+            channels,
+            sample_rate: spec.rate,
+            progress,
+        })?;
+        decoded_any = true;
+    }
+
+    if decoded_any {
+        Ok(())
+    } else {
+        Err("No audio could be decoded".to_string())
+    }
+}
+
+/// Interleaved stereo from interleaved audio of any channel count
+///
+/// Mono is copied to both sides; surround keeps its front left and right.
+pub fn to_stereo(samples: &[i16], channels: usize) -> Cow<'_, [i16]> {
+    match channels {
+        2 => Cow::Borrowed(samples),
+        1 => Cow::Owned(samples.iter().flat_map(|&s| [s, s]).collect()),
+        _ => Cow::Owned(
+            samples
+                .chunks_exact(channels)
+                .flat_map(|frame| [frame[0], frame[1]])
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_stereo() {
+        assert_eq!(&*to_stereo(&[1, 2, 3, 4], 2), &[1, 2, 3, 4]);
+        assert_eq!(&*to_stereo(&[1, 2], 1), &[1, 1, 2, 2]);
+        assert_eq!(&*to_stereo(&[1, 2, 3, 4, 5, 6], 3), &[1, 2, 4, 5]);
+    }
+
+    #[test]
+    fn test_decode_missing_file() {
+        let result = decode_file(Path::new("/nonexistent/track.flac"), &mut |_| Ok(()));
+        assert!(result.unwrap_err().starts_with("Failed to open file"));
+    }
+}
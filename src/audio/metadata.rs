@@ -224,6 +224,7 @@ pub struct AlbumMetadata {
 pub struct TrackMetadata {
     pub title: Option<String>,
     pub artist: Option<String>,
+    pub composer: Option<String>,
 }
 
 /// Extract album metadata (album name, artist, year) from an audio file
@@ -300,7 +301,7 @@ pub fn get_album_metadata(path: &Path) -> AlbumMetadata {
     metadata
 }
 
-/// Extract track metadata (title, artist) from an audio file
+/// Extract track metadata (title, artist, composer) from an audio file
 pub fn get_track_metadata(path: &Path) -> TrackMetadata {
     let mut metadata = TrackMetadata::default();
 
@@ -337,6 +338,11 @@ pub fn get_track_metadata(path: &Path) -> TrackMetadata {
                         metadata.artist = Some(tag.value.to_string());
                     }
                 }
+                Some(StandardTagKey::Composer) => {
+                    if metadata.composer.is_none() {
+                        metadata.composer = Some(tag.value.to_string());
+                    }
+                }
                 _ => {}
             }
         }
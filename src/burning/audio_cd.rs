@@ -0,0 +1,767 @@
+//! Red Book audio CD images
+//!
+//! Decodes the active tracks of a folder list to 44.1 kHz / 16-bit stereo
+//! PCM and writes them as a BIN/CUE pair (one track per active track) that
+//! the burner writes disc-at-once. CD-TEXT from [`super::cd_text`] goes into
+//! the cue sheet and a .cdt file next to it.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::cd_text::{CdText, latin1_bytes};
+use crate::audio::{decode_file, to_stereo};
+use crate::core::{AudioFileInfo, MediaProfile, MusicFolder, format_duration};
+
+/// Bytes of audio in one CD sector (588 stereo 16-bit samples)
+pub const SECTOR_BYTES: u64 = 2352;
+/// CD sectors ("frames") per second of audio
+pub const SECTORS_PER_SECOND: u64 = 75;
+/// Sample rate of CD audio
+const CD_SAMPLE_RATE: u32 = 44_100;
+/// Most tracks an audio CD can hold
+pub const MAX_TRACKS: usize = 99;
+/// Standard pregap between tracks (2 seconds)
+pub const DEFAULT_PREGAP_SECTORS: u32 = 150;
+/// Shortest track allowed by Red Book (4 seconds); shorter tracks are padded
+const MIN_TRACK_SECTORS: u64 = 4 * SECTORS_PER_SECOND;
+
+/// File names of the image written to the output directory
+const BIN_NAME: &str = "mp3cd-audio.bin";
+const CUE_NAME: &str = "mp3cd-audio.cue";
+const CDT_NAME: &str = "mp3cd-audio.cdt";
+
+/// Options for building an audio CD image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioCdOptions {
+    /// Silence written before every track after the first, in sectors
+    pub pregap_sectors: u32,
+}
+
+impl Default for AudioCdOptions {
+    fn default() -> Self {
+        Self {
+            pregap_sectors: DEFAULT_PREGAP_SECTORS,
+        }
+    }
+}
+
+impl AudioCdOptions {
+    /// No gaps between tracks (live albums, DJ mixes)
+    pub fn gapless() -> Self {
+        Self { pregap_sectors: 0 }
+    }
+}
+
+/// Playing time of a folder list laid out as an audio CD
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AudioCdFit {
+    /// Number of CD tracks
+    pub track_count: usize,
+    /// Total playing time including pregaps, in seconds
+    pub duration_secs: f64,
+    /// Playing time the media holds, in seconds
+    pub limit_secs: f64,
+}
+
+/// Check that the active tracks of `folders` fit on one `media` audio CD
+///
+/// Uses the duration totals gathered during scanning, so nothing has to be
+/// decoded first.
+///
+/// # Returns
+/// * `Ok(AudioCdFit)` - the layout fits
+/// * `Err(String)` - not CD media, no tracks, too many tracks, or too long
+pub fn check_audio_cd_fits(
+    folders: &[MusicFolder],
+    media: MediaProfile,
+    options: AudioCdOptions,
+) -> Result<AudioCdFit, String> {
+    let Some(minutes) = media.audio_minutes() else {
+        return Err(format!("Audio CDs need CD media, not {}", media.name()));
+    };
+
+    let track_count: usize = folders.iter().map(|f| f.active_tracks().len()).sum();
+    if track_count == 0 {
+        return Err("No tracks to burn".to_string());
+    }
+    if track_count > MAX_TRACKS {
+        return Err(format!(
+            "{} tracks won't fit on an audio CD (at most {})",
+            track_count, MAX_TRACKS
+        ));
+    }
+
+    let gaps = (track_count - 1) as f64 * options.pregap_sectors as f64;
+    let fit = AudioCdFit {
+        track_count,
+        duration_secs: folders.iter().map(|f| f.active_duration()).sum::<f64>()
+            + gaps / SECTORS_PER_SECOND as f64,
+        limit_secs: minutes as f64 * 60.0,
+    };
+
+    if fit.duration_secs > fit.limit_secs {
+        return Err(format!(
+            "{} of audio is over the {} minute limit",
+            format_duration(fit.duration_secs),
+            minutes
+        ));
+    }
+    Ok(fit)
+}
+
+/// Position of one track in the BIN file, in sectors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CueTrack {
+    /// Silence before the track (INDEX 00 to INDEX 01)
+    pub pregap_sectors: u32,
+    /// Where the track's audio starts (INDEX 01)
+    pub start_sector: u64,
+    /// Length of the track's audio, padded to whole sectors
+    pub length_sectors: u64,
+}
+
+/// A BIN/CUE pair ready to burn
+#[derive(Debug, Clone)]
+pub struct AudioCdImage {
+    pub bin_path: PathBuf,
+    pub cue_path: PathBuf,
+    pub tracks: Vec<CueTrack>,
+    /// Length of the BIN file in sectors
+    pub total_sectors: u64,
+    /// Binary CD-TEXT file, if there was any text to write
+    pub cdt_path: Option<PathBuf>,
+}
+
+/// Format a sector count as a cue sheet "MM:SS:FF" time
+pub fn msf(sectors: u64) -> String {
+    let frames = sectors % SECTORS_PER_SECOND;
+    let seconds = sectors / SECTORS_PER_SECOND;
+    format!("{:02}:{:02}:{:02}", seconds / 60, seconds % 60, frames)
+}
+
+/// Render the cue sheet for `tracks` stored in `bin_name`
+///
+/// With `cd_text`, disc and track TITLE/PERFORMER/SONGWRITER lines are
+/// added, and `cdt_name` (if any) is referenced as the CDTEXTFILE.
+pub fn cue_sheet(
+    bin_name: &str,
+    tracks: &[CueTrack],
+    cd_text: Option<&CdText>,
+    cdt_name: Option<&str>,
+) -> String {
+    let mut cue = String::new();
+    if let Some(cdt_name) = cdt_name {
+        cue.push_str(&format!("CDTEXTFILE \"{}\"\n", cdt_name));
+    }
+    if let Some(cd_text) = cd_text {
+        cue.push_str(&cd_text.disc.cue_lines(""));
+    }
+    cue.push_str(&format!("FILE \"{}\" BINARY\n", bin_name));
+    for (i, track) in tracks.iter().enumerate() {
+        cue.push_str(&format!("  TRACK {:02} AUDIO\n", i + 1));
+        if let Some(entry) = cd_text.and_then(|text| text.tracks.get(i)) {
+            cue.push_str(&entry.cue_lines("    "));
+        }
+        if track.pregap_sectors > 0 {
+            let gap_start = track.start_sector - track.pregap_sectors as u64;
+            cue.push_str(&format!("    INDEX 00 {}\n", msf(gap_start)));
+        }
+        cue.push_str(&format!("    INDEX 01 {}\n", msf(track.start_sector)));
+    }
+    cue
+}
+
+/// Decode the active tracks of `folders` into a BIN/CUE pair in `output_dir`
+///
+/// Tracks follow folder order and each folder's `active_tracks()` order.
+/// `cd_text` must have one entry per track. `on_progress` is called with
+/// (tracks done, total tracks); cancellation is checked between tracks.
+/// Tracks are decoded with ffmpeg when it's there, else with symphonia.
+pub fn write_audio_cd_image(
+    folders: &[MusicFolder],
+    output_dir: &Path,
+    options: AudioCdOptions,
+    cd_text: Option<&CdText>,
+    on_progress: &dyn Fn(usize, usize),
+    cancel_token: &AtomicBool,
+) -> Result<AudioCdImage, String> {
+    let ffmpeg = crate::conversion::verify_ffmpeg().ok();
+    if ffmpeg.is_none() {
+        log::info!("Decoding audio CD tracks with symphonia");
+    }
+    let tracks: Vec<&AudioFileInfo> = folders.iter().flat_map(|f| f.active_tracks()).collect();
+
+    write_image_with(
+        &tracks,
+        output_dir,
+        options,
+        cd_text,
+        &mut |path, out| match &ffmpeg {
+            Some(ffmpeg) => decode_with_ffmpeg(ffmpeg, path, out),
+            None => decode_with_symphonia(path, out),
+        },
+        on_progress,
+        cancel_token,
+    )
+}
+
+/// Build the image using `decode` to write each file's PCM to the BIN
+fn write_image_with(
+    tracks: &[&AudioFileInfo],
+    output_dir: &Path,
+    options: AudioCdOptions,
+    cd_text: Option<&CdText>,
+    decode: &mut dyn FnMut(&Path, &mut dyn Write) -> Result<u64, String>,
+    on_progress: &dyn Fn(usize, usize),
+    cancel_token: &AtomicBool,
+) -> Result<AudioCdImage, String> {
+    if tracks.is_empty() {
+        return Err("No tracks to burn".to_string());
+    }
+    if tracks.len() > MAX_TRACKS {
+        return Err(format!(
+            "{} tracks won't fit on an audio CD (at most {})",
+            tracks.len(),
+            MAX_TRACKS
+        ));
+    }
+
+    fs::create_dir_all(output_dir)
+        .map_err(|e| format!("Failed to create audio CD directory: {}", e))?;
+    let bin_path = output_dir.join(BIN_NAME);
+    let cue_path = output_dir.join(CUE_NAME);
+
+    let file = File::create(&bin_path).map_err(|e| format!("Failed to create BIN file: {}", e))?;
+    let mut bin = BufWriter::new(file);
+    let mut cue_tracks = Vec::with_capacity(tracks.len());
+    let mut position = 0u64;
+
+    for (i, track) in tracks.iter().enumerate() {
+        if cancel_token.load(Ordering::SeqCst) {
+            return Err("Audio CD creation cancelled".to_string());
+        }
+        on_progress(i, tracks.len());
+
+        let pregap_sectors = if i == 0 { 0 } else { options.pregap_sectors };
+        write_silence(&mut bin, pregap_sectors as u64 * SECTOR_BYTES)?;
+        position += pregap_sectors as u64;
+
+        log::debug!("Decoding track {}: {}", i + 1, track.path.display());
+        let bytes = decode(&track.path, &mut bin)?;
+
+        // Pad the audio out to whole sectors (and the Red Book minimum)
+        let length_sectors = bytes.div_ceil(SECTOR_BYTES).max(MIN_TRACK_SECTORS);
+        write_silence(&mut bin, length_sectors * SECTOR_BYTES - bytes)?;
+
+        cue_tracks.push(CueTrack {
+            pregap_sectors,
+            start_sector: position,
+            length_sectors,
+        });
+        position += length_sectors;
+    }
+
+    bin.flush()
+        .map_err(|e| format!("Failed to write BIN file: {}", e))?;
+
+    let cd_text = cd_text.filter(|text| !text.is_empty());
+    let cdt_path = match cd_text.map(CdText::to_cdt) {
+        Some(Ok(data)) => {
+            let path = output_dir.join(CDT_NAME);
+            fs::write(&path, data).map_err(|e| format!("Failed to write CD-TEXT file: {}", e))?;
+            Some(path)
+        }
+        Some(Err(e)) => {
+            log::warn!("Leaving out the CD-TEXT file: {}", e);
+            None
+        }
+        None => None,
+    };
+    let cue = cue_sheet(
+        BIN_NAME,
+        &cue_tracks,
+        cd_text,
+        cdt_path.as_ref().map(|_| CDT_NAME),
+    );
+    // Cue sheets are read as ISO 8859-1, like the CD-TEXT itself
+    fs::write(&cue_path, latin1_bytes(&cue))
+        .map_err(|e| format!("Failed to write cue sheet: {}", e))?;
+    on_progress(tracks.len(), tracks.len());
+
+    log::info!(
+        "Audio CD image written: {} tracks, {} ({})",
+        cue_tracks.len(),
+        msf(position),
+        cue_path.display()
+    );
+    Ok(AudioCdImage {
+        bin_path,
+        cue_path,
+        tracks: cue_tracks,
+        total_sectors: position,
+        cdt_path,
+    })
+}
+
+/// Delete the image [`write_audio_cd_image`] wrote to `output_dir`
+///
+/// A full CD's image is some 800 MB, so it's removed once the burn is over.
+pub fn remove_audio_cd_image(output_dir: &Path) {
+    for name in [BIN_NAME, CUE_NAME, CDT_NAME] {
+        let path = output_dir.join(name);
+        if let Err(e) = fs::remove_file(&path)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            log::warn!("Couldn't remove {}: {}", path.display(), e);
+        }
+    }
+    // Only goes if nothing else was left in it
+    let _ = fs::remove_dir(output_dir);
+}
+
+fn write_silence(out: &mut dyn Write, bytes: u64) -> Result<(), String> {
+    std::io::copy(&mut std::io::repeat(0).take(bytes), out)
+        .map(|_| ())
+        .map_err(|e| format!("Failed to write BIN file: {}", e))
+}
+
+/// Decode `path` to raw CD audio (s16le, 44.1 kHz, stereo) on `out`
+fn decode_with_ffmpeg(ffmpeg: &Path, path: &Path, out: &mut dyn Write) -> Result<u64, String> {
+    let mut child = Command::new(ffmpeg)
+        .args(["-v", "error", "-i"])
+        .arg(path)
+        .args(["-vn", "-f", "s16le", "-acodec", "pcm_s16le"])
+        .args(["-ar", "44100", "-ac", "2", "-"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to execute ffmpeg: {}", e))?;
+
+    // Read stderr alongside stdout, so ffmpeg can't stall on a full pipe
+    let stderr = child.stderr.take().map(|mut stderr| {
+        std::thread::spawn(move || {
+            let mut errors = String::new();
+            let _ = stderr.read_to_string(&mut errors);
+            errors
+        })
+    });
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let copied = std::io::copy(&mut stdout, out);
+    if copied.is_err() {
+        // Nothing is reading ffmpeg's output any more
+        let _ = child.kill();
+    }
+
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to wait for ffmpeg: {}", e))?;
+    let errors = stderr
+        .and_then(|reader| reader.join().ok())
+        .unwrap_or_default();
+    let bytes = copied.map_err(|e| format!("Failed to write BIN file: {}", e))?;
+
+    if !status.success() {
+        return Err(format!(
+            "Failed to decode {}: {}",
+            path.display(),
+            errors.trim()
+        ));
+    }
+    Ok(bytes)
+}
+
+/// Decode `path` to raw CD audio (s16le, 44.1 kHz, stereo) on `out` in-process
+fn decode_with_symphonia(path: &Path, out: &mut dyn Write) -> Result<u64, String> {
+    let mut resampler: Option<Resampler> = None;
+    let mut frames = Vec::new();
+    let mut bytes = 0u64;
+    decode_file(path, &mut |block| {
+        let resampler =
+            resampler.get_or_insert_with(|| Resampler::new(block.sample_rate, CD_SAMPLE_RATE));
+        frames.clear();
+        resampler.process(&to_stereo(block.samples, block.channels), &mut frames);
+        let pcm: Vec<u8> = frames.iter().flat_map(|s| s.to_le_bytes()).collect();
+        out.write_all(&pcm)
+            .map_err(|e| format!("Failed to write BIN file: {}", e))?;
+        bytes += pcm.len() as u64;
+        Ok(())
+    })
+    .map_err(|e| format!("Failed to decode {}: {}", path.display(), e))?;
+    Ok(bytes)
+}
+
+/// Linear resampler for interleaved stereo, fed a block at a time
+struct Resampler {
+    /// Input frames per output frame
+    step: f64,
+    /// Position of the next output frame, in input frames from the start of
+    /// the current block (-1.0 is the last frame of the previous block)
+    position: f64,
+    /// Last frame of the previous block
+    previous: [i16; 2],
+}
+
+impl Resampler {
+    fn new(from_rate: u32, to_rate: u32) -> Self {
+        Self {
+            step: from_rate as f64 / to_rate as f64,
+            position: 0.0,
+            previous: [0, 0],
+        }
+    }
+
+    /// Resample `stereo` onto the end of `out`
+    fn process(&mut self, stereo: &[i16], out: &mut Vec<i16>) {
+        if self.step == 1.0 {
+            out.extend_from_slice(stereo);
+            return;
+        }
+        let frames = stereo.len() / 2;
+        if frames == 0 {
+            return;
+        }
+        let previous = self.previous;
+        let frame = |index: isize| -> [i16; 2] {
+            if index < 0 {
+                previous
+            } else {
+                let i = index as usize * 2;
+                [stereo[i], stereo[i + 1]]
+            }
+        };
+
+        // Every output frame lies between two input frames of this block
+        // (or the last one of the previous block)
+        while self.position.floor() as isize + 1 < frames as isize {
+            let index = self.position.floor() as isize;
+            let t = self.position - index as f64;
+            let (a, b) = (frame(index), frame(index + 1));
+            for channel in 0..2 {
+                let sample = a[channel] as f64 + (b[channel] as f64 - a[channel] as f64) * t;
+                out.push(sample.round() as i16);
+            }
+            self.position += self.step;
+        }
+        self.position -= frames as f64;
+        self.previous = frame(frames as isize - 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burning::cd_text::CdTextEntry;
+    use tempfile::TempDir;
+
+    fn track(name: &str, seconds: f64) -> AudioFileInfo {
+        AudioFileInfo {
+            path: PathBuf::from(format!("/test/{}.flac", name)),
+            duration: seconds,
+            bitrate: 1411,
+            size: (seconds * 176_400.0) as u64,
+            codec: "flac".to_string(),
+            is_lossy: false,
+        }
+    }
+
+    fn album(name: &str, tracks: Vec<AudioFileInfo>) -> MusicFolder {
+        let mut folder = MusicFolder::new_for_test_with_id(name);
+        folder.audio_files = tracks;
+        folder.recalculate_totals();
+        folder
+    }
+
+    /// Decoder producing `seconds * 176_400` bytes of a marker byte per file
+    fn fake_decoder(
+        lengths: Vec<(&'static str, u64, u8)>,
+    ) -> impl FnMut(&Path, &mut dyn Write) -> Result<u64, String> {
+        move |path, out| {
+            let (_, bytes, marker) = lengths
+                .iter()
+                .find(|(name, _, _)| path.to_string_lossy().contains(name))
+                .copied()
+                .ok_or_else(|| format!("unexpected file {}", path.display()))?;
+            out.write_all(&vec![marker; bytes as usize]).unwrap();
+            Ok(bytes)
+        }
+    }
+
+    #[test]
+    fn test_msf() {
+        assert_eq!(msf(0), "00:00:00");
+        assert_eq!(msf(74), "00:00:74");
+        assert_eq!(msf(75), "00:01:00");
+        assert_eq!(msf(80 * 60 * 75 + 2 * 75 + 3), "80:02:03");
+    }
+
+    #[test]
+    fn test_cue_sheet() {
+        let tracks = [
+            CueTrack {
+                pregap_sectors: 0,
+                start_sector: 0,
+                length_sectors: 300,
+            },
+            CueTrack {
+                pregap_sectors: 150,
+                start_sector: 450,
+                length_sectors: 300,
+            },
+        ];
+
+        assert_eq!(
+            cue_sheet("a.bin", &tracks, None, None),
+            "FILE \"a.bin\" BINARY\n  TRACK 01 AUDIO\n    INDEX 01 00:00:00\n  \
+             TRACK 02 AUDIO\n    INDEX 00 00:04:00\n    INDEX 01 00:06:00\n"
+        );
+
+        let cd_text = CdText {
+            disc: CdTextEntry::new("Album", "Artist", ""),
+            tracks: vec![
+                CdTextEntry::new("One", "", "Writer"),
+                CdTextEntry::new("Two", "Guest", ""),
+            ],
+        };
+        assert_eq!(
+            cue_sheet("a.bin", &tracks, Some(&cd_text), Some("a.cdt")),
+            "CDTEXTFILE \"a.cdt\"\nTITLE \"Album\"\nPERFORMER \"Artist\"\n\
+             FILE \"a.bin\" BINARY\n  TRACK 01 AUDIO\n    TITLE \"One\"\n    \
+             SONGWRITER \"Writer\"\n    INDEX 01 00:00:00\n  TRACK 02 AUDIO\n    \
+             TITLE \"Two\"\n    PERFORMER \"Guest\"\n    INDEX 00 00:04:00\n    \
+             INDEX 01 00:06:00\n"
+        );
+    }
+
+    #[test]
+    fn test_fits_uses_active_tracks_and_pregaps() {
+        let mut folder = album(
+            "a",
+            vec![
+                track("one", 1800.0),
+                track("two", 1800.0),
+                track("three", 1100.0),
+            ],
+        );
+        let fit = check_audio_cd_fits(
+            std::slice::from_ref(&folder),
+            MediaProfile::Cd80,
+            AudioCdOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(fit.track_count, 3);
+        assert_eq!(fit.duration_secs, 4704.0);
+        assert_eq!(fit.limit_secs, 4800.0);
+
+        // Too long for a 74 minute disc...
+        assert!(
+            check_audio_cd_fits(
+                std::slice::from_ref(&folder),
+                MediaProfile::Cd74,
+                AudioCdOptions::default()
+            )
+            .is_err()
+        );
+
+        // ...until a track is excluded
+        folder.exclude_track(Path::new("/test/three.flac"));
+        let fit = check_audio_cd_fits(
+            std::slice::from_ref(&folder),
+            MediaProfile::Cd74,
+            AudioCdOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(fit.track_count, 2);
+        assert_eq!(fit.duration_secs, 3602.0);
+    }
+
+    #[test]
+    fn test_fit_errors() {
+        let long = album("long", vec![track("one", 75.0 * 60.0)]);
+        let err = check_audio_cd_fits(
+            std::slice::from_ref(&long),
+            MediaProfile::Cd74,
+            AudioCdOptions::default(),
+        )
+        .unwrap_err();
+        assert!(err.contains("74 minute"));
+        assert!(
+            check_audio_cd_fits(&[long], MediaProfile::Cd80, AudioCdOptions::default()).is_ok()
+        );
+
+        let short = album("short", vec![track("one", 60.0)]);
+        let err = check_audio_cd_fits(&[short], MediaProfile::Dvd5, AudioCdOptions::default())
+            .unwrap_err();
+        assert!(err.contains("CD media"));
+
+        let many = album(
+            "many",
+            (0..100).map(|i| track(&format!("t{}", i), 10.0)).collect(),
+        );
+        let err = check_audio_cd_fits(&[many], MediaProfile::Cd80, AudioCdOptions::gapless())
+            .unwrap_err();
+        assert!(err.contains("99"));
+
+        let empty = album("empty", vec![]);
+        assert!(
+            check_audio_cd_fits(&[empty], MediaProfile::Cd80, AudioCdOptions::default()).is_err()
+        );
+    }
+
+    #[test]
+    fn test_write_image_layout() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut folder = album(
+            "a",
+            vec![track("one", 5.0), track("two", 1.0), track("three", 6.0)],
+        );
+        // Play "three" before "one"
+        folder.set_track_order(vec![2, 0, 1]);
+
+        let mut decode = fake_decoder(vec![
+            ("one", 5 * 176_400 + 100, 1),
+            ("two", 176_400, 2),
+            ("three", 6 * 176_400, 3),
+        ]);
+        let seen = std::sync::Mutex::new(Vec::new());
+        let image = write_image_with(
+            &folder.active_tracks(),
+            temp_dir.path(),
+            AudioCdOptions::default(),
+            None,
+            &mut decode,
+            &|done, total| seen.lock().unwrap().push((done, total)),
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+
+        let lengths: Vec<u64> = image.tracks.iter().map(|t| t.length_sectors).collect();
+        // 6 s, 5 s + a partial sector, and a 1 s track padded to 4 s
+        assert_eq!(lengths, vec![450, 376, 300]);
+        assert_eq!(image.tracks[1].start_sector, 450 + 150);
+        assert_eq!(image.tracks[2].start_sector, 450 + 150 + 376 + 150);
+        assert_eq!(image.total_sectors, 450 + 150 + 376 + 150 + 300);
+        assert_eq!(*seen.lock().unwrap(), vec![(0, 3), (1, 3), (2, 3), (3, 3)]);
+
+        let bin = fs::read(&image.bin_path).unwrap();
+        assert_eq!(bin.len() as u64, image.total_sectors * SECTOR_BYTES);
+        // Track 2 starts right after the silent pregap
+        let start = (image.tracks[1].start_sector * SECTOR_BYTES) as usize;
+        assert_eq!(bin[start - 1], 0);
+        assert_eq!(bin[start], 1);
+
+        let cue = fs::read_to_string(&image.cue_path).unwrap();
+        assert!(cue.starts_with("FILE \"mp3cd-audio.bin\" BINARY"));
+        assert_eq!(cue.matches("AUDIO").count(), 3);
+        assert!(image.cdt_path.is_none());
+    }
+
+    #[test]
+    fn test_write_image_with_cd_text() {
+        let temp_dir = TempDir::new().unwrap();
+        let folder = album("a", vec![track("one", 5.0)]);
+        let mut decode = fake_decoder(vec![("one", 176_400, 1)]);
+        let cd_text = CdText {
+            disc: CdTextEntry::new("Björk", "", ""),
+            tracks: vec![CdTextEntry::new("Jóga", "", "")],
+        };
+
+        let image = write_image_with(
+            &folder.active_tracks(),
+            temp_dir.path(),
+            AudioCdOptions::default(),
+            Some(&cd_text),
+            &mut decode,
+            &|_, _| {},
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+
+        let cdt_path = image.cdt_path.unwrap();
+        assert_eq!(fs::read(&cdt_path).unwrap(), cd_text.to_cdt().unwrap());
+        // The cue sheet is written in ISO 8859-1
+        let cue = fs::read(&image.cue_path).unwrap();
+        assert!(cue.starts_with(b"CDTEXTFILE \"mp3cd-audio.cdt\"\nTITLE \"Bj\xF6rk\"\n"));
+        assert!(cue.windows(4).any(|w| w == b"J\xF3ga"));
+
+        remove_audio_cd_image(temp_dir.path());
+        assert!(!temp_dir.path().exists());
+    }
+
+    #[test]
+    fn test_write_image_gapless() {
+        let temp_dir = TempDir::new().unwrap();
+        let folder = album("a", vec![track("one", 5.0), track("two", 5.0)]);
+        let mut decode = fake_decoder(vec![("one", 5 * 176_400, 1), ("two", 5 * 176_400, 2)]);
+
+        let image = write_image_with(
+            &folder.active_tracks(),
+            temp_dir.path(),
+            AudioCdOptions::gapless(),
+            None,
+            &mut decode,
+            &|_, _| {},
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+
+        assert_eq!(image.tracks[1].start_sector, 375);
+        assert!(
+            !fs::read_to_string(&image.cue_path)
+                .unwrap()
+                .contains("INDEX 00")
+        );
+    }
+
+    #[test]
+    fn test_write_image_cancelled() {
+        let temp_dir = TempDir::new().unwrap();
+        let folder = album("a", vec![track("one", 5.0)]);
+        let mut decode = fake_decoder(vec![("one", 176_400, 1)]);
+
+        let err = write_image_with(
+            &folder.active_tracks(),
+            temp_dir.path(),
+            AudioCdOptions::default(),
+            None,
+            &mut decode,
+            &|_, _| {},
+            &AtomicBool::new(true),
+        )
+        .unwrap_err();
+
+        assert!(err.contains("cancelled"));
+        assert!(!temp_dir.path().join(CUE_NAME).exists());
+    }
+
+    #[test]
+    fn test_resampler_passthrough() {
+        let mut resampler = Resampler::new(44_100, CD_SAMPLE_RATE);
+        let mut out = Vec::new();
+        resampler.process(&[1, 2, 3, 4], &mut out);
+        assert_eq!(out, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_resampler_48k() {
+        // A ramp on the left, a constant on the right, a second at a time
+        let input: Vec<i16> = (0..48_000).flat_map(|i| [(i / 8) as i16, 1000]).collect();
+        let mut whole = Vec::new();
+        Resampler::new(48_000, CD_SAMPLE_RATE).process(&input, &mut whole);
+        let frames = whole.len() / 2;
+        assert!((44_099..=44_100).contains(&frames), "{} frames", frames);
+        assert!(whole.chunks(2).all(|frame| frame[1] == 1000));
+        assert!(whole.chunks(2).map(|frame| frame[0]).is_sorted());
+
+        // Blocks of any size come out the same as one big block
+        let mut blocks = Vec::new();
+        let mut resampler = Resampler::new(48_000, CD_SAMPLE_RATE);
+        for block in input.chunks(2 * 1_151) {
+            resampler.process(block, &mut blocks);
+        }
+        assert_eq!(blocks, whole);
+    }
+}
@@ -0,0 +1,527 @@
+//! Virtual drive used for simulated burns and tests
+//!
+//! The fake drive reports a scripted sequence of media states and "burns" by
+//! emitting the same progress pattern as a real drive (erase pass, write
+//! pass, then an indeterminate finishing value), optionally copying the ISO
+//! to a file so tests can compare what was written. That file (or the ISO
+//! itself, when there is none) is what verification reads back. Erasing a
+//! rewritable disc runs a single pass and leaves the drive holding a blank
+//! disc.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::{Burner, DriveInfo, EraseMode, check_cue_exists, check_iso_exists};
+use crate::burning::cd::{CdStatus, ProgressCallback};
+use crate::burning::error::BurnError;
+use crate::burning::verify::ReadBackSource;
+
+/// Scriptable virtual drive
+pub struct FakeBurner {
+    /// Media states still to come; once empty, `current` keeps being reported
+    script: Mutex<VecDeque<CdStatus>>,
+    current: Mutex<CdStatus>,
+    /// Where to write the "burned" image, if anywhere
+    output_path: Option<PathBuf>,
+    progress_step: i32,
+    step_delay: Duration,
+    /// Fail the write pass once it reaches this percentage
+    fail_at_percent: Option<i32>,
+    /// Byte offset to flip in the written output
+    corrupt_offset: Option<u64>,
+    /// Capacity reported for blank media
+    capacity: Option<u64>,
+    burn_count: AtomicUsize,
+    /// Burns that were audio CDs (also counted in `burn_count`)
+    audio_burn_count: AtomicUsize,
+    /// Standalone erases that completed
+    erase_count: AtomicUsize,
+    last_erase_first: AtomicBool,
+    last_iso: Mutex<Option<PathBuf>>,
+}
+
+impl FakeBurner {
+    /// A drive that always holds a disc in the given state
+    pub fn new(status: CdStatus) -> Self {
+        Self::with_status_sequence(vec![status])
+    }
+
+    /// A drive that reports each state in turn, then keeps the last one
+    ///
+    /// e.g. `[NoDisc, NoDisc, Blank]` behaves like a disc being inserted
+    /// on the third check.
+    pub fn with_status_sequence(statuses: Vec<CdStatus>) -> Self {
+        let mut script: VecDeque<CdStatus> = statuses.into();
+        let current = script.pop_front().unwrap_or(CdStatus::NoDisc);
+        Self {
+            script: Mutex::new(script),
+            current: Mutex::new(current),
+            output_path: None,
+            progress_step: 10,
+            step_delay: Duration::ZERO,
+            fail_at_percent: None,
+            corrupt_offset: None,
+            capacity: None,
+            burn_count: AtomicUsize::new(0),
+            audio_burn_count: AtomicUsize::new(0),
+            erase_count: AtomicUsize::new(0),
+            last_erase_first: AtomicBool::new(false),
+            last_iso: Mutex::new(None),
+        }
+    }
+
+    /// The drive used by the Simulate Burn option: a blank disc and a
+    /// short, visible progress run that doesn't write anything
+    pub fn simulated() -> Self {
+        Self::new(CdStatus::Blank).with_step_delay(Duration::from_millis(100))
+    }
+
+    /// The drive used by the Simulate Burn option for erasing: a CD-RW
+    /// holding data and the same visible progress run
+    pub fn simulated_rewritable() -> Self {
+        Self::new(CdStatus::ErasableWithData).with_step_delay(Duration::from_millis(100))
+    }
+
+    /// Copy the ISO to `path` when a burn succeeds
+    pub fn with_output(mut self, path: &Path) -> Self {
+        self.output_path = Some(path.to_path_buf());
+        self
+    }
+
+    /// Sleep between progress updates (for "slow drive" behaviour)
+    pub fn with_step_delay(mut self, delay: Duration) -> Self {
+        self.step_delay = delay;
+        self
+    }
+
+    /// Percentage added per progress update
+    pub fn with_progress_step(mut self, step: i32) -> Self {
+        self.progress_step = step.clamp(1, 100);
+        self
+    }
+
+    /// Fail the burn once the write pass reaches `percent`
+    pub fn failing_at(mut self, percent: i32) -> Self {
+        self.fail_at_percent = Some(percent);
+        self
+    }
+
+    /// Flip the byte at `offset` in the written output (needs [`Self::with_output`])
+    pub fn corrupting_byte(mut self, offset: u64) -> Self {
+        self.corrupt_offset = Some(offset);
+        self
+    }
+
+    /// Report `bytes` as the capacity of a blank disc
+    pub fn with_capacity(mut self, bytes: u64) -> Self {
+        self.capacity = Some(bytes);
+        self
+    }
+
+    /// Number of burns that completed successfully
+    pub fn burn_count(&self) -> usize {
+        self.burn_count.load(Ordering::SeqCst)
+    }
+
+    /// Number of audio CD burns attempted
+    pub fn audio_burn_count(&self) -> usize {
+        self.audio_burn_count.load(Ordering::SeqCst)
+    }
+
+    /// Number of standalone erases that completed
+    pub fn erase_count(&self) -> usize {
+        self.erase_count.load(Ordering::SeqCst)
+    }
+
+    /// Whether the most recent burn was asked to erase first
+    pub fn last_erase_first(&self) -> bool {
+        self.last_erase_first.load(Ordering::SeqCst)
+    }
+
+    /// Emit one pass of progress from 0 to 100, stopping early on cancel
+    /// or at `fail_at`
+    fn run_pass(
+        &self,
+        on_progress: Option<&ProgressCallback>,
+        cancel_token: Option<&Arc<AtomicBool>>,
+        fail_at: Option<i32>,
+    ) -> Result<(), BurnError> {
+        let mut percent = 0;
+        loop {
+            if cancel_token.is_some_and(|token| token.load(Ordering::SeqCst)) {
+                return Err(BurnError::Cancelled);
+            }
+            if let Some(fail_at) = fail_at
+                && percent >= fail_at
+            {
+                return Err(BurnError::Other(format!(
+                    "Simulated burn failure at {}%",
+                    percent
+                )));
+            }
+            if let Some(callback) = on_progress {
+                callback(percent);
+            }
+            if percent >= 100 {
+                return Ok(());
+            }
+            if !self.step_delay.is_zero() {
+                std::thread::sleep(self.step_delay);
+            }
+            percent = (percent + self.progress_step).min(100);
+        }
+    }
+}
+
+impl Burner for FakeBurner {
+    fn name(&self) -> &str {
+        "fake"
+    }
+
+    fn check_status(&self) -> Result<CdStatus, String> {
+        let mut current = self.current.lock().unwrap();
+        let status = *current;
+        if let Some(next) = self.script.lock().unwrap().pop_front() {
+            *current = next;
+        }
+        Ok(status)
+    }
+
+    fn burn(
+        &self,
+        iso_path: &Path,
+        on_progress: Option<ProgressCallback>,
+        cancel_token: Option<Arc<AtomicBool>>,
+        erase_first: bool,
+    ) -> Result<(), BurnError> {
+        check_iso_exists(iso_path)?;
+        self.last_erase_first.store(erase_first, Ordering::SeqCst);
+
+        let status = *self.current.lock().unwrap();
+        match status {
+            CdStatus::NoDisc => return Err(not_writable("No disc in drive")),
+            CdStatus::NonErasable => return Err(not_writable("Disc is not writable")),
+            CdStatus::ErasableWithData if !erase_first => {
+                return Err(not_writable("Disc is not blank"));
+            }
+            _ => {}
+        }
+
+        log::info!("Simulating burn of {}", iso_path.display());
+
+        if erase_first {
+            self.run_pass(on_progress.as_ref(), cancel_token.as_ref(), None)?;
+        }
+        self.run_pass(
+            on_progress.as_ref(),
+            cancel_token.as_ref(),
+            self.fail_at_percent,
+        )?;
+
+        // Finishing (lead-out / fixation)
+        if let Some(callback) = on_progress.as_ref() {
+            callback(-1);
+        }
+
+        if let Some(output_path) = &self.output_path {
+            fs::copy(iso_path, output_path)
+                .map_err(|e| BurnError::from_io("Failed to write simulated disc", &e))?;
+            if let Some(offset) = self.corrupt_offset {
+                corrupt_byte(output_path, offset)
+                    .map_err(|e| BurnError::from_io("Failed to corrupt simulated disc", &e))?;
+            }
+        }
+        *self.last_iso.lock().unwrap() = Some(iso_path.to_path_buf());
+
+        // The disc now holds data
+        *self.current.lock().unwrap() = if status == CdStatus::ErasableWithData {
+            CdStatus::ErasableWithData
+        } else {
+            CdStatus::NonErasable
+        };
+        self.burn_count.fetch_add(1, Ordering::SeqCst);
+        log::info!("Simulated burn complete");
+        Ok(())
+    }
+
+    fn burn_audio(
+        &self,
+        cue_path: &Path,
+        on_progress: Option<ProgressCallback>,
+        cancel_token: Option<Arc<AtomicBool>>,
+        erase_first: bool,
+    ) -> Result<(), BurnError> {
+        check_cue_exists(cue_path)?;
+        self.audio_burn_count.fetch_add(1, Ordering::SeqCst);
+        // Same drive behaviour; the cue sheet stands in for the written disc
+        self.burn(cue_path, on_progress, cancel_token, erase_first)
+    }
+
+    fn erase(
+        &self,
+        mode: EraseMode,
+        on_progress: Option<ProgressCallback>,
+        cancel_token: Option<Arc<AtomicBool>>,
+    ) -> Result<(), BurnError> {
+        match *self.current.lock().unwrap() {
+            CdStatus::NoDisc => return Err(not_writable("No disc in drive")),
+            CdStatus::NonErasable => return Err(not_writable("Disc is not rewritable")),
+            CdStatus::Blank => return Err(BurnError::Other("Disc is already blank".to_string())),
+            CdStatus::ErasableWithData => {}
+        }
+
+        log::info!("Simulating {}", mode.label().to_lowercase());
+        self.run_pass(on_progress.as_ref(), cancel_token.as_ref(), None)?;
+
+        *self.current.lock().unwrap() = CdStatus::Blank;
+        self.erase_count.fetch_add(1, Ordering::SeqCst);
+        log::info!("Simulated erase complete");
+        Ok(())
+    }
+
+    fn read_back_source(&self) -> Result<ReadBackSource, String> {
+        match (&self.output_path, self.last_iso.lock().unwrap().as_ref()) {
+            (_, None) => Err("No disc has been burned".to_string()),
+            (Some(output), Some(_)) => Ok(ReadBackSource::Image(output.clone())),
+            (None, Some(iso)) => Ok(ReadBackSource::Image(iso.clone())),
+        }
+    }
+
+    fn media_capacity(&self) -> Result<Option<u64>, String> {
+        match *self.current.lock().unwrap() {
+            CdStatus::Blank => Ok(self.capacity),
+            _ => Ok(None),
+        }
+    }
+
+    fn eject(&self) -> Result<(), String> {
+        *self.current.lock().unwrap() = CdStatus::NoDisc;
+        Ok(())
+    }
+
+    fn list_drives(&self) -> Result<Vec<DriveInfo>, String> {
+        Ok(vec![DriveInfo {
+            id: "fake".to_string(),
+            model: "Virtual Drive".to_string(),
+            media: vec!["CD-R".to_string(), "CD-RW".to_string()],
+            write_speeds: vec![4, 8, 16, 24, 48],
+        }])
+    }
+}
+
+/// Error for a disc the fake drive can't write
+fn not_writable(message: &str) -> BurnError {
+    BurnError::MediaNotWritable(message.to_string())
+}
+
+fn corrupt_byte(path: &Path, offset: u64) -> std::io::Result<()> {
+    let mut data = fs::read(path)?;
+    if let Some(byte) = data.get_mut(offset as usize) {
+        *byte ^= 0xFF;
+    }
+    fs::write(path, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn fake_iso(dir: &Path) -> PathBuf {
+        let iso = dir.join("test.iso");
+        fs::write(&iso, b"fake iso contents").unwrap();
+        iso
+    }
+
+    fn recorder() -> (Arc<Mutex<Vec<i32>>>, ProgressCallback) {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let seen_clone = seen.clone();
+        (seen, Box::new(move |p| seen_clone.lock().unwrap().push(p)))
+    }
+
+    #[test]
+    fn test_status_sequence_then_repeats_last() {
+        let burner = FakeBurner::with_status_sequence(vec![
+            CdStatus::NoDisc,
+            CdStatus::NoDisc,
+            CdStatus::Blank,
+        ]);
+        assert_eq!(burner.check_status().unwrap(), CdStatus::NoDisc);
+        assert_eq!(burner.check_status().unwrap(), CdStatus::NoDisc);
+        assert_eq!(burner.check_status().unwrap(), CdStatus::Blank);
+        assert_eq!(burner.check_status().unwrap(), CdStatus::Blank);
+    }
+
+    #[test]
+    fn test_burn_writes_output() {
+        let temp_dir = TempDir::new().unwrap();
+        let iso = fake_iso(temp_dir.path());
+        let disc = temp_dir.path().join("disc.iso");
+
+        let burner = FakeBurner::new(CdStatus::Blank).with_output(&disc);
+        burner.burn(&iso, None, None, false).unwrap();
+
+        assert_eq!(fs::read(&disc).unwrap(), fs::read(&iso).unwrap());
+        assert_eq!(burner.burn_count(), 1);
+        // A burned CD-R is no longer usable
+        assert_eq!(burner.check_status().unwrap(), CdStatus::NonErasable);
+    }
+
+    #[test]
+    fn test_burn_progress_pattern() {
+        let temp_dir = TempDir::new().unwrap();
+        let iso = fake_iso(temp_dir.path());
+        let (seen, callback) = recorder();
+
+        let burner = FakeBurner::new(CdStatus::Blank).with_progress_step(25);
+        burner.burn(&iso, Some(callback), None, false).unwrap();
+
+        assert_eq!(*seen.lock().unwrap(), vec![0, 25, 50, 75, 100, -1]);
+    }
+
+    #[test]
+    fn test_erase_runs_two_passes() {
+        let temp_dir = TempDir::new().unwrap();
+        let iso = fake_iso(temp_dir.path());
+        let (seen, callback) = recorder();
+
+        let burner = FakeBurner::new(CdStatus::ErasableWithData).with_progress_step(50);
+        burner.burn(&iso, Some(callback), None, true).unwrap();
+
+        assert_eq!(*seen.lock().unwrap(), vec![0, 50, 100, 0, 50, 100, -1]);
+        assert!(burner.last_erase_first());
+        assert_eq!(burner.check_status().unwrap(), CdStatus::ErasableWithData);
+    }
+
+    #[test]
+    fn test_standalone_erase() {
+        let (seen, callback) = recorder();
+
+        let burner = FakeBurner::new(CdStatus::ErasableWithData).with_progress_step(50);
+        burner
+            .erase(EraseMode::Quick, Some(callback), None)
+            .unwrap();
+
+        assert_eq!(*seen.lock().unwrap(), vec![0, 50, 100]);
+        assert_eq!(burner.erase_count(), 1);
+        assert_eq!(burner.check_status().unwrap(), CdStatus::Blank);
+        // Nothing left to erase
+        assert!(burner.erase(EraseMode::Quick, None, None).is_err());
+        assert!(
+            FakeBurner::new(CdStatus::NonErasable)
+                .erase(EraseMode::Full, None, None)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_rejects_unusable_media() {
+        let temp_dir = TempDir::new().unwrap();
+        let iso = fake_iso(temp_dir.path());
+
+        assert!(
+            FakeBurner::new(CdStatus::NoDisc)
+                .burn(&iso, None, None, false)
+                .is_err()
+        );
+        assert!(matches!(
+            FakeBurner::new(CdStatus::NonErasable).burn(&iso, None, None, false),
+            Err(BurnError::MediaNotWritable(_))
+        ));
+        assert!(
+            FakeBurner::new(CdStatus::ErasableWithData)
+                .burn(&iso, None, None, false)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_mid_burn_failure() {
+        let temp_dir = TempDir::new().unwrap();
+        let iso = fake_iso(temp_dir.path());
+        let disc = temp_dir.path().join("disc.iso");
+
+        let burner = FakeBurner::new(CdStatus::Blank)
+            .with_output(&disc)
+            .failing_at(40);
+        let err = burner.burn(&iso, None, None, false).unwrap_err();
+
+        assert!(err.to_string().contains("40%"));
+        assert!(!disc.exists());
+        assert_eq!(burner.burn_count(), 0);
+    }
+
+    #[test]
+    fn test_read_back_and_eject() {
+        let temp_dir = TempDir::new().unwrap();
+        let iso = fake_iso(temp_dir.path());
+        let disc = temp_dir.path().join("disc.iso");
+
+        let burner = FakeBurner::with_status_sequence(vec![CdStatus::Blank; 3])
+            .with_output(&disc)
+            .corrupting_byte(0);
+        assert!(burner.read_back_source().is_err());
+
+        burner.check_status().unwrap();
+        burner.burn(&iso, None, None, false).unwrap();
+        assert_eq!(
+            burner.read_back_source().unwrap(),
+            ReadBackSource::Image(disc.clone())
+        );
+        assert_ne!(fs::read(&disc).unwrap(), fs::read(&iso).unwrap());
+
+        // Ejecting empties the drive until the script inserts the next disc
+        burner.eject().unwrap();
+        assert_eq!(burner.check_status().unwrap(), CdStatus::NoDisc);
+        assert_eq!(burner.check_status().unwrap(), CdStatus::Blank);
+    }
+
+    #[test]
+    fn test_cancel_during_burn() {
+        let temp_dir = TempDir::new().unwrap();
+        let iso = fake_iso(temp_dir.path());
+        let cancel = Arc::new(AtomicBool::new(true));
+
+        let burner = FakeBurner::new(CdStatus::Blank);
+        let err = burner.burn(&iso, None, Some(cancel), false).unwrap_err();
+        assert_eq!(err, BurnError::Cancelled);
+    }
+
+    #[test]
+    fn test_media_capacity_only_for_blank_disc() {
+        let burner = FakeBurner::with_status_sequence(vec![CdStatus::Blank, CdStatus::NonErasable])
+            .with_capacity(700_000_000);
+        assert_eq!(burner.media_capacity().unwrap(), Some(700_000_000));
+
+        burner.check_status().unwrap();
+        assert_eq!(burner.media_capacity().unwrap(), None);
+        assert_eq!(
+            FakeBurner::new(CdStatus::Blank).media_capacity().unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_burn_audio() {
+        let temp_dir = TempDir::new().unwrap();
+        let cue = temp_dir.path().join("audio.cue");
+        fs::write(&cue, "FILE \"audio.bin\" BINARY\n").unwrap();
+
+        let burner = FakeBurner::new(CdStatus::Blank);
+        burner.burn_audio(&cue, None, None, false).unwrap();
+
+        assert_eq!(burner.audio_burn_count(), 1);
+        assert_eq!(burner.burn_count(), 1);
+        assert!(
+            burner
+                .burn_audio(&temp_dir.path().join("missing.cue"), None, None, false)
+                .unwrap_err()
+                .to_string()
+                .contains("Cue sheet not found")
+        );
+    }
+}
@@ -0,0 +1,1094 @@
+//! Linux burner backend (cdrecord / wodim / xorriso)
+//!
+//! Media state comes from `xorriso -toc` when xorriso is installed, otherwise
+//! from `cdrecord -minfo` (or `wodim -toc`). Burns use the cdrecord command
+//! line, which xorriso also understands via `-as cdrecord`. Audio CDs are
+//! written disc-at-once from a cue sheet, which only cdrecord and wodim do.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use super::{Burner, DriveInfo, EraseMode, check_cue_exists, check_iso_exists, run_burn_process};
+use crate::burning::cd::{CdStatus, ProgressCallback};
+use crate::burning::error::BurnError;
+use crate::burning::verify::ReadBackSource;
+use crate::core::SECTOR_SIZE;
+
+/// Drive used when none has been chosen
+const DEFAULT_DEVICE: &str = "/dev/sr0";
+
+/// Kernel table of optical drives and what they can write
+const CDROM_INFO: &str = "/proc/sys/dev/cdrom/info";
+
+const NO_TOOL_ERROR: &str = "No CD burning tool found (install cdrecord, wodim or xorriso)";
+
+/// The program used to write discs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WriterKind {
+    Cdrecord,
+    Wodim,
+    Xorriso,
+}
+
+impl WriterKind {
+    fn tool_name(self) -> &'static str {
+        match self {
+            WriterKind::Cdrecord => "cdrecord",
+            WriterKind::Wodim => "wodim",
+            WriterKind::Xorriso => "xorriso",
+        }
+    }
+}
+
+/// Burner driving the cdrecord family of tools
+#[derive(Debug, Clone)]
+pub struct LinuxBurner {
+    writer: Option<(WriterKind, PathBuf)>,
+    xorriso: Option<PathBuf>,
+    device: String,
+    speed: Option<u32>,
+}
+
+impl LinuxBurner {
+    /// Find the burn tools on `PATH`
+    pub fn detect() -> Self {
+        let search_path: Vec<PathBuf> = std::env::var_os("PATH")
+            .map(|path| std::env::split_paths(&path).collect())
+            .unwrap_or_default();
+        Self::from_search_path(&search_path)
+    }
+
+    /// Find the burn tools in the given directories
+    ///
+    /// cdrecord is preferred for writing, then wodim, then xorriso.
+    pub fn from_search_path(dirs: &[PathBuf]) -> Self {
+        let find = |name: &str| dirs.iter().map(|dir| dir.join(name)).find(|p| p.is_file());
+
+        let xorriso = find("xorriso");
+        let writer = find("cdrecord")
+            .map(|p| (WriterKind::Cdrecord, p))
+            .or_else(|| find("wodim").map(|p| (WriterKind::Wodim, p)))
+            .or_else(|| xorriso.clone().map(|p| (WriterKind::Xorriso, p)));
+
+        Self {
+            writer,
+            xorriso,
+            device: DEFAULT_DEVICE.to_string(),
+            speed: None,
+        }
+    }
+
+    /// Use a specific drive (e.g. "/dev/sr1")
+    pub fn with_device(mut self, device: &str) -> Self {
+        self.device = device.to_string();
+        self
+    }
+
+    /// Burn at a fixed speed (None = the drive's fastest)
+    pub fn with_speed(mut self, speed: Option<u32>) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Whether a tool capable of burning was found
+    pub fn is_available(&self) -> bool {
+        self.writer.is_some()
+    }
+
+    fn xorriso_toc(&self, xorriso: &Path) -> Result<String, String> {
+        let output = Command::new(xorriso)
+            .args(["-outdev", &self.device, "-toc"])
+            .output()
+            .map_err(|e| format!("Failed to execute xorriso: {}", e))?;
+        Ok(combined_output(&output))
+    }
+
+    fn cdrecord_minfo(&self, cdrecord: &Path) -> Result<String, String> {
+        let output = Command::new(cdrecord)
+            .arg(format!("dev={}", self.device))
+            .arg("-minfo")
+            .output()
+            .map_err(|e| format!("Failed to execute cdrecord: {}", e))?;
+        Ok(combined_output(&output))
+    }
+
+    /// CD write speeds `device` reports, slowest first
+    fn write_speeds(&self, device: &str) -> Vec<u32> {
+        let output = match &self.writer {
+            Some((WriterKind::Cdrecord | WriterKind::Wodim, path)) => Command::new(path)
+                .arg(format!("dev={}", device))
+                .arg("-prcap")
+                .output(),
+            _ => match &self.xorriso {
+                Some(xorriso) => Command::new(xorriso)
+                    .args(["-outdev", device, "-list_speeds"])
+                    .output(),
+                None => return Vec::new(),
+            },
+        };
+        match output {
+            Ok(output) => parse_write_speeds(&combined_output(&output)),
+            Err(e) => {
+                log::warn!("Failed to read write speeds of {}: {}", device, e);
+                Vec::new()
+            }
+        }
+    }
+}
+
+impl Burner for LinuxBurner {
+    fn name(&self) -> &str {
+        match &self.writer {
+            Some((kind, _)) => kind.tool_name(),
+            None => "linux (no tools)",
+        }
+    }
+
+    fn check_status(&self) -> Result<CdStatus, String> {
+        if let Some(xorriso) = &self.xorriso {
+            return Ok(parse_xorriso_toc(&self.xorriso_toc(xorriso)?));
+        }
+
+        match &self.writer {
+            Some((WriterKind::Cdrecord, path)) => {
+                Ok(parse_cdrecord_minfo(&self.cdrecord_minfo(path)?))
+            }
+            Some((WriterKind::Wodim, path)) => {
+                let output = Command::new(path)
+                    .arg(format!("dev={}", self.device))
+                    .arg("-toc")
+                    .output()
+                    .map_err(|e| format!("Failed to execute wodim: {}", e))?;
+                Ok(parse_wodim_toc(&combined_output(&output)))
+            }
+            _ => Err(NO_TOOL_ERROR.to_string()),
+        }
+    }
+
+    fn burn(
+        &self,
+        iso_path: &Path,
+        on_progress: Option<ProgressCallback>,
+        cancel_token: Option<Arc<AtomicBool>>,
+        erase_first: bool,
+    ) -> Result<(), BurnError> {
+        check_iso_exists(iso_path)?;
+
+        let Some((kind, path)) = &self.writer else {
+            return Err(BurnError::ToolMissing(NO_TOOL_ERROR.to_string()));
+        };
+
+        if erase_first {
+            log::info!(
+                "Starting burn of {} on {} with {} (with erase)",
+                iso_path.display(),
+                self.device,
+                kind.tool_name()
+            );
+        } else {
+            log::info!(
+                "Starting burn of {} on {} with {}",
+                iso_path.display(),
+                self.device,
+                kind.tool_name()
+            );
+        }
+
+        let mut command = Command::new(path);
+        if *kind == WriterKind::Xorriso {
+            command.args(["-as", "cdrecord"]);
+        }
+        command.arg("-v").arg(format!("dev={}", self.device));
+        if let Some(speed) = self.speed {
+            command.arg(format!("speed={}", speed));
+        }
+        if erase_first {
+            command.arg(if *kind == WriterKind::Xorriso {
+                "blank=as_needed"
+            } else {
+                "blank=fast"
+            });
+        }
+        // Written track-at-once, some drives can't read a track's last
+        // sectors back, so pad the track past the end of the image
+        command.arg("-pad").arg("-data").arg(iso_path);
+
+        run_burn_process(
+            command,
+            kind.tool_name(),
+            parse_progress_line,
+            on_progress,
+            cancel_token,
+        )
+    }
+
+    fn burn_audio(
+        &self,
+        cue_path: &Path,
+        on_progress: Option<ProgressCallback>,
+        cancel_token: Option<Arc<AtomicBool>>,
+        erase_first: bool,
+    ) -> Result<(), BurnError> {
+        check_cue_exists(cue_path)?;
+
+        let Some((kind, path)) = &self.writer else {
+            return Err(BurnError::ToolMissing(NO_TOOL_ERROR.to_string()));
+        };
+        if *kind == WriterKind::Xorriso {
+            return Err(BurnError::ToolMissing(
+                "xorriso can't write audio CDs (install cdrecord or wodim)".to_string(),
+            ));
+        }
+
+        log::info!(
+            "Starting audio CD burn of {} on {} with {}",
+            cue_path.display(),
+            self.device,
+            kind.tool_name()
+        );
+
+        // The cue sheet names its BIN file relative to itself
+        let mut command = Command::new(path);
+        if let Some(dir) = cue_path.parent() {
+            command.current_dir(dir);
+        }
+        command.arg("-v").arg(format!("dev={}", self.device));
+        if let Some(speed) = self.speed {
+            command.arg(format!("speed={}", speed));
+        }
+        if erase_first {
+            command.arg("blank=fast");
+        }
+        if cue_has_cd_text(cue_path) {
+            command.arg("-text");
+        }
+        command.arg("-dao").arg(format!(
+            "cuefile={}",
+            cue_path.file_name().unwrap_or_default().to_string_lossy()
+        ));
+
+        run_burn_process(
+            command,
+            kind.tool_name(),
+            parse_progress_line,
+            on_progress,
+            cancel_token,
+        )
+    }
+
+    fn erase(
+        &self,
+        mode: EraseMode,
+        on_progress: Option<ProgressCallback>,
+        cancel_token: Option<Arc<AtomicBool>>,
+    ) -> Result<(), BurnError> {
+        let Some((kind, path)) = &self.writer else {
+            return Err(BurnError::ToolMissing(NO_TOOL_ERROR.to_string()));
+        };
+
+        log::info!(
+            "Starting {} of {} with {}",
+            mode.label().to_lowercase(),
+            self.device,
+            kind.tool_name()
+        );
+
+        let mut command = Command::new(path);
+        if *kind == WriterKind::Xorriso {
+            command.args(["-as", "cdrecord"]);
+        }
+        command.arg("-v").arg(format!("dev={}", self.device));
+        command.arg(match mode {
+            EraseMode::Quick => "blank=fast",
+            EraseMode::Full => "blank=all",
+        });
+
+        run_burn_process(
+            command,
+            kind.tool_name(),
+            parse_progress_line,
+            on_progress,
+            cancel_token,
+        )
+    }
+
+    fn media_capacity(&self) -> Result<Option<u64>, String> {
+        if let Some(xorriso) = &self.xorriso {
+            return Ok(parse_xorriso_capacity(&self.xorriso_toc(xorriso)?));
+        }
+
+        match &self.writer {
+            Some((WriterKind::Cdrecord, path)) => {
+                Ok(parse_cdrecord_capacity(&self.cdrecord_minfo(path)?))
+            }
+            // wodim -toc doesn't report the writable size
+            Some(_) => Ok(None),
+            None => Err(NO_TOOL_ERROR.to_string()),
+        }
+    }
+
+    fn media_type(&self) -> Result<Option<String>, String> {
+        if let Some(xorriso) = &self.xorriso {
+            return Ok(parse_media_type(&self.xorriso_toc(xorriso)?));
+        }
+
+        match &self.writer {
+            Some((WriterKind::Cdrecord, path)) => Ok(parse_media_type(&self.cdrecord_minfo(path)?)),
+            Some(_) => Ok(None),
+            None => Err(NO_TOOL_ERROR.to_string()),
+        }
+    }
+
+    fn read_back_source(&self) -> Result<ReadBackSource, String> {
+        Ok(ReadBackSource::Image(PathBuf::from(&self.device)))
+    }
+
+    fn eject(&self) -> Result<(), String> {
+        let Some((kind, path)) = &self.writer else {
+            return Err(NO_TOOL_ERROR.to_string());
+        };
+
+        // All three understand the cdrecord-style eject option
+        let mut command = Command::new(path);
+        if *kind == WriterKind::Xorriso {
+            command.args(["-as", "cdrecord"]);
+        }
+        let output = command
+            .arg(format!("dev={}", self.device))
+            .arg("-eject")
+            .output()
+            .map_err(|e| format!("Failed to execute {}: {}", kind.tool_name(), e))?;
+
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Failed to eject disc: {}",
+                combined_output(&output).trim()
+            ))
+        }
+    }
+
+    fn list_drives(&self) -> Result<Vec<DriveInfo>, String> {
+        let info = match std::fs::read_to_string(CDROM_INFO) {
+            Ok(info) => info,
+            // No cdrom driver loaded means no optical drives
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("Failed to read {}: {}", CDROM_INFO, e)),
+        };
+
+        let mut drives = parse_cdrom_info(&info);
+        for drive in &mut drives {
+            drive.model = sysfs_model(&drive.id).unwrap_or_else(|| drive.id.clone());
+            drive.write_speeds = self.write_speeds(&drive.id);
+        }
+        Ok(drives)
+    }
+}
+
+/// Vendor and model of a drive ("/dev/sr0") from sysfs
+fn sysfs_model(device: &str) -> Option<String> {
+    let name = Path::new(device).file_name()?.to_string_lossy().to_string();
+    let read = |field: &str| {
+        std::fs::read_to_string(format!("/sys/block/{}/device/{}", name, field))
+            .map(|value| value.trim().to_string())
+            .unwrap_or_default()
+    };
+    let model = format!("{} {}", read("vendor"), read("model"));
+    let model = model.trim();
+    (!model.is_empty()).then(|| model.to_string())
+}
+
+fn combined_output(output: &Output) -> String {
+    format!(
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    )
+}
+
+/// Whether a cue sheet carries CD-TEXT for the writer's `-text` option
+fn cue_has_cd_text(cue_path: &Path) -> bool {
+    let Ok(cue) = std::fs::read(cue_path) else {
+        return false;
+    };
+    String::from_utf8_lossy(&cue).lines().any(|line| {
+        let keyword = line.split_whitespace().next().unwrap_or_default();
+        matches!(keyword, "CDTEXTFILE" | "TITLE" | "PERFORMER" | "SONGWRITER")
+    })
+}
+
+/// Parse `cdrecord -minfo` output
+pub(crate) fn parse_cdrecord_minfo(output: &str) -> CdStatus {
+    let lower = output.to_lowercase();
+
+    if lower.contains("no disk") || lower.contains("medium not present") {
+        return CdStatus::NoDisc;
+    }
+
+    let disk_empty = lower.lines().any(|line| {
+        let line = line.trim();
+        line.starts_with("disk status:") && line.ends_with("empty")
+    });
+    if disk_empty {
+        return CdStatus::Blank;
+    }
+
+    let is_erasable = lower.contains("is erasable")
+        || lower.lines().any(|line| {
+            line.trim().starts_with("mounted media type:")
+                && (line.contains("-rw") || line.contains("+rw"))
+        });
+    if is_erasable {
+        return CdStatus::ErasableWithData;
+    }
+
+    CdStatus::NonErasable
+}
+
+/// Parse `xorriso -toc` output
+pub(crate) fn parse_xorriso_toc(output: &str) -> CdStatus {
+    let lower = output.to_lowercase();
+
+    if lower.contains("not present") || lower.contains("no media") {
+        return CdStatus::NoDisc;
+    }
+
+    if lower.contains("is blank") {
+        return CdStatus::Blank;
+    }
+
+    let media = lower
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("media current:"))
+        .unwrap_or("");
+    if media.contains("rw") || media.contains("-re") {
+        return CdStatus::ErasableWithData;
+    }
+
+    CdStatus::NonErasable
+}
+
+/// Media type from `xorriso -toc` ("Media current: CD-RW") or
+/// `cdrecord -minfo` ("Mounted media type: CD-R")
+pub(crate) fn parse_media_type(output: &str) -> Option<String> {
+    let media = output.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        let key = key.trim();
+        (key.eq_ignore_ascii_case("media current")
+            || key.eq_ignore_ascii_case("mounted media type"))
+        .then(|| value.trim())
+    })?;
+    (!media.is_empty() && !media.contains("not present")).then(|| media.to_string())
+}
+
+/// Writable bytes from `cdrecord -minfo` ("Remaining writable size: 359846")
+pub(crate) fn parse_cdrecord_capacity(output: &str) -> Option<u64> {
+    let blocks: u64 = output.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        if !key.trim().eq_ignore_ascii_case("remaining writable size") {
+            return None;
+        }
+        value.split_whitespace().next()?.parse().ok()
+    })?;
+    (blocks > 0).then_some(blocks * SECTOR_SIZE)
+}
+
+/// Writable bytes from `xorriso -toc`
+/// ("Media blocks : 0 readable , 359846 writable , 359846 overall")
+pub(crate) fn parse_xorriso_capacity(output: &str) -> Option<u64> {
+    let line = output
+        .lines()
+        .find(|line| line.trim_start().to_lowercase().starts_with("media blocks"))?;
+    let (_, counts) = line.split_once(':')?;
+    let blocks: u64 = counts
+        .split(',')
+        .find_map(|part| part.trim().strip_suffix("writable")?.trim().parse().ok())?;
+    (blocks > 0).then_some(blocks * SECTOR_SIZE)
+}
+
+/// Parse `wodim -toc` output
+///
+/// wodim can't report erasability here, so written discs are treated as
+/// non-erasable.
+pub(crate) fn parse_wodim_toc(output: &str) -> CdStatus {
+    let lower = output.to_lowercase();
+
+    if lower.contains("no disk") || lower.contains("medium not present") {
+        CdStatus::NoDisc
+    } else if lower.contains("cannot read toc") {
+        CdStatus::Blank
+    } else {
+        CdStatus::NonErasable
+    }
+}
+
+/// Drives in `/proc/sys/dev/cdrom/info`, with the media each can write
+///
+/// The table has one column per drive: a "drive name:" row, then rows like
+/// "Can write CD-R:" holding 1 or 0. The model is left for the caller.
+pub(crate) fn parse_cdrom_info(info: &str) -> Vec<DriveInfo> {
+    let rows: Vec<(&str, Vec<&str>)> = info
+        .lines()
+        .filter_map(|line| {
+            let (key, values) = line.split_once(':')?;
+            Some((key.trim(), values.split_whitespace().collect()))
+        })
+        .collect();
+    let Some((_, names)) = rows.iter().find(|(key, _)| *key == "drive name") else {
+        return Vec::new();
+    };
+
+    names
+        .iter()
+        .enumerate()
+        .map(|(column, name)| {
+            let media = rows
+                .iter()
+                .filter_map(|(key, values)| {
+                    let media = key.strip_prefix("Can write ")?;
+                    (values.get(column) == Some(&"1")).then(|| media.to_string())
+                })
+                .collect();
+            DriveInfo {
+                id: format!("/dev/{}", name),
+                model: String::new(),
+                media,
+                write_speeds: Vec::new(),
+            }
+        })
+        .collect()
+}
+
+/// CD write speeds from `cdrecord -prcap` / `wodim -prcap`
+/// ("Write speed # 0:  7056 kB/s CLV/PCAV (CD  40x, DVD  5x, BD  1x)") or
+/// `xorriso -list_speeds` ("Write speed  :  7056k ,  40.0xC"), slowest first
+pub(crate) fn parse_write_speeds(output: &str) -> Vec<u32> {
+    let mut speeds: Vec<u32> = output
+        .lines()
+        .filter(|line| line.trim_start().starts_with("Write speed"))
+        .filter_map(|line| {
+            if let Some((_, rest)) = line.split_once("(CD") {
+                let factor = rest.split_whitespace().next()?.trim_end_matches([',', ')']);
+                return factor.strip_suffix('x')?.parse().ok();
+            }
+            line.split_whitespace().find_map(|word| {
+                let factor: f64 = word.strip_suffix("xC")?.parse().ok()?;
+                Some(factor.round() as u32)
+            })
+        })
+        .filter(|&speed| speed > 0)
+        .collect();
+    speeds.sort_unstable();
+    speeds.dedup();
+    speeds
+}
+
+/// Parse a progress line from cdrecord/wodim (`-v`) or xorriso
+///
+/// Returns a percentage, or -1 once the disc is being fixated.
+pub(crate) fn parse_progress_line(line: &str) -> Option<i32> {
+    let lower = line.trim().to_lowercase();
+
+    // cdrecord/wodim: "Track 01:   12 of  300 MB written (fifo 100%) [buf  99%]   4.0x."
+    if lower.starts_with("track") && lower.contains(" of ") && lower.contains("written") {
+        let (_, counts) = lower.split_once(':')?;
+        let mut words = counts.split_whitespace();
+        let written: f64 = words.next()?.parse().ok()?;
+        if words.next()? != "of" {
+            return None;
+        }
+        let total: f64 = words.next()?.parse().ok()?;
+        if total <= 0.0 {
+            return None;
+        }
+        return Some(((written / total) * 100.0).round().clamp(0.0, 100.0) as i32);
+    }
+
+    // cdrecord/wodim report blanking as done rather than as a percentage
+    if lower.starts_with("blanking time") {
+        return Some(100);
+    }
+
+    if lower.starts_with("fixating") || lower.contains("closing track") {
+        return Some(-1);
+    }
+
+    // xorriso: "xorriso : UPDATE : Writing:  2048s  12.3%  fifo 100%  buf  50%"
+    //          "xorriso : UPDATE : Blanking  ( 45.2% done in 12 seconds )"
+    if lower.contains("update") && (lower.contains("writing") || lower.contains("blanking")) {
+        let percent = lower
+            .split_whitespace()
+            .find_map(|word| word.strip_suffix('%')?.parse::<f64>().ok())?;
+        return Some(percent.round().clamp(0.0, 100.0) as i32);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::sync::Mutex;
+    use std::sync::atomic::Ordering;
+    use tempfile::TempDir;
+
+    /// Serialises stub creation and execution so a stub is never exec'd
+    /// while another test still has it open for writing
+    static STUB_LOCK: Mutex<()> = Mutex::new(());
+
+    #[cfg(unix)]
+    fn write_stub(dir: &Path, name: &str, script: &str) {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join(name);
+        fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    fn fake_iso(dir: &Path) -> PathBuf {
+        let iso = dir.join("test.iso");
+        fs::write(&iso, b"fake iso").unwrap();
+        iso
+    }
+
+    #[test]
+    fn test_parse_cdrecord_minfo_blank() {
+        let output = "Mounted media class:      CD\nMounted media type:       CD-R\nDisk Is not erasable\ndisk status:              empty\nsession status:           empty\n";
+        assert_eq!(parse_cdrecord_minfo(output), CdStatus::Blank);
+    }
+
+    #[test]
+    fn test_parse_cdrecord_minfo_erasable() {
+        let output = "Mounted media class:      CD\nMounted media type:       CD-RW\nDisk Is erasable\ndisk status:              complete\nsession status:           complete\n";
+        assert_eq!(parse_cdrecord_minfo(output), CdStatus::ErasableWithData);
+    }
+
+    #[test]
+    fn test_parse_cdrecord_minfo_written_cdr() {
+        let output = "Mounted media type:       CD-R\nDisk Is not erasable\ndisk status:              complete\nsession status:           empty\n";
+        assert_eq!(parse_cdrecord_minfo(output), CdStatus::NonErasable);
+    }
+
+    #[test]
+    fn test_parse_cdrecord_minfo_no_disc() {
+        let output = "cdrecord: No disk / Wrong disk!\n";
+        assert_eq!(parse_cdrecord_minfo(output), CdStatus::NoDisc);
+    }
+
+    #[test]
+    fn test_parse_xorriso_toc() {
+        let blank =
+            "Drive current: -outdev '/dev/sr0'\nMedia current: CD-R\nMedia status : is blank\n";
+        assert_eq!(parse_xorriso_toc(blank), CdStatus::Blank);
+
+        let rw = "Media current: CD-RW\nMedia status : is written , is closed\n";
+        assert_eq!(parse_xorriso_toc(rw), CdStatus::ErasableWithData);
+
+        let cdr = "Media current: CD-R\nMedia status : is written , is closed\n";
+        assert_eq!(parse_xorriso_toc(cdr), CdStatus::NonErasable);
+
+        let none = "Media current: is not present\n";
+        assert_eq!(parse_xorriso_toc(none), CdStatus::NoDisc);
+    }
+
+    #[test]
+    fn test_parse_media_type() {
+        let toc = "Drive current: -outdev '/dev/sr0'\nMedia current: CD-RW\n";
+        assert_eq!(parse_media_type(toc), Some("CD-RW".to_string()));
+        let minfo = "Mounted media class:      CD\nMounted media type:       CD-R\n";
+        assert_eq!(parse_media_type(minfo), Some("CD-R".to_string()));
+        assert_eq!(parse_media_type("Media current: is not present\n"), None);
+    }
+
+    #[test]
+    fn test_parse_media_capacity() {
+        let minfo = "disk status:              empty\nRemaining writable size:  359846\n";
+        assert_eq!(parse_cdrecord_capacity(minfo), Some(359_846 * 2048));
+        assert_eq!(parse_cdrecord_capacity("disk status: complete\n"), None);
+
+        let toc = "Media status : is blank\nMedia blocks :        0 readable ,   359846 writable ,   359846 overall\n";
+        assert_eq!(parse_xorriso_capacity(toc), Some(359_846 * 2048));
+        let full = "Media blocks :   300000 readable ,        0 writable ,   359846 overall\n";
+        assert_eq!(parse_xorriso_capacity(full), None);
+    }
+
+    #[test]
+    fn test_parse_wodim_toc() {
+        assert_eq!(
+            parse_wodim_toc("wodim: No disk / Wrong disk!"),
+            CdStatus::NoDisc
+        );
+        assert_eq!(
+            parse_wodim_toc("wodim: Cannot read TOC header"),
+            CdStatus::Blank
+        );
+        assert_eq!(
+            parse_wodim_toc("first: 1 last 1\ntrack:   1 lba:         0"),
+            CdStatus::NonErasable
+        );
+    }
+
+    #[test]
+    fn test_parse_progress_line_cdrecord() {
+        assert_eq!(
+            parse_progress_line("Track 01:   30 of  300 MB written (fifo 100%) [buf  99%]   4.0x."),
+            Some(10)
+        );
+        assert_eq!(
+            parse_progress_line("Track 01:  300 of  300 MB written (fifo 100%) [buf  99%]   4.0x."),
+            Some(100)
+        );
+        assert_eq!(parse_progress_line("Blanking time:   20.503s"), Some(100));
+        assert_eq!(parse_progress_line("Fixating..."), Some(-1));
+        assert_eq!(
+            parse_progress_line("Track 01: Total bytes read/written: 1234 / 1234"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_progress_line_xorriso() {
+        assert_eq!(
+            parse_progress_line(
+                "xorriso : UPDATE : Writing:     2048s   12.3%   fifo 100%  buf  50%"
+            ),
+            Some(12)
+        );
+        assert_eq!(
+            parse_progress_line("xorriso : UPDATE : Blanking  ( 45.6% done in 12 seconds )"),
+            Some(46)
+        );
+        assert_eq!(
+            parse_progress_line("xorriso : UPDATE : Closing track/session."),
+            Some(-1)
+        );
+        assert_eq!(parse_progress_line("xorriso : NOTE : Drive ready"), None);
+    }
+
+    #[test]
+    fn test_no_tools_found() {
+        let empty = TempDir::new().unwrap();
+        let burner = LinuxBurner::from_search_path(&[empty.path().to_path_buf()]);
+        assert!(!burner.is_available());
+        assert!(
+            burner
+                .check_status()
+                .unwrap_err()
+                .contains("No CD burning tool")
+        );
+
+        let iso = fake_iso(empty.path());
+        let result = burner.burn(&iso, None, None, false);
+        assert!(matches!(result, Err(BurnError::ToolMissing(_))));
+    }
+
+    #[test]
+    fn test_burn_iso_not_found() {
+        let burner = LinuxBurner::from_search_path(&[]);
+        let result = burner.burn(Path::new("/nonexistent/file.iso"), None, None, false);
+        assert!(result.unwrap_err().to_string().contains("not found"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_writer_preference() {
+        let _guard = STUB_LOCK.lock().unwrap();
+        let bin = TempDir::new().unwrap();
+        write_stub(bin.path(), "xorriso", "exit 0");
+        write_stub(bin.path(), "wodim", "exit 0");
+
+        let burner = LinuxBurner::from_search_path(&[bin.path().to_path_buf()]);
+        assert_eq!(burner.name(), "wodim");
+
+        write_stub(bin.path(), "cdrecord", "exit 0");
+        let burner = LinuxBurner::from_search_path(&[bin.path().to_path_buf()]);
+        assert_eq!(burner.name(), "cdrecord");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_check_status_with_stub_cdrecord() {
+        let _guard = STUB_LOCK.lock().unwrap();
+        let bin = TempDir::new().unwrap();
+        write_stub(
+            bin.path(),
+            "cdrecord",
+            "echo 'Mounted media type:       CD-RW'\necho 'Disk Is erasable'\necho 'disk status:              complete'",
+        );
+
+        let burner = LinuxBurner::from_search_path(&[bin.path().to_path_buf()]);
+        assert_eq!(burner.check_status().unwrap(), CdStatus::ErasableWithData);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_check_status_prefers_xorriso_toc() {
+        let _guard = STUB_LOCK.lock().unwrap();
+        let bin = TempDir::new().unwrap();
+        write_stub(
+            bin.path(),
+            "cdrecord",
+            "echo 'cdrecord: No disk / Wrong disk!'",
+        );
+        write_stub(
+            bin.path(),
+            "xorriso",
+            "echo 'Media current: CD-R'\necho 'Media status : is blank'",
+        );
+
+        let burner = LinuxBurner::from_search_path(&[bin.path().to_path_buf()]);
+        assert_eq!(burner.check_status().unwrap(), CdStatus::Blank);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_burn_reports_progress() {
+        let _guard = STUB_LOCK.lock().unwrap();
+        let bin = TempDir::new().unwrap();
+        write_stub(
+            bin.path(),
+            "cdrecord",
+            "printf 'Track 01:   50 of  100 MB written (fifo 100%%)\\r'\nprintf 'Track 01:  100 of  100 MB written (fifo 100%%)\\n'\necho 'Fixating...' >&2",
+        );
+        let iso = fake_iso(bin.path());
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let seen_clone = seen.clone();
+        let callback: ProgressCallback = Box::new(move |p| seen_clone.lock().unwrap().push(p));
+
+        let burner = LinuxBurner::from_search_path(&[bin.path().to_path_buf()]);
+        burner.burn(&iso, Some(callback), None, false).unwrap();
+
+        let seen = seen.lock().unwrap();
+        assert!(seen.contains(&50));
+        assert!(seen.contains(&100));
+        assert!(seen.contains(&-1));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_burn_failure_includes_message() {
+        let _guard = STUB_LOCK.lock().unwrap();
+        let bin = TempDir::new().unwrap();
+        write_stub(
+            bin.path(),
+            "wodim",
+            "echo 'wodim: Input/output error. write_g1: scsi sendcmd: no error' >&2\nexit 1",
+        );
+        let iso = fake_iso(bin.path());
+
+        let burner = LinuxBurner::from_search_path(&[bin.path().to_path_buf()]);
+        let err = burner.burn(&iso, None, None, false).unwrap_err();
+        assert!(matches!(err, BurnError::Other(_)));
+        assert!(err.to_string().contains("Burn process failed"));
+        assert!(err.to_string().contains("Input/output error"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_burn_failure_sorted_by_cause() {
+        let _guard = STUB_LOCK.lock().unwrap();
+        let bin = TempDir::new().unwrap();
+        write_stub(
+            bin.path(),
+            "cdrecord",
+            "echo 'cdrecord: Device or resource busy. Cannot open SCSI driver.' >&2\n\
+             echo 'cdrecord: For possible targets try cdrecord -scanbus.' >&2\nexit 1",
+        );
+        let iso = fake_iso(bin.path());
+
+        let burner = LinuxBurner::from_search_path(&[bin.path().to_path_buf()]);
+        let err = burner.burn(&iso, None, None, false).unwrap_err();
+        assert!(matches!(err, BurnError::DriveBusy(_)));
+        assert!(err.recovery_hint().is_some());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_burn_cancelled() {
+        let _guard = STUB_LOCK.lock().unwrap();
+        let bin = TempDir::new().unwrap();
+        write_stub(bin.path(), "cdrecord", "exec sleep 5");
+        let iso = fake_iso(bin.path());
+
+        let cancel = Arc::new(AtomicBool::new(false));
+        cancel.store(true, Ordering::SeqCst);
+
+        let burner = LinuxBurner::from_search_path(&[bin.path().to_path_buf()]);
+        let err = burner.burn(&iso, None, Some(cancel), false).unwrap_err();
+        assert_eq!(err, BurnError::Cancelled);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_burn_passes_erase_and_device_args() {
+        let _guard = STUB_LOCK.lock().unwrap();
+        let bin = TempDir::new().unwrap();
+        let args_file = bin.path().join("args.txt");
+        write_stub(
+            bin.path(),
+            "cdrecord",
+            &format!("echo \"$@\" > '{}'", args_file.display()),
+        );
+        let iso = fake_iso(bin.path());
+
+        let burner =
+            LinuxBurner::from_search_path(&[bin.path().to_path_buf()]).with_device("/dev/sr1");
+        burner.burn(&iso, None, None, true).unwrap();
+
+        let args = fs::read_to_string(&args_file).unwrap();
+        assert!(args.contains("dev=/dev/sr1"));
+        assert!(args.contains("blank=fast"));
+        assert!(args.contains("-pad -data"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_erase_passes_blank_mode() {
+        let _guard = STUB_LOCK.lock().unwrap();
+        let bin = TempDir::new().unwrap();
+        let args_file = bin.path().join("args.txt");
+        write_stub(
+            bin.path(),
+            "xorriso",
+            &format!(
+                "echo \"$@\" > '{}'\necho 'xorriso : UPDATE : Blanking  ( 45.2% done in 12 seconds )'",
+                args_file.display()
+            ),
+        );
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let seen_clone = seen.clone();
+        let callback: ProgressCallback = Box::new(move |p| seen_clone.lock().unwrap().push(p));
+
+        let burner = LinuxBurner::from_search_path(&[bin.path().to_path_buf()]);
+        burner.erase(EraseMode::Full, Some(callback), None).unwrap();
+
+        let args = fs::read_to_string(&args_file).unwrap();
+        assert!(args.contains("-as cdrecord"));
+        assert!(args.contains("dev=/dev/sr0"));
+        assert!(args.contains("blank=all"));
+        assert_eq!(*seen.lock().unwrap(), vec![45]);
+    }
+
+    #[test]
+    fn test_parse_cdrom_info() {
+        let info = "CD-ROM information, Id: cdrom.c 3.20 2003/12/17\n\ndrive name:\t\tsr1\tsr0\ndrive speed:\t\t24\t40\nCan write CD-R:\t\t1\t0\nCan write CD-RW:\t1\t0\nCan write DVD-R:\t\t1\t0\nCan write DVD-RAM:\t0\t0\n";
+        let drives = parse_cdrom_info(info);
+        assert_eq!(drives.len(), 2);
+        assert_eq!(drives[0].id, "/dev/sr1");
+        assert_eq!(drives[0].media, vec!["CD-R", "CD-RW", "DVD-R"]);
+        assert_eq!(drives[1].id, "/dev/sr0");
+        assert!(drives[1].media.is_empty());
+        assert!(parse_cdrom_info("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_write_speeds() {
+        let prcap = "  Write speed # 0:  7056 kB/s CLV/PCAV (CD  40x, DVD  5x, BD  1x)\n  Write speed # 1:  4234 kB/s CLV/PCAV (CD  24x, DVD  3x, BD  0x)\n  Write speed # 2:  1411 kB/s CLV/PCAV (CD   8x, DVD  1x, BD  0x)\n";
+        assert_eq!(parse_write_speeds(prcap), vec![8, 24, 40]);
+
+        let xorriso = "Write speed  :   7056k ,  40.0xC\nWrite speed  :   2822k ,  16.0xC\nWrite speed L:   1411k ,   8.0xC\nWrite speed H:   7056k ,  40.0xC\n";
+        assert_eq!(parse_write_speeds(xorriso), vec![8, 16, 40]);
+        assert!(parse_write_speeds("No drive found\n").is_empty());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_burn_passes_speed_arg() {
+        let _guard = STUB_LOCK.lock().unwrap();
+        let bin = TempDir::new().unwrap();
+        let args_file = bin.path().join("args.txt");
+        write_stub(
+            bin.path(),
+            "wodim",
+            &format!("echo \"$@\" > '{}'", args_file.display()),
+        );
+        let iso = fake_iso(bin.path());
+
+        let burner = LinuxBurner::from_search_path(&[bin.path().to_path_buf()]);
+        burner.burn(&iso, None, None, false).unwrap();
+        let args = fs::read_to_string(&args_file).unwrap();
+        assert!(!args.contains("speed="));
+
+        let burner = burner.with_speed(Some(8));
+        burner.burn(&iso, None, None, false).unwrap();
+        let args = fs::read_to_string(&args_file).unwrap();
+        assert!(args.contains("speed=8"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_burn_audio_uses_cue_file() {
+        let _guard = STUB_LOCK.lock().unwrap();
+        let bin = TempDir::new().unwrap();
+        let args_file = bin.path().join("args.txt");
+        write_stub(
+            bin.path(),
+            "wodim",
+            &format!("echo \"$(pwd) $@\" > '{}'", args_file.display()),
+        );
+        let cue = bin.path().join("audio.cue");
+        fs::write(&cue, "FILE \"audio.bin\" BINARY\n").unwrap();
+
+        let burner = LinuxBurner::from_search_path(&[bin.path().to_path_buf()]);
+        burner.burn_audio(&cue, None, None, false).unwrap();
+
+        let args = fs::read_to_string(&args_file).unwrap();
+        assert!(args.contains("-dao"));
+        assert!(args.contains("cuefile=audio.cue"));
+        assert!(!args.contains("-data"));
+        assert!(!args.contains("-text"));
+        // Run from the cue sheet's directory so the BIN file is found
+        let cwd = fs::canonicalize(bin.path()).unwrap();
+        assert!(args.starts_with(&cwd.display().to_string()));
+
+        // CD-TEXT in the cue sheet turns on -text
+        fs::write(&cue, "TITLE \"Album\"\nFILE \"audio.bin\" BINARY\n").unwrap();
+        burner.burn_audio(&cue, None, None, false).unwrap();
+        let args = fs::read_to_string(&args_file).unwrap();
+        assert!(args.contains("-text"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_burn_audio_not_supported_by_xorriso() {
+        let _guard = STUB_LOCK.lock().unwrap();
+        let bin = TempDir::new().unwrap();
+        write_stub(bin.path(), "xorriso", "exit 0");
+        let cue = bin.path().join("audio.cue");
+        fs::write(&cue, "").unwrap();
+
+        let burner = LinuxBurner::from_search_path(&[bin.path().to_path_buf()]);
+        let err = burner.burn_audio(&cue, None, None, false).unwrap_err();
+        assert!(matches!(err, BurnError::ToolMissing(_)));
+        assert!(err.to_string().contains("install cdrecord or wodim"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_eject_uses_writer() {
+        let _guard = STUB_LOCK.lock().unwrap();
+        let bin = TempDir::new().unwrap();
+        let args_file = bin.path().join("args.txt");
+        write_stub(
+            bin.path(),
+            "wodim",
+            &format!("echo \"$@\" > '{}'", args_file.display()),
+        );
+
+        let burner = LinuxBurner::from_search_path(&[bin.path().to_path_buf()]);
+        burner.eject().unwrap();
+
+        let args = fs::read_to_string(&args_file).unwrap();
+        assert!(args.contains("dev=/dev/sr0"));
+        assert!(args.contains("-eject"));
+        assert_eq!(
+            burner.read_back_source().unwrap(),
+            ReadBackSource::Image(PathBuf::from("/dev/sr0"))
+        );
+    }
+}
@@ -0,0 +1,525 @@
+//! macOS burner backend (drutil / hdiutil)
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+
+use super::{Burner, DriveInfo, EraseMode, check_cue_exists, check_iso_exists, run_burn_process};
+use crate::burning::cd::{CdStatus, ProgressCallback};
+use crate::burning::error::BurnError;
+use crate::burning::verify::ReadBackSource;
+use crate::core::SECTOR_SIZE;
+
+/// How long to wait for Finder to mount a freshly burned disc
+const MOUNT_WAIT_SECS: u32 = 15;
+
+/// Burner using the tools that ship with macOS
+#[derive(Debug, Clone, Default)]
+pub struct MacBurner {
+    /// `drutil` drive number (None = the first drive)
+    drive: Option<String>,
+    /// Write speed factor passed to `hdiutil burn -speed`
+    speed: Option<u32>,
+}
+
+impl MacBurner {
+    /// Use a specific drive, by its number in `drutil list`
+    pub fn with_drive(mut self, drive: &str) -> Self {
+        self.drive = Some(drive.to_string());
+        self
+    }
+
+    /// Burn at a fixed speed (None = the drive's fastest)
+    pub fn with_speed(mut self, speed: Option<u32>) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Run a `drutil` command against the chosen drive
+    fn drutil(&self, command: &str) -> Result<String, String> {
+        let mut drutil = Command::new("drutil");
+        if let Some(drive) = &self.drive {
+            drutil.args(["-drive", drive]);
+        }
+        let output = drutil
+            .arg(command)
+            .output()
+            .map_err(|e| format!("Failed to execute drutil: {}", e))?;
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// IOService path `hdiutil burn -device` needs for the chosen drive
+    ///
+    /// hdiutil numbers drives differently from drutil, so they're matched
+    /// up by model name.
+    fn hdiutil_device(&self) -> Option<String> {
+        let drive = self.drive.as_deref()?;
+        let list = Command::new("drutil").arg("list").output().ok()?;
+        let model = parse_drutil_list(&String::from_utf8_lossy(&list.stdout))
+            .into_iter()
+            .find(|info| info.id == drive)?
+            .model;
+        let devices = Command::new("hdiutil")
+            .args(["burn", "-list"])
+            .output()
+            .ok()?;
+        let device = find_hdiutil_device(&String::from_utf8_lossy(&devices.stdout), &model);
+        if device.is_none() {
+            log::warn!(
+                "hdiutil doesn't list drive {} ({}), burning with the default drive",
+                drive,
+                model
+            );
+        }
+        device
+    }
+
+    /// `hdiutil burn` with the options every burn shares
+    fn hdiutil_burn(&self, erase_first: bool) -> Command {
+        // The disc stays in the drive so it can be verified; the
+        // coordinator ejects it.
+        let mut command = Command::new("hdiutil");
+        command.args(["burn", "-noverifyburn", "-noeject", "-puppetstrings"]);
+        if let Some(device) = self.hdiutil_device() {
+            command.args(["-device", &device]);
+        }
+        if let Some(speed) = self.speed {
+            command.args(["-speed", &speed.to_string()]);
+        }
+        if erase_first {
+            command.arg("-erase");
+        }
+        command
+    }
+}
+
+impl Burner for MacBurner {
+    fn name(&self) -> &str {
+        "hdiutil"
+    }
+
+    fn check_status(&self) -> Result<CdStatus, String> {
+        Ok(parse_drutil_status(&self.drutil("status")?))
+    }
+
+    fn burn(
+        &self,
+        iso_path: &Path,
+        on_progress: Option<ProgressCallback>,
+        cancel_token: Option<Arc<AtomicBool>>,
+        erase_first: bool,
+    ) -> Result<(), BurnError> {
+        check_iso_exists(iso_path)?;
+
+        if erase_first {
+            log::info!("Starting burn of {} (with erase)", iso_path.display());
+        } else {
+            log::info!("Starting burn of {}", iso_path.display());
+        }
+
+        let mut command = self.hdiutil_burn(erase_first);
+        command.arg(iso_path);
+
+        run_burn_process(
+            command,
+            "hdiutil burn",
+            parse_puppetstrings,
+            on_progress,
+            cancel_token,
+        )
+    }
+
+    fn burn_audio(
+        &self,
+        cue_path: &Path,
+        on_progress: Option<ProgressCallback>,
+        cancel_token: Option<Arc<AtomicBool>>,
+        erase_first: bool,
+    ) -> Result<(), BurnError> {
+        check_cue_exists(cue_path)?;
+        log::info!("Starting audio CD burn of {}", cue_path.display());
+
+        // hdiutil burns a cue sheet as an audio disc, always disc-at-once
+        let mut command = self.hdiutil_burn(erase_first);
+        command.arg(cue_path);
+
+        run_burn_process(
+            command,
+            "hdiutil burn",
+            parse_puppetstrings,
+            on_progress,
+            cancel_token,
+        )
+    }
+
+    fn erase(
+        &self,
+        mode: EraseMode,
+        on_progress: Option<ProgressCallback>,
+        cancel_token: Option<Arc<AtomicBool>>,
+    ) -> Result<(), BurnError> {
+        log::info!("Starting {}", mode.label().to_lowercase());
+
+        let mut command = Command::new("drutil");
+        if let Some(drive) = &self.drive {
+            command.args(["-drive", drive]);
+        }
+        command.arg("erase").arg(match mode {
+            EraseMode::Quick => "quick",
+            EraseMode::Full => "full",
+        });
+
+        run_burn_process(
+            command,
+            "drutil erase",
+            parse_drutil_progress,
+            on_progress,
+            cancel_token,
+        )
+    }
+
+    fn media_capacity(&self) -> Result<Option<u64>, String> {
+        Ok(parse_drutil_space_free(&self.drutil("status")?))
+    }
+
+    fn media_type(&self) -> Result<Option<String>, String> {
+        Ok(parse_drutil_media_type(&self.drutil("status")?))
+    }
+
+    fn read_back_source(&self) -> Result<ReadBackSource, String> {
+        // Raw /dev/rdisk nodes need root, so compare through the mounted volume
+        for _ in 0..MOUNT_WAIT_SECS {
+            if let Some(device) = parse_drutil_device(&self.drutil("status")?) {
+                let info = Command::new("diskutil")
+                    .args(["info", &device])
+                    .output()
+                    .map_err(|e| format!("Failed to execute diskutil: {}", e))?;
+                if let Some(mount) =
+                    parse_diskutil_mount_point(&String::from_utf8_lossy(&info.stdout))
+                {
+                    return Ok(ReadBackSource::Mounted(mount));
+                }
+            }
+            std::thread::sleep(Duration::from_secs(1));
+        }
+        Err("Burned disc was not mounted".to_string())
+    }
+
+    fn eject(&self) -> Result<(), String> {
+        let mut drutil = Command::new("drutil");
+        if let Some(drive) = &self.drive {
+            drutil.args(["-drive", drive]);
+        }
+        let output = drutil
+            .arg("eject")
+            .output()
+            .map_err(|e| format!("Failed to execute drutil: {}", e))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Failed to eject disc: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    }
+
+    fn list_drives(&self) -> Result<Vec<DriveInfo>, String> {
+        let output = Command::new("drutil")
+            .arg("list")
+            .output()
+            .map_err(|e| format!("Failed to execute drutil: {}", e))?;
+        let mut drives = parse_drutil_list(&String::from_utf8_lossy(&output.stdout));
+
+        // drutil doesn't report write speeds without media, so those stay empty
+        for drive in &mut drives {
+            let info = MacBurner::default().with_drive(&drive.id).drutil("info")?;
+            drive.media = parse_drutil_info_media(&info);
+        }
+        Ok(drives)
+    }
+}
+
+/// Parse `drutil status` output
+pub(crate) fn parse_drutil_status(stdout: &str) -> CdStatus {
+    let stdout_lower = stdout.to_lowercase();
+
+    // No disc inserted
+    if stdout_lower.contains("no media") {
+        return CdStatus::NoDisc;
+    }
+
+    // Check for blank media first
+    if stdout_lower.contains("blank") {
+        return CdStatus::Blank;
+    }
+
+    // Check if it's erasable (CD-RW, DVD-RW, etc.)
+    // drutil status shows "Erasable: Yes" for rewritable media
+    let is_erasable = stdout_lower.contains("erasable")
+        || stdout_lower.contains("cd-rw")
+        || stdout_lower.contains("dvd-rw")
+        || stdout_lower.contains("dvd+rw");
+
+    if is_erasable {
+        return CdStatus::ErasableWithData;
+    }
+
+    // Non-blank, non-erasable disc
+    CdStatus::NonErasable
+}
+
+/// Media type from `drutil status` (the "Type: CD-R" field)
+pub(crate) fn parse_drutil_media_type(stdout: &str) -> Option<String> {
+    let media = stdout
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Type:"))?
+        .split_whitespace()
+        .next()?;
+    (!media.eq_ignore_ascii_case("no")).then(|| media.to_string())
+}
+
+/// Device node from `drutil status` (the "Name: /dev/disk4" field)
+pub(crate) fn parse_drutil_device(stdout: &str) -> Option<String> {
+    let (_, rest) = stdout.split_once("Name:")?;
+    rest.split_whitespace()
+        .next()
+        .filter(|name| name.starts_with("/dev/"))
+        .map(str::to_string)
+}
+
+/// Writable bytes from `drutil status` (the "Space Free: ... blocks: 359849" field)
+pub(crate) fn parse_drutil_space_free(stdout: &str) -> Option<u64> {
+    let line = stdout
+        .lines()
+        .find(|line| line.trim_start().starts_with("Space Free:"))?;
+    let (_, rest) = line.split_once("blocks:")?;
+    let blocks: u64 = rest.split_whitespace().next()?.parse().ok()?;
+    (blocks > 0).then_some(blocks * SECTOR_SIZE)
+}
+
+/// Mount point from `diskutil info` output, if the volume is mounted
+pub(crate) fn parse_diskutil_mount_point(stdout: &str) -> Option<PathBuf> {
+    stdout.lines().find_map(|line| {
+        let mount = line.trim().strip_prefix("Mount Point:")?.trim();
+        (!mount.is_empty() && mount != "Not applicable (no file system)")
+            .then(|| PathBuf::from(mount))
+    })
+}
+
+/// Drives from `drutil list`
+///
+/// Each drive is a numbered row under a "Vendor Product Rev Bus SupportLevel"
+/// header. The columns are aligned, so the model is everything between the
+/// drive number and the Rev column.
+pub(crate) fn parse_drutil_list(stdout: &str) -> Vec<DriveInfo> {
+    let mut lines = stdout.lines();
+    let Some(rev_column) = lines
+        .by_ref()
+        .find(|line| line.contains("Vendor") && line.contains("Product"))
+        .and_then(|header| header.find("Rev"))
+    else {
+        return Vec::new();
+    };
+
+    lines
+        .filter_map(|line| {
+            let (id, rest) = line.trim_start().split_once(char::is_whitespace)?;
+            if !id.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            let model_end = rev_column.saturating_sub(line.len() - rest.len());
+            let model = rest.get(..model_end).unwrap_or(rest);
+            Some(DriveInfo {
+                id: id.to_string(),
+                model: model.split_whitespace().collect::<Vec<_>>().join(" "),
+                media: Vec::new(),
+                write_speeds: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+/// Writable media from `drutil info` ("CD-Write: -R, -RW, BUFE, Test")
+pub(crate) fn parse_drutil_info_media(stdout: &str) -> Vec<String> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let (key, values) = line.split_once(':')?;
+            let family = key.trim().strip_suffix("-Write")?;
+            Some(
+                values
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|v| v.starts_with('-') || v.starts_with('+'))
+                    .map(|v| format!("{}{}", family, v))
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .flatten()
+        .collect()
+}
+
+/// IOService path of the drive whose description in `hdiutil burn -list`
+/// matches `model`
+///
+/// Each device is an "IOService:..." line followed by an indented
+/// description line naming the drive.
+pub(crate) fn find_hdiutil_device(stdout: &str, model: &str) -> Option<String> {
+    let squash = |s: &str| {
+        s.chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_lowercase()
+    };
+    let model = squash(model);
+    let mut lines = stdout.lines().peekable();
+    while let Some(line) = lines.next() {
+        let service = line.trim();
+        if !service.starts_with("IOService:") {
+            continue;
+        }
+        if let Some(description) = lines.peek()
+            && !model.is_empty()
+            && squash(description).contains(&model)
+        {
+            return Some(service.to_string());
+        }
+    }
+    None
+}
+
+/// Parse progress lines like "PERCENT:0.059725" or "PERCENT:-1.000000"
+fn parse_puppetstrings(line: &str) -> Option<i32> {
+    let percent_str = line.strip_prefix("PERCENT:")?;
+    let percentage_float = percent_str.trim().parse::<f64>().ok()?;
+    Some(percentage_float.round() as i32)
+}
+
+/// Parse the percentage from `drutil erase`'s progress bar
+///
+/// drutil redraws a bar ending in the percentage done, e.g.
+/// "Erasing: [=====     ] 45%".
+fn parse_drutil_progress(line: &str) -> Option<i32> {
+    let percent = line.split_whitespace().last()?.strip_suffix('%')?;
+    let percent = percent.parse::<f64>().ok()?;
+    Some(percent.round().clamp(0.0, 100.0) as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_drutil_no_media() {
+        let output = " Vendor   Product           Rev\n PIONEER  DVD-RW  DVR-XD11  1.00\n\n           Type: No Media Inserted\n";
+        assert_eq!(parse_drutil_status(output), CdStatus::NoDisc);
+    }
+
+    #[test]
+    fn test_parse_drutil_blank() {
+        let output = "           Type: CD-R                 Name: /dev/disk4\n          Sessions: 0                  Tracks: 0\n   Overwritable:   00:00:00         blocks:        0\n     Space Free:   79:59:74         blocks:   359849\n     Writability: appendable, blank, overwritable\n";
+        assert_eq!(parse_drutil_status(output), CdStatus::Blank);
+    }
+
+    #[test]
+    fn test_parse_drutil_space_free() {
+        let blank = "     Space Free:   79:59:74         blocks:   359849 / 736.97MB / 702.86MiB\n";
+        assert_eq!(parse_drutil_space_free(blank), Some(359_849 * 2048));
+        let full = "     Space Free:   00:00:00         blocks:        0 /   0.00MB /   0.00MiB\n";
+        assert_eq!(parse_drutil_space_free(full), None);
+        assert_eq!(parse_drutil_space_free("Type: No Media Inserted\n"), None);
+    }
+
+    #[test]
+    fn test_parse_drutil_media_type() {
+        let output = "           Type: CD-RW                Name: /dev/disk4\n       Writability: erasable\n";
+        assert_eq!(parse_drutil_media_type(output), Some("CD-RW".to_string()));
+        let empty = "           Type: No Media Inserted\n";
+        assert_eq!(parse_drutil_media_type(empty), None);
+    }
+
+    #[test]
+    fn test_parse_drutil_erasable() {
+        let output = "           Type: CD-RW                Name: /dev/disk4\n       Writability: erasable\n";
+        assert_eq!(parse_drutil_status(output), CdStatus::ErasableWithData);
+    }
+
+    #[test]
+    fn test_parse_drutil_non_erasable() {
+        let output = "           Type: CD-ROM               Name: /dev/disk4\n          Sessions: 1                  Tracks: 1\n";
+        assert_eq!(parse_drutil_status(output), CdStatus::NonErasable);
+    }
+
+    #[test]
+    fn test_parse_puppetstrings() {
+        assert_eq!(parse_puppetstrings("PERCENT:0.059725"), Some(0));
+        assert_eq!(parse_puppetstrings("PERCENT:42.6"), Some(43));
+        assert_eq!(parse_puppetstrings("PERCENT:-1.000000"), Some(-1));
+        assert_eq!(parse_puppetstrings("MESSAGE:Burning"), None);
+    }
+
+    #[test]
+    fn test_parse_drutil_progress() {
+        assert_eq!(parse_drutil_progress("Erasing: [=====     ] 45%"), Some(45));
+        assert_eq!(parse_drutil_progress("[=====] 99.6%"), Some(100));
+        assert_eq!(parse_drutil_progress("Erase complete."), None);
+    }
+
+    #[test]
+    fn test_parse_drutil_device() {
+        let output = "           Type: CD-ROM               Name: /dev/disk4\n          Sessions: 1                  Tracks: 1\n";
+        assert_eq!(parse_drutil_device(output), Some("/dev/disk4".to_string()));
+        assert_eq!(parse_drutil_device("           Type: No Media Inserted\n"), None);
+    }
+
+    #[test]
+    fn test_parse_drutil_list() {
+        let output = "   Vendor   Product           Rev   Bus       SupportLevel\n1  HL-DT-ST DVDRW  GX40N      RQ00  USB       Unsupported\n2  PIONEER  DVD-RW  DVR-XD11  1.00  USB       Apple Shipping\n";
+        let drives = parse_drutil_list(output);
+        assert_eq!(drives.len(), 2);
+        assert_eq!(drives[0].id, "1");
+        assert_eq!(drives[0].model, "HL-DT-ST DVDRW GX40N");
+        assert_eq!(drives[1].id, "2");
+        assert_eq!(drives[1].model, "PIONEER DVD-RW DVR-XD11");
+        assert!(parse_drutil_list("No drives found\n").is_empty());
+    }
+
+    #[test]
+    fn test_parse_drutil_info_media() {
+        let output = " Vendor   Product           Rev\n HL-DT-ST DVDRW  GX40N      RQ00\n\n   Interconnect: USB\n       CD-Write: -R, -RW, BUFE, Test, IndexPts, ISRC\n      DVD-Write: -R, -R DL, -RW, +R, +R DL, +RW, BUFE, Test\n";
+        assert_eq!(
+            parse_drutil_info_media(output),
+            vec![
+                "CD-R", "CD-RW", "DVD-R", "DVD-R DL", "DVD-RW", "DVD+R", "DVD+R DL", "DVD+RW"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_hdiutil_device() {
+        let output = "IOService:/AppleACPIPlatformExpert/PCI0@0/XHC1/IOUSBMassStorageDriver/IOSCSIPeripheralDeviceType05\n\tHL-DT-ST DVDRW  GX40N RQ00\n";
+        assert_eq!(
+            find_hdiutil_device(output, "HL-DT-ST DVDRW GX40N").as_deref(),
+            Some(
+                "IOService:/AppleACPIPlatformExpert/PCI0@0/XHC1/IOUSBMassStorageDriver/IOSCSIPeripheralDeviceType05"
+            )
+        );
+        assert_eq!(find_hdiutil_device(output, "PIONEER DVD-RW"), None);
+    }
+
+    #[test]
+    fn test_parse_diskutil_mount_point() {
+        let output = "   Device Identifier:         disk4\n   Volume Name:               MP3CD\n   Mounted:                   Yes\n   Mount Point:               /Volumes/MP3CD\n";
+        assert_eq!(
+            parse_diskutil_mount_point(output),
+            Some(PathBuf::from("/Volumes/MP3CD"))
+        );
+        assert_eq!(
+            parse_diskutil_mount_point("   Mount Point:               \n"),
+            None
+        );
+    }
+}
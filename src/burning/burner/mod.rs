@@ -0,0 +1,343 @@
+//! Burner backends
+//!
+//! A [`Burner`] knows how to check the media in the drive and write an ISO
+//! (or a BIN/CUE audio image) to it. The macOS backend drives
+//! `drutil`/`hdiutil`, the Linux backend drives cdrecord, wodim or xorriso.
+//! [`detect_burner`] picks one at runtime, and [`burner_for`] points it at
+//! the drive and write speed chosen in [`DriveSelection`]. Rewritable discs
+//! can also be erased on their own, ahead of a burn ([`Burner::erase`]).
+//! [`FakeBurner`] is a virtual drive for simulated burns and tests.
+
+mod fake;
+mod linux;
+mod macos;
+
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::JoinHandle;
+
+use serde::{Deserialize, Serialize};
+
+use crate::burning::cd::{CdStatus, ProgressCallback};
+use crate::burning::error::BurnError;
+use crate::burning::verify::ReadBackSource;
+
+pub use fake::FakeBurner;
+pub use linux::LinuxBurner;
+pub use macos::MacBurner;
+
+/// A disc drive found by [`Burner::list_drives`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DriveInfo {
+    /// How the backend addresses the drive ("/dev/sr1" on Linux, the
+    /// `drutil` drive number on macOS)
+    pub id: String,
+    /// Vendor and product name
+    pub model: String,
+    /// Media the drive can write, e.g. "CD-R", "CD-RW", "DVD-R"
+    pub media: Vec<String>,
+    /// CD write speeds the drive reports, slowest first (empty if unknown)
+    pub write_speeds: Vec<u32>,
+}
+
+/// Which drive to burn with, and how fast
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DriveSelection {
+    /// [`DriveInfo::id`] of the chosen drive (None = the system default)
+    pub drive: Option<String>,
+    /// CD write speed factor, e.g. 8 for 8x (None = the drive's fastest)
+    pub speed: Option<u32>,
+}
+
+impl DriveSelection {
+    /// Short description for logs and menus, e.g. "/dev/sr1 at 8x"
+    pub fn describe(&self) -> String {
+        let drive = self.drive.as_deref().unwrap_or("default drive");
+        match self.speed {
+            Some(speed) => format!("{} at {}x", drive, speed),
+            None => format!("{} at maximum speed", drive),
+        }
+    }
+}
+
+/// How thoroughly [`Burner::erase`] blanks a rewritable disc
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EraseMode {
+    /// Clear the table of contents only; takes a minute or two
+    Quick,
+    /// Overwrite the whole disc; takes as long as a full burn
+    Full,
+}
+
+impl EraseMode {
+    /// Name for menus and dialogs
+    pub fn label(&self) -> &'static str {
+        match self {
+            EraseMode::Quick => "Quick Erase",
+            EraseMode::Full => "Full Erase",
+        }
+    }
+}
+
+/// A platform backend for media detection and burning
+pub trait Burner: Send + Sync {
+    /// Short backend name for logging
+    fn name(&self) -> &str;
+
+    /// Check the media in the drive
+    fn check_status(&self) -> Result<CdStatus, String>;
+
+    /// Burn an ISO file to disc
+    ///
+    /// # Arguments
+    /// * `iso_path` - Path to the ISO file to burn
+    /// * `on_progress` - Optional callback for progress updates (0-100, -1 = indeterminate)
+    /// * `cancel_token` - Optional cancellation token to abort the burn
+    /// * `erase_first` - If true, erase the disc before burning (for CD-RW)
+    ///
+    /// # Returns
+    /// * `Ok(())` on successful burn
+    /// * `Err(BurnError)` on failure ([`BurnError::Cancelled`] if aborted)
+    fn burn(
+        &self,
+        iso_path: &Path,
+        on_progress: Option<ProgressCallback>,
+        cancel_token: Option<Arc<AtomicBool>>,
+        erase_first: bool,
+    ) -> Result<(), BurnError>;
+
+    /// Burn an audio CD from a BIN/CUE pair, disc-at-once
+    ///
+    /// Takes the same arguments as [`Burner::burn`], with the cue sheet in
+    /// place of the ISO. Backends that can't write audio return an error.
+    fn burn_audio(
+        &self,
+        cue_path: &Path,
+        _on_progress: Option<ProgressCallback>,
+        _cancel_token: Option<Arc<AtomicBool>>,
+        _erase_first: bool,
+    ) -> Result<(), BurnError> {
+        check_cue_exists(cue_path)?;
+        Err(BurnError::Other(format!(
+            "{} cannot burn audio CDs",
+            self.name()
+        )))
+    }
+
+    /// Erase the rewritable disc in the drive, leaving it blank
+    ///
+    /// Reports progress and honours cancellation like [`Burner::burn`].
+    /// Backends that can't erase return an error.
+    fn erase(
+        &self,
+        _mode: EraseMode,
+        _on_progress: Option<ProgressCallback>,
+        _cancel_token: Option<Arc<AtomicBool>>,
+    ) -> Result<(), BurnError> {
+        Err(BurnError::Other(format!(
+            "{} cannot erase discs",
+            self.name()
+        )))
+    }
+
+    /// Writable capacity of the blank disc in the drive, in bytes
+    ///
+    /// `None` when there's no blank disc or the backend can't tell.
+    fn media_capacity(&self) -> Result<Option<u64>, String> {
+        Ok(None)
+    }
+
+    /// Kind of disc in the drive as the drive reports it, e.g. "CD-R"
+    ///
+    /// `None` when there's no disc or the backend can't tell.
+    fn media_type(&self) -> Result<Option<String>, String> {
+        Ok(None)
+    }
+
+    /// Where the disc just burned can be read back from for verification
+    ///
+    /// Burns leave the disc in the drive so this can be called afterwards.
+    fn read_back_source(&self) -> Result<ReadBackSource, String> {
+        Err(format!("{} cannot read discs back", self.name()))
+    }
+
+    /// Eject the disc
+    fn eject(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Every drive this backend can burn with
+    fn list_drives(&self) -> Result<Vec<DriveInfo>, String> {
+        Ok(Vec::new())
+    }
+}
+
+/// Pick the burner backend for the platform we're running on, using the
+/// default drive at full speed
+pub fn detect_burner() -> Box<dyn Burner> {
+    burner_for(&DriveSelection::default())
+}
+
+/// Pick the platform backend and point it at `selection`
+pub fn burner_for(selection: &DriveSelection) -> Box<dyn Burner> {
+    let burner: Box<dyn Burner> = if cfg!(target_os = "macos") {
+        let mut burner = MacBurner::default().with_speed(selection.speed);
+        if let Some(drive) = &selection.drive {
+            burner = burner.with_drive(drive);
+        }
+        Box::new(burner)
+    } else {
+        let mut burner = LinuxBurner::detect().with_speed(selection.speed);
+        if let Some(drive) = &selection.drive {
+            burner = burner.with_device(drive);
+        }
+        Box::new(burner)
+    };
+    log::debug!(
+        "Using {} burner backend ({})",
+        burner.name(),
+        selection.describe()
+    );
+    burner
+}
+
+/// Fail early if the ISO to burn doesn't exist
+pub(crate) fn check_iso_exists(iso_path: &Path) -> Result<(), String> {
+    if iso_path.exists() {
+        Ok(())
+    } else {
+        Err(format!("ISO file not found: {}", iso_path.display()))
+    }
+}
+
+/// Fail early if the cue sheet to burn doesn't exist
+pub(crate) fn check_cue_exists(cue_path: &Path) -> Result<(), String> {
+    if cue_path.exists() {
+        Ok(())
+    } else {
+        Err(format!("Cue sheet not found: {}", cue_path.display()))
+    }
+}
+
+/// Run a burn command, forwarding parsed progress and honouring cancellation
+///
+/// Both stdout and stderr are read, and lines are split on `\r` as well as
+/// `\n` since burn tools redraw their progress line in place. A failure is
+/// sorted by cause from what the tool printed and its exit code.
+pub(crate) fn run_burn_process(
+    mut command: Command,
+    tool: &str,
+    parse_progress: fn(&str) -> Option<i32>,
+    on_progress: Option<ProgressCallback>,
+    cancel_token: Option<Arc<AtomicBool>>,
+) -> Result<(), BurnError> {
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| BurnError::from_spawn(tool, &e))?;
+
+    let (tx, rx) = mpsc::channel();
+    let mut readers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        readers.push(forward_lines(stdout, tx.clone()));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        readers.push(forward_lines(stderr, tx.clone()));
+    }
+    drop(tx);
+
+    // Non-progress lines, used to explain failures
+    let mut messages = Vec::new();
+
+    // Poll for cancellation while waiting for the process
+    loop {
+        drain_lines(&rx, parse_progress, on_progress.as_ref(), &mut messages);
+
+        if let Some(ref token) = cancel_token
+            && token.load(Ordering::SeqCst)
+        {
+            log::info!("Burn cancelled - killing {} process", tool);
+            let _ = child.kill();
+            let _ = child.wait(); // Reap the process
+            for reader in readers {
+                let _ = reader.join();
+            }
+            return Err(BurnError::Cancelled);
+        }
+
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                for reader in readers {
+                    let _ = reader.join();
+                }
+                drain_lines(&rx, parse_progress, on_progress.as_ref(), &mut messages);
+
+                if status.success() {
+                    log::info!("Burn completed successfully");
+                    return Ok(());
+                }
+                return Err(BurnError::from_exit(tool, status.code(), &messages));
+            }
+            Ok(None) => {
+                // Process still running, sleep briefly and check again
+                std::thread::sleep(std::time::Duration::from_millis(100));
+            }
+            Err(e) => {
+                for reader in readers {
+                    let _ = reader.join();
+                }
+                return Err(BurnError::from_io("Error checking burn process", &e));
+            }
+        }
+    }
+}
+
+/// Read a stream on a background thread, sending each line to `tx`
+fn forward_lines<R: Read + Send + 'static>(stream: R, tx: Sender<String>) -> JoinHandle<()> {
+    std::thread::spawn(move || {
+        let mut line = Vec::new();
+        for byte in BufReader::new(stream).bytes() {
+            let Ok(byte) = byte else { break };
+            if byte == b'\n' || byte == b'\r' {
+                if !line.is_empty() {
+                    let text = String::from_utf8_lossy(&line).trim().to_string();
+                    line.clear();
+                    if !text.is_empty() && tx.send(text).is_err() {
+                        return;
+                    }
+                }
+            } else {
+                line.push(byte);
+            }
+        }
+        if !line.is_empty() {
+            let _ = tx.send(String::from_utf8_lossy(&line).trim().to_string());
+        }
+    })
+}
+
+/// Handle any lines received so far
+fn drain_lines(
+    rx: &Receiver<String>,
+    parse_progress: fn(&str) -> Option<i32>,
+    on_progress: Option<&ProgressCallback>,
+    messages: &mut Vec<String>,
+) {
+    while let Ok(line) = rx.try_recv() {
+        log::debug!("burn: {}", line);
+        match parse_progress(&line) {
+            Some(percentage) => {
+                if let Some(callback) = on_progress {
+                    callback(percentage);
+                }
+            }
+            None if !line.is_empty() => messages.push(line),
+            None => {}
+        }
+    }
+}
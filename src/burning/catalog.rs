@@ -0,0 +1,425 @@
+//! Catalog of burned discs
+//!
+//! Every disc that burns successfully gets a record: when it was burned, its
+//! volume label, the profile it came from, its folders, the path and tags of
+//! every track on it, the bitrate and the ISO's SHA-256. Records are kept in
+//! `disc_catalog.json` in the app data directory, so a track can be traced
+//! back to the disc that holds it.
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::folder_details::{FolderDetails, folder_details};
+use super::iso_names::{NameChange, renamed_path};
+use crate::audio::metadata::{AlbumMetadata, TrackMetadata};
+use crate::audio::{get_album_metadata, get_track_metadata};
+use crate::core::{MusicFolder, get_app_data_dir};
+
+/// One track on a burned disc
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CatalogTrack {
+    /// Path on the disc, relative to its root
+    pub disc_path: String,
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+}
+
+impl CatalogTrack {
+    /// Whether every word of `query` appears in the track's title, artist,
+    /// album or path (ignoring case)
+    fn matches(&self, words: &[String]) -> bool {
+        let text = format!(
+            "{}\n{}\n{}\n{}",
+            self.title, self.artist, self.album, self.disc_path
+        )
+        .to_lowercase();
+        words.iter().all(|word| text.contains(word.as_str()))
+    }
+}
+
+/// A burned disc
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiscRecord {
+    /// When the burn finished (RFC 3339, UTC)
+    pub burned_at: String,
+    pub volume_label: String,
+    /// Burn profile the folders were loaded from, if they were
+    pub profile_path: Option<PathBuf>,
+    /// Album and mixtape names, in disc order
+    pub folders: Vec<String>,
+    /// Every track on the disc, in disc order
+    pub tracks: Vec<CatalogTrack>,
+    /// Bitrate lossless sources were encoded at (kbps)
+    pub bitrate: Option<u32>,
+    /// SHA-256 of the burned ISO, in hex
+    pub iso_sha256: String,
+}
+
+impl DiscRecord {
+    /// Start a record for a burn
+    ///
+    /// The folders and tracks are added once the disc's layout is known
+    /// ([`Self::with_tracks`]), the time and checksum once the burn has
+    /// finished ([`Self::finish`]).
+    pub fn new(volume_label: &str, profile_path: Option<PathBuf>, bitrate: Option<u32>) -> Self {
+        Self {
+            burned_at: String::new(),
+            volume_label: volume_label.to_string(),
+            profile_path,
+            folders: Vec::new(),
+            tracks: Vec::new(),
+            bitrate,
+            iso_sha256: String::new(),
+        }
+    }
+
+    /// Use a different volume label (each disc of a multi-disc burn has its own)
+    pub fn with_volume_label(mut self, volume_label: &str) -> Self {
+        self.volume_label = volume_label.to_string();
+        self
+    }
+
+    /// Fill in the folders and tracks on the disc
+    ///
+    /// `staged` pairs each track's staged path with its source file (see
+    /// `OutputManager::staged_track_paths`), and `changes` are the renames
+    /// made for the disc's naming level. Titles and artists come from the
+    /// source files' tags.
+    pub fn with_tracks(
+        self,
+        folders: &[MusicFolder],
+        staged: &[(PathBuf, PathBuf)],
+        changes: &[NameChange],
+    ) -> Self {
+        self.with_tracks_from(
+            folders,
+            staged,
+            changes,
+            &get_album_metadata,
+            &get_track_metadata,
+        )
+    }
+
+    fn with_tracks_from(
+        mut self,
+        folders: &[MusicFolder],
+        staged: &[(PathBuf, PathBuf)],
+        changes: &[NameChange],
+        read_album: &dyn Fn(&Path) -> AlbumMetadata,
+        read_track: &dyn Fn(&Path) -> TrackMetadata,
+    ) -> Self {
+        self.folders = folders.iter().map(|f| f.display_name()).collect();
+        let details: Vec<FolderDetails> = folders
+            .iter()
+            .map(|folder| folder_details(folder, read_album, read_track))
+            .collect();
+
+        self.tracks = staged
+            .iter()
+            .filter_map(|(staged_path, source)| {
+                details.iter().find_map(|folder| {
+                    let track = folder.tracks.iter().find(|t| &t.file.path == source)?;
+                    Some(CatalogTrack {
+                        disc_path: renamed_path(staged_path, changes)
+                            .to_string_lossy()
+                            .to_string(),
+                        title: track.title.clone(),
+                        artist: track
+                            .artist
+                            .clone()
+                            .or_else(|| folder.artist.clone())
+                            .unwrap_or_default(),
+                        album: folder.title.clone(),
+                    })
+                })
+            })
+            .collect();
+        self
+    }
+
+    /// Stamp the record with the current time and `iso_path`'s checksum
+    pub fn finish(&mut self, iso_path: &Path) -> Result<(), String> {
+        self.iso_sha256 = sha256_file(iso_path)?;
+        self.burned_at = Utc::now().to_rfc3339();
+        Ok(())
+    }
+
+    /// Burn time in local time, e.g. "2025-03-14 18:02"
+    pub fn burned_at_display(&self) -> String {
+        DateTime::parse_from_rfc3339(&self.burned_at)
+            .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|_| self.burned_at.clone())
+    }
+}
+
+/// SHA-256 of a file, in hex
+fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file =
+        fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// A track found by [`DiscCatalog::search`], with the disc it's on
+#[derive(Debug, Clone, Copy)]
+pub struct CatalogMatch<'a> {
+    pub disc: &'a DiscRecord,
+    pub track: &'a CatalogTrack,
+}
+
+/// Every disc burned so far, oldest first
+///
+/// Persisted to disc_catalog.json in the app data directory
+/// ([`get_app_data_dir`])
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiscCatalog {
+    pub discs: Vec<DiscRecord>,
+}
+
+impl DiscCatalog {
+    const CATALOG_FILE: &'static str = "disc_catalog.json";
+
+    fn catalog_path() -> Result<PathBuf, String> {
+        Ok(get_app_data_dir()?.join(Self::CATALOG_FILE))
+    }
+
+    /// Load the catalog from disk, or an empty one if there isn't one yet
+    pub fn load() -> Self {
+        match Self::catalog_path().and_then(|path| Self::load_from(&path)) {
+            Ok(catalog) => catalog,
+            Err(e) => {
+                log::warn!("Using an empty disc catalog: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Load a catalog file (empty if it doesn't exist)
+    pub fn load_from(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents =
+            fs::read_to_string(path).map_err(|e| format!("Failed to read disc catalog: {}", e))?;
+        serde_json::from_str(&contents).map_err(|e| format!("Failed to parse disc catalog: {}", e))
+    }
+
+    /// Save a catalog file
+    pub fn save_to(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize disc catalog: {}", e))?;
+        fs::write(path, json).map_err(|e| format!("Failed to write disc catalog: {}", e))
+    }
+
+    /// Add finished records to the catalog on disk
+    ///
+    /// A catalog that can't be parsed is left alone rather than replaced.
+    pub fn record(records: &[DiscRecord]) -> Result<(), String> {
+        let path = Self::catalog_path()?;
+        let mut catalog = Self::load_from(&path)?;
+        catalog.discs.extend_from_slice(records);
+        catalog.save_to(&path)?;
+        log::info!(
+            "Added {} disc(s) to the catalog at {}",
+            records.len(),
+            path.display()
+        );
+        Ok(())
+    }
+
+    /// Tracks matching every word of `query` (ignoring case), newest disc first
+    pub fn search(&self, query: &str) -> Vec<CatalogMatch<'_>> {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        if words.is_empty() {
+            return Vec::new();
+        }
+        self.discs
+            .iter()
+            .rev()
+            .flat_map(|disc| {
+                disc.tracks
+                    .iter()
+                    .filter(|track| track.matches(&words))
+                    .map(move |track| CatalogMatch { disc, track })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::AudioFileInfo;
+    use tempfile::TempDir;
+
+    fn album(name: &str, artist: Option<&str>, tracks: &[&str]) -> MusicFolder {
+        let mut folder = MusicFolder::new_for_test_with_id(name);
+        folder.album_name = Some(name.to_string());
+        folder.artist_name = artist.map(str::to_string);
+        folder.audio_files = tracks
+            .iter()
+            .map(|t| AudioFileInfo {
+                path: PathBuf::from(format!("/music/{}/{}.flac", name, t)),
+                duration: 200.0,
+                bitrate: 1411,
+                size: 30_000_000,
+                codec: "flac".to_string(),
+                is_lossy: false,
+            })
+            .collect();
+        folder
+    }
+
+    fn no_album(_: &Path) -> AlbumMetadata {
+        AlbumMetadata::default()
+    }
+
+    /// Tags only for tracks whose file name starts with "Tagged"
+    fn tags(path: &Path) -> TrackMetadata {
+        let stem = path.file_stem().unwrap().to_string_lossy();
+        match stem.strip_prefix("Tagged ") {
+            Some(title) => TrackMetadata {
+                title: Some(title.to_string()),
+                artist: Some("Guest".to_string()),
+                composer: None,
+            },
+            None => TrackMetadata::default(),
+        }
+    }
+
+    fn staged(folder: &MusicFolder, dir: &str) -> Vec<(PathBuf, PathBuf)> {
+        folder
+            .audio_files
+            .iter()
+            .map(|t| {
+                let name = format!("{}.mp3", t.path.file_stem().unwrap().to_string_lossy());
+                (Path::new(dir).join(name), t.path.clone())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_with_tracks_uses_tags_and_name_changes() {
+        let folder = album(
+            "Blue Train",
+            Some("John Coltrane"),
+            &["Moment's Notice", "Tagged Locomotion"],
+        );
+        let changes = vec![
+            NameChange {
+                original: PathBuf::from("01-Blue Train"),
+                renamed: PathBuf::from("01_BLUE_TRAIN"),
+            },
+            NameChange {
+                original: PathBuf::from("01-Blue Train/Moment's Notice.mp3"),
+                renamed: PathBuf::from("01_BLUE_TRAIN/MOMENT_S.MP3"),
+            },
+        ];
+
+        let record = DiscRecord::new("JAZZ", Some(PathBuf::from("/p/jazz.mp3cd")), Some(256))
+            .with_tracks_from(
+                std::slice::from_ref(&folder),
+                &staged(&folder, "01-Blue Train"),
+                &changes,
+                &no_album,
+                &tags,
+            );
+
+        assert_eq!(record.folders, vec!["Blue Train"]);
+        assert_eq!(
+            record.tracks,
+            vec![
+                CatalogTrack {
+                    disc_path: "01_BLUE_TRAIN/MOMENT_S.MP3".to_string(),
+                    title: "Moment's Notice".to_string(),
+                    artist: "John Coltrane".to_string(),
+                    album: "Blue Train".to_string(),
+                },
+                CatalogTrack {
+                    disc_path: "01_BLUE_TRAIN/Tagged Locomotion.mp3".to_string(),
+                    title: "Locomotion".to_string(),
+                    artist: "Guest".to_string(),
+                    album: "Blue Train".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_finish_records_checksum() {
+        let dir = TempDir::new().unwrap();
+        let iso = dir.path().join("disc.iso");
+        fs::write(&iso, b"abc").unwrap();
+
+        let mut record = DiscRecord::new("TEST", None, None);
+        record.finish(&iso).unwrap();
+        assert_eq!(
+            record.iso_sha256,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert!(DateTime::parse_from_rfc3339(&record.burned_at).is_ok());
+        assert!(record.finish(&dir.path().join("missing.iso")).is_err());
+    }
+
+    #[test]
+    fn test_search_and_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("catalog.json");
+        assert!(DiscCatalog::load_from(&path).unwrap().discs.is_empty());
+
+        let first = album(
+            "Kind of Blue",
+            Some("Miles Davis"),
+            &["So What", "Blue in Green"],
+        );
+        let second = album("Blue Train", Some("John Coltrane"), &["Moment's Notice"]);
+        let catalog = DiscCatalog {
+            discs: vec![
+                DiscRecord::new("MILES", None, None).with_tracks_from(
+                    std::slice::from_ref(&first),
+                    &staged(&first, "01-Kind of Blue"),
+                    &[],
+                    &no_album,
+                    &tags,
+                ),
+                DiscRecord::new("TRANE", None, None).with_tracks_from(
+                    std::slice::from_ref(&second),
+                    &staged(&second, "01-Blue Train"),
+                    &[],
+                    &no_album,
+                    &tags,
+                ),
+            ],
+        };
+        catalog.save_to(&path).unwrap();
+        let catalog = DiscCatalog::load_from(&path).unwrap();
+
+        // Newest disc first
+        let hits = catalog.search("blue");
+        let labels: Vec<_> = hits.iter().map(|h| h.disc.volume_label.as_str()).collect();
+        assert_eq!(labels, vec!["TRANE", "MILES", "MILES"]);
+
+        let hits = catalog.search("MILES green");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].track.title, "Blue in Green");
+
+        assert!(catalog.search("coltrane what").is_empty());
+        assert!(catalog.search("  ").is_empty());
+    }
+}
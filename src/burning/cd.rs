@@ -1,12 +1,18 @@
-//! CD burning using hdiutil/drutil
-//! (Future: Stage 10)
+//! CD drive status and burning
+//!
+//! These functions go through the [`Burner`](crate::burning::burner::Burner)
+//! picked by [`detect_burner`], so
+//! callers don't need to know which platform tools do the work.
 #![allow(dead_code)]
 
-use std::io::{BufRead, BufReader};
 use std::path::Path;
-use std::process::{Command, Stdio};
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::AtomicBool;
+
+use crate::burning::burner::{
+    DriveInfo, DriveSelection, burner_for, check_iso_exists, detect_burner,
+};
+use crate::burning::error::BurnError;
 
 /// Progress callback type for burn operations
 pub type ProgressCallback = Box<dyn Fn(i32) + Send>;
@@ -26,37 +32,7 @@ pub enum CdStatus {
 
 /// Check the status of the CD drive
 pub fn check_cd_status() -> Result<CdStatus, String> {
-    let output = Command::new("drutil")
-        .args(["status"])
-        .output()
-        .map_err(|e| format!("Failed to execute drutil: {}", e))?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stdout_lower = stdout.to_lowercase();
-
-    // No disc inserted
-    if stdout_lower.contains("no media") {
-        return Ok(CdStatus::NoDisc);
-    }
-
-    // Check for blank media first
-    if stdout_lower.contains("blank") {
-        return Ok(CdStatus::Blank);
-    }
-
-    // Check if it's erasable (CD-RW, DVD-RW, etc.)
-    // drutil status shows "Erasable: Yes" for rewritable media
-    let is_erasable = stdout_lower.contains("erasable")
-        || stdout_lower.contains("cd-rw")
-        || stdout_lower.contains("dvd-rw")
-        || stdout_lower.contains("dvd+rw");
-
-    if is_erasable {
-        return Ok(CdStatus::ErasableWithData);
-    }
-
-    // Non-blank, non-erasable disc
-    Ok(CdStatus::NonErasable)
+    detect_burner().check_status()
 }
 
 /// Check if a blank CD is inserted (legacy function for compatibility)
@@ -67,6 +43,18 @@ pub fn check_cd_inserted() -> Result<bool, String> {
     }
 }
 
+/// Capacity of the blank disc in the selected drive, in bytes
+///
+/// `Ok(None)` when there is no blank disc or the drive doesn't report it.
+pub fn detect_media_capacity(selection: &DriveSelection) -> Result<Option<u64>, String> {
+    burner_for(selection).media_capacity()
+}
+
+/// Every drive the platform backend can burn with
+pub fn list_drives() -> Result<Vec<DriveInfo>, String> {
+    detect_burner().list_drives()
+}
+
 /// Result of a burn operation
 #[derive(Debug)]
 pub enum BurnResult {
@@ -75,7 +63,7 @@ pub enum BurnResult {
     Error(String),
 }
 
-/// Burn an ISO file to CD using hdiutil
+/// Burn an ISO file to CD
 ///
 /// # Arguments
 /// * `iso_path` - Path to the ISO file to burn
@@ -83,12 +71,12 @@ pub enum BurnResult {
 ///
 /// # Returns
 /// * `Ok(())` on successful burn
-/// * `Err(String)` with error message on failure
-pub fn burn_iso(iso_path: &Path, on_progress: Option<ProgressCallback>) -> Result<(), String> {
+/// * `Err(BurnError)` on failure
+pub fn burn_iso(iso_path: &Path, on_progress: Option<ProgressCallback>) -> Result<(), BurnError> {
     burn_iso_with_cancel(iso_path, on_progress, None, false)
 }
 
-/// Burn an ISO file to CD using hdiutil with cancellation and erase support
+/// Burn an ISO file to CD with cancellation and erase support
 ///
 /// # Arguments
 /// * `iso_path` - Path to the ISO file to burn
@@ -97,106 +85,16 @@ pub fn burn_iso(iso_path: &Path, on_progress: Option<ProgressCallback>) -> Resul
 /// * `erase_first` - If true, erase the disc before burning (for CD-RW)
 ///
 /// # Returns
-/// * `Ok(BurnResult::Success)` on successful burn
-/// * `Ok(BurnResult::Cancelled)` if cancelled
-/// * `Err(String)` with error message on failure
+/// * `Ok(())` on successful burn
+/// * `Err(BurnError)` on failure ([`BurnError::Cancelled`] if aborted)
 pub fn burn_iso_with_cancel(
     iso_path: &Path,
     on_progress: Option<ProgressCallback>,
     cancel_token: Option<Arc<AtomicBool>>,
     erase_first: bool,
-) -> Result<(), String> {
-    if !iso_path.exists() {
-        return Err(format!("ISO file not found: {}", iso_path.display()));
-    }
-
-    if erase_first {
-        log::info!("Starting burn of {} (with erase)", iso_path.display());
-    } else {
-        log::info!("Starting burn of {}", iso_path.display());
-    }
-
-    // Build args - add -erase if erasing CD-RW first
-    let mut args = vec!["burn", "-noverifyburn", "-puppetstrings"];
-    if erase_first {
-        args.push("-erase");
-    }
-    let iso_path_str = iso_path.to_str().unwrap();
-    args.push(iso_path_str);
-
-    // Spawn hdiutil with -puppetstrings to get progress output
-    let mut child = Command::new("hdiutil")
-        .args(&args)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .map_err(|e| format!("Failed to execute hdiutil burn: {}", e))?;
-
-    // Read stdout for progress updates in a separate thread so we can check cancellation
-    let stdout = child.stdout.take();
-    let cancel_token_clone = cancel_token.clone();
-
-    let progress_thread = std::thread::spawn(move || {
-        if let Some(stdout) = stdout {
-            let reader = BufReader::new(stdout);
-
-            for line in reader.lines() {
-                // Check for cancellation
-                if let Some(ref token) = cancel_token_clone
-                    && token.load(Ordering::SeqCst) {
-                        break;
-                    }
-
-                if let Ok(line) = line {
-                    // Parse progress lines like "PERCENT:0.059725" or "PERCENT:-1.000000"
-                    if line.starts_with("PERCENT:")
-                        && let Some(percent_str) = line.strip_prefix("PERCENT:")
-                            && let Ok(percentage_float) = percent_str.trim().parse::<f64>() {
-                                let percentage = percentage_float.round() as i32;
-                                if let Some(ref callback) = on_progress {
-                                    callback(percentage);
-                                }
-                            }
-                }
-            }
-        }
-    });
-
-    // Poll for cancellation while waiting for the process
-    loop {
-        // Check if cancelled
-        if let Some(ref token) = cancel_token
-            && token.load(Ordering::SeqCst) {
-                log::info!("Burn cancelled - killing hdiutil process");
-                let _ = child.kill();
-                let _ = child.wait(); // Reap the process
-                let _ = progress_thread.join();
-                return Err("Burn cancelled by user".to_string());
-            }
-
-        // Check if process has exited
-        match child.try_wait() {
-            Ok(Some(status)) => {
-                // Process has exited
-                let _ = progress_thread.join();
-
-                if status.success() {
-                    log::info!("Burn completed successfully");
-                    return Ok(());
-                } else {
-                    return Err("Burn process failed".to_string());
-                }
-            }
-            Ok(None) => {
-                // Process still running, sleep briefly and check again
-                std::thread::sleep(std::time::Duration::from_millis(100));
-            }
-            Err(e) => {
-                let _ = progress_thread.join();
-                return Err(format!("Error checking burn process: {}", e));
-            }
-        }
-    }
+) -> Result<(), BurnError> {
+    check_iso_exists(iso_path)?;
+    detect_burner().burn(iso_path, on_progress, cancel_token, erase_first)
 }
 
 #[cfg(test)]
@@ -268,14 +166,14 @@ mod tests {
     fn test_burn_iso_file_not_found() {
         let result = burn_iso(Path::new("/nonexistent/file.iso"), None);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("not found"));
+        assert!(result.unwrap_err().to_string().contains("not found"));
     }
 
     #[test]
     fn test_burn_iso_with_cancel_file_not_found() {
         let result = burn_iso_with_cancel(Path::new("/nonexistent/file.iso"), None, None, false);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("not found"));
+        assert!(result.unwrap_err().to_string().contains("not found"));
     }
 
     #[test]
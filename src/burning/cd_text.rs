@@ -0,0 +1,492 @@
+//! CD-TEXT for audio CDs
+//!
+//! Album, artist and track titles for players that display them. The same
+//! text goes into the cue sheet (PERFORMER/TITLE/SONGWRITER lines) and into
+//! a binary pack file (.cdt) for burners that take CD-TEXT that way.
+
+use std::path::Path;
+
+use super::folder_details::folder_details;
+use crate::audio::metadata::{AlbumMetadata, TrackMetadata};
+use crate::audio::{get_album_metadata, get_track_metadata};
+use crate::core::MusicFolder;
+
+/// Longest string kept (the cue sheet limit)
+const MAX_TEXT_CHARS: usize = 80;
+
+/// Pack types
+const PACK_TITLE: u8 = 0x80;
+const PACK_PERFORMER: u8 = 0x81;
+const PACK_SONGWRITER: u8 = 0x82;
+const PACK_SIZE_INFO: u8 = 0x8F;
+
+/// Bytes of text carried by one pack
+const PACK_TEXT_BYTES: usize = 12;
+/// Bytes in one pack (header, text, CRC)
+const PACK_BYTES: usize = 18;
+/// Packs one block can hold (the sequence number is a single byte)
+const MAX_PACKS: usize = 256;
+/// Packs taken by the size information at the end of the block
+const SIZE_INFO_PACKS: usize = 3;
+
+const CHARSET_ISO_8859_1: u8 = 0x00;
+const LANGUAGE_ENGLISH: u8 = 0x09;
+
+/// Picks one field out of an entry
+type FieldFn = fn(&CdTextEntry) -> &str;
+
+/// Text for the disc or for one track; empty strings are left out
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CdTextEntry {
+    pub title: String,
+    pub performer: String,
+    pub songwriter: String,
+}
+
+impl CdTextEntry {
+    /// Build an entry, cleaning each field for CD-TEXT
+    ///
+    /// Text is limited to ISO 8859-1: other characters become '?', double
+    /// quotes (which would end a cue sheet string) become single quotes, and
+    /// long strings are cut to 80 characters.
+    pub fn new(title: &str, performer: &str, songwriter: &str) -> Self {
+        Self {
+            title: clean_text(title),
+            performer: clean_text(performer),
+            songwriter: clean_text(songwriter),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.title.is_empty() && self.performer.is_empty() && self.songwriter.is_empty()
+    }
+
+    /// Cue sheet lines for this entry, each prefixed with `indent`
+    pub fn cue_lines(&self, indent: &str) -> String {
+        let mut lines = String::new();
+        for (command, text) in [
+            ("TITLE", &self.title),
+            ("PERFORMER", &self.performer),
+            ("SONGWRITER", &self.songwriter),
+        ] {
+            if !text.is_empty() {
+                lines.push_str(&format!("{}{} \"{}\"\n", indent, command, text));
+            }
+        }
+        lines
+    }
+}
+
+/// CD-TEXT for a whole disc
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CdText {
+    /// Album-level text
+    pub disc: CdTextEntry,
+    /// One entry per CD track, in track order
+    pub tracks: Vec<CdTextEntry>,
+}
+
+impl CdText {
+    /// Whether there is any text at all
+    pub fn is_empty(&self) -> bool {
+        self.disc.is_empty() && self.tracks.iter().all(|t| t.is_empty())
+    }
+
+    /// Encode as CD-TEXT packs (block 0, ISO 8859-1, English)
+    ///
+    /// # Returns
+    /// * `Err(String)` if the text needs more packs than one block holds
+    pub fn to_packs(&self) -> Result<Vec<[u8; PACK_BYTES]>, String> {
+        let fields: [(u8, FieldFn); 3] = [
+            (PACK_TITLE, |e| e.title.as_str()),
+            (PACK_PERFORMER, |e| e.performer.as_str()),
+            (PACK_SONGWRITER, |e| e.songwriter.as_str()),
+        ];
+
+        let mut packs = Vec::new();
+        let mut counts = [0u8; 16];
+        for (pack_type, field) in fields {
+            let entries: Vec<&str> = std::iter::once(&self.disc)
+                .chain(&self.tracks)
+                .map(field)
+                .collect();
+            if entries.iter().all(|text| text.is_empty()) {
+                continue;
+            }
+            let before = packs.len();
+            push_text_packs(&mut packs, pack_type, &entries);
+            counts[(pack_type & 0x0F) as usize] = (packs.len() - before).min(255) as u8;
+        }
+
+        let total = packs.len() + SIZE_INFO_PACKS;
+        if total > MAX_PACKS {
+            return Err(format!(
+                "CD-TEXT needs {} packs, more than the {} one block holds",
+                total, MAX_PACKS
+            ));
+        }
+        counts[(PACK_SIZE_INFO & 0x0F) as usize] = SIZE_INFO_PACKS as u8;
+
+        // Size information: charset, track range, copyright, pack counts,
+        // last sequence number and language of each block
+        let mut info = Vec::with_capacity(SIZE_INFO_PACKS * PACK_TEXT_BYTES);
+        info.extend([CHARSET_ISO_8859_1, 1, self.tracks.len() as u8, 0]);
+        info.extend(counts);
+        info.extend([(total - 1) as u8, 0, 0, 0, 0, 0, 0, 0]);
+        info.extend([LANGUAGE_ENGLISH, 0, 0, 0, 0, 0, 0, 0]);
+        for (i, chunk) in info.chunks(PACK_TEXT_BYTES).enumerate() {
+            let seq = packs.len() as u8;
+            packs.push(make_pack(PACK_SIZE_INFO, i as u8, seq, 0, chunk));
+        }
+
+        Ok(packs)
+    }
+
+    /// The binary CD-TEXT file: a 4-byte length header followed by the packs
+    pub fn to_cdt(&self) -> Result<Vec<u8>, String> {
+        let packs = self.to_packs()?;
+        let length = (packs.len() * PACK_BYTES + 2) as u16;
+        let mut data = Vec::with_capacity(4 + packs.len() * PACK_BYTES);
+        data.extend(length.to_be_bytes());
+        data.extend([0, 0]);
+        for pack in &packs {
+            data.extend(pack);
+        }
+        Ok(data)
+    }
+}
+
+/// Build CD-TEXT for the active tracks of `folders`, reading tags from disk
+///
+/// The disc title is `disc_title` if given, else the album name when there
+/// is only one folder. Album names and artists edited in the track editor
+/// take precedence over the tags.
+pub fn cd_text_for_folders(folders: &[MusicFolder], disc_title: Option<&str>) -> CdText {
+    build_cd_text(
+        folders,
+        disc_title,
+        &get_album_metadata,
+        &get_track_metadata,
+    )
+}
+
+fn build_cd_text(
+    folders: &[MusicFolder],
+    disc_title: Option<&str>,
+    read_album: &dyn Fn(&Path) -> AlbumMetadata,
+    read_track: &dyn Fn(&Path) -> TrackMetadata,
+) -> CdText {
+    let mut tracks = Vec::new();
+    let mut folder_titles = Vec::with_capacity(folders.len());
+    let mut folder_artists = Vec::with_capacity(folders.len());
+
+    for folder in folders {
+        let details = folder_details(folder, read_album, read_track);
+        for track in details.tracks {
+            let performer = track
+                .artist
+                .or_else(|| details.artist.clone())
+                .unwrap_or_default();
+            tracks.push(CdTextEntry::new(
+                &track.title,
+                &performer,
+                track.composer.as_deref().unwrap_or(""),
+            ));
+        }
+
+        folder_titles.push(details.title);
+        folder_artists.push(details.artist);
+    }
+
+    let title = match disc_title.map(str::trim) {
+        Some(title) if !title.is_empty() => title.to_string(),
+        _ if folder_titles.len() == 1 => folder_titles.remove(0),
+        _ => String::new(),
+    };
+    let performer = match folder_artists.first() {
+        Some(Some(first)) if folder_artists.iter().all(|a| a.as_ref() == Some(first)) => {
+            first.clone()
+        }
+        _ if folders.len() > 1 => "Various Artists".to_string(),
+        _ => String::new(),
+    };
+
+    CdText {
+        disc: CdTextEntry::new(&title, &performer, ""),
+        tracks,
+    }
+}
+
+/// Limit text to what CD-TEXT and cue sheets can carry
+fn clean_text(text: &str) -> String {
+    text.trim()
+        .chars()
+        .map(|c| match c {
+            '"' => '\'',
+            c if c.is_control() => ' ',
+            c if (c as u32) <= 0xFF => c,
+            _ => '?',
+        })
+        .take(MAX_TEXT_CHARS)
+        .collect()
+}
+
+/// ISO 8859-1 bytes of text already passed through [`clean_text`]
+pub(crate) fn latin1_bytes(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| u8::try_from(c as u32).unwrap_or(b'?'))
+        .collect()
+}
+
+/// Append the packs for one pack type: every entry's text, NUL-terminated,
+/// run together and split into 12-byte chunks
+fn push_text_packs(packs: &mut Vec<[u8; PACK_BYTES]>, pack_type: u8, entries: &[&str]) {
+    let mut chunk = Vec::with_capacity(PACK_TEXT_BYTES);
+    let mut chunk_track = 0u8;
+    let mut chunk_position = 0u8;
+
+    for (track, text) in entries.iter().enumerate() {
+        let mut bytes = latin1_bytes(text);
+        bytes.push(0);
+        for (i, byte) in bytes.into_iter().enumerate() {
+            if chunk.is_empty() {
+                // Where the pack starts: which track, and how far into its text
+                chunk_track = track as u8;
+                chunk_position = i.min(15) as u8;
+            }
+            chunk.push(byte);
+            if chunk.len() == PACK_TEXT_BYTES {
+                let seq = packs.len() as u8;
+                packs.push(make_pack(
+                    pack_type,
+                    chunk_track,
+                    seq,
+                    chunk_position,
+                    &chunk,
+                ));
+                chunk.clear();
+            }
+        }
+    }
+
+    if !chunk.is_empty() {
+        let seq = packs.len() as u8;
+        packs.push(make_pack(
+            pack_type,
+            chunk_track,
+            seq,
+            chunk_position,
+            &chunk,
+        ));
+    }
+}
+
+fn make_pack(pack_type: u8, track: u8, seq: u8, position: u8, text: &[u8]) -> [u8; PACK_BYTES] {
+    let mut pack = [0u8; PACK_BYTES];
+    pack[0] = pack_type;
+    pack[1] = track;
+    pack[2] = seq;
+    // Block 0, single-byte characters
+    pack[3] = position & 0x0F;
+    pack[4..4 + text.len()].copy_from_slice(text);
+    let crc = crc16(&pack[..16]);
+    pack[16..].copy_from_slice(&crc.to_be_bytes());
+    pack
+}
+
+/// CRC-16/CCITT (polynomial 0x1021, initial value 0), stored inverted
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::AudioFileInfo;
+    use std::path::PathBuf;
+
+    fn album(name: &str, artist: Option<&str>, tracks: &[&str]) -> MusicFolder {
+        let mut folder = MusicFolder::new_for_test_with_id(name);
+        folder.album_name = Some(name.to_string());
+        folder.artist_name = artist.map(str::to_string);
+        folder.audio_files = tracks
+            .iter()
+            .map(|t| AudioFileInfo {
+                path: PathBuf::from(format!("/test/{}/{}.flac", name, t)),
+                duration: 180.0,
+                bitrate: 1411,
+                size: 30_000_000,
+                codec: "flac".to_string(),
+                is_lossy: false,
+            })
+            .collect();
+        folder
+    }
+
+    fn no_album(_: &Path) -> AlbumMetadata {
+        AlbumMetadata::default()
+    }
+
+    /// Title from a tag for files whose name starts with "tagged"
+    fn tags(path: &Path) -> TrackMetadata {
+        let stem = path.file_stem().unwrap().to_string_lossy().to_string();
+        if let Some(number) = stem.strip_prefix("tagged") {
+            TrackMetadata {
+                title: Some(format!("Tagged {}", number)),
+                artist: Some("Guest".to_string()),
+                composer: Some("Writer".to_string()),
+            }
+        } else {
+            TrackMetadata::default()
+        }
+    }
+
+    #[test]
+    fn test_single_album() {
+        let folder = album("Blue", Some("Joni"), &["tagged1", "Plain Song"]);
+
+        let text = build_cd_text(&[folder], None, &no_album, &tags);
+
+        assert_eq!(text.disc, CdTextEntry::new("Blue", "Joni", ""));
+        assert_eq!(
+            text.tracks[0],
+            CdTextEntry::new("Tagged 1", "Guest", "Writer")
+        );
+        // Untagged tracks fall back to the file name and album artist
+        assert_eq!(text.tracks[1], CdTextEntry::new("Plain Song", "Joni", ""));
+    }
+
+    #[test]
+    fn test_respects_active_tracks_and_disc_title() {
+        let mut a = album("A", Some("One"), &["a1", "a2", "a3"]);
+        a.set_track_order(vec![2, 0, 1]);
+        a.exclude_track(Path::new("/test/A/a1.flac"));
+        let b = album("B", Some("Two"), &["b1"]);
+
+        let text = build_cd_text(&[a, b], Some("Road Trip"), &no_album, &tags);
+
+        let titles: Vec<&str> = text.tracks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, vec!["a3", "a2", "b1"]);
+        assert_eq!(
+            text.disc,
+            CdTextEntry::new("Road Trip", "Various Artists", "")
+        );
+    }
+
+    #[test]
+    fn test_falls_back_to_album_tags() {
+        let mut folder = album("folder", None, &["t1"]);
+        folder.album_name = None;
+        let read_album = |_: &Path| AlbumMetadata {
+            album: Some("Tagged Album".to_string()),
+            artist: Some("Tagged Artist".to_string()),
+            year: None,
+        };
+
+        let text = build_cd_text(&[folder], None, &read_album, &tags);
+
+        assert_eq!(
+            text.disc,
+            CdTextEntry::new("Tagged Album", "Tagged Artist", "")
+        );
+        assert_eq!(text.tracks[0].performer, "Tagged Artist");
+    }
+
+    #[test]
+    fn test_clean_text() {
+        assert_eq!(clean_text("  Björk \"Live\" "), "Björk 'Live'");
+        assert_eq!(clean_text("東京"), "??");
+        assert_eq!(clean_text(&"x".repeat(100)).len(), MAX_TEXT_CHARS);
+        assert_eq!(latin1_bytes("Björk"), b"Bj\xF6rk");
+    }
+
+    #[test]
+    fn test_cue_lines() {
+        let entry = CdTextEntry::new("Song", "Artist", "");
+        assert_eq!(
+            entry.cue_lines("    "),
+            "    TITLE \"Song\"\n    PERFORMER \"Artist\"\n"
+        );
+        assert_eq!(CdTextEntry::default().cue_lines(""), "");
+    }
+
+    #[test]
+    fn test_packs() {
+        let text = CdText {
+            disc: CdTextEntry::new("Album", "Artist", ""),
+            tracks: vec![
+                CdTextEntry::new("A longer track title", "Artist", ""),
+                CdTextEntry::new("Two", "Artist", ""),
+            ],
+        };
+
+        let packs = text.to_packs().unwrap();
+
+        // Titles: "Album\0A longer track title\0Two\0" = 31 bytes -> 3 packs
+        // Performers: 3 x "Artist\0" = 21 bytes -> 2 packs, then 3 size packs
+        assert_eq!(packs.len(), 8);
+        let types: Vec<u8> = packs.iter().map(|p| p[0]).collect();
+        assert_eq!(types, vec![0x80, 0x80, 0x80, 0x81, 0x81, 0x8F, 0x8F, 0x8F]);
+        for (i, pack) in packs.iter().enumerate() {
+            assert_eq!(
+                pack[2] as usize, i,
+                "sequence numbers run through the block"
+            );
+            assert_eq!(crc16(&pack[..16]).to_be_bytes(), [pack[16], pack[17]]);
+        }
+
+        // Second title pack starts 6 characters into track 1's title
+        assert_eq!(&packs[0][4..16], b"Album\0A long");
+        assert_eq!(packs[1][1], 1);
+        assert_eq!(packs[1][3], 6);
+
+        // Size info: track range, pack counts and the last sequence number
+        let info: Vec<u8> = packs[5..].iter().flat_map(|p| p[4..16].to_vec()).collect();
+        assert_eq!(&info[..4], &[0x00, 1, 2, 0]);
+        assert_eq!(info[4], 3);
+        assert_eq!(info[5], 2);
+        assert_eq!(info[4 + 15], 3);
+        assert_eq!(info[20], 7);
+        assert_eq!(info[28], LANGUAGE_ENGLISH);
+    }
+
+    #[test]
+    fn test_cdt_header() {
+        let text = CdText {
+            disc: CdTextEntry::new("Album", "", ""),
+            tracks: vec![CdTextEntry::new("One", "", "")],
+        };
+
+        let data = text.to_cdt().unwrap();
+
+        assert_eq!(data.len(), 4 + 4 * PACK_BYTES);
+        assert_eq!(
+            u16::from_be_bytes([data[0], data[1]]) as usize,
+            4 * PACK_BYTES + 2
+        );
+    }
+
+    #[test]
+    fn test_too_much_text() {
+        let tracks = (0..99)
+            .map(|i| CdTextEntry::new(&format!("{} {}", i, "t".repeat(70)), "", ""))
+            .collect();
+        let text = CdText {
+            disc: CdTextEntry::default(),
+            tracks,
+        };
+
+        assert!(text.to_packs().unwrap_err().contains("packs"));
+    }
+}
@@ -3,29 +3,54 @@
 //! This module coordinates the burn process:
 //! 1. Wait for a usable CD (blank or erasable with user approval)
 //! 2. Set up progress tracking with stage transitions
-//! 3. Execute the burn
-//! 4. Handle results and update state
+//! 3. Execute the burn (an ISO, or a BIN/CUE audio image)
+//! 4. Optionally read the disc back and verify it against the ISO
+//! 5. Handle results and update state
 
 use std::path::Path;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicI32, Ordering};
-
-use crate::burning::cd::{CdStatus, burn_iso_with_cancel, check_cd_status};
-use crate::core::{BurnStage, ConversionState};
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use crate::burning::burner::{Burner, DriveSelection, FakeBurner, burner_for};
+use crate::burning::cd::CdStatus;
+use crate::burning::drive_monitor::{DriveEvent, watch_drive};
+use crate::burning::error::BurnError;
+use crate::burning::iso_names::IsoNaming;
+use crate::burning::playlist::PlaylistFormat;
+use crate::burning::verify::{VerificationResult, verify_disc};
+use crate::core::{BurnStage, CompatProfile, ConversionState};
 
 /// Configuration for burn coordination
+#[derive(Debug, Clone)]
 pub struct BurnConfig {
-    /// If true, skip actual burning (just simulate)
+    /// If true, burn to a virtual drive instead of real hardware
     pub simulate: bool,
+    /// If true, read the disc back after burning and compare it with the ISO
+    pub verify: bool,
     /// Timeout in seconds for waiting for CD
     pub cd_wait_timeout_secs: u32,
+    /// Naming rules staged file names are made to follow
+    pub iso_naming: IsoNaming,
+    /// Car stereo limits the staged layout must keep to
+    pub compat: CompatProfile,
+    /// Playlists written onto the disc alongside the tracks
+    pub playlists: PlaylistFormat,
+    /// Drive and write speed to burn with
+    pub drive: DriveSelection,
 }
 
 impl Default for BurnConfig {
     fn default() -> Self {
         Self {
             simulate: false,
+            verify: false,
             cd_wait_timeout_secs: 120,
+            iso_naming: IsoNaming::default(),
+            compat: CompatProfile::default(),
+            playlists: PlaylistFormat::default(),
+            drive: DriveSelection::default(),
         }
     }
 }
@@ -35,21 +60,25 @@ impl Default for BurnConfig {
 pub enum BurnCoordinationResult {
     /// Burn completed successfully
     Success,
-    /// Burn was simulated (no actual burn)
+    /// Burn ran against the virtual drive (no actual burn)
     Simulated,
     /// Burn was cancelled by user
     Cancelled,
     /// No usable CD found within timeout
     NoCdTimeout,
-    /// Burn failed with error (string used by Debug impl)
-    Error(#[allow(dead_code)] String),
+    /// Burn completed and the disc matched the ISO
+    Verified(VerificationResult),
+    /// Burn completed but the disc did not match the ISO (or couldn't be read)
+    VerificationFailed(VerificationResult),
+    /// Burn failed with error
+    Error(BurnError),
 }
 
 /// Coordinate the burn process for an ISO file
 ///
 /// This function handles:
 /// - Waiting for a usable CD (blank or user-approved erase)
-/// - Stage transitions (WaitingForCd -> Erasing/Burning -> Finishing -> Complete)
+/// - Stage transitions (WaitingForCd -> Erasing/Burning -> Finishing -> [Verifying] -> Complete)
 /// - Progress tracking
 /// - Cancellation
 ///
@@ -60,26 +89,83 @@ pub enum BurnCoordinationResult {
 ///
 /// # Returns
 /// * `BurnCoordinationResult` indicating the outcome
+///
+/// In simulate mode the burn goes through the same stages against a
+/// [`FakeBurner`] holding a blank disc.
 pub fn coordinate_burn(
     iso_path: &Path,
     state: &ConversionState,
     config: &BurnConfig,
 ) -> BurnCoordinationResult {
-    let cancel_token = state.cancel_requested.clone();
-
-    // Check for simulate mode first
-    if config.simulate {
+    let burner: Box<dyn Burner> = if config.simulate {
         log::info!("\n=== SIMULATED BURN ===");
-        log::info!("Would burn ISO: {}", iso_path.display());
-        state.set_stage(BurnStage::Complete);
-        return BurnCoordinationResult::Simulated;
-    }
+        Box::new(FakeBurner::simulated())
+    } else {
+        burner_for(&config.drive)
+    };
+    coordinate_burn_with(iso_path, state, config, burner.as_ref())
+}
+
+/// Coordinate the burn process using a specific burner backend
+pub fn coordinate_burn_with(
+    iso_path: &Path,
+    state: &ConversionState,
+    config: &BurnConfig,
+    burner: &dyn Burner,
+) -> BurnCoordinationResult {
+    coordinate_image_burn(DiscImage::Iso(iso_path), state, config, burner)
+}
+
+/// Coordinate the burn of an audio CD from its cue sheet
+///
+/// Goes through the same stages as [`coordinate_burn`]. Audio discs have no
+/// filesystem to compare, so `config.verify` is ignored.
+pub fn coordinate_audio_burn(
+    cue_path: &Path,
+    state: &ConversionState,
+    config: &BurnConfig,
+) -> BurnCoordinationResult {
+    let burner: Box<dyn Burner> = if config.simulate {
+        log::info!("\n=== SIMULATED AUDIO CD BURN ===");
+        Box::new(FakeBurner::simulated())
+    } else {
+        burner_for(&config.drive)
+    };
+    coordinate_audio_burn_with(cue_path, state, config, burner.as_ref())
+}
+
+/// Coordinate an audio CD burn using a specific burner backend
+pub fn coordinate_audio_burn_with(
+    cue_path: &Path,
+    state: &ConversionState,
+    config: &BurnConfig,
+    burner: &dyn Burner,
+) -> BurnCoordinationResult {
+    coordinate_image_burn(DiscImage::AudioCue(cue_path), state, config, burner)
+}
+
+/// What a burn writes to the disc
+#[derive(Debug, Clone, Copy)]
+enum DiscImage<'a> {
+    /// ISO 9660 data image
+    Iso(&'a Path),
+    /// BIN/CUE audio image, given by its cue sheet
+    AudioCue(&'a Path),
+}
+
+fn coordinate_image_burn(
+    image: DiscImage,
+    state: &ConversionState,
+    config: &BurnConfig,
+    burner: &dyn Burner,
+) -> BurnCoordinationResult {
+    let cancel_token = state.cancel_requested.clone();
 
     // Wait for usable CD
     log::info!("\n=== Waiting for blank CD ===");
     state.set_stage(BurnStage::WaitingForCd);
 
-    let wait_result = wait_for_cd(state, &cancel_token, config.cd_wait_timeout_secs);
+    let wait_result = wait_for_cd(state, burner, &cancel_token, config.cd_wait_timeout_secs);
     let erase_first = match wait_result {
         WaitForCdResult::BlankCd => false,
         WaitForCdResult::ErasableCdApproved => true,
@@ -107,32 +193,107 @@ pub fn coordinate_burn(
     let progress_callback = create_progress_callback(state.clone(), erase_first);
 
     // Execute burn
-    match burn_iso_with_cancel(
-        iso_path,
-        Some(progress_callback),
-        Some(cancel_token),
-        erase_first,
-    ) {
+    let burn_result = match image {
+        DiscImage::Iso(iso_path) => burner.burn(
+            iso_path,
+            Some(progress_callback),
+            Some(cancel_token.clone()),
+            erase_first,
+        ),
+        DiscImage::AudioCue(cue_path) => burner.burn_audio(
+            cue_path,
+            Some(progress_callback),
+            Some(cancel_token.clone()),
+            erase_first,
+        ),
+    };
+
+    let result = match burn_result {
         Ok(()) => {
-            log::info!("CD burned successfully!");
-            state.set_stage(BurnStage::Complete);
-            BurnCoordinationResult::Success
+            log::info!("CD burned successfully! ({})", burner.name());
+            if let DiscImage::Iso(iso_path) = image
+                && config.verify
+            {
+                verify_burn(iso_path, state, burner, &cancel_token)
+            } else if config.simulate {
+                BurnCoordinationResult::Simulated
+            } else {
+                BurnCoordinationResult::Success
+            }
         }
-        Err(e) if e.contains("cancelled") => {
+        Err(BurnError::Cancelled) => {
             log::info!("Burn was cancelled");
-            state.set_stage(BurnStage::Cancelled);
             BurnCoordinationResult::Cancelled
         }
         Err(e) => {
             log::error!("Burn failed: {}", e);
-            state.set_stage(BurnStage::Complete);
+            *state.burn_error.lock().unwrap() = Some(e.clone());
             BurnCoordinationResult::Error(e)
         }
+    };
+
+    if let Err(e) = burner.eject() {
+        log::warn!("{}", e);
+    }
+
+    state.set_stage(match result {
+        BurnCoordinationResult::Cancelled => BurnStage::Cancelled,
+        _ => BurnStage::Complete,
+    });
+    result
+}
+
+/// Read the burned disc back and compare it with the ISO
+fn verify_burn(
+    iso_path: &Path,
+    state: &ConversionState,
+    burner: &dyn Burner,
+    cancel_token: &Arc<std::sync::atomic::AtomicBool>,
+) -> BurnCoordinationResult {
+    log::info!("\n=== Verifying CD ===");
+    state.set_stage(BurnStage::Verifying);
+    state.set_burn_progress(0);
+
+    let verification = match burner.read_back_source() {
+        Ok(source) => {
+            match verify_disc(
+                iso_path,
+                &source,
+                &|progress| state.set_burn_progress(progress),
+                cancel_token,
+            ) {
+                Ok(verification) => verification,
+                Err(e) if e.contains("cancelled") => {
+                    log::info!("Verification was cancelled");
+                    return BurnCoordinationResult::Cancelled;
+                }
+                Err(e) => VerificationResult {
+                    error: Some(e),
+                    ..Default::default()
+                },
+            }
+        }
+        Err(e) => VerificationResult {
+            error: Some(e),
+            ..Default::default()
+        },
+    };
+
+    for path in &verification.mismatched_files {
+        log::warn!("Does not match ISO: {}", path);
+    }
+    *state.verification.lock().unwrap() = Some(verification.clone());
+
+    if verification.passed() {
+        BurnCoordinationResult::Verified(verification)
+    } else {
+        log::error!("{}", verification.summary());
+        BurnCoordinationResult::VerificationFailed(verification)
     }
 }
 
 /// Result of waiting for CD
-enum WaitForCdResult {
+pub(super) enum WaitForCdResult {
     /// Found blank CD
     BlankCd,
     /// Found erasable CD and user approved erase
@@ -143,71 +304,99 @@ enum WaitForCdResult {
     Timeout,
 }
 
+/// How often the drive is checked while waiting for a disc
+const CD_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Wait for a usable CD (blank or user-approved erasable)
-fn wait_for_cd(
+///
+/// The drive is watched on a background thread (see [`watch_drive`]) and
+/// this reacts to its events. A CD-RW that's taken out or swapped while the
+/// erase prompt is up puts the wait back to asking for a disc.
+pub(super) fn wait_for_cd(
     state: &ConversionState,
-    cancel_token: &Arc<std::sync::atomic::AtomicBool>,
+    burner: &dyn Burner,
+    cancel_token: &Arc<AtomicBool>,
     timeout_secs: u32,
 ) -> WaitForCdResult {
-    for _ in 0..timeout_secs {
-        // Check for cancellation
+    if cancel_token.load(Ordering::SeqCst) {
+        log::info!("Cancelled while waiting for CD");
+        return WaitForCdResult::Cancelled;
+    }
+
+    let (sender, events) = mpsc::channel();
+    let stop = AtomicBool::new(false);
+    std::thread::scope(|scope| {
+        scope.spawn(|| watch_drive(burner, CD_CHECK_INTERVAL, &sender, &stop, None));
+        let timeout = Duration::from_secs(timeout_secs.into());
+        let result = wait_for_usable_disc(state, &events, cancel_token, timeout);
+        stop.store(true, Ordering::SeqCst);
+        result
+    })
+}
+
+/// React to drive events until a usable disc turns up
+fn wait_for_usable_disc(
+    state: &ConversionState,
+    events: &Receiver<DriveEvent>,
+    cancel_token: &Arc<AtomicBool>,
+    timeout: Duration,
+) -> WaitForCdResult {
+    let mut deadline = Instant::now() + timeout;
+    let mut awaiting_approval = false;
+
+    loop {
         if cancel_token.load(Ordering::SeqCst) {
-            log::info!("Cancelled while waiting for CD");
+            if awaiting_approval {
+                log::info!("Cancelled while waiting for erase approval");
+            } else {
+                log::info!("Cancelled while waiting for CD");
+            }
             return WaitForCdResult::Cancelled;
         }
+        if awaiting_approval {
+            if state.erase_approved.load(Ordering::SeqCst) {
+                log::info!("User approved erase - will erase and burn");
+                return WaitForCdResult::ErasableCdApproved;
+            }
+        } else if Instant::now() >= deadline {
+            return WaitForCdResult::Timeout;
+        }
+
+        let disc = match events.recv_timeout(Duration::from_millis(100)) {
+            Ok(DriveEvent::DiscInserted(disc) | DriveEvent::MediaChanged(disc)) => Some(disc),
+            Ok(DriveEvent::DiscEjected) => None,
+            Ok(DriveEvent::Error(e)) => {
+                log::error!("Error checking CD: {}", e);
+                continue;
+            }
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => return WaitForCdResult::Timeout,
+        };
 
-        match check_cd_status() {
-            Ok(CdStatus::Blank) => {
+        match disc.map(|d| d.status) {
+            Some(CdStatus::Blank) => {
                 log::info!("Blank CD detected");
                 return WaitForCdResult::BlankCd;
             }
-            Ok(CdStatus::ErasableWithData) => {
+            Some(CdStatus::ErasableWithData) => {
                 log::info!("Erasable disc (CD-RW) with data detected");
                 state.set_stage(BurnStage::ErasableDiscDetected);
-
-                // Wait for user to approve erase or cancel
-                match wait_for_erase_approval(state, cancel_token) {
-                    Some(true) => {
-                        log::info!("User approved erase - will erase and burn");
-                        return WaitForCdResult::ErasableCdApproved;
-                    }
-                    Some(false) | None => {
-                        return WaitForCdResult::Cancelled;
-                    }
-                }
+                awaiting_approval = true;
+                continue;
             }
-            Ok(CdStatus::NonErasable) => {
+            Some(CdStatus::NonErasable) => {
                 log::info!("Non-erasable disc detected - please insert a blank disc");
-                std::thread::sleep(std::time::Duration::from_secs(2));
-            }
-            Ok(CdStatus::NoDisc) => {
-                std::thread::sleep(std::time::Duration::from_secs(1));
-            }
-            Err(e) => {
-                log::error!("Error checking CD: {}", e);
-                std::thread::sleep(std::time::Duration::from_secs(1));
             }
+            Some(CdStatus::NoDisc) | None => {}
         }
-    }
 
-    WaitForCdResult::Timeout
-}
-
-/// Wait for user to approve erase
-/// Returns Some(true) if approved, Some(false) if explicitly rejected, None if cancelled
-fn wait_for_erase_approval(
-    state: &ConversionState,
-    cancel_token: &Arc<std::sync::atomic::AtomicBool>,
-) -> Option<bool> {
-    loop {
-        if cancel_token.load(Ordering::SeqCst) {
-            log::info!("Cancelled while waiting for erase approval");
-            return None;
+        // The CD-RW the prompt was about is gone; start waiting afresh
+        if awaiting_approval {
+            log::info!("Disc changed before the erase was approved");
+            state.set_stage(BurnStage::WaitingForCd);
+            awaiting_approval = false;
+            deadline = Instant::now() + timeout;
         }
-        if state.erase_approved.load(Ordering::SeqCst) {
-            return Some(true);
-        }
-        std::thread::sleep(std::time::Duration::from_millis(100));
     }
 }
 
@@ -256,10 +445,22 @@ mod tests {
     fn test_burn_config_custom() {
         let config = BurnConfig {
             simulate: true,
+            verify: true,
             cd_wait_timeout_secs: 60,
+            iso_naming: IsoNaming::Level2,
+            compat: CompatProfile::GenericHeadUnit,
+            playlists: PlaylistFormat::Both,
+            drive: DriveSelection {
+                drive: Some("/dev/sr1".to_string()),
+                speed: Some(8),
+            },
         };
         assert!(config.simulate);
+        assert!(config.verify);
         assert_eq!(config.cd_wait_timeout_secs, 60);
+        assert_eq!(config.iso_naming, IsoNaming::Level2);
+        assert_eq!(config.compat, CompatProfile::GenericHeadUnit);
+        assert_eq!(config.playlists, PlaylistFormat::Both);
     }
 
     #[test]
@@ -281,7 +482,11 @@ mod tests {
         let debug_str = format!("{:?}", timeout);
         assert!(debug_str.contains("NoCdTimeout"));
 
-        let error = BurnCoordinationResult::Error("test error".to_string());
+        let verified = BurnCoordinationResult::Verified(VerificationResult::default());
+        let debug_str = format!("{:?}", verified);
+        assert!(debug_str.contains("Verified"));
+
+        let error = BurnCoordinationResult::Error(BurnError::Other("test error".to_string()));
         let debug_str = format!("{:?}", error);
         assert!(debug_str.contains("Error"));
     }
@@ -299,6 +504,7 @@ mod tests {
         let config = BurnConfig {
             simulate: true,
             cd_wait_timeout_secs: 1,
+            ..Default::default()
         };
 
         let result = coordinate_burn(&iso_path, &state, &config);
@@ -311,6 +517,255 @@ mod tests {
         assert_eq!(state.get_stage(), BurnStage::Complete);
     }
 
+    /// Burner that always sees a blank disc and reports fixed progress
+    struct BlankDiscBurner;
+
+    impl Burner for BlankDiscBurner {
+        fn name(&self) -> &str {
+            "test"
+        }
+
+        fn check_status(&self) -> Result<CdStatus, String> {
+            Ok(CdStatus::Blank)
+        }
+
+        fn burn(
+            &self,
+            _iso_path: &Path,
+            on_progress: Option<crate::burning::cd::ProgressCallback>,
+            _cancel_token: Option<Arc<std::sync::atomic::AtomicBool>>,
+            _erase_first: bool,
+        ) -> Result<(), BurnError> {
+            if let Some(callback) = on_progress {
+                callback(50);
+                callback(100);
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_coordinate_burn_with_backend() {
+        let state = ConversionState::new();
+        let config = BurnConfig {
+            simulate: false,
+            cd_wait_timeout_secs: 1,
+            ..Default::default()
+        };
+
+        let result = coordinate_burn_with(
+            Path::new("/tmp/test.iso"),
+            &state,
+            &config,
+            &BlankDiscBurner,
+        );
+
+        assert!(matches!(result, BurnCoordinationResult::Success));
+        assert_eq!(state.get_stage(), BurnStage::Complete);
+        assert_eq!(state.get_burn_progress(), 100);
+    }
+
+    fn fake_iso(dir: &Path) -> std::path::PathBuf {
+        let iso_path = dir.join("test.iso");
+        std::fs::write(&iso_path, "fake iso contents").unwrap();
+        iso_path
+    }
+
+    fn test_config() -> BurnConfig {
+        BurnConfig {
+            cd_wait_timeout_secs: 5,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_fake_burn_waits_for_disc() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let iso_path = fake_iso(temp_dir.path());
+        let disc = temp_dir.path().join("disc.iso");
+        let burner =
+            FakeBurner::with_status_sequence(vec![CdStatus::NoDisc, CdStatus::Blank])
+                .with_output(&disc);
+
+        let state = ConversionState::new();
+        let result = coordinate_burn_with(&iso_path, &state, &test_config(), &burner);
+
+        assert!(matches!(result, BurnCoordinationResult::Success));
+        assert_eq!(state.get_stage(), BurnStage::Complete);
+        assert_eq!(state.get_burn_progress(), 100);
+        assert!(!burner.last_erase_first());
+        assert_eq!(
+            std::fs::read(&disc).unwrap(),
+            std::fs::read(&iso_path).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_fake_burn_erases_after_approval() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let iso_path = fake_iso(temp_dir.path());
+        let burner = FakeBurner::new(CdStatus::ErasableWithData);
+
+        let state = ConversionState::new();
+        let approver = {
+            let state = state.clone();
+            std::thread::spawn(move || {
+                while state.get_stage() != BurnStage::ErasableDiscDetected {
+                    std::thread::sleep(std::time::Duration::from_millis(10));
+                }
+                state.erase_approved.store(true, Ordering::SeqCst);
+            })
+        };
+
+        let result = coordinate_burn_with(&iso_path, &state, &test_config(), &burner);
+        approver.join().unwrap();
+
+        assert!(matches!(result, BurnCoordinationResult::Success));
+        assert!(burner.last_erase_first());
+        assert_eq!(burner.burn_count(), 1);
+    }
+
+    #[test]
+    fn test_fake_burn_cancel_at_erase_prompt() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let iso_path = fake_iso(temp_dir.path());
+        let burner = FakeBurner::new(CdStatus::ErasableWithData);
+
+        let state = ConversionState::new();
+        let canceller = {
+            let state = state.clone();
+            std::thread::spawn(move || {
+                while state.get_stage() != BurnStage::ErasableDiscDetected {
+                    std::thread::sleep(std::time::Duration::from_millis(10));
+                }
+                state.request_cancel();
+            })
+        };
+
+        let result = coordinate_burn_with(&iso_path, &state, &test_config(), &burner);
+        canceller.join().unwrap();
+
+        assert!(matches!(result, BurnCoordinationResult::Cancelled));
+        assert_eq!(state.get_stage(), BurnStage::Cancelled);
+        assert_eq!(burner.burn_count(), 0);
+    }
+
+    #[test]
+    fn test_fake_burn_non_erasable_times_out() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let iso_path = fake_iso(temp_dir.path());
+        let burner = FakeBurner::new(CdStatus::NonErasable);
+
+        let state = ConversionState::new();
+        let config = BurnConfig {
+            simulate: false,
+            cd_wait_timeout_secs: 1,
+            ..Default::default()
+        };
+        let result = coordinate_burn_with(&iso_path, &state, &config, &burner);
+
+        assert!(matches!(result, BurnCoordinationResult::NoCdTimeout));
+        assert_eq!(burner.burn_count(), 0);
+    }
+
+    #[test]
+    fn test_fake_burn_mid_burn_failure() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let iso_path = fake_iso(temp_dir.path());
+        let burner = FakeBurner::new(CdStatus::Blank).failing_at(60);
+
+        let state = ConversionState::new();
+        let result = coordinate_burn_with(&iso_path, &state, &test_config(), &burner);
+
+        match result {
+            BurnCoordinationResult::Error(e) => assert!(e.to_string().contains("60%")),
+            other => panic!("Expected Error, got {:?}", other),
+        }
+        // Kept for the failure dialog
+        assert!(state.burn_error.lock().unwrap().is_some());
+        assert_eq!(state.get_stage(), BurnStage::Complete);
+        assert_eq!(state.get_burn_progress(), 50);
+    }
+
+    /// Write a real ISO (verification needs a parseable image)
+    fn real_iso(dir: &Path) -> std::path::PathBuf {
+        let source = dir.join("source");
+        std::fs::create_dir_all(&source).unwrap();
+        std::fs::write(source.join("01 Track.mp3"), vec![7u8; 10_000]).unwrap();
+        let iso_path = dir.join("real.iso");
+        crate::burning::iso9660::write_iso_image(
+            &source,
+            &iso_path,
+            &crate::burning::iso9660::IsoOptions::new("Test"),
+        )
+        .unwrap();
+        iso_path
+    }
+
+    fn verify_config() -> BurnConfig {
+        BurnConfig {
+            verify: true,
+            ..test_config()
+        }
+    }
+
+    #[test]
+    fn test_fake_burn_verified() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let iso_path = real_iso(temp_dir.path());
+        let burner = FakeBurner::new(CdStatus::Blank).with_output(&temp_dir.path().join("disc"));
+
+        let state = ConversionState::new();
+        let result = coordinate_burn_with(&iso_path, &state, &verify_config(), &burner);
+
+        match result {
+            BurnCoordinationResult::Verified(v) => assert!(v.passed()),
+            other => panic!("Expected Verified, got {:?}", other),
+        }
+        assert_eq!(state.get_stage(), BurnStage::Complete);
+        assert_eq!(state.get_burn_progress(), 100);
+        assert!(state.verification.lock().unwrap().as_ref().unwrap().passed());
+        // The disc is ejected once verification is done
+        assert_eq!(burner.check_status().unwrap(), CdStatus::NoDisc);
+    }
+
+    #[test]
+    fn test_fake_burn_verification_finds_bad_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let iso_path = real_iso(temp_dir.path());
+        let track = crate::burning::iso9660::read_file_table(&iso_path).unwrap()[0].clone();
+        let burner = FakeBurner::new(CdStatus::Blank)
+            .with_output(&temp_dir.path().join("disc"))
+            .corrupting_byte(track.sector as u64 * 2048 + 100);
+
+        let state = ConversionState::new();
+        let result = coordinate_burn_with(&iso_path, &state, &verify_config(), &burner);
+
+        match result {
+            BurnCoordinationResult::VerificationFailed(v) => {
+                assert_eq!(v.mismatched_files, vec!["01 Track.mp3"]);
+            }
+            other => panic!("Expected VerificationFailed, got {:?}", other),
+        }
+        assert_eq!(state.get_stage(), BurnStage::Complete);
+    }
+
+    #[test]
+    fn test_simulated_burn_with_verify() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let iso_path = real_iso(temp_dir.path());
+        let burner = FakeBurner::new(CdStatus::Blank);
+
+        let state = ConversionState::new();
+        let config = BurnConfig {
+            simulate: true,
+            ..verify_config()
+        };
+        let result = coordinate_burn_with(&iso_path, &state, &config, &burner);
+
+        assert!(matches!(result, BurnCoordinationResult::Verified(_)));
+    }
+
     #[test]
     fn test_create_progress_callback() {
         let state = ConversionState::new();
@@ -346,4 +801,20 @@ mod tests {
         callback(10);
         assert_eq!(state.get_stage(), BurnStage::Burning);
     }
+
+    #[test]
+    fn test_fake_audio_burn_skips_verification() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let cue_path = temp_dir.path().join("audio.cue");
+        std::fs::write(&cue_path, "FILE \"audio.bin\" BINARY\n").unwrap();
+        let burner = FakeBurner::new(CdStatus::Blank);
+
+        let state = ConversionState::new();
+        let result = coordinate_audio_burn_with(&cue_path, &state, &verify_config(), &burner);
+
+        assert!(matches!(result, BurnCoordinationResult::Success));
+        assert_eq!(burner.audio_burn_count(), 1);
+        assert_eq!(state.get_stage(), BurnStage::Complete);
+        assert!(state.verification.lock().unwrap().is_none());
+    }
 }
@@ -0,0 +1,292 @@
+//! Multi-copy burns
+//!
+//! Burns the same ISO onto several discs in a row. Each disc is ejected when
+//! it's done and the next copy waits for a fresh blank disc, just like a
+//! single burn does. A copy that fails doesn't stop the rest; cancelling or
+//! running out of discs does.
+
+use std::path::Path;
+use std::sync::atomic::Ordering;
+
+use crate::burning::burner::Burner;
+use crate::burning::coordinator::{
+    BurnConfig, BurnCoordinationResult, coordinate_burn, coordinate_burn_with,
+};
+use crate::core::{BurnStage, ConversionState};
+
+/// Most copies a single run will make
+pub const MAX_COPIES: usize = 99;
+
+/// What happened to one copy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CopyOutcome {
+    /// Burned (or simulated) without verification
+    Burned,
+    /// Burned and matched the ISO
+    Verified,
+    /// Burned but didn't match the ISO (verification summary)
+    VerificationFailed(String),
+    /// The burn itself failed
+    Failed(String),
+    /// No usable disc was inserted in time
+    NoDisc,
+    /// Cancelled by the user
+    Cancelled,
+}
+
+impl CopyOutcome {
+    fn from_result(result: BurnCoordinationResult) -> Self {
+        match result {
+            BurnCoordinationResult::Success | BurnCoordinationResult::Simulated => Self::Burned,
+            BurnCoordinationResult::Verified(_) => Self::Verified,
+            BurnCoordinationResult::VerificationFailed(v) => Self::VerificationFailed(v.summary()),
+            BurnCoordinationResult::Error(e) => Self::Failed(e.to_string()),
+            BurnCoordinationResult::NoCdTimeout => Self::NoDisc,
+            BurnCoordinationResult::Cancelled => Self::Cancelled,
+        }
+    }
+
+    /// Whether this copy ended up as a good disc
+    pub fn succeeded(&self) -> bool {
+        matches!(self, Self::Burned | Self::Verified)
+    }
+
+    /// Whether the remaining copies should be skipped after this one
+    fn stops_queue(&self) -> bool {
+        matches!(self, Self::NoDisc | Self::Cancelled)
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Self::Burned => "burned".to_string(),
+            Self::Verified => "burned and verified".to_string(),
+            Self::VerificationFailed(summary) => format!("failed verification ({})", summary),
+            Self::Failed(e) => format!("failed ({})", e),
+            Self::NoDisc => "no blank disc inserted".to_string(),
+            Self::Cancelled => "cancelled".to_string(),
+        }
+    }
+}
+
+/// Burn `copies` copies of an ISO, one disc after another
+///
+/// Progress goes to `state` (see [`ConversionState::copy_progress`]) and
+/// each copy's outcome is added to `state.copy_results` as it finishes.
+pub fn burn_copies(
+    iso_path: &Path,
+    state: &ConversionState,
+    config: &BurnConfig,
+    copies: usize,
+) -> Vec<CopyOutcome> {
+    burn_each(state, copies, &|| coordinate_burn(iso_path, state, config))
+}
+
+/// Burn copies of an ISO using a specific burner backend
+pub fn burn_copies_with(
+    iso_path: &Path,
+    state: &ConversionState,
+    config: &BurnConfig,
+    copies: usize,
+    burner: &dyn Burner,
+) -> Vec<CopyOutcome> {
+    burn_each(state, copies, &|| {
+        coordinate_burn_with(iso_path, state, config, burner)
+    })
+}
+
+fn burn_each(
+    state: &ConversionState,
+    copies: usize,
+    burn_one: &dyn Fn() -> BurnCoordinationResult,
+) -> Vec<CopyOutcome> {
+    state.copy_results.lock().unwrap().clear();
+
+    for copy in 1..=copies {
+        state.set_copy(copy, copies);
+        // Every copy needs its own approval before a CD-RW is erased
+        state.erase_approved.store(false, Ordering::SeqCst);
+        *state.verification.lock().unwrap() = None;
+        log::info!("\n=== Copy {} of {} ===", copy, copies);
+
+        let outcome = CopyOutcome::from_result(burn_one());
+        log::info!("Copy {} of {}: {}", copy, copies, outcome.describe());
+        state.copy_results.lock().unwrap().push(outcome.clone());
+
+        if outcome.stops_queue() {
+            if copy < copies {
+                log::info!("Skipping the remaining {} copies", copies - copy);
+            }
+            break;
+        }
+    }
+
+    let outcomes = state.copy_results.lock().unwrap().clone();
+    // Finish with the summary unless nothing was attempted before cancelling
+    if outcomes.first() != Some(&CopyOutcome::Cancelled) {
+        state.set_stage(BurnStage::Complete);
+    }
+    outcomes
+}
+
+/// Dialog text summing up a multi-copy burn
+///
+/// # Returns
+/// * `(true, text)` if every requested copy succeeded
+/// * `(false, text)` listing the copies that failed, were cancelled or
+///   were never burned
+pub fn copies_summary(outcomes: &[CopyOutcome], requested: usize) -> (bool, String) {
+    let good = outcomes.iter().filter(|o| o.succeeded()).count();
+    if good == requested {
+        let verified = outcomes.iter().all(|o| *o == CopyOutcome::Verified);
+        let text = format!(
+            "All {} copies have been burned{} successfully.",
+            requested,
+            if verified { " and verified" } else { "" }
+        );
+        return (true, text);
+    }
+
+    let mut text = format!("{} of {} copies burned successfully.", good, requested);
+    for (i, outcome) in outcomes.iter().enumerate() {
+        if !outcome.succeeded() {
+            text.push_str(&format!("\n• Copy {}: {}", i + 1, outcome.describe()));
+        }
+    }
+    for copy in outcomes.len() + 1..=requested {
+        text.push_str(&format!("\n• Copy {}: not burned", copy));
+    }
+    (false, text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burning::burner::FakeBurner;
+    use crate::burning::cd::CdStatus;
+    use crate::burning::iso::create_iso;
+    use tempfile::TempDir;
+
+    fn test_iso(temp_dir: &TempDir) -> std::path::PathBuf {
+        let staging = temp_dir.path().join("_iso_staging");
+        std::fs::create_dir_all(&staging).unwrap();
+        std::fs::write(staging.join("track.mp3"), b"mp3 data").unwrap();
+        create_iso(&staging, "Copies").unwrap().iso_path
+    }
+
+    /// A drive that gets a fresh blank disc after each eject
+    fn disc_changer(copies: usize) -> FakeBurner {
+        FakeBurner::with_status_sequence(vec![CdStatus::Blank; copies * 2 - 1])
+    }
+
+    fn config(verify: bool) -> BurnConfig {
+        BurnConfig {
+            verify,
+            cd_wait_timeout_secs: 5,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_burns_every_copy() {
+        let temp_dir = TempDir::new().unwrap();
+        let iso_path = test_iso(&temp_dir);
+        let burner = disc_changer(3);
+        let state = ConversionState::new();
+        state.reset(0);
+
+        let outcomes = burn_copies_with(&iso_path, &state, &config(true), 3, &burner);
+
+        assert_eq!(outcomes, vec![CopyOutcome::Verified; 3]);
+        assert_eq!(burner.burn_count(), 3);
+        assert_eq!(state.copy_progress(), Some((3, 3)));
+        assert_eq!(state.get_stage(), BurnStage::Complete);
+        assert_eq!(*state.copy_results.lock().unwrap(), outcomes);
+    }
+
+    #[test]
+    fn test_failed_verification_doesnt_stop_the_queue() {
+        let temp_dir = TempDir::new().unwrap();
+        let iso_path = test_iso(&temp_dir);
+        let burner = disc_changer(2)
+            .with_output(&temp_dir.path().join("disc"))
+            .corrupting_byte(16 * 2048 + 40);
+        let state = ConversionState::new();
+        state.reset(0);
+
+        let outcomes = burn_copies_with(&iso_path, &state, &config(true), 2, &burner);
+
+        assert_eq!(outcomes.len(), 2);
+        assert!(
+            outcomes
+                .iter()
+                .all(|o| matches!(o, CopyOutcome::VerificationFailed(_)))
+        );
+        assert_eq!(burner.burn_count(), 2);
+        assert_eq!(state.get_stage(), BurnStage::Complete);
+    }
+
+    #[test]
+    fn test_missing_disc_stops_the_queue() {
+        let temp_dir = TempDir::new().unwrap();
+        let iso_path = test_iso(&temp_dir);
+        // The first disc is burned and ejected, but no second one arrives
+        let burner = FakeBurner::new(CdStatus::Blank);
+        let state = ConversionState::new();
+        state.reset(0);
+        let config = BurnConfig {
+            cd_wait_timeout_secs: 1,
+            ..Default::default()
+        };
+
+        let outcomes = burn_copies_with(&iso_path, &state, &config, 3, &burner);
+
+        assert_eq!(outcomes, vec![CopyOutcome::Burned, CopyOutcome::NoDisc]);
+        assert_eq!(burner.burn_count(), 1);
+        assert_eq!(state.get_stage(), BurnStage::Complete);
+    }
+
+    #[test]
+    fn test_cancel_before_first_copy() {
+        let temp_dir = TempDir::new().unwrap();
+        let iso_path = test_iso(&temp_dir);
+        let burner = FakeBurner::new(CdStatus::Blank);
+        let state = ConversionState::new();
+        state.reset(0);
+        state.request_cancel();
+
+        let outcomes = burn_copies_with(&iso_path, &state, &config(false), 3, &burner);
+
+        assert_eq!(outcomes, vec![CopyOutcome::Cancelled]);
+        assert_eq!(burner.burn_count(), 0);
+        assert_eq!(state.get_stage(), BurnStage::Cancelled);
+    }
+
+    #[test]
+    fn test_copies_summary() {
+        let (ok, text) = copies_summary(&[CopyOutcome::Verified, CopyOutcome::Verified], 2);
+        assert!(ok);
+        assert_eq!(
+            text,
+            "All 2 copies have been burned and verified successfully."
+        );
+
+        let (ok, text) = copies_summary(&[CopyOutcome::Burned, CopyOutcome::Verified], 2);
+        assert!(ok);
+        assert_eq!(text, "All 2 copies have been burned successfully.");
+
+        let outcomes = vec![
+            CopyOutcome::Verified,
+            CopyOutcome::VerificationFailed("2 file(s) differ from the ISO".to_string()),
+            CopyOutcome::Cancelled,
+        ];
+        let (ok, text) = copies_summary(&outcomes, 4);
+        assert!(!ok);
+        assert_eq!(
+            text,
+            "1 of 4 copies burned successfully.\n\
+             • Copy 2: failed verification (2 file(s) differ from the ISO)\n\
+             • Copy 3: cancelled\n\
+             • Copy 4: not burned"
+        );
+    }
+}
@@ -0,0 +1,639 @@
+//! Index documents at the root of each disc
+//!
+//! Every data disc carries `index.html` and `TRACKLIST.txt`, listing each
+//! folder with its tracks' numbers, titles, artists, lengths and bitrates.
+//! They also record when the disc was made and with which settings. The
+//! HTML page shows a small thumbnail of each folder's album art, embedded so
+//! the page is a single file. Space for both is held back when working out
+//! what fits on a disc (see [`disc_index_reserve`]).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use chrono::{DateTime, Local};
+
+use super::coordinator::BurnConfig;
+use super::insert::{Tracklist, TracklistFolder, base64, clock, escape_xml, image_data_uri};
+use super::iso_names::IsoNaming;
+use super::playlist::PlaylistFormat;
+use crate::core::{CompatProfile, MusicFolder};
+
+/// File names at the disc root
+pub const INDEX_HTML: &str = "index.html";
+pub const TRACKLIST_TEXT: &str = "TRACKLIST.txt";
+
+/// Longest side of an album art thumbnail, in pixels
+const THUMBNAIL_SIZE: u32 = 160;
+
+/// Largest thumbnail embedded in the HTML index
+const THUMBNAIL_MAX_BYTES: u64 = 32 * 1024;
+
+/// Space held back for each file itself, besides its folders and rows
+const INDEX_BASE_BYTES: u64 = 8 * 1024;
+
+/// Space held back per folder in each file (its heading), besides its art
+const INDEX_BYTES_PER_FOLDER: u64 = 512;
+
+/// Space held back per track in each file (one row)
+const INDEX_BYTES_PER_ROW: u64 = 512;
+
+/// Most bytes a title, artist or byline takes in either file, once escaped
+///
+/// Cutting them to this keeps a row and a heading within the space held
+/// back for them, whatever the tags hold.
+const INDEX_FIELD_MAX_BYTES: usize = 128;
+
+/// What the index records about how the disc was made
+#[derive(Debug, Clone, Default)]
+pub struct DiscIndexInfo {
+    /// Disc title (the volume label); blank to use the album name
+    pub title: String,
+    /// Car stereo limits the disc keeps to
+    pub compat: CompatProfile,
+    /// Naming rules the disc's names follow (None for exports)
+    pub naming: Option<IsoNaming>,
+    /// Playlists on the disc
+    pub playlists: PlaylistFormat,
+}
+
+impl DiscIndexInfo {
+    /// Index details for a burn titled `title` with `config`
+    pub fn for_burn(title: &str, config: &BurnConfig) -> Self {
+        Self {
+            title: title.to_string(),
+            compat: config.compat,
+            naming: Some(config.iso_naming),
+            playlists: config.playlists,
+        }
+    }
+}
+
+/// Files written by [`write_disc_index`]
+#[derive(Debug, Clone)]
+pub struct DiscIndexFiles {
+    pub html: PathBuf,
+    pub text: PathBuf,
+}
+
+/// Each index file, with the space to hold back for it on a disc of `folders`
+///
+/// Covers a heading per folder and a row per track in both files, and the
+/// largest thumbnail per folder with album art in the HTML page. Titles are
+/// cut when written, so this is the most either file can take. Sector
+/// rounding is left to the image layout (see [`super::iso_size`]).
+pub fn disc_index_reserve(folders: &[MusicFolder]) -> [(&'static str, u64); 2] {
+    let tracks: usize = folders.iter().map(|f| f.active_tracks().len()).sum();
+    let thumbnails = folders.iter().filter(|f| f.album_art.is_some()).count();
+    // Base64 takes 4 bytes for every 3
+    let thumbnail_bytes = THUMBNAIL_MAX_BYTES.div_ceil(3) * 4;
+    let listing = INDEX_BASE_BYTES
+        + folders.len() as u64 * INDEX_BYTES_PER_FOLDER
+        + tracks as u64 * INDEX_BYTES_PER_ROW;
+    [
+        (INDEX_HTML, listing + thumbnails as u64 * thumbnail_bytes),
+        (TRACKLIST_TEXT, listing),
+    ]
+}
+
+/// Write `index.html` and `TRACKLIST.txt` into `staging_dir`
+///
+/// `staged` is the staged path and source of every active track, in disc
+/// order (from `OutputManager::staged_track_paths`); each track's bitrate is
+/// worked out from its staged file. Call before the names are changed to
+/// fit the disc.
+pub fn write_disc_index(
+    staging_dir: &Path,
+    folders: &[MusicFolder],
+    staged: &[(PathBuf, PathBuf)],
+    info: &DiscIndexInfo,
+) -> Result<DiscIndexFiles, String> {
+    let tracklist = Tracklist::for_folders(folders, &info.title);
+
+    // Bitrate from the encoded file's size, as the scanner works it out
+    let mut staged = staged.iter();
+    let bitrates: Vec<Vec<Option<u32>>> = tracklist
+        .folders
+        .iter()
+        .map(|folder| {
+            folder
+                .tracks
+                .iter()
+                .map(|track| {
+                    let (path, _) = staged.next()?;
+                    let size = fs::metadata(staging_dir.join(path)).ok()?.len();
+                    (track.duration > 0.0)
+                        .then(|| ((size * 8) as f64 / track.duration / 1000.0) as u32)
+                })
+                .collect()
+        })
+        .collect();
+
+    let index = DiscIndex {
+        tracklist: &tracklist,
+        bitrates: &bitrates,
+        info,
+        made_at: Local::now(),
+    };
+    let files = DiscIndexFiles {
+        html: staging_dir.join(INDEX_HTML),
+        text: staging_dir.join(TRACKLIST_TEXT),
+    };
+    let thumbnails: Vec<Option<String>> = tracklist
+        .folders
+        .iter()
+        .map(|folder| folder.art.as_deref().and_then(thumbnail_data_uri))
+        .collect();
+    fs::write(&files.html, index.to_html(&thumbnails))
+        .map_err(|e| format!("Failed to write {}: {}", files.html.display(), e))?;
+    fs::write(&files.text, index.to_text())
+        .map_err(|e| format!("Failed to write {}: {}", files.text.display(), e))?;
+    Ok(files)
+}
+
+/// A disc's tracklist with the details only the index shows
+struct DiscIndex<'a> {
+    tracklist: &'a Tracklist,
+    /// Bitrate of each track in kbps, by folder, where it's known
+    bitrates: &'a [Vec<Option<u32>>],
+    info: &'a DiscIndexInfo,
+    made_at: DateTime<Local>,
+}
+
+impl DiscIndex<'_> {
+    /// "Made" line and the settings the disc was made with, as label/value pairs
+    fn settings(&self) -> Vec<(&'static str, String)> {
+        let mut settings = vec![(
+            "Made",
+            format!(
+                "{} with MP3 CD Burner {}",
+                self.made_at.format("%Y-%m-%d %H:%M"),
+                env!("CARGO_PKG_VERSION")
+            ),
+        )];
+
+        let known: Vec<u32> = self.bitrates.iter().flatten().flatten().copied().collect();
+        if let (Some(min), Some(max)) = (known.iter().min(), known.iter().max()) {
+            let bitrate = if min == max {
+                format!("{} kbps", min)
+            } else {
+                format!("{}-{} kbps", min, max)
+            };
+            settings.push(("Bitrate", bitrate));
+        }
+        settings.push((
+            "Car stereo compatibility",
+            self.info.compat.label().to_string(),
+        ));
+        if let Some(naming) = self.info.naming {
+            settings.push(("File names", naming.label().to_string()));
+        }
+        settings.push(("Playlists", self.info.playlists.label().to_string()));
+        settings
+    }
+
+    fn bitrate(&self, folder: usize, track: usize) -> Option<u32> {
+        self.bitrates.get(folder)?.get(track).copied().flatten()
+    }
+
+    /// `TRACKLIST.txt`
+    fn to_text(&self) -> String {
+        let title = fit_field(&self.tracklist.title, str::to_string);
+        let mut text = format!("{}\n{}\n\n", title, "=".repeat(title.chars().count()));
+        for (label, value) in self.settings() {
+            text.push_str(&format!("{}: {}\n", label, value));
+        }
+
+        for (f, folder) in self.tracklist.folders.iter().enumerate() {
+            text.push('\n');
+            text.push_str(&folder_heading(folder));
+            text.push('\n');
+            for (t, track) in folder.tracks.iter().enumerate() {
+                text.push_str(&format!(
+                    "{:>3}. {}",
+                    track.number,
+                    fit_field(&track.title, str::to_string)
+                ));
+                if let Some(artist) = &track.artist {
+                    text.push_str(&format!(" - {}", fit_field(artist, str::to_string)));
+                }
+                text.push_str(&format!("  {}", clock(track.duration)));
+                if let Some(bitrate) = self.bitrate(f, t) {
+                    text.push_str(&format!("  {} kbps", bitrate));
+                }
+                text.push('\n');
+            }
+        }
+        text.push_str(&format!(
+            "\nTotal time {}\n",
+            clock(self.tracklist.duration())
+        ));
+        text
+    }
+
+    /// `index.html`, with `thumbnails` (as `data:` URIs) by folder
+    fn to_html(&self, thumbnails: &[Option<String>]) -> String {
+        let title = fit_field(&self.tracklist.title, escape_xml);
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+             <style>\n\
+             body {{ font-family: -apple-system, Helvetica, Arial, sans-serif; max-width: 48em; margin: 2em auto; color: #222; }}\n\
+             dl {{ display: grid; grid-template-columns: max-content auto; gap: 0.2em 1em; color: #666; }}\n\
+             dd {{ margin: 0; }}\n\
+             header {{ display: flex; gap: 1em; align-items: center; margin-top: 2em; }}\n\
+             header img {{ width: {size}px; height: {size}px; object-fit: cover; }}\n\
+             h2 {{ margin: 0; }}\n\
+             .byline {{ margin: 0.2em 0 0; color: #666; }}\n\
+             table {{ width: 100%; border-collapse: collapse; }}\n\
+             td {{ padding: 0.2em 0.5em 0.2em 0; }}\n\
+             .number, .time, .bitrate {{ text-align: right; white-space: nowrap; color: #666; }}\n\
+             .artist {{ color: #666; }}\n\
+             </style>\n</head>\n<body>\n<h1>{}</h1>\n<dl>\n",
+            title,
+            title,
+            size = THUMBNAIL_SIZE / 2
+        );
+        for (label, value) in self.settings() {
+            html.push_str(&format!(
+                "<dt>{}</dt><dd>{}</dd>\n",
+                label,
+                escape_xml(&value)
+            ));
+        }
+        html.push_str("</dl>\n");
+
+        for (f, folder) in self.tracklist.folders.iter().enumerate() {
+            html.push_str("<section>\n<header>\n");
+            if let Some(Some(uri)) = thumbnails.get(f) {
+                html.push_str(&format!("<img src=\"{}\" alt=\"\">\n", uri));
+            }
+            html.push_str(&format!(
+                "<div>\n<h2>{}</h2>\n",
+                fit_field(&folder.title, escape_xml)
+            ));
+            let byline = folder.byline();
+            if !byline.is_empty() {
+                html.push_str(&format!(
+                    "<p class=\"byline\">{}</p>\n",
+                    fit_field(&byline, escape_xml)
+                ));
+            }
+            html.push_str("</div>\n</header>\n<table>\n");
+            for (t, track) in folder.tracks.iter().enumerate() {
+                html.push_str(&format!(
+                    "<tr><td class=\"number\">{}</td><td>{}</td><td class=\"artist\">{}</td>\
+                     <td class=\"time\">{}</td><td class=\"bitrate\">{}</td></tr>\n",
+                    track.number,
+                    fit_field(&track.title, escape_xml),
+                    fit_field(track.artist.as_deref().unwrap_or_default(), escape_xml),
+                    clock(track.duration),
+                    self.bitrate(f, t)
+                        .map(|b| format!("{} kbps", b))
+                        .unwrap_or_default()
+                ));
+            }
+            html.push_str("</table>\n</section>\n");
+        }
+        html.push_str(&format!(
+            "<p class=\"byline\">Total time {}</p>\n</body>\n</html>\n",
+            clock(self.tracklist.duration())
+        ));
+        html
+    }
+}
+
+/// "Title - Artist (Year)", with whichever parts are known
+fn folder_heading(folder: &TracklistFolder) -> String {
+    let title = fit_field(&folder.title, str::to_string);
+    let byline = folder.byline();
+    if byline.is_empty() {
+        title
+    } else {
+        format!("{} - {}", title, fit_field(&byline, str::to_string))
+    }
+}
+
+/// `text` run through `escape`, cut to [`INDEX_FIELD_MAX_BYTES`] and ending
+/// with "…" if anything was cut
+fn fit_field(text: &str, escape: fn(&str) -> String) -> String {
+    let escaped = escape(text);
+    if escaped.len() <= INDEX_FIELD_MAX_BYTES {
+        return escaped;
+    }
+    let budget = INDEX_FIELD_MAX_BYTES - '…'.len_utf8();
+    let mut fitted = String::new();
+    for c in text.chars() {
+        let piece = escape(c.encode_utf8(&mut [0; 4]));
+        if fitted.len() + piece.len() > budget {
+            break;
+        }
+        fitted.push_str(&piece);
+    }
+    fitted.push('…');
+    fitted
+}
+
+/// Album art small enough to embed, as a `data:` URI
+///
+/// Art that's already small is embedded as it is. Anything larger is scaled
+/// down with ffmpeg (or `sips` on a Mac without it); if that fails the art
+/// is left out.
+fn thumbnail_data_uri(art: &Path) -> Option<String> {
+    let size = fs::metadata(art).ok()?.len();
+    if size <= THUMBNAIL_MAX_BYTES {
+        return image_data_uri(art);
+    }
+
+    let thumbnail =
+        std::env::temp_dir().join(format!("mp3cd-thumbnail-{}.jpg", uuid::Uuid::new_v4()));
+    let data = match scale_art(art, &thumbnail) {
+        Ok(()) => fs::read(&thumbnail).ok(),
+        Err(e) => {
+            log::warn!("Couldn't make a thumbnail of {}: {}", art.display(), e);
+            None
+        }
+    };
+    let _ = fs::remove_file(&thumbnail);
+
+    data.filter(|data| data.len() as u64 <= THUMBNAIL_MAX_BYTES)
+        .map(|data| format!("data:image/jpeg;base64,{}", base64(&data)))
+}
+
+/// Scale `art` to a JPEG at `thumbnail`, no larger than [`THUMBNAIL_SIZE`]
+fn scale_art(art: &Path, thumbnail: &Path) -> Result<(), String> {
+    let mut command = match crate::conversion::verify_ffmpeg() {
+        Ok(ffmpeg) => {
+            let mut command = Command::new(ffmpeg);
+            command
+                .args(["-v", "error", "-y", "-i"])
+                .arg(art)
+                .args([
+                    "-vf",
+                    &format!(
+                        "scale={0}:{0}:force_original_aspect_ratio=decrease",
+                        THUMBNAIL_SIZE
+                    ),
+                    "-frames:v",
+                    "1",
+                    "-q:v",
+                    "5",
+                ])
+                .arg(thumbnail);
+            command
+        }
+        #[cfg(target_os = "macos")]
+        Err(_) => {
+            let mut command = Command::new("sips");
+            command
+                .args(["-s", "format", "jpeg", "-Z", &THUMBNAIL_SIZE.to_string()])
+                .arg(art)
+                .arg("--out")
+                .arg(thumbnail);
+            command
+        }
+        #[cfg(not(target_os = "macos"))]
+        Err(e) => return Err(e),
+    };
+
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to run {:?}: {}", command.get_program(), e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burning::insert::TracklistTrack;
+    use crate::core::AudioFileInfo;
+    use tempfile::TempDir;
+
+    fn tracklist() -> Tracklist {
+        let track = |number, title: &str, artist: Option<&str>, duration| TracklistTrack {
+            number,
+            title: title.to_string(),
+            artist: artist.map(str::to_string),
+            duration,
+        };
+        Tracklist {
+            title: "Road Trip".to_string(),
+            folders: vec![
+                TracklistFolder {
+                    title: "First Album".to_string(),
+                    artist: Some("Band".to_string()),
+                    year: Some("1999".to_string()),
+                    art: None,
+                    tracks: vec![
+                        track(1, "Opener", None, 61.0),
+                        track(2, "Duet", Some("Guest"), 125.4),
+                    ],
+                },
+                TracklistFolder {
+                    title: "Mix & Match".to_string(),
+                    artist: None,
+                    year: None,
+                    art: None,
+                    tracks: vec![track(1, "Closer", Some("Other"), 200.0)],
+                },
+            ],
+        }
+    }
+
+    fn index<'a>(
+        tracklist: &'a Tracklist,
+        bitrates: &'a [Vec<Option<u32>>],
+        info: &'a DiscIndexInfo,
+    ) -> DiscIndex<'a> {
+        DiscIndex {
+            tracklist,
+            bitrates,
+            info,
+            made_at: DateTime::parse_from_rfc3339("2024-05-01T09:30:00+00:00")
+                .unwrap()
+                .with_timezone(&Local),
+        }
+    }
+
+    #[test]
+    fn test_index_text() {
+        let list = tracklist();
+        let bitrates = vec![vec![Some(192), Some(256)], vec![None]];
+        let info = DiscIndexInfo {
+            title: "Road Trip".to_string(),
+            naming: Some(IsoNaming::Joliet),
+            playlists: PlaylistFormat::M3u8,
+            ..Default::default()
+        };
+
+        let text = index(&list, &bitrates, &info).to_text();
+
+        assert!(text.starts_with("Road Trip\n=========\n\nMade: "));
+        assert!(text.contains("\nBitrate: 192-256 kbps\n"));
+        assert!(text.contains("\nCar stereo compatibility: Modern\n"));
+        assert!(text.contains("\nFile names: Joliet\n"));
+        assert!(text.contains("\nPlaylists: M3U8\n"));
+        assert!(text.contains("\nFirst Album - Band (1999)\n  1. Opener  1:01  192 kbps\n"));
+        assert!(text.contains("\n  2. Duet - Guest  2:05  256 kbps\n"));
+        assert!(text.contains("\nMix & Match\n  1. Closer - Other  3:20\n"));
+        assert!(text.ends_with("\nTotal time 6:26\n"));
+    }
+
+    #[test]
+    fn test_index_html() {
+        let list = tracklist();
+        let bitrates = vec![vec![Some(192), Some(192)], vec![Some(192)]];
+        let info = DiscIndexInfo::default();
+        let thumbnails = vec![Some("data:image/jpeg;base64,AAAA".to_string()), None];
+
+        let html = index(&list, &bitrates, &info).to_html(&thumbnails);
+
+        assert!(html.contains("<h1>Road Trip</h1>"));
+        assert!(html.contains("<dt>Bitrate</dt><dd>192 kbps</dd>"));
+        assert!(!html.contains("<dt>File names</dt>"));
+        assert_eq!(html.matches("<img ").count(), 1);
+        assert!(html.contains("<img src=\"data:image/jpeg;base64,AAAA\""));
+        assert!(html.contains("<h2>Mix &amp; Match</h2>"));
+        assert!(html.contains(
+            "<tr><td class=\"number\">2</td><td>Duet</td><td class=\"artist\">Guest</td>\
+             <td class=\"time\">2:05</td><td class=\"bitrate\">192 kbps</td></tr>"
+        ));
+    }
+
+    #[test]
+    fn test_index_fits_its_reserve() {
+        let long = "\"Quoted\" & <Tagged> ".repeat(50);
+        let track = |number| TracklistTrack {
+            number,
+            title: long.clone(),
+            artist: Some(long.clone()),
+            duration: 1e9,
+        };
+        let folder = TracklistFolder {
+            title: long.clone(),
+            artist: Some(long.clone()),
+            year: Some(long.clone()),
+            art: None,
+            tracks: (1..=20).map(track).collect(),
+        };
+        let list = Tracklist {
+            title: long.clone(),
+            folders: vec![folder.clone(), folder],
+        };
+        let bitrates = vec![vec![Some(u32::MAX); 20]; 2];
+        let info = DiscIndexInfo {
+            title: long.clone(),
+            naming: Some(IsoNaming::RockRidge),
+            playlists: PlaylistFormat::Both,
+            ..Default::default()
+        };
+        let thumbnail = format!(
+            "data:image/jpeg;base64,{}",
+            "A".repeat((THUMBNAIL_MAX_BYTES.div_ceil(3) * 4) as usize)
+        );
+        let index = index(&list, &bitrates, &info);
+
+        let listing = INDEX_BASE_BYTES + 2 * INDEX_BYTES_PER_FOLDER + 40 * INDEX_BYTES_PER_ROW;
+        let html = index.to_html(&[Some(thumbnail.clone()), Some(thumbnail)]);
+        assert!(html.len() as u64 <= listing + 2 * (THUMBNAIL_MAX_BYTES.div_ceil(3) * 4));
+        assert!(html.contains("<td>&quot;Quoted&quot; &amp; &lt;Tagged&gt;"));
+        assert!(html.contains("…</td>"));
+        assert!(index.to_text().len() as u64 <= listing);
+    }
+
+    #[test]
+    fn test_write_disc_index() {
+        let temp_dir = TempDir::new().unwrap();
+        let staging = temp_dir.path();
+        fs::create_dir_all(staging.join("01-Album")).unwrap();
+        // 10 seconds in 240,000 bytes is 192 kbps
+        fs::write(staging.join("01-Album/One.mp3"), vec![0u8; 240_000]).unwrap();
+        let art = staging.join("cover.png");
+        fs::write(&art, b"png").unwrap();
+
+        let mut folder = MusicFolder::new_for_test_with_id("Album");
+        folder.album_name = Some("Album".to_string());
+        folder.artist_name = Some("Band".to_string());
+        folder.year = Some("2001".to_string());
+        folder.album_art = Some(art.to_string_lossy().to_string());
+        folder.audio_files = vec![AudioFileInfo {
+            path: PathBuf::from("/music/Album/One.flac"),
+            duration: 10.0,
+            bitrate: 1411,
+            size: 1_000_000,
+            codec: "flac".to_string(),
+            is_lossy: false,
+        }];
+        let staged = vec![(
+            PathBuf::from("01-Album/One.mp3"),
+            PathBuf::from("/music/Album/One.flac"),
+        )];
+
+        let files =
+            write_disc_index(staging, &[folder], &staged, &DiscIndexInfo::default()).unwrap();
+
+        assert_eq!(files.text, staging.join(TRACKLIST_TEXT));
+        let text = fs::read_to_string(&files.text).unwrap();
+        assert!(text.starts_with("Album\n"));
+        assert!(text.contains("  1. One  0:10  192 kbps\n"));
+        let html = fs::read_to_string(&files.html).unwrap();
+        assert!(html.contains("src=\"data:image/png;base64,cG5n\""));
+    }
+
+    #[test]
+    fn test_large_art_is_scaled() {
+        let temp_dir = TempDir::new().unwrap();
+        let art = temp_dir.path().join("cover.png");
+        // Noise doesn't compress, so this is far over the thumbnail limit
+        let status = Command::new(crate::conversion::get_ffmpeg_path().unwrap())
+            .args(["-v", "error", "-f", "lavfi", "-i"])
+            .arg("nullsrc=s=800x800,geq=random(1)*255:128:128")
+            .args(["-frames:v", "1"])
+            .arg(&art)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        assert!(fs::metadata(&art).unwrap().len() > THUMBNAIL_MAX_BYTES);
+
+        let uri = thumbnail_data_uri(&art).unwrap();
+        assert!(uri.starts_with("data:image/jpeg;base64,"));
+    }
+
+    #[test]
+    fn test_disc_index_reserve() {
+        let mut folder = MusicFolder::new_for_test_with_id("Album");
+        folder.audio_files = vec![
+            AudioFileInfo {
+                path: PathBuf::from("/music/Album/One.mp3"),
+                duration: 10.0,
+                bitrate: 192,
+                size: 240_000,
+                codec: "mp3".to_string(),
+                is_lossy: true,
+            };
+            10
+        ];
+        let total = |folders: &[MusicFolder]| -> u64 {
+            disc_index_reserve(folders)
+                .iter()
+                .map(|(_, bytes)| bytes)
+                .sum()
+        };
+        let without_art = total(std::slice::from_ref(&folder));
+        folder.album_art = Some("/tmp/cover.jpg".to_string());
+        let with_art = total(std::slice::from_ref(&folder));
+
+        assert!(without_art >= 2 * (INDEX_BASE_BYTES + 10 * INDEX_BYTES_PER_ROW));
+        assert!(with_art - without_art > THUMBNAIL_MAX_BYTES);
+        assert_eq!(
+            disc_index_reserve(&[]),
+            [
+                (INDEX_HTML, INDEX_BASE_BYTES),
+                (TRACKLIST_TEXT, INDEX_BASE_BYTES)
+            ]
+        );
+    }
+}
@@ -0,0 +1,290 @@
+//! Background watching of the burn drive
+//!
+//! The drive is checked on its own thread and every change of media is sent
+//! over a channel as a [`DriveEvent`]: a disc going in, coming out, or
+//! turning from blank to written after a burn. The UI uses it to show what's
+//! in the drive before a burn starts, and the burn coordinator waits on the
+//! same events for a usable disc.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Duration;
+
+use crate::burning::burner::Burner;
+use crate::burning::cd::CdStatus;
+use crate::core::MediaProfile;
+
+/// How long the watcher sleeps between checks of the stop flag
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(50);
+
+/// What's in the drive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscInfo {
+    pub status: CdStatus,
+    /// Writable bytes, for a blank disc whose drive reports it
+    pub capacity: Option<u64>,
+    /// Kind of disc as the drive reports it, e.g. "CD-R"
+    pub media_type: Option<String>,
+}
+
+impl DiscInfo {
+    /// Status bar text, e.g. "Blank 700 MB CD-R ready"
+    pub fn describe(&self) -> String {
+        let media = self.media_type.as_deref().unwrap_or("disc");
+        match self.status {
+            CdStatus::Blank => match self.capacity {
+                Some(bytes) => format!(
+                    "Blank {} {} ready",
+                    MediaProfile::from_capacity(bytes).capacity_label(),
+                    media
+                ),
+                None => format!("Blank {} ready", media),
+            },
+            CdStatus::ErasableWithData => match &self.media_type {
+                Some(media) => format!("{} with data (erase to reuse)", media),
+                None => "Rewritable disc with data (erase to reuse)".to_string(),
+            },
+            CdStatus::NonErasable => format!("Closed {} (not writable)", media),
+            CdStatus::NoDisc => "No disc".to_string(),
+        }
+    }
+}
+
+/// A change in the drive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DriveEvent {
+    /// A disc was put in (or was already there when watching started)
+    DiscInserted(DiscInfo),
+    /// The disc in the drive changed state, e.g. it was burned or erased
+    MediaChanged(DiscInfo),
+    /// The drive is empty
+    DiscEjected,
+    /// The drive couldn't be checked
+    Error(String),
+}
+
+/// Watches a drive on a background thread until dropped
+pub struct DriveMonitor {
+    events: Receiver<DriveEvent>,
+    stop: Arc<AtomicBool>,
+}
+
+impl DriveMonitor {
+    /// Start checking `burner`'s drive every `interval`
+    ///
+    /// Checks are skipped while `pause` is set, so the monitor stays out of
+    /// the way of a burn that's using the drive.
+    pub fn start(burner: Box<dyn Burner>, interval: Duration, pause: Arc<AtomicBool>) -> Self {
+        let (sender, events) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        std::thread::spawn(move || {
+            watch_drive(
+                burner.as_ref(),
+                interval,
+                &sender,
+                &thread_stop,
+                Some(&pause),
+            );
+        });
+        Self { events, stop }
+    }
+
+    /// Next event, if one is waiting
+    pub fn try_recv(&self) -> Option<DriveEvent> {
+        self.events.try_recv().ok()
+    }
+}
+
+impl Drop for DriveMonitor {
+    fn drop(&mut self) {
+        // Not joined: a drive that's slow to answer would hold up the UI.
+        // The thread exits on its own once its current check returns.
+        self.stop.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Check the drive every `interval` and send an event whenever it changes
+///
+/// The first check always sends one, so the receiver learns what's already
+/// in the drive. Runs until `stop` is set or the receiver goes away; `stop`
+/// is looked at before every check, so no check starts once it's set.
+pub(crate) fn watch_drive(
+    burner: &dyn Burner,
+    interval: Duration,
+    events: &Sender<DriveEvent>,
+    stop: &AtomicBool,
+    pause: Option<&AtomicBool>,
+) {
+    let mut last: Option<Result<DiscInfo, String>> = None;
+
+    while !stop.load(Ordering::SeqCst) {
+        if !pause.is_some_and(|p| p.load(Ordering::SeqCst)) {
+            let current = disc_info(burner);
+            if last.as_ref() != Some(&current) {
+                let event = match (&last, &current) {
+                    (_, Err(e)) => DriveEvent::Error(e.clone()),
+                    (_, Ok(info)) if info.status == CdStatus::NoDisc => DriveEvent::DiscEjected,
+                    (Some(Ok(previous)), Ok(info)) if previous.status != CdStatus::NoDisc => {
+                        DriveEvent::MediaChanged(info.clone())
+                    }
+                    (_, Ok(info)) => DriveEvent::DiscInserted(info.clone()),
+                };
+                if events.send(event).is_err() {
+                    return;
+                }
+                last = Some(current);
+            }
+        }
+
+        let mut waited = Duration::ZERO;
+        while waited < interval && !stop.load(Ordering::SeqCst) {
+            let step = STOP_CHECK_INTERVAL.min(interval - waited);
+            std::thread::sleep(step);
+            waited += step;
+        }
+    }
+}
+
+/// Check what's in the drive; capacity and type are only looked up for a disc
+fn disc_info(burner: &dyn Burner) -> Result<DiscInfo, String> {
+    let status = burner.check_status()?;
+    let (capacity, media_type) = match status {
+        CdStatus::NoDisc => (None, None),
+        CdStatus::Blank => (
+            burner.media_capacity().unwrap_or(None),
+            burner.media_type().unwrap_or(None),
+        ),
+        _ => (None, burner.media_type().unwrap_or(None)),
+    };
+    Ok(DiscInfo {
+        status,
+        capacity,
+        media_type,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burning::burner::FakeBurner;
+
+    /// Run the watcher over `burner` until it has sent `count` events
+    fn watch(burner: &FakeBurner, count: usize) -> Vec<DriveEvent> {
+        let (sender, events) = mpsc::channel();
+        let stop = AtomicBool::new(false);
+        std::thread::scope(|scope| {
+            scope.spawn(|| watch_drive(burner, Duration::from_millis(10), &sender, &stop, None));
+            let received = (0..count)
+                .map_while(|_| events.recv_timeout(Duration::from_secs(5)).ok())
+                .collect();
+            stop.store(true, Ordering::SeqCst);
+            received
+        })
+    }
+
+    fn blank(capacity: Option<u64>) -> DiscInfo {
+        DiscInfo {
+            status: CdStatus::Blank,
+            capacity,
+            media_type: None,
+        }
+    }
+
+    #[test]
+    fn test_events_on_change_only() {
+        let burner = FakeBurner::with_status_sequence(vec![
+            CdStatus::NoDisc,
+            CdStatus::NoDisc,
+            CdStatus::Blank,
+            CdStatus::Blank,
+            CdStatus::NonErasable,
+            CdStatus::NoDisc,
+        ]);
+
+        let events = watch(&burner, 4);
+
+        assert_eq!(
+            events,
+            vec![
+                DriveEvent::DiscEjected,
+                DriveEvent::DiscInserted(blank(None)),
+                DriveEvent::MediaChanged(DiscInfo {
+                    status: CdStatus::NonErasable,
+                    capacity: None,
+                    media_type: None,
+                }),
+                DriveEvent::DiscEjected,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_first_check_reports_disc() {
+        let burner = FakeBurner::new(CdStatus::Blank).with_capacity(700 * 1024 * 1024);
+        assert_eq!(
+            watch(&burner, 1),
+            vec![DriveEvent::DiscInserted(blank(Some(700 * 1024 * 1024)))]
+        );
+    }
+
+    #[test]
+    fn test_paused_monitor_doesnt_check() {
+        let burner = FakeBurner::new(CdStatus::Blank);
+        let (sender, events) = mpsc::channel();
+        let stop = AtomicBool::new(false);
+        let pause = AtomicBool::new(true);
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                watch_drive(
+                    &burner,
+                    Duration::from_millis(10),
+                    &sender,
+                    &stop,
+                    Some(&pause),
+                )
+            });
+            std::thread::sleep(Duration::from_millis(30));
+            stop.store(true, Ordering::SeqCst);
+        });
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_monitor_start() {
+        let monitor = DriveMonitor::start(
+            Box::new(FakeBurner::new(CdStatus::ErasableWithData)),
+            Duration::from_millis(10),
+            Arc::new(AtomicBool::new(false)),
+        );
+        let mut event = None;
+        for _ in 0..100 {
+            event = monitor.try_recv();
+            if event.is_some() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        let Some(DriveEvent::DiscInserted(info)) = event else {
+            panic!("expected a disc, got {:?}", event);
+        };
+        assert_eq!(
+            info.describe(),
+            "Rewritable disc with data (erase to reuse)"
+        );
+    }
+
+    #[test]
+    fn test_describe() {
+        let mut info = DiscInfo {
+            media_type: Some("CD-R".to_string()),
+            ..blank(Some(359_846 * 2048))
+        };
+        assert_eq!(info.describe(), "Blank 700 MB CD-R ready");
+        info.capacity = None;
+        assert_eq!(info.describe(), "Blank CD-R ready");
+        info.status = CdStatus::NonErasable;
+        assert_eq!(info.describe(), "Closed CD-R (not writable)");
+    }
+}
@@ -0,0 +1,248 @@
+//! Standalone CD-RW erasing
+//!
+//! Erases a rewritable disc on its own, so a stack of CD-RWs can be made
+//! blank ahead of time and the burn itself stays a pure write. The disc goes
+//! through the same steps as a burn: wait for it to be inserted, ask before
+//! erasing it, then erase with progress and cancellation. The disc is
+//! ejected once it's blank, ready for the next one.
+
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use crate::burning::burner::{Burner, EraseMode, FakeBurner, burner_for};
+use crate::burning::coordinator::{
+    BurnConfig, BurnCoordinationResult, WaitForCdResult, wait_for_cd,
+};
+use crate::burning::error::BurnError;
+use crate::core::{BurnStage, ConversionState};
+
+/// How a standalone erase ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EraseResult {
+    pub mode: EraseMode,
+    /// Why the disc wasn't erased, if it wasn't
+    pub error: Option<BurnError>,
+}
+
+impl EraseResult {
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// Summary for dialogs, with a hint on what to do if the erase failed
+    pub fn summary(&self) -> String {
+        match &self.error {
+            Some(error) => error.with_hint(),
+            None => format!(
+                "{} finished. The disc is blank and ready to burn.",
+                self.mode.label()
+            ),
+        }
+    }
+}
+
+/// Erase the CD-RW in the drive
+///
+/// Waits for a rewritable disc holding data and for the user to approve
+/// erasing it (`state.erase_approved`), like a burn does. Stages go
+/// WaitingForCd -> ErasableDiscDetected -> Erasing -> Complete. In simulate
+/// mode the erase runs against a [`FakeBurner`] holding a CD-RW.
+pub fn coordinate_erase(
+    state: &ConversionState,
+    config: &BurnConfig,
+    mode: EraseMode,
+) -> BurnCoordinationResult {
+    let burner: Box<dyn Burner> = if config.simulate {
+        log::info!("\n=== SIMULATED ERASE ===");
+        Box::new(FakeBurner::simulated_rewritable())
+    } else {
+        burner_for(&config.drive)
+    };
+    coordinate_erase_with(state, config, mode, burner.as_ref())
+}
+
+/// Erase the CD-RW in the drive using a specific burner backend
+pub fn coordinate_erase_with(
+    state: &ConversionState,
+    config: &BurnConfig,
+    mode: EraseMode,
+    burner: &dyn Burner,
+) -> BurnCoordinationResult {
+    let cancel_token = state.cancel_requested.clone();
+
+    log::info!("\n=== Waiting for CD-RW ===");
+    state.set_stage(BurnStage::WaitingForCd);
+
+    match wait_for_cd(state, burner, &cancel_token, config.cd_wait_timeout_secs) {
+        WaitForCdResult::ErasableCdApproved => {}
+        WaitForCdResult::BlankCd => {
+            log::info!("Disc is already blank - nothing to erase");
+            state.set_stage(BurnStage::Complete);
+            return BurnCoordinationResult::Error(BurnError::Other(
+                "The disc in the drive is already blank.".to_string(),
+            ));
+        }
+        WaitForCdResult::Cancelled => {
+            state.set_stage(BurnStage::Cancelled);
+            return BurnCoordinationResult::Cancelled;
+        }
+        WaitForCdResult::Timeout => {
+            log::info!("No CD-RW found after timeout");
+            state.set_stage(BurnStage::Complete);
+            return BurnCoordinationResult::NoCdTimeout;
+        }
+    }
+
+    log::info!("\n=== {} ===", mode.label());
+    state.set_stage(BurnStage::Erasing);
+    state.set_burn_progress(-1);
+
+    let result = match erase(state, burner, mode, cancel_token) {
+        Ok(()) => {
+            log::info!("Disc erased successfully ({})", burner.name());
+            if config.simulate {
+                BurnCoordinationResult::Simulated
+            } else {
+                BurnCoordinationResult::Success
+            }
+        }
+        Err(BurnError::Cancelled) => {
+            log::info!("Erase was cancelled");
+            BurnCoordinationResult::Cancelled
+        }
+        Err(e) => {
+            log::error!("Erase failed: {}", e);
+            BurnCoordinationResult::Error(e)
+        }
+    };
+
+    if let Err(e) = burner.eject() {
+        log::warn!("{}", e);
+    }
+
+    state.set_stage(match result {
+        BurnCoordinationResult::Cancelled => BurnStage::Cancelled,
+        _ => BurnStage::Complete,
+    });
+    result
+}
+
+fn erase(
+    state: &ConversionState,
+    burner: &dyn Burner,
+    mode: EraseMode,
+    cancel_token: Arc<AtomicBool>,
+) -> Result<(), BurnError> {
+    let progress_state = state.clone();
+    burner.erase(
+        mode,
+        Some(Box::new(move |progress| {
+            progress_state.set_burn_progress(progress)
+        })),
+        Some(cancel_token),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burning::cd::CdStatus;
+    use std::sync::atomic::Ordering;
+
+    fn config() -> BurnConfig {
+        BurnConfig {
+            cd_wait_timeout_secs: 5,
+            ..Default::default()
+        }
+    }
+
+    /// Approve the erase as soon as the prompt would show
+    fn approve_when_asked(state: &ConversionState) -> std::thread::JoinHandle<()> {
+        let state = state.clone();
+        std::thread::spawn(move || {
+            while state.get_stage() != BurnStage::ErasableDiscDetected {
+                std::thread::sleep(std::time::Duration::from_millis(5));
+            }
+            state.erase_approved.store(true, Ordering::SeqCst);
+        })
+    }
+
+    #[test]
+    fn test_erase_after_approval() {
+        let burner =
+            FakeBurner::with_status_sequence(vec![CdStatus::NoDisc, CdStatus::ErasableWithData])
+                .with_progress_step(25);
+        let state = ConversionState::new();
+        let approver = approve_when_asked(&state);
+
+        let result = coordinate_erase_with(&state, &config(), EraseMode::Quick, &burner);
+        approver.join().unwrap();
+
+        assert!(matches!(result, BurnCoordinationResult::Success));
+        assert_eq!(burner.erase_count(), 1);
+        assert_eq!(burner.burn_count(), 0);
+        assert_eq!(state.get_stage(), BurnStage::Complete);
+        assert_eq!(state.get_burn_progress(), 100);
+        // Ejected, ready for the next disc
+        assert_eq!(burner.check_status().unwrap(), CdStatus::NoDisc);
+    }
+
+    #[test]
+    fn test_erase_cancelled_at_prompt() {
+        let burner = FakeBurner::new(CdStatus::ErasableWithData);
+        let state = ConversionState::new();
+        let canceller = {
+            let state = state.clone();
+            std::thread::spawn(move || {
+                while state.get_stage() != BurnStage::ErasableDiscDetected {
+                    std::thread::sleep(std::time::Duration::from_millis(5));
+                }
+                state.request_cancel();
+            })
+        };
+
+        let result = coordinate_erase_with(&state, &config(), EraseMode::Full, &burner);
+        canceller.join().unwrap();
+
+        assert!(matches!(result, BurnCoordinationResult::Cancelled));
+        assert_eq!(burner.erase_count(), 0);
+        assert_eq!(state.get_stage(), BurnStage::Cancelled);
+    }
+
+    #[test]
+    fn test_erase_blank_disc() {
+        let burner = FakeBurner::new(CdStatus::Blank);
+        let state = ConversionState::new();
+
+        let result = coordinate_erase_with(&state, &config(), EraseMode::Quick, &burner);
+
+        assert!(matches!(
+            result,
+            BurnCoordinationResult::Error(e) if e.to_string().contains("already blank")
+        ));
+        assert_eq!(burner.erase_count(), 0);
+    }
+
+    #[test]
+    fn test_erase_result_summary() {
+        let done = EraseResult {
+            mode: EraseMode::Full,
+            error: None,
+        };
+        assert!(done.succeeded());
+        assert!(done.summary().starts_with("Full Erase finished."));
+
+        let failed = EraseResult {
+            mode: EraseMode::Quick,
+            error: Some(BurnError::Other("Disc is not rewritable".to_string())),
+        };
+        assert!(!failed.succeeded());
+        assert_eq!(failed.summary(), "Disc is not rewritable");
+
+        let busy = EraseResult {
+            mode: EraseMode::Quick,
+            error: Some(BurnError::DriveBusy("Device busy".to_string())),
+        };
+        assert!(busy.summary().starts_with("Device busy\n\n"));
+    }
+}
@@ -0,0 +1,375 @@
+//! Why a burn or ISO image failed
+//!
+//! Burn tools explain failures in free text and exit codes. [`BurnError`]
+//! sorts them into the causes a user can do something about, each with a
+//! hint on how to recover, and keeps the tool's own words as the details.
+
+use std::fmt;
+use std::io;
+
+/// Exit code a shell gives for a program it found but couldn't run
+const EXIT_CANNOT_EXECUTE: i32 = 126;
+
+/// Exit code a shell gives for a program it couldn't find
+const EXIT_NOT_FOUND: i32 = 127;
+
+/// Severities xorriso and libburn give the messages that stop a burn, as in
+/// "xorriso : FAILURE : ..."
+const XORRISO_ERROR_SEVERITIES: [&str; 3] = ["FAILURE", "SORRY", "FATAL"];
+
+/// A failed burn, erase or ISO write, with the tool's message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BurnError {
+    /// The disc can't be written: it's closed, missing or the wrong kind
+    MediaNotWritable(String),
+    /// Another program is using the drive
+    DriveBusy(String),
+    /// The drive ran out of data mid-burn, spoiling the disc
+    BufferUnderrun(String),
+    /// The image doesn't fit on the disc, or the ISO didn't fit on disk
+    DiscFull(String),
+    /// Not allowed to use the drive or write the file
+    PermissionDenied(String),
+    /// The burn tool isn't installed
+    ToolMissing(String),
+    /// The user cancelled
+    Cancelled,
+    /// Anything else
+    Other(String),
+}
+
+impl BurnError {
+    /// Sort a failure message by the cause it names
+    ///
+    /// Only whole phrases count, so words like "closed" or "busy" elsewhere
+    /// in a message don't decide it.
+    pub fn classify(message: &str) -> Self {
+        let lower = message.to_lowercase();
+        let has = |phrases: &[&str]| phrases.iter().any(|p| lower.contains(p));
+        let message = message.to_string();
+
+        if has(&["cancelled"]) {
+            BurnError::Cancelled
+        } else if has(&[
+            "no cd burning tool",
+            "install cdrecord",
+            "command not found",
+        ]) {
+            BurnError::ToolMissing(message)
+        } else if has(&[
+            "permission denied",
+            "operation not permitted",
+            "insufficient privileges",
+            "not authorized",
+        ]) {
+            BurnError::PermissionDenied(message)
+        } else if has(&["buffer underrun"]) {
+            BurnError::BufferUnderrun(message)
+        } else if has(&[
+            "device or resource busy",
+            "drive is busy",
+            "device is busy",
+            "in use by another",
+            "exclusive access",
+        ]) {
+            BurnError::DriveBusy(message)
+        } else if has(&[
+            "no space left",
+            "not enough space",
+            "will not fit",
+            "may not fit",
+            "does not fit",
+            "doesn't fit",
+            "too large for",
+            "exceeds free space",
+            "disc full",
+            "disk full",
+        ]) {
+            BurnError::DiscFull(message)
+        } else if has(&[
+            "not writable",
+            "not blank",
+            "not rewritable",
+            "not appendable",
+            "write protected",
+            "write-protected",
+            "cannot write medium",
+            "no disc in",
+            "no disk in",
+            "no media",
+            "medium not present",
+            "is closed",
+            "closed media",
+            "closed disc",
+        ]) {
+            BurnError::MediaNotWritable(message)
+        } else {
+            BurnError::Other(message)
+        }
+    }
+
+    /// Error for a file or drive operation that failed
+    pub(crate) fn from_io(context: &str, e: &io::Error) -> Self {
+        let message = format!("{}: {}", context, e);
+        match e.kind() {
+            io::ErrorKind::PermissionDenied => BurnError::PermissionDenied(message),
+            io::ErrorKind::StorageFull => BurnError::DiscFull(message),
+            io::ErrorKind::ResourceBusy => BurnError::DriveBusy(message),
+            _ => BurnError::classify(&message),
+        }
+    }
+
+    /// Error for a tool that couldn't be started
+    pub(crate) fn from_spawn(tool: &str, e: &io::Error) -> Self {
+        if e.kind() == io::ErrorKind::NotFound {
+            BurnError::ToolMissing(format!("{} is not installed", tool))
+        } else {
+            BurnError::from_io(&format!("Failed to execute {}", tool), e)
+        }
+    }
+
+    /// Error for a tool that exited unsuccessfully
+    ///
+    /// `output` is what the tool printed, oldest first. A shell's exit code
+    /// for a tool it couldn't run decides first. Otherwise only the tool's
+    /// own error lines are sorted (see [`is_error_line`]), and the first one
+    /// that names a cause wins, since later errors follow from it. Failing
+    /// that, the first error line (or the last line) is kept as the details.
+    pub(crate) fn from_exit(tool: &str, code: Option<i32>, output: &[String]) -> Self {
+        if code == Some(EXIT_NOT_FOUND) {
+            return BurnError::ToolMissing(format!("{} is not installed", tool));
+        }
+
+        let errors: Vec<&String> = output
+            .iter()
+            .filter(|line| is_error_line(tool, line))
+            .collect();
+        if code != Some(EXIT_CANNOT_EXECUTE)
+            && let Some(error) = errors
+                .iter()
+                .map(|line| BurnError::classify(line))
+                .find(|error| !matches!(error, BurnError::Other(_)))
+        {
+            return error;
+        }
+
+        let message = match errors.first().copied().or(output.last()) {
+            Some(line) => format!("Burn process failed: {}", line),
+            None => "Burn process failed".to_string(),
+        };
+        match code {
+            Some(EXIT_CANNOT_EXECUTE) => BurnError::PermissionDenied(message),
+            _ => BurnError::Other(message),
+        }
+    }
+
+    /// Dialog title
+    pub fn title(&self) -> &'static str {
+        match self {
+            BurnError::MediaNotWritable(_) => "Disc Not Writable",
+            BurnError::DriveBusy(_) => "Drive Busy",
+            BurnError::BufferUnderrun(_) => "Buffer Underrun",
+            BurnError::DiscFull(_) => "Not Enough Space",
+            BurnError::PermissionDenied(_) => "Permission Denied",
+            BurnError::ToolMissing(_) => "Burning Tool Missing",
+            BurnError::Cancelled => "Cancelled",
+            BurnError::Other(_) => "Burn Failed",
+        }
+    }
+
+    /// What the user can do about it, if there's anything specific
+    pub fn recovery_hint(&self) -> Option<&'static str> {
+        match self {
+            BurnError::MediaNotWritable(_) => Some(
+                "Insert a blank CD-R, or a CD-RW for the app to erase. \
+                 A disc that's been closed can't be written again.",
+            ),
+            BurnError::DriveBusy(_) => Some(
+                "Quit any other app that's using the drive, such as Finder burning \
+                 or Disk Utility, then try again.",
+            ),
+            BurnError::BufferUnderrun(_) => Some(
+                "This disc is probably spoiled. Try a new one at a lower write speed \
+                 (Options > Burner Drive and Speed), with other busy apps closed.",
+            ),
+            BurnError::DiscFull(_) => Some(
+                "Lower the bitrate, remove a folder or pick a larger disc. If the ISO \
+                 itself couldn't be written, free up space on your computer.",
+            ),
+            BurnError::PermissionDenied(_) => Some(
+                "Check that you're allowed to use the drive (on Linux, join the cdrom \
+                 group) and to write to the app's output folder.",
+            ),
+            BurnError::ToolMissing(_) => Some(
+                "Install cdrecord, wodim or xorriso. On macOS, drutil and hdiutil \
+                 come with the system.",
+            ),
+            BurnError::Cancelled | BurnError::Other(_) => None,
+        }
+    }
+
+    /// The message followed by the recovery hint, for dialogs
+    pub fn with_hint(&self) -> String {
+        match self.recovery_hint() {
+            Some(hint) => format!("{}\n\n{}", self, hint),
+            None => self.to_string(),
+        }
+    }
+}
+
+/// Whether `line` is an error `tool` reported, rather than progress or
+/// information
+///
+/// cdrecord, wodim and hdiutil start their errors with their own name
+/// ("cdrecord: ..."); xorriso and libburn give a severity
+/// ("xorriso : FAILURE : ...").
+fn is_error_line(tool: &str, line: &str) -> bool {
+    let program = tool.split_whitespace().next().unwrap_or(tool);
+    if line.starts_with(&format!("{}:", program)) {
+        return true;
+    }
+    let mut fields = line.split(" : ");
+    matches!(
+        (fields.next(), fields.next()),
+        (Some(_), Some(severity)) if XORRISO_ERROR_SEVERITIES.contains(&severity.trim())
+    )
+}
+
+impl fmt::Display for BurnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BurnError::MediaNotWritable(message)
+            | BurnError::DriveBusy(message)
+            | BurnError::BufferUnderrun(message)
+            | BurnError::DiscFull(message)
+            | BurnError::PermissionDenied(message)
+            | BurnError::ToolMissing(message)
+            | BurnError::Other(message) => f.write_str(message),
+            BurnError::Cancelled => f.write_str("Cancelled by user"),
+        }
+    }
+}
+
+impl std::error::Error for BurnError {}
+
+impl From<String> for BurnError {
+    fn from(message: String) -> Self {
+        BurnError::classify(&message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_tool_output() {
+        let cases = [
+            (
+                "cdrecord: Device or resource busy. Cannot open '/dev/sr0'",
+                "Drive Busy",
+            ),
+            ("wodim: Buffer underrun at 45%", "Buffer Underrun"),
+            ("Data will not fit on any disk.", "Not Enough Space"),
+            (
+                "hdiutil: burn failed - Permission denied",
+                "Permission Denied",
+            ),
+            ("drutil: The media is not writable", "Disc Not Writable"),
+            ("Burn cancelled by user", "Cancelled"),
+            ("Input/output error", "Burn Failed"),
+            (
+                "cdrecord: Cannot open SCSI driver, device closed",
+                "Burn Failed",
+            ),
+            ("Checking disc usage", "Burn Failed"),
+        ];
+        for (output, title) in cases {
+            assert_eq!(BurnError::classify(output).title(), title, "{}", output);
+        }
+    }
+
+    #[test]
+    fn test_from_exit() {
+        let lines =
+            |lines: &[&str]| -> Vec<String> { lines.iter().map(|l| l.to_string()).collect() };
+
+        // The first error line naming a cause wins over the ones after it
+        let output = lines(&[
+            "Starting to write CD/DVD at speed 8.0 in real TAO mode",
+            "cdrecord: Buffer underrun at 45%",
+            "cdrecord: Device or resource busy. Cannot close session.",
+        ]);
+        assert_eq!(
+            BurnError::from_exit("cdrecord", Some(255), &output),
+            BurnError::BufferUnderrun("cdrecord: Buffer underrun at 45%".to_string())
+        );
+
+        let output = lines(&[
+            "libburn : NOTE : Drive is busy with the previous session",
+            "xorriso : FAILURE : Image size 400000s exceeds free space on media 359844s",
+        ]);
+        assert!(matches!(
+            BurnError::from_exit("xorriso", Some(5), &output),
+            BurnError::DiscFull(_)
+        ));
+
+        // Progress and information aren't errors, whatever words they use
+        let output = lines(&[
+            "Track 01: data  650 MB",
+            "Fixating... the disc will be closed",
+            "cdrecord: Input/output error. write_g1: scsi sendcmd: no error",
+        ]);
+        assert_eq!(
+            BurnError::from_exit("cdrecord", Some(255), &output),
+            BurnError::Other(
+                "Burn process failed: cdrecord: Input/output error. write_g1: scsi sendcmd: no error"
+                    .to_string()
+            )
+        );
+
+        let error = BurnError::from_exit("cdrecord", Some(1), &lines(&["I/O error"]));
+        assert_eq!(
+            error,
+            BurnError::Other("Burn process failed: I/O error".to_string())
+        );
+        assert!(matches!(
+            BurnError::from_exit("cdrecord", Some(127), &lines(&["cdrecord: Drive is busy"])),
+            BurnError::ToolMissing(_)
+        ));
+        assert!(matches!(
+            BurnError::from_exit(
+                "hdiutil burn",
+                Some(126),
+                &lines(&["hdiutil: disc is closed"])
+            ),
+            BurnError::PermissionDenied(_)
+        ));
+    }
+
+    #[test]
+    fn test_from_io() {
+        let full = io::Error::new(io::ErrorKind::StorageFull, "No space left on device");
+        assert!(matches!(
+            BurnError::from_io("Failed to write ISO image", &full),
+            BurnError::DiscFull(_)
+        ));
+        let missing = io::Error::new(io::ErrorKind::NotFound, "No such file or directory");
+        assert_eq!(
+            BurnError::from_spawn("wodim", &missing),
+            BurnError::ToolMissing("wodim is not installed".to_string())
+        );
+    }
+
+    #[test]
+    fn test_with_hint() {
+        let busy = BurnError::DriveBusy("Device busy".to_string());
+        assert!(
+            busy.with_hint()
+                .starts_with("Device busy\n\nQuit any other app")
+        );
+        let other = BurnError::Other("Something odd".to_string());
+        assert_eq!(other.with_hint(), "Something odd");
+        assert_eq!(BurnError::Cancelled.to_string(), "Cancelled by user");
+    }
+}
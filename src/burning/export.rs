@@ -0,0 +1,557 @@
+//! Export to a folder or USB drive
+//!
+//! Copies the `_iso_staging` layout into any directory instead of writing an
+//! ISO. Staged symlinks are followed, so the destination gets real files.
+//! Names and sizes are checked against FAT32's limits, since that's what
+//! most USB sticks (and the car stereos reading them) use.
+
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Largest file FAT32 can store (4 GiB - 1)
+pub const FAT32_MAX_FILE_SIZE: u64 = 4 * 1024 * 1024 * 1024 - 1;
+
+/// Longest long file name, in UTF-16 code units
+const FAT32_MAX_NAME_UNITS: usize = 255;
+
+/// 32-byte entries one FAT32 directory can hold
+const FAT32_DIR_SLOTS: usize = 65_536;
+
+/// Characters a long file name may hold per directory entry
+const LFN_UNITS_PER_SLOT: usize = 13;
+
+/// Names Windows reserves for devices (with or without an extension)
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+const COPY_BUFFER_BYTES: usize = 1024 * 1024;
+
+/// One file to copy
+#[derive(Debug, Clone)]
+pub struct ExportFile {
+    /// Staged path (may be a symlink)
+    pub source: PathBuf,
+    /// FAT32-safe path relative to the destination
+    pub relative: PathBuf,
+    /// Size of the file the symlink points to
+    pub size: u64,
+}
+
+/// What an export will create
+#[derive(Debug, Clone, Default)]
+pub struct ExportPlan {
+    /// Directories to create (relative, parents first)
+    pub dirs: Vec<PathBuf>,
+    /// Files to copy, in staging order
+    pub files: Vec<ExportFile>,
+    /// Number of names that had to be changed for FAT32
+    pub renamed: usize,
+}
+
+impl ExportPlan {
+    /// Total bytes of file data
+    pub fn total_bytes(&self) -> u64 {
+        self.files.iter().map(|f| f.size).sum()
+    }
+
+    /// Space the files take on a volume with `block_size` allocation units
+    pub fn space_needed(&self, block_size: u64) -> u64 {
+        let block_size = block_size.max(1);
+        self.files
+            .iter()
+            .map(|f| f.size.div_ceil(block_size) * block_size)
+            .sum()
+    }
+}
+
+/// Outcome of an export
+#[derive(Debug, Clone, Default)]
+pub struct ExportResult {
+    /// Where the files went
+    pub dest_dir: PathBuf,
+    /// Files copied
+    pub files_copied: usize,
+    /// Bytes copied
+    pub bytes_copied: u64,
+    /// Names that were changed to suit FAT32
+    pub renamed: usize,
+    /// Why the export stopped, if it didn't finish
+    pub error: Option<String>,
+}
+
+impl ExportResult {
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// One-line summary for logs and dialogs
+    pub fn summary(&self) -> String {
+        if let Some(error) = &self.error {
+            return error.clone();
+        }
+        let mut summary = format!(
+            "Copied {} files ({:.1} MB) to {}.",
+            self.files_copied,
+            self.bytes_copied as f64 / 1_000_000.0,
+            self.dest_dir.display()
+        );
+        if self.renamed > 0 {
+            summary.push_str(&format!(
+                " {} name(s) were changed to suit FAT32.",
+                self.renamed
+            ));
+        }
+        summary
+    }
+}
+
+/// Make a file or folder name safe for FAT32
+///
+/// Illegal and control characters become `_`, trailing dots and spaces are
+/// dropped, device names get a `_` prefix, and long names are shortened
+/// (keeping the extension) to 255 UTF-16 units.
+pub fn fat32_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| match c {
+            '"' | '*' | '/' | ':' | '<' | '>' | '?' | '\\' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let mut cleaned = cleaned.trim_end_matches(['.', ' ']).to_string();
+    if cleaned.is_empty() {
+        cleaned = "_".to_string();
+    }
+
+    let base = cleaned.split('.').next().unwrap_or_default();
+    if RESERVED_NAMES.iter().any(|r| r.eq_ignore_ascii_case(base)) {
+        cleaned.insert(0, '_');
+    }
+
+    if utf16_len(&cleaned) <= FAT32_MAX_NAME_UNITS {
+        return cleaned;
+    }
+    let (stem, ext) = split_for_shortening(&cleaned, 0);
+    let room = FAT32_MAX_NAME_UNITS.saturating_sub(utf16_len(ext));
+    let mut stem = truncate_utf16(stem, room);
+    stem.truncate(stem.trim_end_matches(['.', ' ']).len());
+    format!("{}{}", stem, ext)
+}
+
+/// Work out the destination layout for `staging_dir`
+///
+/// # Returns
+/// * `Err(String)` if a file is too large for FAT32 or a folder holds more
+///   entries than a FAT32 directory can
+pub fn plan_export(staging_dir: &Path) -> Result<ExportPlan, String> {
+    let mut plan = ExportPlan::default();
+    plan_dir(staging_dir, Path::new(""), &mut plan)?;
+    Ok(plan)
+}
+
+fn plan_dir(dir: &Path, relative: &Path, plan: &mut ExportPlan) -> Result<(), String> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .collect();
+    entries.sort();
+
+    // FAT32 compares names case-insensitively
+    let mut taken = HashSet::new();
+    let mut slots = 2; // "." and ".."
+    let mut subdirs = Vec::new();
+    for path in entries {
+        let original = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let name = unique_name(&fat32_name(&original), &mut taken);
+        if name != original {
+            log::debug!("Renamed for FAT32: {} -> {}", original, name);
+            plan.renamed += 1;
+        }
+        slots += 1 + utf16_len(&name).div_ceil(LFN_UNITS_PER_SLOT);
+
+        // fs::metadata follows symlinks, so staged links resolve to the encoded files
+        let metadata =
+            fs::metadata(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let dest = relative.join(&name);
+        if metadata.is_dir() {
+            plan.dirs.push(dest.clone());
+            subdirs.push((path, dest));
+        } else {
+            if metadata.len() > FAT32_MAX_FILE_SIZE {
+                return Err(format!(
+                    "\"{}\" is larger than FAT32's 4 GB file size limit",
+                    original
+                ));
+            }
+            plan.files.push(ExportFile {
+                source: path,
+                relative: dest,
+                size: metadata.len(),
+            });
+        }
+    }
+
+    if slots > FAT32_DIR_SLOTS {
+        let folder = if relative.as_os_str().is_empty() {
+            "The top-level folder".to_string()
+        } else {
+            format!("\"{}\"", relative.display())
+        };
+        return Err(format!("{} has too many files for a FAT32 folder", folder));
+    }
+
+    for (path, dest) in subdirs {
+        plan_dir(&path, &dest, plan)?;
+    }
+    Ok(())
+}
+
+/// Check that `dest_dir` has room for everything in `plan`
+pub fn check_free_space(plan: &ExportPlan, dest_dir: &Path) -> Result<(), String> {
+    let (available, block_size) = free_space(dest_dir)?;
+    let needed = plan.space_needed(block_size);
+    if needed > available {
+        return Err(format!(
+            "Not enough free space on {}: {:.1} MB needed, {:.1} MB available",
+            dest_dir.display(),
+            needed as f64 / 1_000_000.0,
+            available as f64 / 1_000_000.0
+        ));
+    }
+    Ok(())
+}
+
+/// Copy a staged layout into `dest_dir`
+///
+/// # Arguments
+/// * `staging_dir` - Staging directory from `create_iso_staging`
+/// * `dest_dir` - Existing directory or mounted volume to copy into
+/// * `on_progress` - Called with the percentage of bytes copied (0-100)
+/// * `cancel_token` - Checked between buffer-sized chunks
+///
+/// # Returns
+/// * `Ok(ExportResult)` once every file has been copied
+/// * `Err(String)` on failure ("cancelled" if aborted)
+///
+/// Existing files with the same names are overwritten.
+pub fn export_staging(
+    staging_dir: &Path,
+    dest_dir: &Path,
+    on_progress: &dyn Fn(i32),
+    cancel_token: &AtomicBool,
+) -> Result<ExportResult, String> {
+    if !dest_dir.is_dir() {
+        return Err(format!("{} is not a folder", dest_dir.display()));
+    }
+    let plan = plan_export(staging_dir)?;
+    check_free_space(&plan, dest_dir)?;
+    log::info!(
+        "Exporting {} files ({} bytes) to {}",
+        plan.files.len(),
+        plan.total_bytes(),
+        dest_dir.display()
+    );
+
+    for dir in &plan.dirs {
+        let path = dest_dir.join(dir);
+        fs::create_dir_all(&path)
+            .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    }
+
+    let total = plan.total_bytes().max(1);
+    let mut copied = 0u64;
+    let mut buffer = vec![0u8; COPY_BUFFER_BYTES];
+    on_progress(0);
+    for file in &plan.files {
+        let dest = dest_dir.join(&file.relative);
+        let mut reader = File::open(&file.source)
+            .map_err(|e| format!("Failed to open {}: {}", file.source.display(), e))?;
+        let mut writer = File::create(&dest)
+            .map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+        loop {
+            if cancel_token.load(Ordering::SeqCst) {
+                drop(writer);
+                let _ = fs::remove_file(&dest);
+                return Err("Export cancelled".to_string());
+            }
+            let read = reader
+                .read(&mut buffer)
+                .map_err(|e| format!("Failed to read {}: {}", file.source.display(), e))?;
+            if read == 0 {
+                break;
+            }
+            writer
+                .write_all(&buffer[..read])
+                .map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+            copied += read as u64;
+            on_progress((copied * 100 / total) as i32);
+        }
+        writer
+            .sync_all()
+            .map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+    }
+    on_progress(100);
+
+    Ok(ExportResult {
+        dest_dir: dest_dir.to_path_buf(),
+        files_copied: plan.files.len(),
+        bytes_copied: copied,
+        renamed: plan.renamed,
+        error: None,
+    })
+}
+
+/// Free bytes and allocation unit size of the volume holding `dir`
+#[cfg(unix)]
+// The statvfs field widths differ between macOS (u32 block counts) and Linux
+#[allow(clippy::useless_conversion)]
+fn free_space(dir: &Path) -> Result<(u64, u64), String> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = std::ffi::CString::new(dir.as_os_str().as_bytes())
+        .map_err(|e| format!("Failed to check free space: {}", e))?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(format!(
+            "Failed to check free space on {}: {}",
+            dir.display(),
+            std::io::Error::last_os_error()
+        ));
+    }
+    let block_size = u64::from(stat.f_frsize);
+    Ok((u64::from(stat.f_bavail) * block_size, block_size))
+}
+
+#[cfg(not(unix))]
+fn free_space(_dir: &Path) -> Result<(u64, u64), String> {
+    // No portable way to ask; let the copy itself fail if the volume fills up
+    Ok((u64::MAX, 1))
+}
+
+/// Pick a name not yet used in this directory (case-insensitively)
+fn unique_name(name: &str, taken: &mut HashSet<String>) -> String {
+    let mut candidate = name.to_string();
+    let mut n = 2;
+    while !taken.insert(candidate.to_lowercase()) {
+        let suffix = format!(" ({})", n);
+        let (stem, ext) = split_for_shortening(name, suffix.len());
+        let room = FAT32_MAX_NAME_UNITS.saturating_sub(utf16_len(ext) + suffix.len());
+        let stem = truncate_utf16(stem, room);
+        candidate = format!("{}{}{}", stem, suffix, ext);
+        n += 1;
+    }
+    candidate
+}
+
+/// Split "name.ext" into ("name", ".ext"); names without a dot keep it all
+fn split_extension(name: &str) -> (&str, &str) {
+    match name.rfind('.') {
+        Some(i) if i > 0 => name.split_at(i),
+        _ => (name, ""),
+    }
+}
+
+/// Split `name` for shortening, keeping the extension only if it and
+/// `extra` units still leave room for some of the stem
+///
+/// Folder names come from album tags, so the text after a dot can be long.
+fn split_for_shortening(name: &str, extra: usize) -> (&str, &str) {
+    let (stem, ext) = split_extension(name);
+    if utf16_len(ext) + extra < FAT32_MAX_NAME_UNITS {
+        (stem, ext)
+    } else {
+        (name, "")
+    }
+}
+
+fn utf16_len(s: &str) -> usize {
+    s.chars().map(char::len_utf16).sum()
+}
+
+fn truncate_utf16(s: &str, max_units: usize) -> String {
+    let mut units = 0;
+    s.chars()
+        .take_while(|c| {
+            units += c.len_utf16();
+            units <= max_units
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    /// Staging layout with one album whose track is a symlink
+    fn staging(temp_dir: &TempDir) -> PathBuf {
+        let encoded = temp_dir.path().join("encoded");
+        fs::create_dir_all(&encoded).unwrap();
+        fs::write(encoded.join("Song.mp3"), vec![7u8; 3000]).unwrap();
+
+        let staging = temp_dir.path().join("_iso_staging");
+        let album = staging.join("01-What? Album");
+        fs::create_dir_all(&album).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(encoded.join("Song.mp3"), album.join("Song.mp3")).unwrap();
+        #[cfg(not(unix))]
+        fs::copy(encoded.join("Song.mp3"), album.join("Song.mp3")).unwrap();
+        fs::write(album.join("cover.jpg"), b"jpeg").unwrap();
+        staging
+    }
+
+    #[test]
+    fn test_fat32_name() {
+        assert_eq!(fat32_name("01-Album"), "01-Album");
+        assert_eq!(fat32_name("AC/DC: Live?"), "AC_DC_ Live_");
+        assert_eq!(fat32_name("Tab\there"), "Tab_here");
+        assert_eq!(fat32_name("Ends with dots..."), "Ends with dots");
+        assert_eq!(fat32_name("..."), "_");
+        assert_eq!(fat32_name("con.mp3"), "_con.mp3");
+        assert_eq!(fat32_name("Console.mp3"), "Console.mp3");
+
+        let long = format!("{}.mp3", "x".repeat(300));
+        let short = fat32_name(&long);
+        assert_eq!(utf16_len(&short), FAT32_MAX_NAME_UNITS);
+        assert!(short.ends_with("x.mp3"));
+
+        // Text after the dot that's longer than a whole name
+        let long_ext = format!("Vol. {}", "x".repeat(300));
+        let short = fat32_name(&long_ext);
+        assert_eq!(utf16_len(&short), FAT32_MAX_NAME_UNITS);
+        assert!(short.starts_with("Vol. x"));
+    }
+
+    #[test]
+    fn test_unique_name_is_case_insensitive() {
+        let mut taken = HashSet::new();
+        assert_eq!(unique_name("Song.mp3", &mut taken), "Song.mp3");
+        assert_eq!(unique_name("song.MP3", &mut taken), "song (2).MP3");
+        assert_eq!(unique_name("Song.mp3", &mut taken), "Song (3).mp3");
+
+        let long_ext = fat32_name(&format!("Vol. {}", "x".repeat(300)));
+        assert_eq!(unique_name(&long_ext, &mut taken), long_ext);
+        let second = unique_name(&long_ext, &mut taken);
+        assert_eq!(utf16_len(&second), FAT32_MAX_NAME_UNITS);
+        assert!(second.ends_with("x (2)"));
+    }
+
+    #[test]
+    fn test_plan_export() {
+        let temp_dir = TempDir::new().unwrap();
+        let plan = plan_export(&staging(&temp_dir)).unwrap();
+
+        assert_eq!(plan.dirs, vec![PathBuf::from("01-What_ Album")]);
+        let names: Vec<_> = plan.files.iter().map(|f| f.relative.clone()).collect();
+        assert_eq!(
+            names,
+            vec![
+                PathBuf::from("01-What_ Album/Song.mp3"),
+                PathBuf::from("01-What_ Album/cover.jpg"),
+            ]
+        );
+        assert_eq!(plan.renamed, 1);
+        // The symlink's target size, not the link's
+        assert_eq!(plan.total_bytes(), 3004);
+        assert_eq!(plan.space_needed(4096), 8192);
+    }
+
+    #[test]
+    fn test_plan_export_too_many_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let staging = temp_dir.path().join("_iso_staging");
+        fs::create_dir_all(&staging).unwrap();
+        // Names of 27+ units take 4 slots each: 16,400 files need 65,602
+        let prefix = "a".repeat(30);
+        for i in 0..16_400 {
+            File::create(staging.join(format!("{}{:05}", prefix, i))).unwrap();
+        }
+
+        let err = plan_export(&staging).unwrap_err();
+        assert!(err.contains("too many files"));
+    }
+
+    #[test]
+    fn test_export_staging() {
+        let temp_dir = TempDir::new().unwrap();
+        let staging = staging(&temp_dir);
+        let dest = temp_dir.path().join("usb");
+        fs::create_dir_all(&dest).unwrap();
+
+        let seen = Mutex::new(Vec::new());
+        let result = export_staging(
+            &staging,
+            &dest,
+            &|p| seen.lock().unwrap().push(p),
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+
+        let song = dest.join("01-What_ Album/Song.mp3");
+        assert!(!fs::symlink_metadata(&song).unwrap().is_symlink());
+        assert_eq!(fs::read(&song).unwrap(), vec![7u8; 3000]);
+        assert_eq!(result.files_copied, 2);
+        assert_eq!(result.bytes_copied, 3004);
+        assert_eq!(result.renamed, 1);
+        assert!(result.succeeded());
+        assert!(result.summary().contains("1 name(s) were changed"));
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.first(), Some(&0));
+        assert_eq!(seen.last(), Some(&100));
+    }
+
+    #[test]
+    fn test_export_staging_cancelled() {
+        let temp_dir = TempDir::new().unwrap();
+        let staging = staging(&temp_dir);
+        let dest = temp_dir.path().join("usb");
+        fs::create_dir_all(&dest).unwrap();
+
+        let err = export_staging(&staging, &dest, &|_| {}, &AtomicBool::new(true)).unwrap_err();
+
+        assert!(err.contains("cancelled"));
+        assert!(!dest.join("01-What_ Album/Song.mp3").exists());
+    }
+
+    #[test]
+    fn test_export_staging_missing_destination() {
+        let temp_dir = TempDir::new().unwrap();
+        let staging = staging(&temp_dir);
+
+        let err = export_staging(
+            &staging,
+            &temp_dir.path().join("nowhere"),
+            &|_| {},
+            &AtomicBool::new(false),
+        )
+        .unwrap_err();
+
+        assert!(err.contains("not a folder"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_check_free_space() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut plan = ExportPlan::default();
+        assert!(check_free_space(&plan, temp_dir.path()).is_ok());
+
+        plan.files.push(ExportFile {
+            source: PathBuf::from("huge.mp3"),
+            relative: PathBuf::from("huge.mp3"),
+            size: u64::MAX / 2,
+        });
+        let err = check_free_space(&plan, temp_dir.path()).unwrap_err();
+        assert!(err.contains("Not enough free space"));
+    }
+}
@@ -0,0 +1,158 @@
+//! Album and track details for what's printed about a disc
+//!
+//! CD-TEXT, the jewel-case insert, the disc index and the disc catalog all
+//! name each folder's album, artist and year and each track's title and
+//! artist. Album names, artists and years edited in the track editor take
+//! precedence; the tags fill in the rest.
+
+use std::path::Path;
+
+use crate::audio::metadata::{AlbumMetadata, TrackMetadata};
+use crate::core::{AudioFileInfo, MusicFolder};
+
+/// A folder's details, with its active tracks in disc order
+#[derive(Debug, Clone)]
+pub struct FolderDetails<'a> {
+    /// Album name, or the folder's name for mixtapes and untagged folders
+    pub title: String,
+    pub artist: Option<String>,
+    pub year: Option<String>,
+    pub tracks: Vec<TrackDetails<'a>>,
+}
+
+/// One track's details
+#[derive(Debug, Clone)]
+pub struct TrackDetails<'a> {
+    pub file: &'a AudioFileInfo,
+    /// Title from the tags, or the file name without its extension
+    pub title: String,
+    pub artist: Option<String>,
+    pub composer: Option<String>,
+}
+
+/// Details of `folder`, reading tags with `read_album` and `read_track`
+pub fn folder_details<'a>(
+    folder: &'a MusicFolder,
+    read_album: &dyn Fn(&Path) -> AlbumMetadata,
+    read_track: &dyn Fn(&Path) -> TrackMetadata,
+) -> FolderDetails<'a> {
+    let active = folder.active_tracks();
+
+    // Only go to the tags for what the folder doesn't already know
+    let album = match active.first() {
+        Some(first)
+            if !folder.is_mixtape()
+                && (folder.album_name.is_none()
+                    || folder.artist_name.is_none()
+                    || folder.year.is_none()) =>
+        {
+            read_album(&first.path)
+        }
+        _ => AlbumMetadata::default(),
+    };
+    let title = if folder.is_mixtape() {
+        folder.display_name()
+    } else {
+        folder
+            .album_name
+            .clone()
+            .or(album.album)
+            .unwrap_or_else(|| folder.display_name())
+    };
+
+    let tracks = active
+        .into_iter()
+        .map(|file| {
+            let meta = read_track(&file.path);
+            let title = meta.title.unwrap_or_else(|| {
+                file.path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default()
+            });
+            TrackDetails {
+                file,
+                title,
+                artist: meta.artist,
+                composer: meta.composer,
+            }
+        })
+        .collect();
+
+    FolderDetails {
+        title,
+        artist: folder.artist_name.clone().or(album.artist),
+        year: folder.year.clone().or(album.year),
+        tracks,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn folder(name: &str) -> MusicFolder {
+        let mut folder = MusicFolder::new_for_test_with_id(name);
+        folder.audio_files = ["Song.flac", "Other.flac"]
+            .iter()
+            .map(|file| AudioFileInfo {
+                path: PathBuf::from(format!("/music/{}/{}", name, file)),
+                duration: 100.0,
+                bitrate: 900,
+                size: 10_000_000,
+                codec: "flac".to_string(),
+                is_lossy: false,
+            })
+            .collect();
+        folder
+    }
+
+    fn album_tags(_: &Path) -> AlbumMetadata {
+        AlbumMetadata {
+            album: Some("Tagged Album".to_string()),
+            artist: Some("Tagged Artist".to_string()),
+            year: Some("1999".to_string()),
+        }
+    }
+
+    /// "Song" is tagged; anything else isn't
+    fn track_tags(path: &Path) -> TrackMetadata {
+        if path.ends_with("Song.flac") {
+            TrackMetadata {
+                title: Some("A Song".to_string()),
+                artist: Some("Guest".to_string()),
+                composer: Some("Writer".to_string()),
+            }
+        } else {
+            TrackMetadata::default()
+        }
+    }
+
+    #[test]
+    fn test_edits_come_before_tags() {
+        let mut album = folder("Album");
+        album.album_name = Some("Edited Album".to_string());
+        let details = folder_details(&album, &album_tags, &track_tags);
+
+        assert_eq!(details.title, "Edited Album");
+        assert_eq!(details.artist.as_deref(), Some("Tagged Artist"));
+        assert_eq!(details.year.as_deref(), Some("1999"));
+        assert_eq!(details.tracks[0].title, "A Song");
+        assert_eq!(details.tracks[0].composer.as_deref(), Some("Writer"));
+        assert_eq!(details.tracks[1].title, "Other");
+        assert_eq!(details.tracks[1].artist, None);
+    }
+
+    #[test]
+    fn test_tags_only_read_when_needed() {
+        let mut album = folder("Album");
+        album.album_name = Some("Edited Album".to_string());
+        album.artist_name = Some("Edited Artist".to_string());
+        album.year = Some("2001".to_string());
+        let details = folder_details(&album, &|_| panic!("tags read"), &track_tags);
+
+        assert_eq!(details.artist.as_deref(), Some("Edited Artist"));
+        assert_eq!(details.year.as_deref(), Some("2001"));
+    }
+}
@@ -0,0 +1,711 @@
+//! Jewel-case inserts and track listings
+//!
+//! Builds a printable insert for a disc's case from the folder list: a
+//! 120 × 120 mm front cover with the album art and a 151 × 118 mm back tray
+//! card with a numbered tracklist for each folder. Both go on one SVG page
+//! measured in millimetres (small enough for A4 and US Letter), so it prints
+//! to scale from any browser. The same listing is also written as plain
+//! text and HTML.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::folder_details::folder_details;
+use crate::audio::metadata::{AlbumMetadata, TrackMetadata};
+use crate::audio::{get_album_metadata, get_track_metadata};
+use crate::core::MusicFolder;
+
+/// Page size in mm: A4's width and US Letter's height, so it fits on both
+const PAGE_WIDTH: f64 = 210.0;
+const PAGE_HEIGHT: f64 = 279.0;
+/// Front cover size in mm
+const FRONT_SIZE: f64 = 120.0;
+/// Back tray card size in mm, spines included
+const BACK_WIDTH: f64 = 151.0;
+const BACK_HEIGHT: f64 = 118.0;
+/// Width of each spine on the tray card in mm
+const SPINE_WIDTH: f64 = 6.5;
+/// Margin around the page and between the two pieces in mm
+const MARGIN: f64 = 10.0;
+
+/// One track in a listing
+#[derive(Debug, Clone, PartialEq)]
+pub struct TracklistTrack {
+    /// Position within its folder, from 1
+    pub number: usize,
+    pub title: String,
+    /// Only set when it differs from the folder's artist
+    pub artist: Option<String>,
+    /// Length in seconds
+    pub duration: f64,
+}
+
+/// One album or mixtape in a listing
+#[derive(Debug, Clone, PartialEq)]
+pub struct TracklistFolder {
+    pub title: String,
+    pub artist: Option<String>,
+    pub year: Option<String>,
+    /// Album art image, if the folder has one
+    pub art: Option<PathBuf>,
+    pub tracks: Vec<TracklistTrack>,
+}
+
+impl TracklistFolder {
+    /// Total length of the folder's tracks in seconds
+    pub fn duration(&self) -> f64 {
+        self.tracks.iter().map(|t| t.duration).sum()
+    }
+
+    /// "Artist (Year)", with whichever parts are known
+    pub(super) fn byline(&self) -> String {
+        match (&self.artist, &self.year) {
+            (Some(artist), Some(year)) => format!("{} ({})", artist, year),
+            (Some(artist), None) => artist.clone(),
+            (None, Some(year)) => year.clone(),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+/// The folders and tracks on a disc, for inserts and listings
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tracklist {
+    /// Disc title
+    pub title: String,
+    pub folders: Vec<TracklistFolder>,
+}
+
+/// Files written by [`write_insert`]
+#[derive(Debug, Clone)]
+pub struct InsertFiles {
+    pub svg: PathBuf,
+    pub html: PathBuf,
+    pub text: PathBuf,
+}
+
+impl Tracklist {
+    /// Build the listing for the active tracks of `folders`, reading tags from disk
+    ///
+    /// The title is `disc_title` if it isn't blank, else the album name when
+    /// there's only one folder. Album names and artists edited in the track
+    /// editor take precedence over the tags.
+    pub fn for_folders(folders: &[MusicFolder], disc_title: &str) -> Self {
+        build_tracklist(
+            folders,
+            disc_title,
+            &get_album_metadata,
+            &get_track_metadata,
+        )
+    }
+
+    /// Total length of every track in seconds
+    pub fn duration(&self) -> f64 {
+        self.folders.iter().map(|f| f.duration()).sum()
+    }
+
+    /// Plain-text listing
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "{}\n{}\n",
+            self.title,
+            "=".repeat(self.title.chars().count())
+        );
+        for folder in &self.folders {
+            text.push('\n');
+            text.push_str(&folder.title);
+            let byline = folder.byline();
+            if !byline.is_empty() {
+                text.push_str(&format!(" - {}", byline));
+            }
+            text.push('\n');
+            for track in &folder.tracks {
+                text.push_str(&format!("{:>3}. {}", track.number, track.title));
+                if let Some(artist) = &track.artist {
+                    text.push_str(&format!(" - {}", artist));
+                }
+                text.push_str(&format!("  {}\n", clock(track.duration)));
+            }
+        }
+        text.push_str(&format!("\nTotal time {}\n", clock(self.duration())));
+        text
+    }
+
+    /// Standalone HTML listing
+    pub fn to_html(&self) -> String {
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+             <style>\n\
+             body {{ font-family: -apple-system, Helvetica, Arial, sans-serif; max-width: 40em; margin: 2em auto; color: #222; }}\n\
+             h2 {{ margin-bottom: 0; }}\n\
+             .byline {{ margin-top: 0.2em; color: #666; }}\n\
+             li {{ display: flex; gap: 1em; }}\n\
+             .title {{ flex: 1; }}\n\
+             .artist, .time {{ color: #666; }}\n\
+             </style>\n</head>\n<body>\n<h1>{}</h1>\n",
+            escape_xml(&self.title),
+            escape_xml(&self.title)
+        );
+        for folder in &self.folders {
+            html.push_str(&format!(
+                "<section>\n<h2>{}</h2>\n",
+                escape_xml(&folder.title)
+            ));
+            let byline = folder.byline();
+            if !byline.is_empty() {
+                html.push_str(&format!(
+                    "<p class=\"byline\">{}</p>\n",
+                    escape_xml(&byline)
+                ));
+            }
+            html.push_str("<ol>\n");
+            for track in &folder.tracks {
+                html.push_str(&format!(
+                    "<li><span class=\"title\">{}",
+                    escape_xml(&track.title)
+                ));
+                if let Some(artist) = &track.artist {
+                    html.push_str(&format!(
+                        " <span class=\"artist\">{}</span>",
+                        escape_xml(artist)
+                    ));
+                }
+                html.push_str(&format!(
+                    "</span><span class=\"time\">{}</span></li>\n",
+                    clock(track.duration)
+                ));
+            }
+            html.push_str("</ol>\n</section>\n");
+        }
+        html.push_str(&format!(
+            "<p class=\"byline\">Total time {}</p>\n</body>\n</html>\n",
+            clock(self.duration())
+        ));
+        html
+    }
+
+    /// Printable jewel-case insert: front cover above the back tray card
+    ///
+    /// Album art is embedded, so the SVG doesn't depend on the temporary
+    /// files the art was extracted to. Art that can't be read is left out.
+    pub fn to_svg(&self) -> String {
+        let front_y = MARGIN;
+        let back_y = front_y + FRONT_SIZE + MARGIN;
+
+        let mut svg = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}mm\" height=\"{h}mm\" viewBox=\"0 0 {w} {h}\" \
+             font-family=\"Helvetica, Arial, sans-serif\">\n",
+            w = PAGE_WIDTH,
+            h = PAGE_HEIGHT
+        );
+        svg.push_str(&self.svg_front(MARGIN, front_y));
+        svg.push_str(&self.svg_back(MARGIN, back_y));
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    fn svg_front(&self, x: f64, y: f64) -> String {
+        let mut svg = format!("<g transform=\"translate({} {})\">\n", x, y);
+
+        // One cover fills the front; four or more get a 2 × 2 grid
+        let art: Vec<&Path> = self
+            .folders
+            .iter()
+            .filter_map(|f| f.art.as_deref())
+            .collect();
+        let tiles: Vec<(&Path, f64, f64, f64)> = if art.len() >= 4 {
+            let half = FRONT_SIZE / 2.0;
+            vec![
+                (art[0], 0.0, 0.0, half),
+                (art[1], half, 0.0, half),
+                (art[2], 0.0, half, half),
+                (art[3], half, half, half),
+            ]
+        } else {
+            art.first()
+                .map(|a| vec![(*a, 0.0, 0.0, FRONT_SIZE)])
+                .unwrap_or_default()
+        };
+        let mut has_art = false;
+        for (path, tx, ty, size) in tiles {
+            if let Some(uri) = image_data_uri(path) {
+                svg.push_str(&format!(
+                    "<image x=\"{}\" y=\"{}\" width=\"{s}\" height=\"{s}\" preserveAspectRatio=\"xMidYMid slice\" href=\"{}\"/>\n",
+                    tx,
+                    ty,
+                    uri,
+                    s = size
+                ));
+                has_art = true;
+            }
+        }
+
+        let title = escape_xml(&truncate(&self.title, 40));
+        if has_art {
+            // Title on a band across the bottom of the art
+            svg.push_str(&format!(
+                "<rect x=\"0\" y=\"{}\" width=\"{s}\" height=\"14\" fill=\"#000\" fill-opacity=\"0.6\"/>\n\
+                 <text x=\"{}\" y=\"{}\" font-size=\"7\" fill=\"#fff\" text-anchor=\"middle\">{}</text>\n",
+                FRONT_SIZE - 14.0,
+                FRONT_SIZE / 2.0,
+                FRONT_SIZE - 4.5,
+                title,
+                s = FRONT_SIZE
+            ));
+        } else {
+            svg.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" font-size=\"9\" text-anchor=\"middle\">{}</text>\n",
+                FRONT_SIZE / 2.0,
+                FRONT_SIZE / 2.0,
+                title
+            ));
+        }
+        svg.push_str(&cut_outline(FRONT_SIZE, FRONT_SIZE));
+        svg.push_str("</g>\n");
+        svg
+    }
+
+    fn svg_back(&self, x: f64, y: f64) -> String {
+        let mut svg = format!("<g transform=\"translate({} {})\">\n", x, y);
+
+        // Spines, folded along the dashed lines, read from the outside
+        let title = escape_xml(&truncate(&self.title, 50));
+        for (spine_x, rotate) in [
+            (SPINE_WIDTH / 2.0, -90),
+            (BACK_WIDTH - SPINE_WIDTH / 2.0, 90),
+        ] {
+            svg.push_str(&format!(
+                "<text transform=\"translate({} {}) rotate({})\" font-size=\"3.5\" text-anchor=\"middle\" \
+                 dominant-baseline=\"central\">{}</text>\n",
+                spine_x,
+                BACK_HEIGHT / 2.0,
+                rotate,
+                title
+            ));
+            svg.push_str(&format!(
+                "<line x1=\"{x}\" y1=\"0\" x2=\"{x}\" y2=\"{}\" stroke=\"#999\" stroke-width=\"0.2\" stroke-dasharray=\"1 1\"/>\n",
+                BACK_HEIGHT,
+                x = if rotate < 0 {
+                    SPINE_WIDTH
+                } else {
+                    BACK_WIDTH - SPINE_WIDTH
+                }
+            ));
+        }
+
+        // Tracklist, flowed into as many columns as it needs
+        let lines = self.back_lines();
+        let inner_x = SPINE_WIDTH + 5.0;
+        let inner_width = BACK_WIDTH - 2.0 * (SPINE_WIDTH + 5.0);
+        let top = 8.0;
+        let height = BACK_HEIGHT - top - 6.0;
+        let columns = match lines.len() {
+            0..=26 => 1,
+            27..=80 => 2,
+            _ => 3,
+        };
+        let per_column = lines.len().div_ceil(columns).max(1);
+        let line_height = (height / per_column as f64).min(4.2);
+        let font_size = line_height * 0.78;
+        let gap = 4.0;
+        let column_width = (inner_width - gap * (columns - 1) as f64) / columns as f64;
+        // Rough average glyph width for proportional fonts
+        let max_chars = (column_width / (font_size * 0.5)) as usize;
+
+        for (index, line) in lines.iter().enumerate() {
+            let column = index / per_column;
+            let cx = inner_x + column as f64 * (column_width + gap);
+            let cy = top + (index % per_column) as f64 * line_height + font_size;
+            match line {
+                BackLine::Heading(text) => svg.push_str(&format!(
+                    "<text x=\"{}\" y=\"{:.2}\" font-size=\"{:.2}\" font-weight=\"bold\">{}</text>\n",
+                    cx,
+                    cy,
+                    font_size,
+                    escape_xml(&truncate(text, max_chars))
+                )),
+                BackLine::Track(text, time) => {
+                    let time_chars = time.chars().count() + 1;
+                    svg.push_str(&format!(
+                        "<text x=\"{}\" y=\"{:.2}\" font-size=\"{:.2}\">{}</text>\n\
+                         <text x=\"{:.2}\" y=\"{:.2}\" font-size=\"{:.2}\" text-anchor=\"end\" fill=\"#555\">{}</text>\n",
+                        cx,
+                        cy,
+                        font_size,
+                        escape_xml(&truncate(text, max_chars.saturating_sub(time_chars))),
+                        cx + column_width,
+                        cy,
+                        font_size,
+                        time
+                    ));
+                }
+                BackLine::Blank => {}
+            }
+        }
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-size=\"2.8\" fill=\"#555\" text-anchor=\"end\">Total time {}</text>\n",
+            BACK_WIDTH - SPINE_WIDTH - 5.0,
+            BACK_HEIGHT - 3.0,
+            clock(self.duration())
+        ));
+        svg.push_str(&cut_outline(BACK_WIDTH, BACK_HEIGHT));
+        svg.push_str("</g>\n");
+        svg
+    }
+
+    /// Lines of the tray card listing: a heading per folder, then its tracks
+    fn back_lines(&self) -> Vec<BackLine> {
+        let mut lines = Vec::new();
+        for (index, folder) in self.folders.iter().enumerate() {
+            if index > 0 {
+                lines.push(BackLine::Blank);
+            }
+            let byline = folder.byline();
+            lines.push(BackLine::Heading(if byline.is_empty() {
+                folder.title.clone()
+            } else {
+                format!("{} · {}", folder.title, byline)
+            }));
+            for track in &folder.tracks {
+                let text = match &track.artist {
+                    Some(artist) => format!("{:02}  {} – {}", track.number, track.title, artist),
+                    None => format!("{:02}  {}", track.number, track.title),
+                };
+                lines.push(BackLine::Track(text, clock(track.duration)));
+            }
+        }
+        lines
+    }
+}
+
+/// One line of the tray card listing
+enum BackLine {
+    Heading(String),
+    /// Track text and its length
+    Track(String, String),
+    Blank,
+}
+
+/// Write the insert SVG and the HTML and text listings into `dir`
+///
+/// Files are named after the disc title, e.g. "Road Trip - Insert.svg".
+/// Existing files with the same names are replaced.
+pub fn write_insert(tracklist: &Tracklist, dir: &Path) -> Result<InsertFiles, String> {
+    let stem: String = tracklist
+        .title
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            _ => c,
+        })
+        .collect();
+    let stem = match stem.trim() {
+        "" => "Disc".to_string(),
+        stem => stem.to_string(),
+    };
+
+    let files = InsertFiles {
+        svg: dir.join(format!("{} - Insert.svg", stem)),
+        html: dir.join(format!("{} - Tracklist.html", stem)),
+        text: dir.join(format!("{} - Tracklist.txt", stem)),
+    };
+    for (path, contents) in [
+        (&files.svg, tracklist.to_svg()),
+        (&files.html, tracklist.to_html()),
+        (&files.text, tracklist.to_text()),
+    ] {
+        fs::write(path, contents)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    log::info!(
+        "Wrote jewel-case insert and tracklists to {}",
+        dir.display()
+    );
+    Ok(files)
+}
+
+fn build_tracklist(
+    folders: &[MusicFolder],
+    disc_title: &str,
+    read_album: &dyn Fn(&Path) -> AlbumMetadata,
+    read_track: &dyn Fn(&Path) -> TrackMetadata,
+) -> Tracklist {
+    let mut listed = Vec::with_capacity(folders.len());
+
+    for folder in folders {
+        let details = folder_details(folder, read_album, read_track);
+        let artist = details.artist;
+        let tracks = details
+            .tracks
+            .into_iter()
+            .enumerate()
+            .map(|(index, track)| TracklistTrack {
+                number: index + 1,
+                title: track.title,
+                artist: track.artist.filter(|a| Some(a) != artist.as_ref()),
+                duration: track.file.duration,
+            })
+            .collect();
+
+        listed.push(TracklistFolder {
+            title: details.title,
+            artist,
+            year: details.year,
+            art: folder.album_art.as_ref().map(PathBuf::from),
+            tracks,
+        });
+    }
+
+    let title = match disc_title.trim() {
+        "" if listed.len() == 1 => listed[0].title.clone(),
+        "" => "Untitled".to_string(),
+        title => title.to_string(),
+    };
+    Tracklist {
+        title,
+        folders: listed,
+    }
+}
+
+/// Track length as "m:ss", or "h:mm:ss" from an hour up
+pub(super) fn clock(seconds: f64) -> String {
+    let total = seconds.round() as u64;
+    let (hours, mins, secs) = (total / 3600, total / 60 % 60, total % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, mins, secs)
+    } else {
+        format!("{}:{:02}", mins, secs)
+    }
+}
+
+/// Cut `text` to `max` characters, ending with "…" if anything was cut
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(max.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+/// Escape text for SVG and HTML
+pub(super) fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Thin outline to cut along
+fn cut_outline(width: f64, height: f64) -> String {
+    format!(
+        "<rect x=\"0\" y=\"0\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"#999\" stroke-width=\"0.2\"/>\n",
+        width, height
+    )
+}
+
+/// An image file as a base64 `data:` URI
+pub(super) fn image_data_uri(path: &Path) -> Option<String> {
+    let data = match fs::read(path) {
+        Ok(data) => data,
+        Err(e) => {
+            log::warn!("Leaving out album art {}: {}", path.display(), e);
+            return None;
+        }
+    };
+    let mime = match path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .as_deref()
+    {
+        Some("png") => "image/png",
+        _ => "image/jpeg",
+    };
+    Some(format!("data:{};base64,{}", mime, base64(&data)))
+}
+
+/// Standard base64 with padding
+pub(super) fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3F) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{AudioFileInfo, FolderKind};
+    use tempfile::TempDir;
+
+    fn folder(name: &str, tracks: &[(&str, f64)]) -> MusicFolder {
+        let mut folder = MusicFolder::new_for_test_with_id(name);
+        folder.audio_files = tracks
+            .iter()
+            .map(|(t, duration)| AudioFileInfo {
+                path: PathBuf::from(format!("/music/{}/{}.mp3", name, t)),
+                duration: *duration,
+                bitrate: 320,
+                size: 8_000_000,
+                codec: "mp3".to_string(),
+                is_lossy: true,
+            })
+            .collect();
+        folder
+    }
+
+    fn album_tags(_: &Path) -> AlbumMetadata {
+        AlbumMetadata {
+            album: Some("Tagged Album".to_string()),
+            artist: Some("Band".to_string()),
+            year: Some("1999".to_string()),
+        }
+    }
+
+    /// Tracks named "Guest ..." have their own artist
+    fn track_tags(path: &Path) -> TrackMetadata {
+        let stem = path.file_stem().unwrap().to_string_lossy().to_string();
+        TrackMetadata {
+            artist: Some(if stem.starts_with("Guest") {
+                "Guest".to_string()
+            } else {
+                "Band".to_string()
+            }),
+            title: Some(stem),
+            composer: None,
+        }
+    }
+
+    #[test]
+    fn test_build_tracklist_follows_active_tracks() {
+        let mut album = folder(
+            "album",
+            &[("One", 61.0), ("Two", 125.4), ("Guest Three", 3600.0)],
+        );
+        album.track_order = Some(vec![2, 0, 1]);
+        album.excluded_tracks = vec![PathBuf::from("/music/album/Two.mp3")];
+        let mut mixtape = folder("mix", &[("Song", 200.0)]);
+        mixtape.kind = FolderKind::Mixtape {
+            name: "Road Mix".to_string(),
+        };
+
+        let list = build_tracklist(&[album, mixtape], " ", &album_tags, &track_tags);
+
+        assert_eq!(list.title, "Untitled");
+        assert_eq!(list.folders[0].title, "Tagged Album");
+        assert_eq!(list.folders[0].byline(), "Band (1999)");
+        assert_eq!(
+            list.folders[0].tracks,
+            vec![
+                TracklistTrack {
+                    number: 1,
+                    title: "Guest Three".to_string(),
+                    artist: Some("Guest".to_string()),
+                    duration: 3600.0,
+                },
+                TracklistTrack {
+                    number: 2,
+                    title: "One".to_string(),
+                    artist: None,
+                    duration: 61.0,
+                },
+            ]
+        );
+        // Mixtapes keep their own name and don't read album tags
+        assert_eq!(list.folders[1].title, "Road Mix");
+        assert_eq!(list.folders[1].artist, None);
+        assert_eq!(list.folders[1].tracks[0].artist, Some("Band".to_string()));
+
+        let text = list.to_text();
+        assert!(text.starts_with("Untitled\n========\n"));
+        assert!(text.contains("  1. Guest Three - Guest  1:00:00\n"));
+        assert!(text.contains("  2. One  1:01\n"));
+        assert!(text.contains("Total time 1:04:21"));
+    }
+
+    #[test]
+    fn test_single_folder_title_and_escaping() {
+        let mut album = folder("album", &[("Rock & Roll", 180.0)]);
+        album.album_name = Some("<Live>".to_string());
+        album.artist_name = Some("Band".to_string());
+        album.year = Some("1970".to_string());
+
+        let list = build_tracklist(&[album], "", &album_tags, &track_tags);
+        assert_eq!(list.title, "<Live>");
+
+        let html = list.to_html();
+        assert!(html.contains("<h1>&lt;Live&gt;</h1>"));
+        assert!(html.contains("<span class=\"title\">Rock &amp; Roll</span>"));
+
+        let svg = list.to_svg();
+        assert!(svg.contains("width=\"210mm\" height=\"279mm\""));
+        assert!(svg.contains("01  Rock &amp; Roll"));
+        assert!(!svg.contains("<image"));
+    }
+
+    #[test]
+    fn test_svg_embeds_album_art() {
+        let dir = TempDir::new().unwrap();
+        let art = dir.path().join("cover.png");
+        fs::write(&art, b"png").unwrap();
+        let mut album = folder("album", &[("One", 60.0)]);
+        album.album_art = Some(art.to_string_lossy().to_string());
+
+        let list = build_tracklist(&[album], "Disc", &album_tags, &track_tags);
+        assert!(
+            list.to_svg()
+                .contains("href=\"data:image/png;base64,cG5n\"")
+        );
+    }
+
+    #[test]
+    fn test_write_insert() {
+        let dir = TempDir::new().unwrap();
+        let list = build_tracklist(
+            &[folder("a", &[("One", 60.0)])],
+            "Mix: Vol/1",
+            &album_tags,
+            &track_tags,
+        );
+
+        let files = write_insert(&list, dir.path()).unwrap();
+        assert_eq!(files.svg, dir.path().join("Mix_ Vol_1 - Insert.svg"));
+        assert!(files.svg.exists() && files.html.exists() && files.text.exists());
+        assert!(write_insert(&list, &dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_helpers() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+        assert_eq!(clock(59.6), "1:00");
+        assert_eq!(clock(3725.0), "1:02:05");
+        assert_eq!(truncate("abcdef", 4), "abc…");
+        assert_eq!(truncate("abc", 4), "abc");
+    }
+}
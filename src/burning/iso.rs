@@ -1,10 +1,16 @@
-//! ISO creation using hdiutil
-//! (Future: Stage 9)
+//! ISO creation
+//!
+//! Images are written natively by [`super::iso9660`], so this works the same
+//! on every platform.
 #![allow(dead_code)]
 
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::error::BurnError;
+use super::iso9660::{IsoOptions, write_iso_image_with_progress};
+use crate::core::ConversionState;
 
 /// Result of ISO creation
 #[derive(Debug)]
@@ -12,7 +18,7 @@ pub struct IsoResult {
     pub iso_path: PathBuf,
 }
 
-/// Create an ISO image from a directory using hdiutil
+/// Create an ISO image from a directory
 ///
 /// # Arguments
 /// * `source_dir` - Directory containing files to burn (may contain symlinks)
@@ -20,98 +26,120 @@ pub struct IsoResult {
 ///
 /// # Returns
 /// * `Ok(IsoResult)` with the path to the created ISO
-/// * `Err(String)` with error message on failure
+/// * `Err(BurnError)` on failure
 ///
 /// # Note
-/// If the source directory contains symlinks, they will be dereferenced
-/// (copied as real files) before creating the ISO, since hdiutil doesn't
-/// follow symlinks properly.
-pub fn create_iso(source_dir: &Path, volume_label: &str) -> Result<IsoResult, String> {
+/// Symlinks in the source directory are followed while the image is written,
+/// so the staging directory never needs to be copied first.
+pub fn create_iso(source_dir: &Path, volume_label: &str) -> Result<IsoResult, BurnError> {
     let iso_path = source_dir.parent().unwrap_or(source_dir).join("mp3cd.iso");
+    create_iso_at(source_dir, &iso_path, volume_label)
+}
+
+/// Create an ISO image from a directory at a specific path
+///
+/// Used for multi-disc burns, where every disc needs its own image.
+pub fn create_iso_at(
+    source_dir: &Path,
+    iso_path: &Path,
+    volume_label: &str,
+) -> Result<IsoResult, BurnError> {
+    create_iso_with_progress(
+        source_dir,
+        iso_path,
+        volume_label,
+        &|_| {},
+        &AtomicBool::new(false),
+    )
+}
+
+/// Create an ISO image at `iso_path`, reporting progress as it's written
+///
+/// `on_progress` gets the percentage written (0-100), and `cancel_token`
+/// stops the write ([`BurnError::Cancelled`]). A cancelled or failed image
+/// is removed.
+pub fn create_iso_with_progress(
+    source_dir: &Path,
+    iso_path: &Path,
+    volume_label: &str,
+    on_progress: &dyn Fn(i32),
+    cancel_token: &AtomicBool,
+) -> Result<IsoResult, BurnError> {
+    let iso_path = iso_path.to_path_buf();
 
     // Remove existing ISO file if it exists
     if iso_path.exists() {
         log::info!("Removing existing ISO file at {}", iso_path.display());
-        fs::remove_file(&iso_path).map_err(|e| format!("Failed to remove existing ISO: {}", e))?;
+        fs::remove_file(&iso_path)
+            .map_err(|e| BurnError::from_io("Failed to remove existing ISO", &e))?;
     }
 
-    // Check if source directory contains symlinks - if so, dereference them
-    let effective_source = if contains_symlinks(source_dir) {
-        let dereferenced_dir = source_dir
-            .parent()
-            .unwrap_or(source_dir)
-            .join("_iso_dereferenced");
-
-        // Clean up any existing dereferenced directory
-        if dereferenced_dir.exists() {
-            fs::remove_dir_all(&dereferenced_dir)
-                .map_err(|e| format!("Failed to clean dereferenced directory: {}", e))?;
-        }
-
-        log::info!(
-            "Dereferencing symlinks from {} to {}",
-            source_dir.display(),
-            dereferenced_dir.display()
+    if contains_symlinks(source_dir) {
+        log::debug!(
+            "{} contains symlinks - following them while writing the ISO",
+            source_dir.display()
         );
+    }
 
-        // Use cp -RL to copy with symlink dereferencing
-        // -R = recursive, -L = follow symbolic links (dereference them)
-        let output = Command::new("cp")
-            .args([
-                "-RL",
-                source_dir.to_str().unwrap(),
-                dereferenced_dir.to_str().unwrap(),
-            ])
-            .output()
-            .map_err(|e| format!("Failed to dereference symlinks with cp: {}", e))?;
-
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("Failed to dereference symlinks: {}", error_msg));
-        }
+    log::info!(
+        "Creating ISO from {} to {} with volume label '{}'",
+        source_dir.display(),
+        iso_path.display(),
+        volume_label
+    );
 
-        // cp -RL copies INTO the destination, so the actual content is in a subdirectory
-        // We need to use the subdirectory that matches the source name
-        let source_name = source_dir.file_name().unwrap_or_default();
-        let actual_dereferenced = dereferenced_dir.join(source_name);
-        if actual_dereferenced.exists() {
-            actual_dereferenced
+    let size = write_iso_image_with_progress(
+        source_dir,
+        &iso_path,
+        &IsoOptions::new(volume_label),
+        on_progress,
+        cancel_token,
+    )
+    .map_err(|e| {
+        if cancel_token.load(Ordering::SeqCst) {
+            BurnError::Cancelled
         } else {
-            dereferenced_dir
+            BurnError::classify(&e)
         }
-    } else {
-        source_dir.to_path_buf()
-    };
+    })?;
 
     log::info!(
-        "Creating ISO from {} to {} with volume label '{}'",
-        effective_source.display(),
+        "ISO created successfully at {} ({} bytes)",
         iso_path.display(),
-        volume_label
+        size
     );
+    Ok(IsoResult { iso_path })
+}
 
-    // Create ISO using hdiutil makehybrid with Joliet extensions and custom volume label
-    let output = Command::new("hdiutil")
-        .args([
-            "makehybrid",
-            "-iso",
-            "-joliet",
-            "-joliet-volume-name",
-            volume_label,
-            "-o",
-            iso_path.to_str().unwrap(),
-            effective_source.to_str().unwrap(),
-        ])
-        .output()
-        .map_err(|e| format!("Failed to execute hdiutil makehybrid: {}", e))?;
-
-    if output.status.success() {
-        log::info!("ISO created successfully at {}", iso_path.display());
-        Ok(IsoResult { iso_path })
-    } else {
-        let error_msg = String::from_utf8_lossy(&output.stderr);
-        Err(format!("Failed to create ISO: {}", error_msg))
+/// Write a staging directory to `iso_path` as part of a burn
+///
+/// Progress goes to `state.burn_progress` and the state's cancel flag stops
+/// the write. The staging directory only holds links to the encoded files,
+/// so it's removed afterwards, whether or not the image was written.
+pub fn create_iso_from_staging(
+    staging_dir: &Path,
+    iso_path: &Path,
+    volume_label: &str,
+    state: &ConversionState,
+) -> Result<IsoResult, BurnError> {
+    state.set_burn_progress(0);
+    let result = create_iso_with_progress(
+        staging_dir,
+        iso_path,
+        volume_label,
+        &|percent| state.set_burn_progress(percent),
+        &state.cancel_requested,
+    );
+    state.set_burn_progress(-1);
+
+    if let Err(e) = fs::remove_dir_all(staging_dir) {
+        log::warn!(
+            "Failed to remove staging directory {}: {}",
+            staging_dir.display(),
+            e
+        );
     }
+    result
 }
 
 /// Check if a directory contains any symlinks (recursively)
@@ -275,6 +303,70 @@ mod tests {
         assert!(debug_str.contains("test.iso"));
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn test_create_iso_from_staging_with_symlinks() {
+        let temp_dir = TempDir::new().unwrap();
+        let encoded = temp_dir.path().join("encoded");
+        let staging = temp_dir.path().join("_iso_staging");
+        fs::create_dir_all(&encoded).unwrap();
+        fs::create_dir_all(staging.join("01-Album")).unwrap();
+        fs::write(encoded.join("track.mp3"), b"mp3 data").unwrap();
+        std::os::unix::fs::symlink(
+            encoded.join("track.mp3"),
+            staging.join("01-Album/01-track.mp3"),
+        )
+        .unwrap();
+
+        let result = create_iso(&staging, "Test CD").unwrap();
+
+        assert_eq!(result.iso_path, temp_dir.path().join("mp3cd.iso"));
+        let image = fs::read(&result.iso_path).unwrap();
+        assert_eq!(&image[16 * 2048 + 1..16 * 2048 + 6], b"CD001");
+        assert!(image.windows(8).any(|w| w == b"mp3 data"));
+        // No dereferenced copy is made any more
+        assert!(!temp_dir.path().join("_iso_dereferenced").exists());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_create_iso_from_staging_reports_progress_and_cleans_up() {
+        let temp_dir = TempDir::new().unwrap();
+        let encoded = temp_dir.path().join("encoded");
+        let staging = temp_dir.path().join("_iso_staging");
+        fs::create_dir_all(&encoded).unwrap();
+        fs::create_dir_all(&staging).unwrap();
+        fs::write(encoded.join("track.mp3"), vec![1u8; 100_000]).unwrap();
+        std::os::unix::fs::symlink(encoded.join("track.mp3"), staging.join("track.mp3")).unwrap();
+        let iso_path = temp_dir.path().join("mp3cd.iso");
+
+        let state = ConversionState::new();
+        let result = create_iso_from_staging(&staging, &iso_path, "Test", &state).unwrap();
+
+        assert!(result.iso_path.exists());
+        assert!(!staging.exists());
+        // The link target is untouched
+        assert!(encoded.join("track.mp3").exists());
+        assert_eq!(state.get_burn_progress(), -1);
+    }
+
+    #[test]
+    fn test_create_iso_from_staging_cancelled() {
+        let temp_dir = TempDir::new().unwrap();
+        let staging = temp_dir.path().join("_iso_staging");
+        fs::create_dir_all(&staging).unwrap();
+        fs::write(staging.join("track.mp3"), b"mp3 data").unwrap();
+        let iso_path = temp_dir.path().join("mp3cd.iso");
+
+        let state = ConversionState::new();
+        state.request_cancel();
+        let result = create_iso_from_staging(&staging, &iso_path, "Test", &state);
+
+        assert_eq!(result.unwrap_err(), BurnError::Cancelled);
+        assert!(!iso_path.exists());
+        assert!(!staging.exists());
+    }
+
     #[test]
     fn test_contains_symlinks_nonexistent() {
         let result = contains_symlinks(Path::new("/nonexistent/path/does/not/exist"));
@@ -0,0 +1,1730 @@
+//! Native ISO 9660 image writer
+//!
+//! Builds an ISO 9660 image with Joliet and Rock Ridge extensions directly
+//! from a directory tree, so no external tools are needed. Symlinks are
+//! followed while streaming file data, which lets the `_iso_staging` tree be
+//! written as-is without dereferencing it first. Progress and cancellation
+//! are checked as the image is written.
+//!
+//! [`read_file_table`] goes the other way and lists where each file lives in
+//! an existing image, which is what burn verification needs.
+
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use chrono::{DateTime, Datelike, Timelike, Utc};
+
+/// Logical sector size used for every ISO 9660 structure
+pub const SECTOR_SIZE: u64 = 2048;
+
+/// Sectors reserved for the system area before the volume descriptors
+const SYSTEM_AREA_SECTORS: u32 = 16;
+
+/// Maximum directory depth allowed by ISO 9660 (including the root)
+const MAX_DEPTH: usize = 8;
+
+/// Maximum length of a level 2 file identifier (name + "." + extension)
+const ISO_FILE_NAME_MAX: usize = 30;
+
+/// Maximum length of a level 2 directory identifier
+const ISO_DIR_NAME_MAX: usize = 31;
+
+/// Maximum length of a Joliet identifier in UCS-2 code units
+const JOLIET_NAME_MAX: usize = 64;
+
+/// Application identifier written into both volume descriptors
+const APPLICATION_ID: &str = "MP3 CD BURNER";
+
+const RR_EXTENSION_ID: &str = "RRIP_1991A";
+const RR_EXTENSION_DESCRIPTOR: &str =
+    "THE ROCK RIDGE INTERCHANGE PROTOCOL PROVIDES SUPPORT FOR POSIX FILE SYSTEM SEMANTICS";
+const RR_EXTENSION_SOURCE: &str = "PLEASE CONTACT DISC PUBLISHER FOR SPECIFICATION SOURCE.  SEE PUBLISHER IDENTIFIER IN PRIMARY VOLUME DESCRIPTOR FOR CONTACT INFORMATION.";
+
+/// Bytes before the name in a Rock Ridge "NM" entry
+const RR_NM_HEADER: usize = 5;
+
+/// Most name bytes one "NM" entry can hold (its length is a single byte)
+const RR_NM_MAX: usize = 255 - RR_NM_HEADER;
+
+/// Length of a SUSP "CE" entry
+const RR_CE_LEN: usize = 28;
+
+/// Options controlling how an image is written
+#[derive(Debug, Clone)]
+pub struct IsoOptions {
+    /// Volume label for the primary and Joliet volume descriptors
+    pub volume_label: String,
+    /// Whether to add Rock Ridge entries to the primary directory tree
+    pub rock_ridge: bool,
+}
+
+impl IsoOptions {
+    /// Default options for the given volume label (Joliet + Rock Ridge)
+    pub fn new(volume_label: &str) -> Self {
+        Self {
+            volume_label: volume_label.to_string(),
+            rock_ridge: true,
+        }
+    }
+}
+
+/// Write an ISO 9660 image of `source_dir` to `iso_path`
+///
+/// The image contains a primary ISO 9660 (level 2) tree, a Joliet tree with
+/// the original (Unicode) names, and optionally Rock Ridge name/permission
+/// entries. Symlinks in `source_dir` are followed.
+///
+/// # Returns
+/// * `Ok(u64)` with the size of the written image in bytes
+/// * `Err(String)` with error message on failure (the partial image is removed)
+pub fn write_iso_image(
+    source_dir: &Path,
+    iso_path: &Path,
+    options: &IsoOptions,
+) -> Result<u64, String> {
+    write_iso_image_with_progress(
+        source_dir,
+        iso_path,
+        options,
+        &|_| {},
+        &AtomicBool::new(false),
+    )
+}
+
+/// Write an ISO image like [`write_iso_image`], reporting progress
+///
+/// # Arguments
+/// * `on_progress` - Called with the percentage of the image written (0-100)
+/// * `cancel_token` - Checked before every write
+///
+/// # Returns
+/// * `Ok(u64)` with the size of the written image in bytes
+/// * `Err(String)` on failure ("cancelled" if aborted); the partial image is
+///   removed either way
+pub fn write_iso_image_with_progress(
+    source_dir: &Path,
+    iso_path: &Path,
+    options: &IsoOptions,
+    on_progress: &dyn Fn(i32),
+    cancel_token: &AtomicBool,
+) -> Result<u64, String> {
+    let image = IsoImage::build(source_dir, options)?;
+    let total_bytes = image.total_sectors as u64 * SECTOR_SIZE;
+
+    let file = File::create(iso_path).map_err(|e| format!("Failed to create ISO file: {}", e))?;
+    let mut out = ProgressWriter {
+        inner: BufWriter::new(file),
+        written: 0,
+        total: total_bytes.max(1),
+        percent: -1,
+        on_progress,
+        cancel_token,
+    };
+
+    let result = image.write_to(&mut out).and_then(|_| out.flush());
+    if let Err(e) = result {
+        drop(out);
+        let _ = fs::remove_file(iso_path);
+        if cancel_token.load(Ordering::SeqCst) {
+            return Err("ISO creation cancelled".to_string());
+        }
+        return Err(format!("Failed to write ISO image: {}", e));
+    }
+    on_progress(100);
+
+    Ok(total_bytes)
+}
+
+/// Size in bytes of the image [`write_iso_image`] would write for `files`
+///
+/// `files` lists every file by its path relative to the image root, with its
+/// size; directories come from the paths. Nothing is read from disk, so the
+/// files needn't exist yet. Names, directory records, path tables, the Joliet
+/// tree, Rock Ridge entries and sector rounding are laid out exactly as when
+/// writing, so the result matches the written image byte for byte.
+pub fn image_size(files: &[(PathBuf, u64)], options: &IsoOptions) -> Result<u64, String> {
+    let image = IsoImage::from_nodes(plan_tree(files)?, options);
+    Ok(image.total_sectors as u64 * SECTOR_SIZE)
+}
+
+/// Writer that reports how much of the image is written and stops when
+/// cancelled
+struct ProgressWriter<'a, W: Write> {
+    inner: W,
+    written: u64,
+    total: u64,
+    /// Last percentage reported, so each one is only sent once
+    percent: i32,
+    on_progress: &'a dyn Fn(i32),
+    cancel_token: &'a AtomicBool,
+}
+
+impl<W: Write> Write for ProgressWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.cancel_token.load(Ordering::SeqCst) {
+            return Err(io::Error::other("cancelled"));
+        }
+        let written = self.inner.write(buf)?;
+        self.written += written as u64;
+        let percent = (self.written * 100 / self.total).min(100) as i32;
+        if percent != self.percent {
+            self.percent = percent;
+            (self.on_progress)(percent);
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A file's location inside an ISO image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IsoFileEntry {
+    /// Path relative to the image root, using `/` separators
+    pub path: String,
+    /// First sector of the file data
+    pub sector: u32,
+    /// File size in bytes
+    pub size: u64,
+}
+
+impl IsoFileEntry {
+    /// Sectors occupied by the file data
+    pub fn sector_count(&self) -> u32 {
+        sectors_for(self.size)
+    }
+}
+
+/// List every file in an ISO image with its data extent
+///
+/// Joliet names are used when the image has a Joliet tree, otherwise the
+/// primary ISO 9660 names (without the `;1` version suffix).
+pub fn read_file_table(iso_path: &Path) -> Result<Vec<IsoFileEntry>, String> {
+    let mut file =
+        File::open(iso_path).map_err(|e| format!("Failed to open ISO image: {}", e))?;
+
+    let mut primary_root = None;
+    let mut joliet_root = None;
+    let mut sector = SYSTEM_AREA_SECTORS;
+    loop {
+        let descriptor = read_sectors(&mut file, sector, 1)
+            .map_err(|e| format!("Failed to read volume descriptor: {}", e))?;
+        if &descriptor[1..6] != b"CD001" {
+            return Err("Not an ISO 9660 image".to_string());
+        }
+        let root = (le_u32(&descriptor[158..]), le_u32(&descriptor[166..]));
+        match descriptor[0] {
+            1 => primary_root = Some(root),
+            2 if &descriptor[88..90] == b"%/" => joliet_root = Some(root),
+            255 => break,
+            _ => {}
+        }
+        sector += 1;
+    }
+
+    let (root, ns) = match (joliet_root, primary_root) {
+        (Some(root), _) => (root, Namespace::Joliet),
+        (None, Some(root)) => (root, Namespace::Primary),
+        (None, None) => return Err("ISO image has no primary volume descriptor".to_string()),
+    };
+
+    let mut entries = Vec::new();
+    let mut visited = HashSet::new();
+    read_dir_entries(&mut file, root, "", ns, 1, &mut visited, &mut entries)?;
+    entries.sort_by_key(|entry| entry.sector);
+    Ok(entries)
+}
+
+/// Walk one directory extent, recursing into subdirectories
+fn read_dir_entries(
+    file: &mut File,
+    (extent, size): (u32, u32),
+    prefix: &str,
+    ns: Namespace,
+    depth: usize,
+    visited: &mut HashSet<u32>,
+    entries: &mut Vec<IsoFileEntry>,
+) -> Result<(), String> {
+    if depth > MAX_DEPTH || !visited.insert(extent) {
+        return Err("Invalid directory structure in ISO image".to_string());
+    }
+
+    let data = read_sectors(file, extent, sectors_for(size as u64))
+        .map_err(|e| format!("Failed to read ISO directory: {}", e))?;
+    let sector = SECTOR_SIZE as usize;
+    let mut pos = 0;
+    while pos < size as usize {
+        let len = data[pos] as usize;
+        if len == 0 {
+            // Records never cross sectors; the rest of this one is padding
+            pos = (pos / sector + 1) * sector;
+            continue;
+        }
+        if len < 34 || pos + len > data.len() {
+            return Err("Invalid directory record in ISO image".to_string());
+        }
+        let record = &data[pos..pos + len];
+        pos += len;
+
+        let id_len = record[32] as usize;
+        let id = &record[33..(33 + id_len).min(len)];
+        if id == [0] || id == [1] {
+            continue;
+        }
+
+        let name = decode_identifier(id, ns);
+        let path = if prefix.is_empty() {
+            name
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        let child = (le_u32(&record[2..]), le_u32(&record[10..]));
+        if record[25] & 0x02 != 0 {
+            read_dir_entries(file, child, &path, ns, depth + 1, visited, entries)?;
+        } else {
+            entries.push(IsoFileEntry {
+                path,
+                sector: child.0,
+                size: child.1 as u64,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Turn a directory record identifier back into a file name
+fn decode_identifier(id: &[u8], ns: Namespace) -> String {
+    match ns {
+        Namespace::Joliet => {
+            let units: Vec<u16> = id
+                .chunks_exact(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        Namespace::Primary => {
+            let name = String::from_utf8_lossy(id);
+            let name = name.split(';').next().unwrap_or_default();
+            name.strip_suffix('.').unwrap_or(name).to_string()
+        }
+    }
+}
+
+fn read_sectors(file: &mut File, first: u32, count: u32) -> io::Result<Vec<u8>> {
+    let mut buf = vec![0u8; count as usize * SECTOR_SIZE as usize];
+    file.seek(SeekFrom::Start(first as u64 * SECTOR_SIZE))?;
+    file.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn le_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+/// Which directory hierarchy a structure belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Namespace {
+    Primary,
+    Joliet,
+}
+
+/// The kind of directory record being written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RecordKind {
+    /// The "." entry of a directory
+    Current,
+    /// The ".." entry of a directory
+    Parent,
+    /// A regular file or subdirectory entry
+    Entry,
+}
+
+#[derive(Debug)]
+enum NodeKind {
+    Dir { children: Vec<usize> },
+    File { source: PathBuf, size: u64 },
+}
+
+/// A file or directory in the image, stored in a flat arena (root = 0)
+#[derive(Debug)]
+struct Node {
+    /// Original name on disk (used for Rock Ridge)
+    name: String,
+    /// ISO 9660 identifier, without the ";1" version suffix
+    iso_name: String,
+    /// Joliet identifier
+    joliet_name: String,
+    parent: usize,
+    kind: NodeKind,
+    modified: DateTime<Utc>,
+    /// File data extent, or the primary directory extent for directories
+    extent: u32,
+    /// Size of the primary directory extent in bytes
+    dir_size: u32,
+    /// Joliet directory extent (directories only)
+    joliet_extent: u32,
+    /// Size of the Joliet directory extent in bytes
+    joliet_dir_size: u32,
+    /// Where the rest of a long Rock Ridge name starts in the continuation area
+    name_continuation: u32,
+}
+
+impl Node {
+    fn new(name: String, parent: usize, kind: NodeKind, modified: DateTime<Utc>) -> Self {
+        Self {
+            name,
+            iso_name: String::new(),
+            joliet_name: String::new(),
+            parent,
+            kind,
+            modified,
+            extent: 0,
+            dir_size: 0,
+            joliet_extent: 0,
+            joliet_dir_size: 0,
+            name_continuation: 0,
+        }
+    }
+
+    fn is_dir(&self) -> bool {
+        matches!(self.kind, NodeKind::Dir { .. })
+    }
+
+    fn children(&self) -> &[usize] {
+        match &self.kind {
+            NodeKind::Dir { children } => children,
+            NodeKind::File { .. } => &[],
+        }
+    }
+
+    fn data_len(&self) -> u64 {
+        match self.kind {
+            NodeKind::File { size, .. } => size,
+            NodeKind::Dir { .. } => 0,
+        }
+    }
+}
+
+/// A fully laid-out image, ready to be written
+struct IsoImage {
+    nodes: Vec<Node>,
+    volume_label: String,
+    rock_ridge: bool,
+    created: DateTime<Utc>,
+    primary_order: Vec<usize>,
+    joliet_order: Vec<usize>,
+    /// First sector of the Rock Ridge continuation area
+    ce_sector: u32,
+    /// The root's "ER" entry, then the rest of every name too long for its
+    /// directory record
+    continuation: Vec<u8>,
+    path_table_size: u32,
+    joliet_path_table_size: u32,
+    l_path_table: u32,
+    m_path_table: u32,
+    joliet_l_path_table: u32,
+    joliet_m_path_table: u32,
+    total_sectors: u32,
+}
+
+impl IsoImage {
+    fn build(source_dir: &Path, options: &IsoOptions) -> Result<Self, String> {
+        Ok(Self::from_nodes(scan_tree(source_dir)?, options))
+    }
+
+    fn from_nodes(mut nodes: Vec<Node>, options: &IsoOptions) -> Self {
+        assign_names(&mut nodes);
+
+        let mut image = Self {
+            nodes,
+            volume_label: options.volume_label.clone(),
+            rock_ridge: options.rock_ridge,
+            created: Utc::now(),
+            primary_order: Vec::new(),
+            joliet_order: Vec::new(),
+            ce_sector: 0,
+            continuation: Vec::new(),
+            path_table_size: 0,
+            joliet_path_table_size: 0,
+            l_path_table: 0,
+            m_path_table: 0,
+            joliet_l_path_table: 0,
+            joliet_m_path_table: 0,
+            total_sectors: 0,
+        };
+        image.primary_order = image.path_table_order(Namespace::Primary);
+        image.joliet_order = image.path_table_order(Namespace::Joliet);
+        image.layout();
+        image
+    }
+
+    /// Assign sector locations to every structure in the image
+    fn layout(&mut self) {
+        // System area + primary descriptor + Joliet descriptor + terminator
+        let mut next = SYSTEM_AREA_SECTORS + 3;
+
+        // Path table sizes don't depend on extents, so they can be measured first
+        self.path_table_size = self.path_table(Namespace::Primary, false).len() as u32;
+        self.joliet_path_table_size = self.path_table(Namespace::Joliet, false).len() as u32;
+
+        let table_sectors = sectors_for(self.path_table_size as u64);
+        self.l_path_table = next;
+        next += table_sectors;
+        self.m_path_table = next;
+        next += table_sectors;
+
+        let joliet_table_sectors = sectors_for(self.joliet_path_table_size as u64);
+        self.joliet_l_path_table = next;
+        next += joliet_table_sectors;
+        self.joliet_m_path_table = next;
+        next += joliet_table_sectors;
+
+        for i in 0..self.primary_order.len() {
+            let dir = self.primary_order[i];
+            let size = pack_records(&self.directory_records(dir, Namespace::Primary)).len() as u32;
+            self.nodes[dir].extent = next;
+            self.nodes[dir].dir_size = size;
+            next += sectors_for(size as u64);
+        }
+
+        // Readers that scan sequentially expect continuation areas after the
+        // directory that references them
+        if self.rock_ridge {
+            self.layout_continuation();
+            self.ce_sector = next;
+            next += sectors_for(self.continuation.len() as u64);
+        }
+
+        for i in 0..self.joliet_order.len() {
+            let dir = self.joliet_order[i];
+            let size = pack_records(&self.directory_records(dir, Namespace::Joliet)).len() as u32;
+            self.nodes[dir].joliet_extent = next;
+            self.nodes[dir].joliet_dir_size = size;
+            next += sectors_for(size as u64);
+        }
+
+        // File data is shared by both trees and laid out in scan order,
+        // which keeps each folder's tracks contiguous on disc
+        for node in self.nodes.iter_mut() {
+            let size = node.data_len();
+            if size > 0 {
+                node.extent = next;
+                next += sectors_for(size);
+            }
+        }
+
+        self.total_sectors = next;
+    }
+
+    fn write_to<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        out.write_all(&vec![
+            0u8;
+            (SYSTEM_AREA_SECTORS as u64 * SECTOR_SIZE) as usize
+        ])?;
+        out.write_all(&self.volume_descriptor(Namespace::Primary))?;
+        out.write_all(&self.volume_descriptor(Namespace::Joliet))?;
+
+        let mut terminator = vec![0u8; SECTOR_SIZE as usize];
+        terminator[0] = 255;
+        terminator[1..6].copy_from_slice(b"CD001");
+        terminator[6] = 1;
+        out.write_all(&terminator)?;
+
+        for ns in [Namespace::Primary, Namespace::Joliet] {
+            write_padded(out, &self.path_table(ns, false))?;
+            write_padded(out, &self.path_table(ns, true))?;
+        }
+
+        for &dir in &self.primary_order {
+            out.write_all(&pack_records(
+                &self.directory_records(dir, Namespace::Primary),
+            ))?;
+        }
+        if self.rock_ridge {
+            write_padded(out, &self.continuation)?;
+        }
+        for &dir in &self.joliet_order {
+            out.write_all(&pack_records(
+                &self.directory_records(dir, Namespace::Joliet),
+            ))?;
+        }
+
+        for node in &self.nodes {
+            match &node.kind {
+                NodeKind::File { source, size } if *size > 0 => stream_file(out, source, *size)?,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fill the continuation area, keeping each entry's part within a sector
+    fn layout_continuation(&mut self) {
+        let sector = SECTOR_SIZE as usize;
+        let mut area = rr_er();
+        for index in 1..self.nodes.len() {
+            let id_len = self.identifier(index, Namespace::Primary).len();
+            let (_, rest) = self.rock_ridge_name(index, id_len);
+            if rest.is_empty() {
+                continue;
+            }
+            if area.len() % sector + rest.len() > sector {
+                area.resize(area.len().next_multiple_of(sector), 0);
+            }
+            self.nodes[index].name_continuation = area.len() as u32;
+            area.extend_from_slice(&rest);
+        }
+        self.continuation = area;
+    }
+
+    /// Directories in path table order (breadth-first, sorted by identifier)
+    fn path_table_order(&self, ns: Namespace) -> Vec<usize> {
+        let mut order = vec![0];
+        let mut i = 0;
+        while i < order.len() {
+            for child in self.sorted_children(order[i], ns) {
+                if self.nodes[child].is_dir() {
+                    order.push(child);
+                }
+            }
+            i += 1;
+        }
+        order
+    }
+
+    fn sorted_children(&self, dir: usize, ns: Namespace) -> Vec<usize> {
+        let mut children = self.nodes[dir].children().to_vec();
+        children.sort_by_key(|&child| self.identifier(child, ns));
+        children
+    }
+
+    /// On-disc identifier bytes for a node in the given namespace
+    fn identifier(&self, index: usize, ns: Namespace) -> Vec<u8> {
+        let node = &self.nodes[index];
+        match ns {
+            Namespace::Primary if node.is_dir() => node.iso_name.as_bytes().to_vec(),
+            Namespace::Primary => format!("{};1", node.iso_name).into_bytes(),
+            Namespace::Joliet => ucs2_bytes(&node.joliet_name),
+        }
+    }
+
+    fn path_table(&self, ns: Namespace, big_endian: bool) -> Vec<u8> {
+        let order = match ns {
+            Namespace::Primary => &self.primary_order,
+            Namespace::Joliet => &self.joliet_order,
+        };
+
+        let mut table = Vec::new();
+        for &dir in order {
+            let node = &self.nodes[dir];
+            let id = if dir == 0 {
+                vec![0u8]
+            } else {
+                self.identifier(dir, ns)
+            };
+            let extent = match ns {
+                Namespace::Primary => node.extent,
+                Namespace::Joliet => node.joliet_extent,
+            };
+            let parent_number =
+                order.iter().position(|&d| d == node.parent).unwrap_or(0) as u16 + 1;
+
+            table.push(id.len() as u8);
+            table.push(0);
+            if big_endian {
+                table.extend_from_slice(&extent.to_be_bytes());
+                table.extend_from_slice(&parent_number.to_be_bytes());
+            } else {
+                table.extend_from_slice(&extent.to_le_bytes());
+                table.extend_from_slice(&parent_number.to_le_bytes());
+            }
+            table.extend_from_slice(&id);
+            if id.len() % 2 == 1 {
+                table.push(0);
+            }
+        }
+        table
+    }
+
+    /// All directory records for a directory, including "." and ".."
+    fn directory_records(&self, dir: usize, ns: Namespace) -> Vec<Vec<u8>> {
+        let parent = self.nodes[dir].parent;
+        let mut records = vec![
+            self.record(dir, &[0], RecordKind::Current, ns),
+            self.record(parent, &[1], RecordKind::Parent, ns),
+        ];
+        for child in self.sorted_children(dir, ns) {
+            let id = self.identifier(child, ns);
+            records.push(self.record(child, &id, RecordKind::Entry, ns));
+        }
+        records
+    }
+
+    fn record(&self, index: usize, id: &[u8], kind: RecordKind, ns: Namespace) -> Vec<u8> {
+        let node = &self.nodes[index];
+        let (extent, size, flags) = match (node.is_dir(), ns) {
+            (true, Namespace::Primary) => (node.extent, node.dir_size, 0x02),
+            (true, Namespace::Joliet) => (node.joliet_extent, node.joliet_dir_size, 0x02),
+            (false, _) => (node.extent, node.data_len() as u32, 0x00),
+        };
+        let system_use = if ns == Namespace::Primary && self.rock_ridge {
+            self.rock_ridge_fields(index, kind, id.len())
+        } else {
+            Vec::new()
+        };
+        directory_record(
+            extent,
+            size,
+            record_date(&node.modified),
+            flags,
+            id,
+            &system_use,
+        )
+    }
+
+    /// Rock Ridge system use entries for a primary directory record
+    fn rock_ridge_fields(&self, index: usize, kind: RecordKind, id_len: usize) -> Vec<u8> {
+        let node = &self.nodes[index];
+        let mut fields = Vec::new();
+
+        // The root's "." record announces SUSP and points at the ER entry
+        if index == 0 && kind == RecordKind::Current {
+            fields.extend_from_slice(&[b'S', b'P', 7, 1, 0xBE, 0xEF, 0]);
+            fields.extend_from_slice(&rr_ce(self.ce_sector, 0, rr_er().len() as u32));
+        }
+
+        fields.extend_from_slice(&rr_px(node.is_dir()));
+
+        if kind == RecordKind::Entry {
+            let (inline, rest) = self.rock_ridge_name(index, id_len);
+            fields.extend_from_slice(&inline);
+            if !rest.is_empty() {
+                let offset = node.name_continuation;
+                fields.extend_from_slice(&rr_ce(
+                    self.ce_sector + offset / SECTOR_SIZE as u32,
+                    offset % SECTOR_SIZE as u32,
+                    rest.len() as u32,
+                ));
+            }
+        }
+
+        fields
+    }
+
+    /// A node's Rock Ridge name as "NM" entries, split between its directory
+    /// record and the continuation area
+    ///
+    /// A directory record can't exceed 255 bytes, so a name that doesn't fit
+    /// after the identifier and "PX" entry carries on in the continuation
+    /// area, pointed to by a "CE" entry.
+    fn rock_ridge_name(&self, index: usize, id_len: usize) -> (Vec<u8>, Vec<u8>) {
+        let name = &self.nodes[index].name;
+        // Fixed fields, identifier and its padding, "PX", and a byte in case
+        // the record needs padding to an even length
+        let room = 255 - (33 + id_len + (1 - id_len % 2) + rr_px(false).len() + 1);
+        if RR_NM_HEADER + name.len() <= room {
+            return (rr_nm(name, false), Vec::new());
+        }
+
+        let mut parts = split_name(name, room - RR_NM_HEADER - RR_CE_LEN).into_iter();
+        let inline = rr_nm(parts.next().unwrap_or_default(), true);
+        let mut rest = Vec::new();
+        let mut parts = parts.peekable();
+        while let Some(part) = parts.next() {
+            rest.extend_from_slice(&rr_nm(part, parts.peek().is_some()));
+        }
+        (inline, rest)
+    }
+
+    fn volume_descriptor(&self, ns: Namespace) -> Vec<u8> {
+        let mut vd = vec![0u8; SECTOR_SIZE as usize];
+        vd[0] = if ns == Namespace::Joliet { 2 } else { 1 };
+        vd[1..6].copy_from_slice(b"CD001");
+        vd[6] = 1;
+
+        put_text(&mut vd[8..40], "", ns);
+        let volume_id = match ns {
+            Namespace::Primary => primary_volume_id(&self.volume_label),
+            Namespace::Joliet => self.volume_label.clone(),
+        };
+        put_text(&mut vd[40..72], &volume_id, ns);
+        vd[80..88].copy_from_slice(&both_u32(self.total_sectors));
+
+        if ns == Namespace::Joliet {
+            // UCS-2 Level 3 escape sequence
+            vd[88..91].copy_from_slice(b"%/E");
+        }
+
+        vd[120..124].copy_from_slice(&both_u16(1));
+        vd[124..128].copy_from_slice(&both_u16(1));
+        vd[128..132].copy_from_slice(&both_u16(SECTOR_SIZE as u16));
+
+        let (table_size, l_table, m_table, root_extent, root_size) = match ns {
+            Namespace::Primary => (
+                self.path_table_size,
+                self.l_path_table,
+                self.m_path_table,
+                self.nodes[0].extent,
+                self.nodes[0].dir_size,
+            ),
+            Namespace::Joliet => (
+                self.joliet_path_table_size,
+                self.joliet_l_path_table,
+                self.joliet_m_path_table,
+                self.nodes[0].joliet_extent,
+                self.nodes[0].joliet_dir_size,
+            ),
+        };
+        vd[132..140].copy_from_slice(&both_u32(table_size));
+        vd[140..144].copy_from_slice(&l_table.to_le_bytes());
+        vd[148..152].copy_from_slice(&m_table.to_be_bytes());
+
+        let root = directory_record(
+            root_extent,
+            root_size,
+            record_date(&self.nodes[0].modified),
+            0x02,
+            &[0],
+            &[],
+        );
+        vd[156..190].copy_from_slice(&root);
+
+        put_text(&mut vd[190..318], "", ns);
+        put_text(&mut vd[318..446], "", ns);
+        put_text(&mut vd[446..574], "", ns);
+        put_text(&mut vd[574..702], APPLICATION_ID, ns);
+        put_text(&mut vd[702..813], "", ns);
+
+        let created = volume_date(Some(&self.created));
+        vd[813..830].copy_from_slice(&created);
+        vd[830..847].copy_from_slice(&created);
+        vd[847..864].copy_from_slice(&volume_date(None));
+        vd[864..881].copy_from_slice(&created);
+        vd[881] = 1;
+
+        vd
+    }
+}
+
+/// Scan `source_dir` into a node arena, following symlinks
+fn scan_tree(source_dir: &Path) -> Result<Vec<Node>, String> {
+    let meta = fs::metadata(source_dir)
+        .map_err(|e| format!("Failed to read {}: {}", source_dir.display(), e))?;
+    if !meta.is_dir() {
+        return Err(format!("{} is not a directory", source_dir.display()));
+    }
+
+    let mut nodes = vec![Node::new(
+        String::new(),
+        0,
+        NodeKind::Dir {
+            children: Vec::new(),
+        },
+        modified_time(&meta),
+    )];
+    scan_dir(source_dir, 0, 1, &mut nodes)?;
+    Ok(nodes)
+}
+
+fn scan_dir(dir: &Path, index: usize, depth: usize, nodes: &mut Vec<Node>) -> Result<(), String> {
+    if depth > MAX_DEPTH {
+        return Err(format!(
+            "Directory tree too deep for ISO 9660 at {}",
+            dir.display()
+        ));
+    }
+
+    let mut entries: Vec<_> = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read directory {}: {}", dir.display(), e))?
+        .filter_map(|e| e.ok())
+        .collect();
+    entries.sort_by_key(|e| e.file_name());
+
+    let mut children = Vec::new();
+    for entry in entries {
+        let path = entry.path();
+        // fs::metadata follows symlinks, so staged links resolve to the encoded files
+        let meta =
+            fs::metadata(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let child = nodes.len();
+
+        if meta.is_dir() {
+            nodes.push(Node::new(
+                name,
+                index,
+                NodeKind::Dir {
+                    children: Vec::new(),
+                },
+                modified_time(&meta),
+            ));
+            children.push(child);
+            scan_dir(&path, child, depth + 1, nodes)?;
+        } else {
+            if meta.len() > u32::MAX as u64 {
+                return Err(format!(
+                    "{} is too large for an ISO 9660 image (max 4 GB per file)",
+                    path.display()
+                ));
+            }
+            nodes.push(Node::new(
+                name,
+                index,
+                NodeKind::File {
+                    source: path,
+                    size: meta.len(),
+                },
+                modified_time(&meta),
+            ));
+            children.push(child);
+        }
+    }
+
+    if let NodeKind::Dir { children: slot } = &mut nodes[index].kind {
+        *slot = children;
+    }
+    Ok(())
+}
+
+/// Build a node arena from relative file paths, as [`scan_tree`] would for
+/// a directory holding those files
+fn plan_tree(files: &[(PathBuf, u64)]) -> Result<Vec<Node>, String> {
+    let now = Utc::now();
+    let new_dir = |name: String, parent: usize| {
+        Node::new(
+            name,
+            parent,
+            NodeKind::Dir {
+                children: Vec::new(),
+            },
+            now,
+        )
+    };
+    let mut nodes = vec![new_dir(String::new(), 0)];
+
+    for (path, size) in files {
+        let names: Vec<String> = path
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        let Some((file_name, dir_names)) = names.split_last() else {
+            continue;
+        };
+        if dir_names.len() >= MAX_DEPTH {
+            return Err(format!(
+                "Directory tree too deep for ISO 9660 at {}",
+                path.display()
+            ));
+        }
+        if *size > u32::MAX as u64 {
+            return Err(format!(
+                "{} is too large for an ISO 9660 image (max 4 GB per file)",
+                path.display()
+            ));
+        }
+
+        let mut dir = 0;
+        for name in dir_names {
+            let existing = nodes[dir]
+                .children()
+                .iter()
+                .copied()
+                .find(|&child| nodes[child].is_dir() && nodes[child].name == *name);
+            dir = match existing {
+                Some(child) => child,
+                None => {
+                    let child = nodes.len();
+                    nodes.push(new_dir(name.clone(), dir));
+                    if let NodeKind::Dir { children } = &mut nodes[dir].kind {
+                        children.push(child);
+                    }
+                    child
+                }
+            };
+        }
+
+        let child = nodes.len();
+        nodes.push(Node::new(
+            file_name.clone(),
+            dir,
+            NodeKind::File {
+                source: path.clone(),
+                size: *size,
+            },
+            now,
+        ));
+        if let NodeKind::Dir { children } = &mut nodes[dir].kind {
+            children.push(child);
+        }
+    }
+
+    // Same child order as scanning, which sorts entries by name
+    for dir in 0..nodes.len() {
+        let mut children = nodes[dir].children().to_vec();
+        children.sort_by(|&a, &b| nodes[a].name.cmp(&nodes[b].name));
+        if let NodeKind::Dir { children: slot } = &mut nodes[dir].kind {
+            *slot = children;
+        }
+    }
+    Ok(nodes)
+}
+
+/// Assign unique ISO 9660 and Joliet identifiers to every node
+fn assign_names(nodes: &mut [Node]) {
+    for dir in 0..nodes.len() {
+        let children = nodes[dir].children().to_vec();
+        let mut iso_used = HashSet::new();
+        let mut joliet_used = HashSet::new();
+
+        for child in children {
+            let is_dir = nodes[child].is_dir();
+            let (stem, ext) = split_extension(&nodes[child].name, is_dir);
+
+            let iso_name = if is_dir {
+                fit_name(&d_characters(stem), "", ISO_DIR_NAME_MAX, &mut iso_used)
+            } else {
+                let ext: String = d_characters(ext).chars().take(8).collect();
+                let ext = format!(".{}", ext);
+                fit_name(&d_characters(stem), &ext, ISO_FILE_NAME_MAX, &mut iso_used)
+            };
+
+            let joliet_stem = joliet_characters(stem);
+            let joliet_ext = if ext.is_empty() {
+                String::new()
+            } else {
+                format!(
+                    ".{}",
+                    joliet_characters(ext).chars().take(16).collect::<String>()
+                )
+            };
+            let joliet_name =
+                fit_name(&joliet_stem, &joliet_ext, JOLIET_NAME_MAX, &mut joliet_used);
+
+            nodes[child].iso_name = iso_name;
+            nodes[child].joliet_name = joliet_name;
+        }
+    }
+}
+
+/// Split a file name into stem and extension (directories have no extension)
+fn split_extension(name: &str, is_dir: bool) -> (&str, &str) {
+    if is_dir {
+        return (name, "");
+    }
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, ext),
+        _ => (name, ""),
+    }
+}
+
+/// Map a name to ISO 9660 d-characters (A-Z, 0-9, _)
+fn d_characters(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            let c = c.to_ascii_uppercase();
+            if c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Replace characters that Joliet does not allow
+fn joliet_characters(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '*' | '/' | ':' | ';' | '?' | '\\' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// Truncate `stem` so `stem + ext` fits in `max_len` UCS-2 units, adding a
+/// `~N` suffix if the result collides with a name already in `used`
+fn fit_name(stem: &str, ext: &str, max_len: usize, used: &mut HashSet<String>) -> String {
+    let mut n = 0usize;
+    loop {
+        let suffix = if n == 0 {
+            String::new()
+        } else {
+            format!("~{}", n)
+        };
+        let budget = max_len.saturating_sub(ucs2_len(ext) + suffix.len());
+
+        let mut base = String::new();
+        let mut width = 0;
+        for c in stem.chars() {
+            if width + c.len_utf16() > budget {
+                break;
+            }
+            width += c.len_utf16();
+            base.push(c);
+        }
+
+        let candidate = format!("{}{}{}", base, suffix, ext);
+        if used.insert(candidate.to_lowercase()) {
+            return candidate;
+        }
+        n += 1;
+    }
+}
+
+/// Primary volume identifier: upper-case d-characters, at most 32
+fn primary_volume_id(label: &str) -> String {
+    d_characters(label.trim()).chars().take(32).collect()
+}
+
+fn ucs2_len(s: &str) -> usize {
+    s.encode_utf16().count()
+}
+
+fn ucs2_bytes(s: &str) -> Vec<u8> {
+    s.encode_utf16().flat_map(|u| u.to_be_bytes()).collect()
+}
+
+/// Fill a space-padded text field in a volume descriptor
+fn put_text(field: &mut [u8], text: &str, ns: Namespace) {
+    match ns {
+        Namespace::Primary => {
+            field.fill(b' ');
+            for (slot, c) in field.iter_mut().zip(text.chars()) {
+                let c = c.to_ascii_uppercase();
+                *slot = if c.is_ascii_graphic() || c == ' ' {
+                    c as u8
+                } else {
+                    b'_'
+                };
+            }
+        }
+        Namespace::Joliet => {
+            for pair in field.chunks_mut(2) {
+                pair.copy_from_slice(&[0x00, 0x20][..pair.len()]);
+            }
+            for (pair, unit) in field.chunks_exact_mut(2).zip(text.encode_utf16()) {
+                pair.copy_from_slice(&unit.to_be_bytes());
+            }
+        }
+    }
+}
+
+/// Build a single directory record
+fn directory_record(
+    extent: u32,
+    size: u32,
+    date: [u8; 7],
+    flags: u8,
+    id: &[u8],
+    system_use: &[u8],
+) -> Vec<u8> {
+    let mut record = Vec::with_capacity(34 + id.len() + system_use.len());
+    record.push(0); // Record length, filled in below
+    record.push(0); // Extended attribute record length
+    record.extend_from_slice(&both_u32(extent));
+    record.extend_from_slice(&both_u32(size));
+    record.extend_from_slice(&date);
+    record.push(flags);
+    record.push(0); // File unit size
+    record.push(0); // Interleave gap
+    record.extend_from_slice(&both_u16(1)); // Volume sequence number
+    record.push(id.len() as u8);
+    record.extend_from_slice(id);
+    if id.len().is_multiple_of(2) {
+        record.push(0);
+    }
+    record.extend_from_slice(system_use);
+    if record.len() % 2 == 1 {
+        record.push(0);
+    }
+    record[0] = record.len() as u8;
+    record
+}
+
+/// Pack directory records into whole sectors (records never span sectors)
+fn pack_records(records: &[Vec<u8>]) -> Vec<u8> {
+    let sector = SECTOR_SIZE as usize;
+    let mut data = Vec::new();
+    let mut used = 0;
+    for record in records {
+        if used + record.len() > sector {
+            data.resize(data.len() + sector - used, 0);
+            used = 0;
+        }
+        data.extend_from_slice(record);
+        used += record.len();
+    }
+    data.resize(data.len() + sector - used, 0);
+    data
+}
+
+/// Rock Ridge "PX" (POSIX attributes) entry
+fn rr_px(is_dir: bool) -> Vec<u8> {
+    let (mode, links) = if is_dir { (0o040555, 2) } else { (0o100444, 1) };
+    let mut px = vec![b'P', b'X', 36, 1];
+    px.extend_from_slice(&both_u32(mode));
+    px.extend_from_slice(&both_u32(links));
+    px.extend_from_slice(&both_u32(0)); // uid
+    px.extend_from_slice(&both_u32(0)); // gid
+    px
+}
+
+/// Rock Ridge "NM" (alternate name) entry holding `part` of a name
+///
+/// `continues` sets the CONTINUE flag, meaning the next "NM" entry holds
+/// more of the same name.
+fn rr_nm(part: &str, continues: bool) -> Vec<u8> {
+    let mut nm = vec![
+        b'N',
+        b'M',
+        (RR_NM_HEADER + part.len()) as u8,
+        1,
+        u8::from(continues),
+    ];
+    nm.extend_from_slice(part.as_bytes());
+    nm
+}
+
+/// Split `name` on character boundaries into parts for "NM" entries, the
+/// first at most `first_max` bytes and the others as long as an entry allows
+fn split_name(name: &str, first_max: usize) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut rest = name;
+    let mut max = first_max;
+    while !rest.is_empty() {
+        let mut end = rest.len().min(max);
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (part, tail) = rest.split_at(end);
+        parts.push(part);
+        rest = tail;
+        max = RR_NM_MAX;
+    }
+    parts
+}
+
+/// SUSP "CE" (continuation area) entry for `len` bytes at `offset` in `sector`
+fn rr_ce(sector: u32, offset: u32, len: u32) -> Vec<u8> {
+    let mut ce = vec![b'C', b'E', RR_CE_LEN as u8, 1];
+    ce.extend_from_slice(&both_u32(sector));
+    ce.extend_from_slice(&both_u32(offset));
+    ce.extend_from_slice(&both_u32(len));
+    ce
+}
+
+/// SUSP "ER" (extensions reference) entry identifying Rock Ridge
+fn rr_er() -> Vec<u8> {
+    let mut er = vec![
+        b'E',
+        b'R',
+        0,
+        1,
+        RR_EXTENSION_ID.len() as u8,
+        RR_EXTENSION_DESCRIPTOR.len() as u8,
+        RR_EXTENSION_SOURCE.len() as u8,
+        1,
+    ];
+    er.extend_from_slice(RR_EXTENSION_ID.as_bytes());
+    er.extend_from_slice(RR_EXTENSION_DESCRIPTOR.as_bytes());
+    er.extend_from_slice(RR_EXTENSION_SOURCE.as_bytes());
+    er[2] = er.len() as u8;
+    er
+}
+
+/// Copy a file's data into the image, padded to a whole sector
+fn stream_file<W: Write>(out: &mut W, source: &Path, size: u64) -> io::Result<()> {
+    let file = File::open(source)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", source.display(), e)))?;
+    let copied = io::copy(&mut file.take(size), out)?;
+    if copied != size {
+        return Err(io::Error::other(format!(
+            "{} changed size while writing the image",
+            source.display()
+        )));
+    }
+    write_zeros(out, padding_for(size))
+}
+
+fn write_padded<W: Write>(out: &mut W, data: &[u8]) -> io::Result<()> {
+    out.write_all(data)?;
+    write_zeros(out, padding_for(data.len() as u64))
+}
+
+fn write_zeros<W: Write>(out: &mut W, len: u64) -> io::Result<()> {
+    out.write_all(&vec![0u8; len as usize])
+}
+
+fn padding_for(len: u64) -> u64 {
+    (SECTOR_SIZE - len % SECTOR_SIZE) % SECTOR_SIZE
+}
+
+fn sectors_for(len: u64) -> u32 {
+    len.div_ceil(SECTOR_SIZE) as u32
+}
+
+fn both_u16(value: u16) -> [u8; 4] {
+    let mut bytes = [0u8; 4];
+    bytes[..2].copy_from_slice(&value.to_le_bytes());
+    bytes[2..].copy_from_slice(&value.to_be_bytes());
+    bytes
+}
+
+fn both_u32(value: u32) -> [u8; 8] {
+    let mut bytes = [0u8; 8];
+    bytes[..4].copy_from_slice(&value.to_le_bytes());
+    bytes[4..].copy_from_slice(&value.to_be_bytes());
+    bytes
+}
+
+fn modified_time(meta: &fs::Metadata) -> DateTime<Utc> {
+    meta.modified()
+        .map(DateTime::<Utc>::from)
+        .unwrap_or_else(|_| Utc::now())
+}
+
+/// 7-byte directory record timestamp
+fn record_date(t: &DateTime<Utc>) -> [u8; 7] {
+    [
+        (t.year() - 1900).clamp(0, 255) as u8,
+        t.month() as u8,
+        t.day() as u8,
+        t.hour() as u8,
+        t.minute() as u8,
+        t.second() as u8,
+        0, // GMT offset
+    ]
+}
+
+/// 17-byte volume descriptor timestamp (`None` = "not specified")
+fn volume_date(t: Option<&DateTime<Utc>>) -> [u8; 17] {
+    let mut bytes = [b'0'; 17];
+    bytes[16] = 0;
+    if let Some(t) = t {
+        let text = format!(
+            "{:04}{:02}{:02}{:02}{:02}{:02}00",
+            t.year().clamp(0, 9999),
+            t.month(),
+            t.day(),
+            t.hour(),
+            t.minute(),
+            t.second()
+        );
+        bytes[..16].copy_from_slice(text.as_bytes());
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const SECTOR: usize = SECTOR_SIZE as usize;
+
+    fn write_image(source: &Path, label: &str) -> (TempDir, Vec<u8>) {
+        let out_dir = TempDir::new().unwrap();
+        let iso_path = out_dir.path().join("test.iso");
+        let size = write_iso_image(source, &iso_path, &IsoOptions::new(label)).unwrap();
+        let image = fs::read(&iso_path).unwrap();
+        assert_eq!(image.len() as u64, size);
+        (out_dir, image)
+    }
+
+    /// Root (extent, size) from the descriptor in `sector`
+    fn root_of(image: &[u8], sector: usize) -> (u32, u32) {
+        let root = &image[sector * SECTOR + 156..];
+        (le_u32(&root[2..]), le_u32(&root[10..]))
+    }
+
+    /// Find an entry by identifier in a directory extent
+    fn find_entry(image: &[u8], dir: (u32, u32), id: &[u8]) -> Option<(u32, u32)> {
+        let start = dir.0 as usize * SECTOR;
+        let end = start + dir.1 as usize;
+        let mut pos = start;
+        while pos < end {
+            let len = image[pos] as usize;
+            if len == 0 {
+                pos = (pos / SECTOR + 1) * SECTOR;
+                continue;
+            }
+            let id_len = image[pos + 32] as usize;
+            if &image[pos + 33..pos + 33 + id_len] == id {
+                return Some((le_u32(&image[pos + 2..]), le_u32(&image[pos + 10..])));
+            }
+            pos += len;
+        }
+        None
+    }
+
+    fn read_data(image: &[u8], entry: (u32, u32)) -> &[u8] {
+        let start = entry.0 as usize * SECTOR;
+        &image[start..start + entry.1 as usize]
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack.windows(needle.len()).any(|w| w == needle)
+    }
+
+    /// Rock Ridge names of the entries in a directory extent, following
+    /// "CE" entries into the continuation area
+    fn rock_ridge_names(image: &[u8], dir: (u32, u32)) -> Vec<String> {
+        let start = dir.0 as usize * SECTOR;
+        let mut names = Vec::new();
+        let mut pos = start;
+        while pos < start + dir.1 as usize {
+            let len = image[pos] as usize;
+            if len == 0 {
+                pos = (pos / SECTOR + 1) * SECTOR;
+                continue;
+            }
+            let id_len = image[pos + 32] as usize;
+            let mut area = &image[pos + 33 + id_len + (1 - id_len % 2)..pos + len];
+            let mut name = Vec::new();
+            while area.len() >= 4 && area[2] > 0 {
+                let entry = &area[..area[2] as usize];
+                match &entry[..2] {
+                    b"NM" => name.extend_from_slice(&entry[5..]),
+                    b"CE" => {
+                        let at =
+                            le_u32(&entry[4..]) as usize * SECTOR + le_u32(&entry[12..]) as usize;
+                        area = &image[at..at + le_u32(&entry[20..]) as usize];
+                        continue;
+                    }
+                    _ => {}
+                }
+                area = &area[entry.len()..];
+            }
+            if id_len > 1 {
+                names.push(String::from_utf8(name).unwrap());
+            }
+            pos += len;
+        }
+        names
+    }
+
+    #[test]
+    fn test_volume_descriptors() {
+        let source = TempDir::new().unwrap();
+        fs::write(source.path().join("file.mp3"), b"data").unwrap();
+
+        let (_out, image) = write_image(source.path(), "My Mix");
+
+        assert_eq!(image.len() % SECTOR, 0);
+        // Primary volume descriptor
+        assert_eq!(image[16 * SECTOR], 1);
+        assert_eq!(&image[16 * SECTOR + 1..16 * SECTOR + 6], b"CD001");
+        assert_eq!(&image[16 * SECTOR + 40..16 * SECTOR + 48], b"MY_MIX  ");
+        assert_eq!(
+            le_u32(&image[16 * SECTOR + 80..]) as usize,
+            image.len() / SECTOR
+        );
+        // Joliet supplementary volume descriptor
+        assert_eq!(image[17 * SECTOR], 2);
+        assert_eq!(&image[17 * SECTOR + 88..17 * SECTOR + 91], b"%/E");
+        assert_eq!(
+            &image[17 * SECTOR + 40..17 * SECTOR + 52],
+            &ucs2_bytes("My Mix")[..]
+        );
+        // Terminator
+        assert_eq!(image[18 * SECTOR], 255);
+    }
+
+    #[test]
+    fn test_primary_tree_contents() {
+        let source = TempDir::new().unwrap();
+        fs::create_dir(source.path().join("01-Album")).unwrap();
+        fs::write(source.path().join("01-Album/01-Track.mp3"), b"track data").unwrap();
+
+        let (_out, image) = write_image(source.path(), "Test");
+
+        let root = root_of(&image, 16);
+        let album = find_entry(&image, root, b"01_ALBUM").expect("album dir");
+        let track = find_entry(&image, album, b"01_TRACK.MP3;1").expect("track");
+        assert_eq!(read_data(&image, track), b"track data");
+    }
+
+    #[test]
+    fn test_joliet_tree_preserves_names() {
+        let source = TempDir::new().unwrap();
+        fs::create_dir(source.path().join("01-Album")).unwrap();
+        fs::write(source.path().join("01-Album/Track One.mp3"), b"joliet").unwrap();
+
+        let (_out, image) = write_image(source.path(), "Test");
+
+        let root = root_of(&image, 17);
+        let album = find_entry(&image, root, &ucs2_bytes("01-Album")).expect("album dir");
+        let track = find_entry(&image, album, &ucs2_bytes("Track One.mp3")).expect("track");
+        assert_eq!(read_data(&image, track), b"joliet");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_follows_symlinks() {
+        let encoded = TempDir::new().unwrap();
+        fs::write(encoded.path().join("song.mp3"), b"linked data").unwrap();
+
+        let source = TempDir::new().unwrap();
+        std::os::unix::fs::symlink(
+            encoded.path().join("song.mp3"),
+            source.path().join("01-song.mp3"),
+        )
+        .unwrap();
+
+        let (_out, image) = write_image(source.path(), "Test");
+
+        let root = root_of(&image, 16);
+        let track = find_entry(&image, root, b"01_SONG.MP3;1").expect("track");
+        assert_eq!(read_data(&image, track), b"linked data");
+    }
+
+    #[test]
+    fn test_progress_reaches_100() {
+        let source = TempDir::new().unwrap();
+        fs::write(source.path().join("big.mp3"), vec![1u8; 500_000]).unwrap();
+        let out_dir = TempDir::new().unwrap();
+        let iso_path = out_dir.path().join("test.iso");
+
+        let reported = std::sync::Mutex::new(Vec::new());
+        write_iso_image_with_progress(
+            source.path(),
+            &iso_path,
+            &IsoOptions::new("Test"),
+            &|percent| reported.lock().unwrap().push(percent),
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+
+        let reported = reported.into_inner().unwrap();
+        assert!(reported.len() > 2);
+        assert!(reported.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(reported.last(), Some(&100));
+    }
+
+    #[test]
+    fn test_cancel_removes_partial_image() {
+        let source = TempDir::new().unwrap();
+        fs::write(source.path().join("big.mp3"), vec![1u8; 500_000]).unwrap();
+        let out_dir = TempDir::new().unwrap();
+        let iso_path = out_dir.path().join("test.iso");
+
+        // Cancel partway through
+        let cancel = AtomicBool::new(false);
+        let result = write_iso_image_with_progress(
+            source.path(),
+            &iso_path,
+            &IsoOptions::new("Test"),
+            &|percent| {
+                if percent >= 50 {
+                    cancel.store(true, Ordering::SeqCst);
+                }
+            },
+            &cancel,
+        );
+
+        assert!(result.unwrap_err().contains("cancelled"));
+        assert!(!iso_path.exists());
+    }
+
+    #[test]
+    fn test_rock_ridge_entries() {
+        let source = TempDir::new().unwrap();
+        fs::write(source.path().join("Mixed Case Name.mp3"), b"rr").unwrap();
+
+        let (_out, image) = write_image(source.path(), "Test");
+
+        assert!(contains(&image, b"RRIP_1991A"));
+        assert!(contains(&image, b"NM\x18\x01\x00Mixed Case Name.mp3"));
+    }
+
+    #[test]
+    fn test_rock_ridge_long_names() {
+        let source = TempDir::new().unwrap();
+        let long = format!("{}.mp3", "a".repeat(196));
+        let longest = format!("x{}.mp3", "é".repeat(125));
+        let names = [long.clone(), longest.clone(), "short.mp3".to_string()];
+        for name in &names {
+            fs::write(source.path().join(name), b"rr").unwrap();
+        }
+
+        let (_out, image) = write_image(source.path(), "Test");
+
+        let mut read = rock_ridge_names(&image, root_of(&image, 16));
+        read.sort();
+        assert_eq!(read, [long, "short.mp3".to_string(), longest]);
+    }
+
+    #[test]
+    fn test_without_rock_ridge() {
+        let source = TempDir::new().unwrap();
+        fs::write(source.path().join("Mixed Case Name.mp3"), b"plain").unwrap();
+        let out_dir = TempDir::new().unwrap();
+        let iso_path = out_dir.path().join("plain.iso");
+
+        let options = IsoOptions {
+            volume_label: "Test".to_string(),
+            rock_ridge: false,
+        };
+        write_iso_image(source.path(), &iso_path, &options).unwrap();
+
+        let image = fs::read(&iso_path).unwrap();
+        assert!(!contains(&image, b"RRIP_1991A"));
+    }
+
+    #[test]
+    fn test_empty_file() {
+        let source = TempDir::new().unwrap();
+        fs::write(source.path().join("empty.txt"), b"").unwrap();
+
+        let (_out, image) = write_image(source.path(), "Test");
+
+        let root = root_of(&image, 16);
+        let entry = find_entry(&image, root, b"EMPTY.TXT;1").expect("empty file");
+        assert_eq!(entry.1, 0);
+    }
+
+    #[test]
+    fn test_many_files_span_directory_sectors() {
+        let source = TempDir::new().unwrap();
+        for i in 0..100 {
+            fs::write(
+                source
+                    .path()
+                    .join(format!("{:03}-A fairly long track name.mp3", i)),
+                format!("track {}", i),
+            )
+            .unwrap();
+        }
+
+        let (_out, image) = write_image(source.path(), "Test");
+
+        let root = root_of(&image, 16);
+        assert!(root.1 as usize > SECTOR);
+        let last = find_entry(&image, root, b"099_A_FAIRLY_LONG_TRACK_NA.MP3;1").expect("last");
+        assert_eq!(read_data(&image, last), b"track 99");
+    }
+
+    #[test]
+    fn test_image_size_matches_written_image() {
+        let mut files = vec![
+            (PathBuf::from("index.html"), 20_000),
+            (PathBuf::from("00-All Tracks.m3u8"), 0),
+            (PathBuf::from("02-Ünïcödé Album/01-Track.mp3"), 2048),
+            (PathBuf::from("02-Ünïcödé Album/Deeper/Nested/Track.mp3"), 1),
+        ];
+        for i in 0..60 {
+            files.push((
+                PathBuf::from(format!("01-Album/{:03}-A fairly long track name.mp3", i)),
+                1000 + i * 777,
+            ));
+        }
+
+        let source = TempDir::new().unwrap();
+        for (path, size) in &files {
+            let path = source.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, vec![0u8; *size as usize]).unwrap();
+        }
+        let (_out, image) = write_image(source.path(), "Test");
+
+        for rock_ridge in [true, false] {
+            let options = IsoOptions {
+                rock_ridge,
+                ..IsoOptions::new("Test")
+            };
+            let out = TempDir::new().unwrap();
+            let written = write_iso_image(source.path(), &out.path().join("t.iso"), &options);
+            assert_eq!(image_size(&files, &options), written);
+        }
+        assert_eq!(
+            image_size(&files, &IsoOptions::new("Test")).unwrap(),
+            image.len() as u64
+        );
+
+        let too_deep = PathBuf::from("1/2/3/4/5/6/7/8/track.mp3");
+        assert!(image_size(&[(too_deep, 1)], &IsoOptions::new("Test")).is_err());
+    }
+
+    #[test]
+    fn test_missing_source_dir() {
+        let out_dir = TempDir::new().unwrap();
+        let iso_path = out_dir.path().join("missing.iso");
+        let result = write_iso_image(
+            Path::new("/nonexistent/staging"),
+            &iso_path,
+            &IsoOptions::new("Test"),
+        );
+        assert!(result.is_err());
+        assert!(!iso_path.exists());
+    }
+
+    #[test]
+    fn test_fit_name_truncates_and_dedupes() {
+        let mut used = HashSet::new();
+        let first = fit_name("ABCDEFGHIJ", ".MP3", 8, &mut used);
+        let second = fit_name("ABCDEFGHIJ", ".MP3", 8, &mut used);
+        assert_eq!(first, "ABCD.MP3");
+        assert_eq!(second, "AB~1.MP3");
+    }
+
+    #[test]
+    fn test_d_characters() {
+        assert_eq!(d_characters("01-Café Song"), "01_CAF__SONG");
+        assert_eq!(primary_volume_id("  my cd "), "MY_CD");
+    }
+
+    #[test]
+    fn test_joliet_characters() {
+        assert_eq!(joliet_characters("AC/DC: Live?"), "AC_DC_ Live_");
+    }
+
+    #[test]
+    fn test_directory_record_is_even_length() {
+        let record = directory_record(20, 2048, [0; 7], 0x02, b"ABC", &[]);
+        assert_eq!(record.len() % 2, 0);
+        assert_eq!(record[0] as usize, record.len());
+    }
+
+    #[test]
+    fn test_read_file_table() {
+        let source = TempDir::new().unwrap();
+        fs::create_dir(source.path().join("01 - Some Album")).unwrap();
+        fs::write(source.path().join("01 - Some Album/01 Long Track Name.mp3"), b"one").unwrap();
+        fs::write(source.path().join("notes.txt"), b"two two").unwrap();
+        fs::write(source.path().join("empty.txt"), b"").unwrap();
+
+        let out_dir = TempDir::new().unwrap();
+        let iso_path = out_dir.path().join("test.iso");
+        write_iso_image(source.path(), &iso_path, &IsoOptions::new("Test")).unwrap();
+        let image = fs::read(&iso_path).unwrap();
+
+        let entries = read_file_table(&iso_path).unwrap();
+        assert_eq!(entries.len(), 3);
+
+        let track = entries
+            .iter()
+            .find(|e| e.path == "01 - Some Album/01 Long Track Name.mp3")
+            .unwrap();
+        assert_eq!(read_data(&image, (track.sector, track.size as u32)), b"one");
+        let notes = entries.iter().find(|e| e.path == "notes.txt").unwrap();
+        assert_eq!(read_data(&image, (notes.sector, notes.size as u32)), b"two two");
+        assert!(entries.iter().any(|e| e.path == "empty.txt" && e.size == 0));
+    }
+
+    #[test]
+    fn test_read_file_table_rejects_non_iso() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("junk.iso");
+        fs::write(&path, vec![0u8; 20 * SECTOR]).unwrap();
+
+        assert!(read_file_table(&path).is_err());
+        assert!(read_file_table(&temp_dir.path().join("missing.iso")).is_err());
+    }
+}
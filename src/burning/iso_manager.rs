@@ -5,9 +5,12 @@
 
 use std::path::PathBuf;
 
-use super::iso::create_iso;
+use super::disc_index::{DiscIndexInfo, write_disc_index};
+use super::iso::create_iso_from_staging;
+use super::iso_names::{IsoNaming, apply_iso_naming};
+use super::playlist::{PlaylistFormat, rename_playlist_entries};
 use crate::conversion::OutputManager;
-use crate::core::{BurnStage, ConversionState, MusicFolder};
+use crate::core::{BurnStage, CompatProfile, ConversionState, MusicFolder};
 
 /// Conditions required for ISO generation
 pub struct IsoGenerationCheck {
@@ -37,26 +40,46 @@ impl IsoGenerationCheck {
 /// Generate an ISO from the given folders
 ///
 /// This function:
-/// 1. Creates a staging directory with symlinks to encoded folders
-/// 2. Runs hdiutil to create the ISO
-/// 3. Returns the path to the created ISO
+/// 1. Creates a staging directory with symlinks to encoded folders, laid out
+///    for `compat`, with `playlists` and the disc index
+/// 2. Renames the staged entries to fit `naming` (recorded in `state.name_changes`)
+///    and updates the playlists to match
+/// 3. Writes the ISO image from the staging directory, reporting progress
+///    through `state.burn_progress`, then removes the staging directory
+/// 4. Returns the path to the created ISO
 ///
 /// This is a blocking operation that should be run in a background thread.
 pub fn generate_iso(
     output_manager: &OutputManager,
     folders: &[MusicFolder],
     volume_label: &str,
+    naming: IsoNaming,
+    compat: CompatProfile,
+    playlists: PlaylistFormat,
     state: &ConversionState,
 ) -> Result<PathBuf, String> {
     // Mark as creating ISO
     state.set_stage(BurnStage::CreatingIso);
 
     // Create staging directory with symlinks
-    let staging_dir = output_manager.create_iso_staging(folders)?;
+    let staging_dir = output_manager.create_iso_staging(folders, compat, playlists)?;
     log::info!("ISO staging directory: {:?}", staging_dir);
-
-    // Create ISO from staging directory
-    let result = create_iso(&staging_dir, volume_label)?;
+    let index = DiscIndexInfo {
+        title: volume_label.to_string(),
+        compat,
+        naming: Some(naming),
+        playlists,
+    };
+    let staged = output_manager.staged_track_paths(folders, compat);
+    write_disc_index(&staging_dir, folders, &staged, &index)?;
+    let changes = apply_iso_naming(&staging_dir, naming)?;
+    rename_playlist_entries(&staging_dir, &changes)?;
+    *state.name_changes.lock().unwrap() = changes;
+
+    // Create ISO from staging directory (which is removed afterwards)
+    let iso_path = staging_dir.with_file_name("mp3cd.iso");
+    let result = create_iso_from_staging(&staging_dir, &iso_path, volume_label, state)
+        .map_err(|e| e.to_string())?;
     log::info!("ISO created successfully: {:?}", result.iso_path);
 
     // Store ISO path in conversion state
@@ -77,6 +100,9 @@ pub fn spawn_iso_generation(
     folders: Vec<MusicFolder>,
     state: ConversionState,
     volume_label: String,
+    naming: IsoNaming,
+    compat: CompatProfile,
+    playlists: PlaylistFormat,
 ) {
     // Reset state for ISO generation
     state.reset(0);
@@ -85,10 +111,22 @@ pub fn spawn_iso_generation(
     std::thread::spawn(move || {
         let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
         rt.block_on(async {
-            match generate_iso(&output_manager, &folders, &volume_label, &state) {
+            match generate_iso(
+                &output_manager,
+                &folders,
+                &volume_label,
+                naming,
+                compat,
+                playlists,
+                &state,
+            ) {
                 Ok(_result) => {
                     state.set_stage(BurnStage::Complete);
                 }
+                Err(e) if state.is_cancelled() => {
+                    log::info!("{}", e);
+                    state.set_stage(BurnStage::Cancelled);
+                }
                 Err(e) => {
                     log::error!("ISO generation failed: {}", e);
                     state.set_stage(BurnStage::Complete);
@@ -0,0 +1,672 @@
+//! Disc file name compliance
+//!
+//! Staged names come straight from album and track names, which can be far
+//! longer than a disc's file system allows. Left alone, the ISO writer would
+//! shorten them silently; this pass renames the staged entries up front to
+//! fit the chosen naming level, keeping the numeric ordering prefixes
+//! ("01-", "02-") intact, and reports every change.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Which file system's naming rules the disc's names must follow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IsoNaming {
+    /// ISO 9660 level 1: upper-case 8.3 names (any player)
+    Level1,
+    /// ISO 9660 level 2: upper-case names up to 30 characters
+    Level2,
+    /// Joliet: Unicode names up to 64 characters (Windows, macOS, most car stereos)
+    #[default]
+    Joliet,
+    /// Rock Ridge: names up to 255 bytes (Linux and Unix)
+    RockRidge,
+}
+
+impl IsoNaming {
+    /// Name shown in the Options menu
+    pub fn label(&self) -> &'static str {
+        match self {
+            IsoNaming::Level1 => "ISO 9660 Level 1 (8.3)",
+            IsoNaming::Level2 => "ISO 9660 Level 2",
+            IsoNaming::Joliet => "Joliet",
+            IsoNaming::RockRidge => "Rock Ridge",
+        }
+    }
+
+    /// Longest file name, as (whole name, extension without the dot)
+    fn file_limits(&self) -> (usize, usize) {
+        match self {
+            // 8 + "." + 3
+            IsoNaming::Level1 => (12, 3),
+            IsoNaming::Level2 => (30, 8),
+            IsoNaming::Joliet => (64, 16),
+            IsoNaming::RockRidge => (255, 255),
+        }
+    }
+
+    /// Longest file name before the extension, where it's limited apart
+    /// from the whole name
+    fn stem_limit(&self) -> Option<usize> {
+        match self {
+            // 8.3, whether or not there's an extension
+            IsoNaming::Level1 => Some(8),
+            _ => None,
+        }
+    }
+
+    fn dir_limit(&self) -> usize {
+        match self {
+            IsoNaming::Level1 => 8,
+            IsoNaming::Level2 => 31,
+            IsoNaming::Joliet => 64,
+            IsoNaming::RockRidge => 255,
+        }
+    }
+
+    /// Length of `s` in the units this level's limits are counted in
+    fn measure(&self, s: &str) -> usize {
+        match self {
+            IsoNaming::Level1 | IsoNaming::Level2 => s.chars().count(),
+            IsoNaming::Joliet => s.encode_utf16().count(),
+            IsoNaming::RockRidge => s.len(),
+        }
+    }
+
+    /// Replace characters this level doesn't allow
+    fn map_chars(&self, s: &str) -> String {
+        s.chars()
+            .map(|c| match self {
+                IsoNaming::Level1 | IsoNaming::Level2 => {
+                    let c = c.to_ascii_uppercase();
+                    if c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_' {
+                        c
+                    } else {
+                        '_'
+                    }
+                }
+                IsoNaming::Joliet => match c {
+                    '*' | '/' | ':' | ';' | '?' | '\\' => '_',
+                    c if c.is_control() => '_',
+                    c => c,
+                },
+                IsoNaming::RockRidge => match c {
+                    '/' | '\0' => '_',
+                    c => c,
+                },
+            })
+            .collect()
+    }
+
+    /// Separator before a de-duplication number
+    fn dedupe_marker(&self) -> char {
+        match self {
+            // "~" isn't an ISO 9660 d-character
+            IsoNaming::Level1 | IsoNaming::Level2 => '_',
+            IsoNaming::Joliet | IsoNaming::RockRidge => '~',
+        }
+    }
+}
+
+/// One staged entry whose name had to change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameChange {
+    /// Path as staged, relative to the staging directory
+    pub original: PathBuf,
+    /// Path as it will appear on the disc
+    pub renamed: PathBuf,
+}
+
+impl NameChange {
+    /// "original → renamed", for logs and dialogs
+    pub fn describe(&self) -> String {
+        format!("{} → {}", self.original.display(), self.renamed.display())
+    }
+}
+
+/// Rename everything in `staging_dir` to fit `naming`
+///
+/// Names in the same folder that end up identical (ignoring case) get a
+/// number after the shortened part, e.g. "01-A very long~1.mp3".
+///
+/// # Returns
+/// * `Ok(Vec<NameChange>)` listing every entry that was renamed
+/// * `Err(String)` if the staging directory couldn't be read or renamed
+pub fn apply_iso_naming(staging_dir: &Path, naming: IsoNaming) -> Result<Vec<NameChange>, String> {
+    let mut changes = Vec::new();
+    rename_dir(
+        staging_dir,
+        Path::new(""),
+        Path::new(""),
+        naming,
+        &mut changes,
+    )?;
+    for change in &changes {
+        log::info!("Renamed for the disc: {}", change.describe());
+    }
+    Ok(changes)
+}
+
+fn rename_dir(
+    dir: &Path,
+    original: &Path,
+    renamed: &Path,
+    naming: IsoNaming,
+    changes: &mut Vec<NameChange>,
+) -> Result<(), String> {
+    let entries: Vec<(String, bool)> = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            // fs::metadata follows symlinks, so staged tracks count as files
+            let is_dir = fs::metadata(entry.path()).is_ok_and(|m| m.is_dir());
+            (name, is_dir)
+        })
+        .collect();
+    let plan = plan_names(entries, naming);
+
+    // Move changed entries aside first, so a new name can't land on an
+    // entry that hasn't been renamed yet
+    for (i, (name, new_name, _)) in plan.iter().enumerate() {
+        if name != new_name {
+            let from = dir.join(name);
+            let to = dir.join(format!(".mp3cd-rename-{}", i));
+            fs::rename(&from, &to)
+                .map_err(|e| format!("Failed to rename {}: {}", from.display(), e))?;
+        }
+    }
+    for (i, (name, new_name, _)) in plan.iter().enumerate() {
+        if name != new_name {
+            let from = dir.join(format!(".mp3cd-rename-{}", i));
+            let to = dir.join(new_name);
+            fs::rename(&from, &to)
+                .map_err(|e| format!("Failed to rename {}: {}", to.display(), e))?;
+            changes.push(NameChange {
+                original: original.join(name),
+                renamed: renamed.join(new_name),
+            });
+        }
+    }
+
+    for (name, new_name, is_dir) in plan {
+        if is_dir {
+            rename_dir(
+                &dir.join(&new_name),
+                &original.join(&name),
+                &renamed.join(&new_name),
+                naming,
+                changes,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// The renames [`apply_iso_naming`] would make to a staging tree of `paths`
+///
+/// `paths` are files relative to the staging directory, and the folders
+/// holding them are implied. Nothing is touched on disk, so this previews
+/// the names before anything is staged.
+pub fn plan_iso_naming(paths: &[PathBuf], naming: IsoNaming) -> Vec<NameChange> {
+    let mut changes = Vec::new();
+    let paths: Vec<&Path> = paths.iter().map(PathBuf::as_path).collect();
+    plan_dir(&paths, Path::new(""), Path::new(""), naming, &mut changes);
+    changes
+}
+
+fn plan_dir(
+    paths: &[&Path],
+    original: &Path,
+    renamed: &Path,
+    naming: IsoNaming,
+    changes: &mut Vec<NameChange>,
+) {
+    // What's directly in this folder, with the paths below each subfolder
+    let mut children: BTreeMap<String, Vec<&Path>> = BTreeMap::new();
+    let mut entries = Vec::new();
+    for path in paths {
+        let mut components = path.components();
+        let Some(first) = components.next() else {
+            continue;
+        };
+        let name = first.as_os_str().to_string_lossy().to_string();
+        let rest = components.as_path();
+        if rest.as_os_str().is_empty() {
+            entries.push((name, false));
+        } else {
+            children.entry(name).or_default().push(rest);
+        }
+    }
+    entries.extend(children.keys().map(|name| (name.clone(), true)));
+
+    let plan = plan_names(entries, naming);
+    for (name, new_name, _) in &plan {
+        if name != new_name {
+            changes.push(NameChange {
+                original: original.join(name),
+                renamed: renamed.join(new_name),
+            });
+        }
+    }
+    for (name, new_name, is_dir) in plan {
+        if is_dir {
+            plan_dir(
+                &children[&name],
+                &original.join(&name),
+                &renamed.join(&new_name),
+                naming,
+                changes,
+            );
+        }
+    }
+}
+
+/// New names for one folder's `entries` (name, is_dir) under `naming`
+///
+/// Returns (name, new name, is_dir) for every entry, in the order the ISO
+/// writer uses so de-duplication numbers are stable.
+fn plan_names(mut entries: Vec<(String, bool)>, naming: IsoNaming) -> Vec<(String, String, bool)> {
+    entries.sort();
+
+    let mut used = HashSet::new();
+    let mut plan = Vec::new();
+    for (name, is_dir) in entries {
+        let mut n = 0;
+        let new_name = loop {
+            let candidate = fit_name(&name, is_dir, naming, n);
+            if used.insert(candidate.to_lowercase()) {
+                break candidate;
+            }
+            n += 1;
+        };
+        plan.push((name, new_name, is_dir));
+    }
+    plan
+}
+
+/// Where a staged path ended up on the disc after `changes`
+///
+/// A change to a folder moves everything in it, so the deepest change
+/// covering the path wins.
+pub fn renamed_path(staged: &Path, changes: &[NameChange]) -> PathBuf {
+    map_path(staged, changes, |c| (&c.original, &c.renamed))
+}
+
+/// Where a path on the disc was staged before `changes`
+///
+/// The reverse of [`renamed_path`].
+pub fn original_path(renamed: &Path, changes: &[NameChange]) -> PathBuf {
+    map_path(renamed, changes, |c| (&c.renamed, &c.original))
+}
+
+fn map_path(
+    path: &Path,
+    changes: &[NameChange],
+    from_to: impl Fn(&NameChange) -> (&PathBuf, &PathBuf),
+) -> PathBuf {
+    changes
+        .iter()
+        .filter_map(|change| {
+            let (from, to) = from_to(change);
+            let rest = path.strip_prefix(from).ok()?;
+            let mapped = if rest.as_os_str().is_empty() {
+                to.clone()
+            } else {
+                to.join(rest)
+            };
+            Some((from.components().count(), mapped))
+        })
+        .max_by_key(|(depth, _)| *depth)
+        .map(|(_, mapped)| mapped)
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// Dialog text listing the renames (the first 10, then a count)
+pub fn name_changes_summary(changes: &[NameChange], naming: IsoNaming) -> String {
+    let mut text = format!(
+        "{} name(s) were changed to fit {} naming:",
+        changes.len(),
+        naming.label()
+    );
+    for change in changes.iter().take(10) {
+        text.push_str(&format!("\n• {}", change.describe()));
+    }
+    if changes.len() > 10 {
+        text.push_str(&format!("\n…and {} more", changes.len() - 10));
+    }
+    text
+}
+
+/// Fit `name` to `naming`, with de-duplication number `n` (0 = none)
+fn fit_name(name: &str, is_dir: bool, naming: IsoNaming, n: usize) -> String {
+    let (stem, ext) = split_extension(name, is_dir);
+    let (prefix, rest) = split_prefix(stem);
+
+    let (max_len, ext_max) = if is_dir {
+        (naming.dir_limit(), 0)
+    } else {
+        naming.file_limits()
+    };
+    let ext: String = naming.map_chars(ext).chars().take(ext_max).collect();
+    let ext = if ext.is_empty() {
+        ext
+    } else {
+        format!(".{}", ext)
+    };
+    let prefix = naming.map_chars(prefix);
+    let rest = naming.map_chars(rest);
+    let suffix = if n == 0 {
+        String::new()
+    } else {
+        format!("{}{}", naming.dedupe_marker(), n)
+    };
+
+    // The prefix and extension are kept whole; the rest is shortened
+    let mut budget = max_len
+        .saturating_sub(naming.measure(&prefix) + naming.measure(&ext) + naming.measure(&suffix));
+    if !is_dir && let Some(stem_max) = naming.stem_limit() {
+        budget =
+            budget.min(stem_max.saturating_sub(naming.measure(&prefix) + naming.measure(&suffix)));
+    }
+    let mut base = String::new();
+    let mut width = 0;
+    for c in rest.chars() {
+        let w = naming.measure(c.encode_utf8(&mut [0; 4]));
+        if width + w > budget {
+            break;
+        }
+        width += w;
+        base.push(c);
+    }
+    if naming != IsoNaming::RockRidge {
+        // Windows drops trailing spaces, so don't leave one where the cut fell
+        base.truncate(base.trim_end_matches(' ').len());
+    }
+
+    let fitted = format!("{}{}{}{}", prefix, base, suffix, ext);
+    if fitted.is_empty() {
+        "_".to_string()
+    } else {
+        fitted
+    }
+}
+
+/// Split "name.ext" into ("name", "ext"); directories have no extension
+fn split_extension(name: &str, is_dir: bool) -> (&str, &str) {
+    if is_dir {
+        return (name, "");
+    }
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, ext),
+        _ => (name, ""),
+    }
+}
+
+/// Split off a numeric ordering prefix such as "01-" or "07 "
+fn split_prefix(stem: &str) -> (&str, &str) {
+    let digits = stem.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits == 0 {
+        return ("", stem);
+    }
+    let end = match stem[digits..].chars().next() {
+        Some('-' | '_' | ' ' | '.') => digits + 1,
+        _ => digits,
+    };
+    stem.split_at(end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const LONG_ALBUM: &str =
+        "01-The Extremely Long Deluxe Anniversary Edition of an Album With Bonus Tracks";
+
+    #[test]
+    fn test_fit_name_levels() {
+        let track = "03-Bohemian Rhapsody (Remastered).mp3";
+        assert_eq!(fit_name(track, false, IsoNaming::Level1, 0), "03_BOHEM.MP3");
+        assert_eq!(
+            fit_name(track, false, IsoNaming::Level2, 0),
+            "03_BOHEMIAN_RHAPSODY__REMA.MP3"
+        );
+        assert_eq!(fit_name(track, false, IsoNaming::Joliet, 0), track);
+        assert_eq!(fit_name(track, false, IsoNaming::RockRidge, 0), track);
+
+        assert_eq!(fit_name(LONG_ALBUM, true, IsoNaming::Level1, 0), "01_THE_E");
+        let joliet = fit_name(LONG_ALBUM, true, IsoNaming::Joliet, 0);
+        assert_eq!(joliet.encode_utf16().count(), 64);
+        assert!(joliet.starts_with("01-The Extremely"));
+    }
+
+    #[test]
+    fn test_fit_name_characters() {
+        assert_eq!(
+            fit_name("What? Now: Live.mp3", false, IsoNaming::Joliet, 0),
+            "What_ Now_ Live.mp3"
+        );
+        assert_eq!(
+            fit_name("AC/DC.mp3", false, IsoNaming::RockRidge, 0),
+            "AC_DC.mp3"
+        );
+        assert_eq!(
+            fit_name("cover.jpeg", false, IsoNaming::Level1, 0),
+            "COVER.JPE"
+        );
+        assert_eq!(fit_name("", false, IsoNaming::Joliet, 0), "_");
+    }
+
+    #[test]
+    fn test_fit_name_level1_stem() {
+        assert_eq!(fit_name("README", false, IsoNaming::Level1, 0), "README");
+        assert_eq!(
+            fit_name("Liner Notes", false, IsoNaming::Level1, 0),
+            "LINER_NO"
+        );
+        assert_eq!(
+            fit_name("Liner Notes", false, IsoNaming::Level1, 1),
+            "LINER__1"
+        );
+        assert_eq!(
+            fit_name("Liner Notes.md", false, IsoNaming::Level1, 0),
+            "LINER_NO.MD"
+        );
+    }
+
+    #[test]
+    fn test_fit_name_rock_ridge_length() {
+        // The ISO writer stores names this long whole
+        let long = format!("01-{}.flac", "a".repeat(192));
+        assert_eq!(long.len(), 200);
+        assert_eq!(fit_name(&long, false, IsoNaming::RockRidge, 0), long);
+
+        let too_long = format!("01-{}.flac", "é".repeat(150));
+        let fitted = fit_name(&too_long, false, IsoNaming::RockRidge, 0);
+        assert!(fitted.len() <= 255);
+        assert!(fitted.starts_with("01-é") && fitted.ends_with("é.flac"));
+    }
+
+    #[test]
+    fn test_fit_name_keeps_prefix_when_deduplicating() {
+        assert_eq!(fit_name(LONG_ALBUM, true, IsoNaming::Level1, 2), "01_THE_2");
+        let name = fit_name("12-Track.mp3", false, IsoNaming::Level1, 3);
+        assert_eq!(name, "12_TRA_3.MP3");
+    }
+
+    #[test]
+    fn test_split_prefix() {
+        assert_eq!(split_prefix("01-Album"), ("01-", "Album"));
+        assert_eq!(split_prefix("7 Seas"), ("7 ", "Seas"));
+        assert_eq!(split_prefix("1999"), ("1999", ""));
+        assert_eq!(split_prefix("Album"), ("", "Album"));
+    }
+
+    #[test]
+    fn test_apply_iso_naming() {
+        let temp_dir = TempDir::new().unwrap();
+        let staging = temp_dir.path().join("_iso_staging");
+        let album = staging.join(LONG_ALBUM);
+        fs::create_dir_all(&album).unwrap();
+        fs::write(album.join("01-Introduction to the Story.mp3"), b"one").unwrap();
+        fs::write(album.join("02-Introduction to the Sequel.mp3"), b"two").unwrap();
+        fs::write(album.join("03-Short.mp3"), b"three").unwrap();
+        fs::create_dir_all(staging.join("02-Short Album")).unwrap();
+
+        let changes = apply_iso_naming(&staging, IsoNaming::Level1).unwrap();
+
+        let mut names: Vec<_> = fs::read_dir(&staging)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["01_THE_E", "02_SHORT"]);
+
+        let album = staging.join("01_THE_E");
+        assert_eq!(fs::read(album.join("01_INTRO.MP3")).unwrap(), b"one");
+        assert_eq!(fs::read(album.join("02_INTRO.MP3")).unwrap(), b"two");
+        assert_eq!(fs::read(album.join("03_SHORT.MP3")).unwrap(), b"three");
+
+        // Two folders and three tracks, reported with full paths
+        assert_eq!(changes.len(), 5);
+        assert!(changes.contains(&NameChange {
+            original: PathBuf::from(LONG_ALBUM).join("03-Short.mp3"),
+            renamed: PathBuf::from("01_THE_E/03_SHORT.MP3"),
+        }));
+    }
+
+    #[test]
+    fn test_apply_iso_naming_deduplicates() {
+        let temp_dir = TempDir::new().unwrap();
+        let staging = temp_dir.path().to_path_buf();
+        let base = "A Track Name That Goes On Far Too Long For Joliet To Keep In One Piece";
+        fs::write(staging.join(format!("{} (Part 1).mp3", base)), b"1").unwrap();
+        fs::write(staging.join(format!("{} (Part 2).mp3", base)), b"2").unwrap();
+        fs::write(staging.join("short.mp3"), b"s").unwrap();
+
+        let changes = apply_iso_naming(&staging, IsoNaming::Joliet).unwrap();
+
+        assert_eq!(changes.len(), 2);
+        let first = changes[0].renamed.to_string_lossy().to_string();
+        let second = changes[1].renamed.to_string_lossy().to_string();
+        assert_eq!(first.encode_utf16().count(), 64);
+        assert!(second.ends_with("~1.mp3"));
+        assert_eq!(fs::read(staging.join(&first)).unwrap(), b"1");
+        assert_eq!(fs::read(staging.join(&second)).unwrap(), b"2");
+        assert!(staging.join("short.mp3").exists());
+    }
+
+    #[test]
+    fn test_plan_iso_naming_matches_apply() {
+        let temp_dir = TempDir::new().unwrap();
+        let staging = temp_dir.path().join("_iso_staging");
+        let paths = vec![
+            PathBuf::from(LONG_ALBUM).join("01-Introduction to the Story.mp3"),
+            PathBuf::from(LONG_ALBUM).join("02-Introduction to the Sequel.mp3"),
+            PathBuf::from("02-Short Album/01-Short.mp3"),
+        ];
+        for path in &paths {
+            fs::create_dir_all(staging.join(path).parent().unwrap()).unwrap();
+            fs::write(staging.join(path), b"mp3").unwrap();
+        }
+
+        for naming in [IsoNaming::Level1, IsoNaming::Level2, IsoNaming::Joliet] {
+            let planned = plan_iso_naming(&paths, naming);
+            let copy = temp_dir.path().join(format!("{:?}", naming));
+            copy_tree(&staging, &copy);
+            assert_eq!(planned, apply_iso_naming(&copy, naming).unwrap());
+        }
+    }
+
+    fn copy_tree(from: &Path, to: &Path) {
+        fs::create_dir_all(to).unwrap();
+        for entry in fs::read_dir(from).unwrap() {
+            let entry = entry.unwrap();
+            if entry.file_type().unwrap().is_dir() {
+                copy_tree(&entry.path(), &to.join(entry.file_name()));
+            } else {
+                fs::copy(entry.path(), to.join(entry.file_name())).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_renamed_and_original_path() {
+        let changes = vec![
+            NameChange {
+                original: PathBuf::from("01-Long Album"),
+                renamed: PathBuf::from("01_LONG_"),
+            },
+            NameChange {
+                original: PathBuf::from("01-Long Album/02-Long Track.mp3"),
+                renamed: PathBuf::from("01_LONG_/02_LONG_.MP3"),
+            },
+        ];
+
+        let staged = Path::new("01-Long Album/02-Long Track.mp3");
+        assert_eq!(
+            renamed_path(staged, &changes),
+            PathBuf::from("01_LONG_/02_LONG_.MP3")
+        );
+        assert_eq!(
+            renamed_path(Path::new("01-Long Album/03-Short.mp3"), &changes),
+            PathBuf::from("01_LONG_/03-Short.mp3")
+        );
+        assert_eq!(
+            renamed_path(Path::new("01-Long Album"), &changes),
+            PathBuf::from("01_LONG_")
+        );
+        assert_eq!(
+            original_path(Path::new("01_LONG_/02_LONG_.MP3"), &changes),
+            staged
+        );
+        assert_eq!(
+            renamed_path(Path::new("02-Other"), &changes),
+            PathBuf::from("02-Other")
+        );
+    }
+
+    #[test]
+    fn test_name_changes_summary() {
+        let changes: Vec<_> = (1..=12)
+            .map(|i| NameChange {
+                original: PathBuf::from(format!("{:02}-Track.mp3", i)),
+                renamed: PathBuf::from(format!("{:02}_TRACK.MP3", i)),
+            })
+            .collect();
+
+        let text = name_changes_summary(&changes[..1], IsoNaming::Level1);
+        assert_eq!(
+            text,
+            "1 name(s) were changed to fit ISO 9660 Level 1 (8.3) naming:\n\
+             • 01-Track.mp3 → 01_TRACK.MP3"
+        );
+
+        let text = name_changes_summary(&changes, IsoNaming::Level1);
+        assert!(text.starts_with("12 name(s)"));
+        assert!(text.contains("• 10-Track.mp3 → 10_TRACK.MP3"));
+        assert!(!text.contains("11-Track.mp3"));
+        assert!(text.ends_with("…and 2 more"));
+    }
+
+    #[test]
+    fn test_apply_iso_naming_doesnt_clobber() {
+        let temp_dir = TempDir::new().unwrap();
+        let staging = temp_dir.path().to_path_buf();
+        // "B C.MP3" sorts first and takes "B_C.MP3", so the existing
+        // "B_C.MP3" has to move out of its way
+        fs::write(staging.join("B C.MP3"), b"space").unwrap();
+        fs::write(staging.join("B_C.MP3"), b"underscore").unwrap();
+
+        let changes = apply_iso_naming(&staging, IsoNaming::Level2).unwrap();
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(fs::read(staging.join("B_C.MP3")).unwrap(), b"space");
+        assert_eq!(fs::read(staging.join("B_C_1.MP3")).unwrap(), b"underscore");
+    }
+}
@@ -0,0 +1,226 @@
+//! Predicting the size of a disc's ISO image
+//!
+//! Tracks are only part of an image. Every file is rounded up to whole
+//! 2048-byte sectors, and the image adds volume descriptors, path tables and
+//! directory records for its ISO 9660 and Joliet trees, as well as the
+//! playlists and index files staging writes. Laying the planned disc out the
+//! way the writer does (see [`image_size`]) counts the structures exactly.
+//! The playlists and index files are counted at the most they can take,
+//! since their titles are cut to fit when they're written, so none of it
+//! needs a margin. Only the tracks' estimated sizes keep one (see
+//! [`calculate_multipass_bitrate`](crate::conversion::calculate_multipass_bitrate)).
+
+use std::path::PathBuf;
+
+use super::disc_index::disc_index_reserve;
+use super::iso_names::{IsoNaming, plan_iso_naming, renamed_path};
+use super::iso9660::{IsoOptions, SECTOR_SIZE, image_size};
+use super::playlist::{PlaylistFormat, playlist_reserve};
+use crate::conversion::{StagingEntry, disc_names};
+use crate::core::{CompatProfile, MusicFolder};
+
+/// Space a disc of `folders` needs besides its tracks' own bytes
+///
+/// Covers the image's structures, the playlists `playlists` asks for, the
+/// index files, and the padding after each track's last byte. A one-byte
+/// track takes a whole sector, and a track of any size takes at most one
+/// byte less than that past its own bytes, so tracks of any size fit in the
+/// rest of the disc. Names are taken before they're fitted to the disc's
+/// naming level, which only shortens them.
+pub fn disc_overhead(
+    folders: &[MusicFolder],
+    compat: CompatProfile,
+    playlists: PlaylistFormat,
+) -> u64 {
+    let mut files: Vec<(PathBuf, u64)> = track_paths(folders, compat)
+        .into_iter()
+        .map(|path| (path, 1))
+        .collect();
+    let track_count = files.len() as u64;
+    files.extend(reserved_files(folders, compat, playlists));
+    predicted_size(&files).saturating_sub(track_count)
+}
+
+/// Predicted size of the ISO image for a planned disc of `folders`
+///
+/// `entries` is the plan from
+/// [`OutputManager::plan_iso_staging`](crate::conversion::OutputManager::plan_iso_staging),
+/// so the prediction is exact once every track is encoded. The playlists and
+/// index files get the same `naming` renames as the tracks.
+pub fn predict_image_size(
+    entries: &[StagingEntry],
+    folders: &[MusicFolder],
+    compat: CompatProfile,
+    playlists: PlaylistFormat,
+    naming: IsoNaming,
+) -> u64 {
+    let reserved = reserved_files(folders, compat, playlists);
+    let staged: Vec<PathBuf> = track_paths(folders, compat)
+        .into_iter()
+        .chain(reserved.iter().map(|(path, _)| path.clone()))
+        .collect();
+    let changes = plan_iso_naming(&staged, naming);
+
+    let files: Vec<(PathBuf, u64)> = entries
+        .iter()
+        .map(|entry| (entry.disc_path.clone(), entry.estimated_size))
+        .chain(
+            reserved
+                .into_iter()
+                .map(|(path, bytes)| (renamed_path(&path, &changes), bytes)),
+        )
+        .collect();
+    predicted_size(&files)
+}
+
+/// Staged path of every track on a disc of `folders`
+fn track_paths(folders: &[MusicFolder], compat: CompatProfile) -> Vec<PathBuf> {
+    disc_names(folders, compat)
+        .into_iter()
+        .flat_map(|(dir_name, tracks)| {
+            tracks
+                .into_iter()
+                .map(move |name| PathBuf::from(&dir_name).join(name))
+        })
+        .collect()
+}
+
+/// The playlists and index files staging writes next to the tracks
+fn reserved_files(
+    folders: &[MusicFolder],
+    compat: CompatProfile,
+    playlists: PlaylistFormat,
+) -> Vec<(PathBuf, u64)> {
+    let names = disc_names(folders, compat);
+    let mut files = playlist_reserve(&names, playlists, compat.max_files_per_folder());
+    files.extend(
+        disc_index_reserve(folders)
+            .into_iter()
+            .map(|(name, bytes)| (PathBuf::from(name), bytes)),
+    );
+    files
+}
+
+/// Image size for `files`, laid out the way the writer does
+fn predicted_size(files: &[(PathBuf, u64)]) -> u64 {
+    match image_size(files, &IsoOptions::new("")) {
+        Ok(size) => size,
+        Err(e) => {
+            // Only a tree the writer would refuse gets here
+            log::warn!("Couldn't lay out the disc image: {}", e);
+            files
+                .iter()
+                .map(|(_, size)| size.div_ceil(SECTOR_SIZE) * SECTOR_SIZE)
+                .sum()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burning::iso_names::apply_iso_naming;
+    use crate::burning::iso9660::write_iso_image;
+    use crate::conversion::OutputManager;
+    use crate::core::{AudioFileInfo, FolderConversionStatus};
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// An album of `count` encoded tracks of `size` bytes each
+    fn album(name: &str, count: usize, size: u64, output: &TempDir) -> MusicFolder {
+        let mut folder = MusicFolder::new_for_test_with_id(name);
+        folder.audio_files = (0..count)
+            .map(|i| {
+                let stem = format!("{} Track {}", name, i);
+                fs::write(
+                    output.path().join(format!("{}.mp3", stem)),
+                    vec![0u8; size as usize],
+                )
+                .unwrap();
+                AudioFileInfo {
+                    path: PathBuf::from(format!("/music/{}/{}.mp3", name, stem)),
+                    duration: 200.0,
+                    bitrate: 192,
+                    size,
+                    codec: "mp3".to_string(),
+                    is_lossy: true,
+                }
+            })
+            .collect();
+        folder.conversion_status = FolderConversionStatus::Converted {
+            output_dir: output.path().to_path_buf(),
+            lossless_bitrate: None,
+            output_size: count as u64 * size,
+            completed_at: 0,
+        };
+        folder
+    }
+
+    #[test]
+    fn test_prediction_matches_staged_image() {
+        let output = TempDir::new().unwrap();
+        let folders = vec![
+            album("First", 12, 3001, &output),
+            album("Second", 30, 70_000, &output),
+        ];
+        let manager = OutputManager::new().unwrap();
+        let compat = CompatProfile::Modern;
+
+        for naming in [IsoNaming::Joliet, IsoNaming::Level1] {
+            let entries = manager
+                .plan_iso_staging(&folders, compat, naming, 256, false)
+                .unwrap();
+            let predicted =
+                predict_image_size(&entries, &folders, compat, PlaylistFormat::None, naming);
+
+            // Stage the tracks, plus index files as large as the space held for them
+            let staging = manager
+                .create_iso_staging(&folders, compat, PlaylistFormat::None)
+                .unwrap();
+            for (name, bytes) in disc_index_reserve(&folders) {
+                fs::write(staging.join(name), vec![b' '; bytes as usize]).unwrap();
+            }
+            apply_iso_naming(&staging, naming).unwrap();
+            let iso_path = output.path().join("disc.iso");
+            let written = write_iso_image(&staging, &iso_path, &IsoOptions::new("Disc")).unwrap();
+
+            assert_eq!(predicted, written, "{:?}", naming);
+        }
+        let _ = manager.cleanup();
+    }
+
+    #[test]
+    fn test_overhead_bounds_any_track_sizes() {
+        let output = TempDir::new().unwrap();
+        let folders = vec![album("Album", 20, 1, &output)];
+        let compat = CompatProfile::Modern;
+        let overhead = disc_overhead(&folders, compat, PlaylistFormat::Both);
+
+        let manager = OutputManager::new().unwrap();
+        let entries = manager
+            .plan_iso_staging(&folders, compat, IsoNaming::Joliet, 256, false)
+            .unwrap();
+        for size in [1, 2048, 2049, 4_000_000] {
+            let sized: Vec<StagingEntry> = entries
+                .iter()
+                .map(|entry| StagingEntry {
+                    estimated_size: size,
+                    ..entry.clone()
+                })
+                .collect();
+            let image = predict_image_size(
+                &sized,
+                &folders,
+                compat,
+                PlaylistFormat::Both,
+                IsoNaming::Joliet,
+            );
+            assert!(image <= 20 * size + overhead, "{} byte tracks", size);
+        }
+
+        // Far less than the flat 5% of a CD that used to be held back
+        assert!(overhead < 1_000_000);
+        assert!(overhead > disc_overhead(&folders, compat, PlaylistFormat::None));
+        let _ = manager.cleanup();
+    }
+}
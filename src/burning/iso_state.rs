@@ -7,11 +7,7 @@
 
 use std::path::PathBuf;
 
-use crate::core::{FolderId, MusicFolder, calculate_folder_hash};
-
-/// Maximum ISO size for CD burning (700 MB decimal)
-/// CD-Rs are labeled 700 MB using decimal (not binary) megabytes
-pub const MAX_ISO_SIZE_BYTES: u64 = 700 * 1000 * 1000;
+use crate::core::{FolderId, MediaProfile, MusicFolder, calculate_folder_hash};
 
 /// Tracks the state of the current ISO image
 #[derive(Debug, Clone)]
@@ -63,9 +59,9 @@ impl IsoState {
         self.is_valid && self.file_exists() && self.matches_folders(folders)
     }
 
-    /// Check if the ISO size exceeds the CD limit
-    pub fn exceeds_cd_limit(&self) -> bool {
-        self.size_bytes > MAX_ISO_SIZE_BYTES
+    /// Check if the ISO is too large for the target media
+    pub fn exceeds_capacity(&self, media: MediaProfile) -> bool {
+        self.size_bytes > media.capacity_bytes()
     }
 }
 
@@ -190,7 +186,7 @@ mod tests {
             size_bytes: 600 * 1000 * 1000, // 600 MB decimal
             is_valid: true,
         };
-        assert!(!iso_under.exceeds_cd_limit());
+        assert!(!iso_under.exceeds_capacity(MediaProfile::Cd80));
 
         // Exactly at limit (700 MB)
         let iso_at_limit = IsoState {
@@ -199,7 +195,7 @@ mod tests {
             size_bytes: 700 * 1000 * 1000, // 700 MB decimal
             is_valid: true,
         };
-        assert!(!iso_at_limit.exceeds_cd_limit());
+        assert!(!iso_at_limit.exceeds_capacity(MediaProfile::Cd80));
 
         // Just over limit (701 MB)
         let iso_just_over = IsoState {
@@ -208,7 +204,7 @@ mod tests {
             size_bytes: 701 * 1000 * 1000, // 701 MB decimal
             is_valid: true,
         };
-        assert!(iso_just_over.exceeds_cd_limit());
+        assert!(iso_just_over.exceeds_capacity(MediaProfile::Cd80));
 
         // Well over limit
         let iso_over = IsoState {
@@ -217,7 +213,7 @@ mod tests {
             size_bytes: 750 * 1000 * 1000, // 750 MB decimal
             is_valid: true,
         };
-        assert!(iso_over.exceeds_cd_limit());
+        assert!(iso_over.exceeds_capacity(MediaProfile::Cd80));
     }
 
     #[test]
@@ -415,8 +411,16 @@ mod tests {
     }
 
     #[test]
-    fn test_max_iso_size_constant() {
-        // Verify the constant is 700 MB in decimal
-        assert_eq!(MAX_ISO_SIZE_BYTES, 700_000_000);
+    fn test_exceeds_capacity_depends_on_media() {
+        let iso = IsoState {
+            path: PathBuf::from("/tmp/test.iso"),
+            folder_hash: "abc".to_string(),
+            size_bytes: 680 * 1000 * 1000,
+            is_valid: true,
+        };
+        assert!(iso.exceeds_capacity(MediaProfile::Cd74));
+        assert!(!iso.exceeds_capacity(MediaProfile::Cd80));
+        assert!(!iso.exceeds_capacity(MediaProfile::Dvd5));
+        assert!(iso.exceeds_capacity(MediaProfile::custom_mb(512)));
     }
 }
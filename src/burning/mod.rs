@@ -3,13 +3,46 @@
 //! This module is framework-agnostic. It uses callbacks/Result types
 //! for progress and error reporting instead of Tauri-specific APIs.
 
+pub mod audio_cd;
+pub mod burner;
 pub mod cd;
+pub mod catalog;
+pub mod cd_text;
 pub mod coordinator;
+pub mod copies;
+pub mod disc_index;
+pub mod drive_monitor;
+pub mod erase;
+pub mod error;
+pub mod export;
+pub mod folder_details;
+pub mod insert;
 pub mod iso;
+pub mod iso9660;
+pub mod iso_names;
+pub mod iso_size;
 pub mod iso_manager;
 pub mod iso_state;
+pub mod playlist;
+pub mod spanning;
+pub mod verify;
 pub mod workflow;
 
 pub use iso_manager::{IsoGenerationCheck, spawn_iso_generation};
+pub use burner::{DriveInfo, DriveSelection, EraseMode};
+pub use coordinator::BurnConfig;
+pub use error::BurnError;
 pub use iso_state::{IsoAction, IsoState, determine_iso_action};
-pub use workflow::{execute_burn_existing, execute_full_burn};
+pub use iso_names::{IsoNaming, NameChange, name_changes_summary, plan_iso_naming};
+pub use iso_size::{disc_overhead, predict_image_size};
+pub use spanning::{DEFAULT_MIN_BITRATE, plan_discs};
+pub use audio_cd::{AudioCdOptions, check_audio_cd_fits};
+pub use copies::{MAX_COPIES, copies_summary};
+pub use catalog::{DiscCatalog, DiscRecord};
+pub use drive_monitor::{DiscInfo, DriveEvent, DriveMonitor};
+pub use insert::{InsertFiles, Tracklist, write_insert};
+pub use playlist::PlaylistFormat;
+pub use workflow::{
+    execute_audio_cd_burn, execute_burn_copies, execute_burn_existing, execute_erase,
+    execute_export, execute_full_burn, execute_spanned_burn,
+};
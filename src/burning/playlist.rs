@@ -0,0 +1,614 @@
+//! Playlists on the disc
+//!
+//! Staging can write a playlist into each album folder and a master playlist
+//! covering the whole disc at its root, as M3U8 (UTF-8 with relative paths),
+//! PLS, or both. Entries use the staged file names, with each track's length
+//! and its "Artist - Title" from the tags. When the names are later changed
+//! to fit the disc's naming level, [`rename_playlist_entries`] rewrites the
+//! entries to match.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::iso_names::{NameChange, original_path, renamed_path};
+use crate::audio::get_track_metadata;
+use crate::audio::metadata::TrackMetadata;
+use crate::core::AudioFileInfo;
+
+/// Name of the disc-wide playlist, without its extension ("00-" sorts it first)
+const MASTER_PLAYLIST_NAME: &str = "00-All Tracks";
+
+/// Space held back for each playlist, besides its entries
+const PLAYLIST_BASE_BYTES: u64 = 64;
+
+/// Longest "Artist - Title" written for an entry, in bytes
+const PLAYLIST_TITLE_MAX_BYTES: usize = 256;
+
+/// Space held back per entry besides its path: the title, the length and
+/// each format's keys, with room to spare for a de-duplication number the
+/// disc's naming may add to the path
+const PLAYLIST_BYTES_PER_ENTRY: u64 = 512;
+
+/// Which playlists staging writes onto the disc
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaylistFormat {
+    /// No playlists
+    #[default]
+    None,
+    /// Extended M3U in UTF-8 (`.m3u8`)
+    M3u8,
+    /// PLS (`.pls`)
+    Pls,
+    /// Both M3U8 and PLS
+    Both,
+}
+
+impl PlaylistFormat {
+    /// Name shown in the Options menu
+    pub fn label(&self) -> &'static str {
+        match self {
+            PlaylistFormat::None => "None",
+            PlaylistFormat::M3u8 => "M3U8",
+            PlaylistFormat::Pls => "PLS",
+            PlaylistFormat::Both => "M3U8 and PLS",
+        }
+    }
+
+    fn writes_m3u8(&self) -> bool {
+        matches!(self, PlaylistFormat::M3u8 | PlaylistFormat::Both)
+    }
+
+    fn writes_pls(&self) -> bool {
+        matches!(self, PlaylistFormat::Pls | PlaylistFormat::Both)
+    }
+
+    fn extensions(&self) -> Vec<&'static str> {
+        let mut extensions = Vec::new();
+        if self.writes_m3u8() {
+            extensions.push("m3u8");
+        }
+        if self.writes_pls() {
+            extensions.push("pls");
+        }
+        extensions
+    }
+}
+
+/// The playlists `format` puts in a folder of `tracks` tracks
+///
+/// A folder can't go over `max_files`, so a full one keeps only the M3U8,
+/// or no playlist at all. The master playlist still lists its tracks.
+fn folder_format(
+    format: PlaylistFormat,
+    tracks: usize,
+    max_files: Option<usize>,
+) -> PlaylistFormat {
+    match max_files.map(|max| max.saturating_sub(tracks)) {
+        Some(0) => PlaylistFormat::None,
+        Some(1) if format == PlaylistFormat::Both => PlaylistFormat::M3u8,
+        _ => format,
+    }
+}
+
+/// Each playlist `format` puts on a disc, with the space to hold back for it
+///
+/// `folders` is each folder's name on the disc with its tracks' staged file
+/// names, and `max_files` the most files a folder may hold. Paths are
+/// relative to the disc root. Titles are cut when written, so the space
+/// held back is enough for any tags.
+pub fn playlist_reserve(
+    folders: &[(String, Vec<String>)],
+    format: PlaylistFormat,
+    max_files: Option<usize>,
+) -> Vec<(PathBuf, u64)> {
+    let reserve = |paths: &mut dyn Iterator<Item = usize>| {
+        PLAYLIST_BASE_BYTES
+            + paths
+                .map(|len| PLAYLIST_BYTES_PER_ENTRY + len as u64)
+                .sum::<u64>()
+    };
+
+    let mut playlists = Vec::new();
+    for (dir_name, tracks) in folders {
+        for extension in folder_format(format, tracks.len(), max_files).extensions() {
+            playlists.push((
+                Path::new(dir_name).join(format!("{}.{}", dir_name, extension)),
+                reserve(&mut tracks.iter().map(String::len)),
+            ));
+        }
+    }
+    for extension in format.extensions() {
+        // "Folder/Track.mp3"
+        let mut paths = folders.iter().flat_map(|(dir_name, tracks)| {
+            tracks
+                .iter()
+                .map(move |name| dir_name.len() + 1 + name.len())
+        });
+        playlists.push((
+            PathBuf::from(format!("{}.{}", MASTER_PLAYLIST_NAME, extension)),
+            reserve(&mut paths),
+        ));
+    }
+    playlists
+}
+
+/// A folder as staged, for writing its playlists
+pub struct StagedFolder<'a> {
+    /// Folder name on the disc, e.g. `01-Album`
+    pub dir_name: &'a str,
+    /// Each active track in disc order, with its staged file name
+    pub tracks: &'a [(&'a AudioFileInfo, String)],
+}
+
+/// One line of a playlist
+#[derive(Debug, Clone, PartialEq)]
+struct PlaylistEntry {
+    /// File name within the folder
+    file_name: String,
+    /// "Artist - Title", or the title alone if there's no artist
+    title: String,
+    /// Length in whole seconds
+    seconds: u64,
+}
+
+/// Write the playlists `format` asks for into `staging_dir`
+///
+/// Each folder gets a playlist named after it, inside it, listing its own
+/// tracks; the master playlist at the root lists every track on the disc.
+/// Folder playlists are left out where they'd take a folder over
+/// `max_files`.
+pub fn write_playlists(
+    staging_dir: &Path,
+    folders: &[StagedFolder],
+    format: PlaylistFormat,
+    max_files: Option<usize>,
+) -> Result<(), String> {
+    write_playlists_with(staging_dir, folders, format, max_files, &get_track_metadata)
+}
+
+fn write_playlists_with(
+    staging_dir: &Path,
+    folders: &[StagedFolder],
+    format: PlaylistFormat,
+    max_files: Option<usize>,
+    read_track: &dyn Fn(&Path) -> TrackMetadata,
+) -> Result<(), String> {
+    if format == PlaylistFormat::None {
+        return Ok(());
+    }
+
+    let mut master = Vec::new();
+    for folder in folders {
+        let entries: Vec<PlaylistEntry> = folder
+            .tracks
+            .iter()
+            .map(|(track, file_name)| PlaylistEntry {
+                file_name: file_name.clone(),
+                title: entry_title(track, read_track),
+                seconds: track.duration.round() as u64,
+            })
+            .collect();
+
+        write_formats(
+            &staging_dir.join(folder.dir_name),
+            folder.dir_name,
+            &entries,
+            folder_format(format, entries.len(), max_files),
+        )?;
+
+        master.extend(entries.into_iter().map(|entry| PlaylistEntry {
+            file_name: format!("{}/{}", folder.dir_name, entry.file_name),
+            ..entry
+        }));
+    }
+    write_formats(staging_dir, MASTER_PLAYLIST_NAME, &master, format)?;
+
+    log::debug!(
+        "Wrote {} playlists for {} folders",
+        format.label(),
+        folders.len()
+    );
+    Ok(())
+}
+
+/// Write `entries` to `dir` as `name.m3u8` and/or `name.pls`
+fn write_formats(
+    dir: &Path,
+    name: &str,
+    entries: &[PlaylistEntry],
+    format: PlaylistFormat,
+) -> Result<(), String> {
+    if format.writes_m3u8() {
+        write_file(&dir.join(format!("{}.m3u8", name)), &to_m3u8(entries))?;
+    }
+    if format.writes_pls() {
+        write_file(&dir.join(format!("{}.pls", name)), &to_pls(entries))?;
+    }
+    Ok(())
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<(), String> {
+    fs::write(path, contents)
+        .map_err(|e| format!("Failed to write playlist {}: {}", path.display(), e))
+}
+
+/// "Artist - Title" from the track's tags, falling back to its file name
+///
+/// Cut to [`PLAYLIST_TITLE_MAX_BYTES`], ending with "…" if anything was cut.
+fn entry_title(track: &AudioFileInfo, read_track: &dyn Fn(&Path) -> TrackMetadata) -> String {
+    let meta = read_track(&track.path);
+    let title = meta.title.unwrap_or_else(|| {
+        track
+            .path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default()
+    });
+    let mut title = match meta.artist {
+        Some(artist) => format!("{} - {}", artist, title),
+        None => title,
+    };
+    if title.len() > PLAYLIST_TITLE_MAX_BYTES {
+        let mut end = PLAYLIST_TITLE_MAX_BYTES - '…'.len_utf8();
+        while !title.is_char_boundary(end) {
+            end -= 1;
+        }
+        title.truncate(end);
+        title.push('…');
+    }
+    title
+}
+
+/// Extended M3U: one `#EXTINF` line before each path
+fn to_m3u8(entries: &[PlaylistEntry]) -> String {
+    let mut text = String::from("#EXTM3U\n");
+    for entry in entries {
+        text.push_str(&format!(
+            "#EXTINF:{},{}\n{}\n",
+            entry.seconds,
+            one_line(&entry.title),
+            entry.file_name
+        ));
+    }
+    text
+}
+
+/// PLS version 2
+fn to_pls(entries: &[PlaylistEntry]) -> String {
+    let mut text = String::from("[playlist]\n");
+    for (index, entry) in entries.iter().enumerate() {
+        let n = index + 1;
+        text.push_str(&format!(
+            "File{}={}\nTitle{}={}\nLength{}={}\n",
+            n,
+            entry.file_name,
+            n,
+            one_line(&entry.title),
+            n,
+            entry.seconds
+        ));
+    }
+    text.push_str(&format!("NumberOfEntries={}\nVersion=2\n", entries.len()));
+    text
+}
+
+/// Tags can hold line breaks, which would split an entry in two
+fn one_line(s: &str) -> String {
+    s.replace(['\r', '\n'], " ")
+}
+
+/// Point the playlists in `staging_dir` at their tracks' new names
+///
+/// Call after renaming the staged entries for the disc, with the renames
+/// that were made. Playlists that were themselves renamed are still found,
+/// since they keep an `.m3u8`/`.m3u`/`.pls` extension in any case.
+pub fn rename_playlist_entries(staging_dir: &Path, changes: &[NameChange]) -> Result<(), String> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    for playlist in find_playlists(staging_dir, Path::new(""))? {
+        let path = staging_dir.join(&playlist);
+        let text = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read playlist {}: {}", path.display(), e))?;
+
+        // Entries are relative to the playlist, as it was staged
+        let staged_dir = original_path(&playlist, changes)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let disc_dir = playlist.parent().unwrap_or(Path::new(""));
+        let rename = |entry: &str| -> String {
+            let renamed = renamed_path(&staged_dir.join(entry), changes);
+            renamed
+                .strip_prefix(disc_dir)
+                .unwrap_or(&renamed)
+                .to_string_lossy()
+                .replace('\\', "/")
+        };
+
+        let is_pls = playlist
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("pls"));
+        let mut rewritten = String::with_capacity(text.len());
+        for line in text.lines() {
+            if is_pls {
+                match line.split_once('=') {
+                    Some((key, value)) if key.starts_with("File") => {
+                        rewritten.push_str(&format!("{}={}", key, rename(value)));
+                    }
+                    _ => rewritten.push_str(line),
+                }
+            } else if line.is_empty() || line.starts_with('#') {
+                rewritten.push_str(line);
+            } else {
+                rewritten.push_str(&rename(line));
+            }
+            rewritten.push('\n');
+        }
+        write_file(&path, &rewritten)?;
+    }
+    Ok(())
+}
+
+/// Every playlist under `dir`, relative to the staging directory
+fn find_playlists(dir: &Path, relative: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut playlists = Vec::new();
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let name = relative.join(entry.file_name());
+        // Staged tracks are symlinks; only real directories hold playlists
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            playlists.extend(find_playlists(&path, &name)?);
+        } else if path.extension().is_some_and(|ext| {
+            ["m3u8", "m3u", "pls"]
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known))
+        }) {
+            playlists.push(name);
+        }
+    }
+    Ok(playlists)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burning::iso_names::{IsoNaming, apply_iso_naming};
+    use tempfile::TempDir;
+
+    fn track(path: &str, duration: f64) -> AudioFileInfo {
+        AudioFileInfo {
+            path: PathBuf::from(path),
+            duration,
+            bitrate: 320,
+            size: 8_000_000,
+            codec: "flac".to_string(),
+            is_lossy: false,
+        }
+    }
+
+    /// Tracks named "Untagged ..." have no tags
+    fn tags(path: &Path) -> TrackMetadata {
+        let stem = path.file_stem().unwrap().to_string_lossy().to_string();
+        if stem.starts_with("Untagged") {
+            return TrackMetadata::default();
+        }
+        TrackMetadata {
+            title: Some(stem.to_uppercase()),
+            artist: Some("Band".to_string()),
+            composer: None,
+        }
+    }
+
+    /// Stage two folders with empty track files and write their playlists
+    fn stage(dir: &Path, format: PlaylistFormat, max_files: Option<usize>) {
+        let (one, two, three) = (
+            track("/music/one.flac", 61.4),
+            track("/music/Untagged two.flac", 125.6),
+            track("/music/three.flac", 200.0),
+        );
+        let album = [(&one, "one.mp3".to_string()), (&two, "two.mp3".to_string())];
+        let mix = [(&three, "01-three.mp3".to_string())];
+        let folders = [
+            StagedFolder {
+                dir_name: "01-Album",
+                tracks: &album,
+            },
+            StagedFolder {
+                dir_name: "02-Road Mix",
+                tracks: &mix,
+            },
+        ];
+        for folder in &folders {
+            fs::create_dir_all(dir.join(folder.dir_name)).unwrap();
+            for (_, name) in folder.tracks {
+                fs::write(dir.join(folder.dir_name).join(name), b"mp3").unwrap();
+            }
+        }
+        write_playlists_with(dir, &folders, format, max_files, &tags).unwrap();
+    }
+
+    /// The folders and track names [`stage`] writes
+    fn staged_names() -> Vec<(String, Vec<String>)> {
+        vec![
+            (
+                "01-Album".to_string(),
+                vec!["one.mp3".to_string(), "two.mp3".to_string()],
+            ),
+            ("02-Road Mix".to_string(), vec!["01-three.mp3".to_string()]),
+        ]
+    }
+
+    #[test]
+    fn test_write_m3u8() {
+        let temp_dir = TempDir::new().unwrap();
+        stage(temp_dir.path(), PlaylistFormat::M3u8, None);
+
+        let album = fs::read_to_string(temp_dir.path().join("01-Album/01-Album.m3u8")).unwrap();
+        assert_eq!(
+            album,
+            "#EXTM3U\n#EXTINF:61,Band - ONE\none.mp3\n#EXTINF:126,Untagged two\ntwo.mp3\n"
+        );
+
+        let master = fs::read_to_string(temp_dir.path().join("00-All Tracks.m3u8")).unwrap();
+        assert!(master.contains("\n01-Album/one.mp3\n"));
+        assert!(master.ends_with("#EXTINF:200,Band - THREE\n02-Road Mix/01-three.mp3\n"));
+        assert!(!temp_dir.path().join("00-All Tracks.pls").exists());
+    }
+
+    #[test]
+    fn test_write_pls() {
+        let temp_dir = TempDir::new().unwrap();
+        stage(temp_dir.path(), PlaylistFormat::Both, None);
+
+        let master = fs::read_to_string(temp_dir.path().join("00-All Tracks.pls")).unwrap();
+        assert!(
+            master
+                .starts_with("[playlist]\nFile1=01-Album/one.mp3\nTitle1=Band - ONE\nLength1=61\n")
+        );
+        assert!(master.contains("File3=02-Road Mix/01-three.mp3\n"));
+        assert!(master.ends_with("NumberOfEntries=3\nVersion=2\n"));
+        assert!(temp_dir.path().join("02-Road Mix/02-Road Mix.pls").exists());
+        assert!(
+            temp_dir
+                .path()
+                .join("02-Road Mix/02-Road Mix.m3u8")
+                .exists()
+        );
+    }
+
+    #[test]
+    fn test_write_none() {
+        let temp_dir = TempDir::new().unwrap();
+        stage(temp_dir.path(), PlaylistFormat::None, None);
+
+        assert_eq!(
+            find_playlists(temp_dir.path(), Path::new("")).unwrap(),
+            Vec::<PathBuf>::new()
+        );
+    }
+
+    #[test]
+    fn test_playlist_reserve_covers_written_playlists() {
+        let temp_dir = TempDir::new().unwrap();
+        stage(temp_dir.path(), PlaylistFormat::Both, None);
+
+        let folders = staged_names();
+        let reserved = playlist_reserve(&folders, PlaylistFormat::Both, None);
+        assert_eq!(reserved.len(), 6);
+        for (path, bytes) in &reserved {
+            let written = fs::metadata(temp_dir.path().join(path)).unwrap().len();
+            assert!(written <= *bytes, "{}", path.display());
+        }
+        assert!(playlist_reserve(&folders, PlaylistFormat::None, None).is_empty());
+    }
+
+    #[test]
+    fn test_playlist_reserve_covers_long_names() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir_name = format!("01-{}", "Ü".repeat(100));
+        let file_name = format!("{}.mp3", "é".repeat(120));
+        let long = track("/music/long.flac", 359_999.0);
+        let tracks = [(&long, file_name.clone())];
+        let folders = [StagedFolder {
+            dir_name: &dir_name,
+            tracks: &tracks,
+        }];
+        fs::create_dir_all(temp_dir.path().join(&dir_name)).unwrap();
+        let long_tags = |_: &Path| TrackMetadata {
+            title: Some("Ä & Ö ".repeat(100)),
+            artist: Some("Band".to_string()),
+            composer: None,
+        };
+        write_playlists_with(
+            temp_dir.path(),
+            &folders,
+            PlaylistFormat::Both,
+            None,
+            &long_tags,
+        )
+        .unwrap();
+
+        let m3u8 = fs::read_to_string(temp_dir.path().join("00-All Tracks.m3u8")).unwrap();
+        assert!(m3u8.contains("Band - Ä & Ö"));
+        assert!(m3u8.contains("…\n"));
+        let names = [(dir_name, vec![file_name])];
+        for (path, bytes) in playlist_reserve(&names, PlaylistFormat::Both, None) {
+            let written = fs::metadata(temp_dir.path().join(&path)).unwrap().len();
+            assert!(written <= bytes, "{}", path.display());
+        }
+    }
+
+    #[test]
+    fn test_full_folders_skip_playlists() {
+        let temp_dir = TempDir::new().unwrap();
+        stage(temp_dir.path(), PlaylistFormat::Both, Some(2));
+
+        // The album is full; the mix only has room for its M3U8
+        let mut written = find_playlists(temp_dir.path(), Path::new("")).unwrap();
+        written.sort();
+        assert_eq!(
+            written,
+            [
+                PathBuf::from("00-All Tracks.m3u8"),
+                PathBuf::from("00-All Tracks.pls"),
+                PathBuf::from("02-Road Mix/02-Road Mix.m3u8"),
+            ]
+        );
+
+        let folders = staged_names();
+        let mut reserved: Vec<PathBuf> = playlist_reserve(&folders, PlaylistFormat::Both, Some(2))
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        reserved.sort();
+        assert_eq!(reserved, written);
+    }
+
+    #[test]
+    fn test_rename_playlist_entries_follows_iso_naming() {
+        let temp_dir = TempDir::new().unwrap();
+        let staging = temp_dir.path();
+        stage(staging, PlaylistFormat::Both, None);
+
+        let changes = apply_iso_naming(staging, IsoNaming::Level1).unwrap();
+        rename_playlist_entries(staging, &changes).unwrap();
+
+        // Every entry of every playlist names a file that's really there
+        let mut playlists = find_playlists(staging, Path::new("")).unwrap();
+        playlists.sort();
+        assert_eq!(playlists.len(), 6);
+        assert!(playlists.contains(&PathBuf::from("00_ALL_T.M3U")));
+        for playlist in playlists {
+            let dir = staging.join(&playlist).parent().unwrap().to_path_buf();
+            let text = fs::read_to_string(staging.join(&playlist)).unwrap();
+            let entries: Vec<&str> = text
+                .lines()
+                .filter_map(|line| match line.split_once('=') {
+                    Some((key, value)) if key.starts_with("File") => Some(value),
+                    _ if line.starts_with('#') || line.starts_with('[') || line.contains('=') => {
+                        None
+                    }
+                    _ => Some(line),
+                })
+                .collect();
+            assert!(!entries.is_empty(), "{}", playlist.display());
+            for entry in entries {
+                assert!(
+                    dir.join(entry).is_file(),
+                    "{}: {}",
+                    playlist.display(),
+                    entry
+                );
+            }
+        }
+        let master = fs::read_to_string(staging.join("00_ALL_T.M3U")).unwrap();
+        assert!(master.contains("\n01_ALBUM/ONE.MP3\n"));
+    }
+}
@@ -0,0 +1,363 @@
+//! Multi-disc spanning
+//!
+//! Splits a folder list that doesn't fit on one disc across several discs.
+//! Folders are never broken apart and keep their list order; a disc is closed
+//! as soon as the next folder would push its lossless bitrate below the
+//! chosen minimum.
+
+use super::iso_size::disc_overhead;
+use super::playlist::PlaylistFormat;
+use crate::conversion::{MultipassEstimate, calculate_multipass_bitrate};
+use crate::core::{AudioFileInfo, BitrateMode, CompatProfile, MediaProfile, MusicFolder};
+
+/// Minimum lossless bitrate used for spanning when none has been chosen
+pub const DEFAULT_MIN_BITRATE: u32 = 192;
+
+/// Longest volume label that survives on every filesystem view
+/// (the Joliet volume identifier holds 16 characters)
+const MAX_LABEL_CHARS: usize = 16;
+
+/// One disc of a spanned burn
+#[derive(Debug, Clone)]
+pub struct DiscPlan {
+    /// Folders on this disc, in list order
+    pub folders: Vec<MusicFolder>,
+    /// Bitrate estimate for this disc's content
+    pub estimate: MultipassEstimate,
+}
+
+/// Split `folders` into consecutive groups that each fit on one `media` disc
+///
+/// A group fits when its lossless files can be encoded at `min_bitrate` or
+/// better (or, with no lossless files, when the copied/transcoded lossy files
+/// fit at all), and when its folder and track counts are within `compat`'s
+/// limits. Each disc's playlists (in `playlists` format), index files and
+/// filesystem overhead are counted too, and transcodes are sized for `mode`.
+///
+/// # Returns
+/// * `Ok(Vec<DiscPlan>)` - one entry per disc (a single entry if everything fits)
+/// * `Err(String)` - a single folder is too large for one disc by itself
+pub fn plan_discs(
+    folders: &[MusicFolder],
+    min_bitrate: u32,
+    media: MediaProfile,
+    compat: CompatProfile,
+    playlists: PlaylistFormat,
+    mode: BitrateMode,
+) -> Result<Vec<DiscPlan>, String> {
+    let capacity = media.capacity_bytes();
+    let mut discs = Vec::new();
+    let mut current: Vec<MusicFolder> = Vec::new();
+
+    for folder in folders {
+        current.push(folder.clone());
+        if fits_on_disc(&current, min_bitrate, capacity, compat, playlists, mode) {
+            continue;
+        }
+
+        // Doesn't fit - close the disc without this folder and start a new one
+        current.pop();
+        let alone = std::slice::from_ref(folder);
+        compat.check_layout(alone)?;
+        if current.is_empty()
+            || !fits_on_disc(alone, min_bitrate, capacity, compat, playlists, mode)
+        {
+            return Err(format!(
+                "\"{}\" doesn't fit on one disc at {} kbps",
+                folder.display_name(),
+                min_bitrate
+            ));
+        }
+        discs.push(disc_plan(
+            std::mem::take(&mut current),
+            capacity,
+            compat,
+            playlists,
+            mode,
+        ));
+        current.push(folder.clone());
+    }
+
+    if !current.is_empty() {
+        discs.push(disc_plan(current, capacity, compat, playlists, mode));
+    }
+
+    log::debug!(
+        "Spanning plan: {} folders across {} disc(s) at >= {} kbps",
+        folders.len(),
+        discs.len(),
+        min_bitrate
+    );
+    Ok(discs)
+}
+
+/// Volume label for one disc of a set, e.g. "MIX 1/3"
+///
+/// The base label is shortened if needed so the disc number is never cut off.
+pub fn disc_volume_label(base: &str, disc: usize, count: usize) -> String {
+    if count <= 1 {
+        return base.to_string();
+    }
+
+    let suffix = format!("{}/{}", disc, count);
+    let base = base.trim();
+    if base.is_empty() {
+        return format!("DISC {}", suffix);
+    }
+
+    let room = MAX_LABEL_CHARS.saturating_sub(suffix.len() + 1);
+    let base: String = base.chars().take(room).collect();
+    format!("{} {}", base.trim_end(), suffix)
+}
+
+fn active_files(folders: &[MusicFolder]) -> Vec<AudioFileInfo> {
+    folders
+        .iter()
+        .flat_map(|f| f.active_tracks().into_iter().cloned())
+        .collect()
+}
+
+fn fits_on_disc(
+    folders: &[MusicFolder],
+    min_bitrate: u32,
+    capacity: u64,
+    compat: CompatProfile,
+    playlists: PlaylistFormat,
+    mode: BitrateMode,
+) -> bool {
+    if compat.check_layout(folders).is_err() {
+        return false;
+    }
+    let estimate = calculate_multipass_bitrate(
+        &active_files(folders),
+        capacity.saturating_sub(disc_overhead(folders, compat, playlists)),
+        mode,
+    );
+    !estimate.would_exceed_capacity
+        && (estimate.lossless_count == 0 || estimate.target_bitrate >= min_bitrate)
+}
+
+fn disc_plan(
+    folders: Vec<MusicFolder>,
+    capacity: u64,
+    compat: CompatProfile,
+    playlists: PlaylistFormat,
+    mode: BitrateMode,
+) -> DiscPlan {
+    let estimate = calculate_multipass_bitrate(
+        &active_files(&folders),
+        capacity.saturating_sub(disc_overhead(&folders, compat, playlists)),
+        mode,
+    );
+    DiscPlan { folders, estimate }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// An album of `hours` of FLAC
+    fn flac_album(name: &str, hours: f64) -> MusicFolder {
+        let mut folder = MusicFolder::new_for_test_with_id(name);
+        folder.audio_files = vec![AudioFileInfo {
+            path: PathBuf::from(format!("/test/{}/track.flac", name)),
+            duration: hours * 3600.0,
+            bitrate: 1411,
+            size: (hours * 400_000_000.0) as u64,
+            codec: "flac".to_string(),
+            is_lossy: false,
+        }];
+        folder
+    }
+
+    fn names(disc: &DiscPlan) -> Vec<&str> {
+        disc.folders.iter().map(|f| f.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_everything_fits_on_one_disc() {
+        let folders = vec![flac_album("a", 1.0), flac_album("b", 1.0)];
+
+        let discs = plan_discs(
+            &folders,
+            192,
+            MediaProfile::Cd80,
+            CompatProfile::Modern,
+            PlaylistFormat::None,
+            BitrateMode::Abr,
+        )
+        .unwrap();
+
+        assert_eq!(discs.len(), 1);
+        assert_eq!(names(&discs[0]), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_splits_in_order_without_breaking_albums() {
+        // ~7.5 hours fit at 192 kbps (700 MB * 8 / 192 kbps)
+        let folders = vec![
+            flac_album("a", 3.0),
+            flac_album("b", 3.0),
+            flac_album("c", 3.0),
+            flac_album("d", 1.0),
+        ];
+
+        let discs = plan_discs(
+            &folders,
+            192,
+            MediaProfile::Cd80,
+            CompatProfile::Modern,
+            PlaylistFormat::None,
+            BitrateMode::Abr,
+        )
+        .unwrap();
+
+        assert_eq!(discs.len(), 2);
+        assert_eq!(names(&discs[0]), vec!["a", "b"]);
+        assert_eq!(names(&discs[1]), vec!["c", "d"]);
+        for disc in &discs {
+            assert!(disc.estimate.target_bitrate >= 192);
+        }
+    }
+
+    #[test]
+    fn test_lower_minimum_means_fewer_discs() {
+        let folders: Vec<_> = (0..6)
+            .map(|i| flac_album(&format!("album{}", i), 3.0))
+            .collect();
+
+        let at_256 = plan_discs(
+            &folders,
+            256,
+            MediaProfile::Cd80,
+            CompatProfile::Modern,
+            PlaylistFormat::None,
+            BitrateMode::Abr,
+        )
+        .unwrap()
+        .len();
+        let at_128 = plan_discs(
+            &folders,
+            128,
+            MediaProfile::Cd80,
+            CompatProfile::Modern,
+            PlaylistFormat::None,
+            BitrateMode::Abr,
+        )
+        .unwrap()
+        .len();
+
+        assert!(at_128 < at_256);
+    }
+
+    #[test]
+    fn test_larger_media_means_fewer_discs() {
+        let folders: Vec<_> = (0..6)
+            .map(|i| flac_album(&format!("album{}", i), 3.0))
+            .collect();
+
+        assert!(
+            plan_discs(
+                &folders,
+                192,
+                MediaProfile::Cd80,
+                CompatProfile::Modern,
+                PlaylistFormat::None,
+                BitrateMode::Abr
+            )
+            .unwrap()
+            .len()
+                > 1
+        );
+        assert_eq!(
+            plan_discs(
+                &folders,
+                192,
+                MediaProfile::Dvd5,
+                CompatProfile::Modern,
+                PlaylistFormat::None,
+                BitrateMode::Abr
+            )
+            .unwrap()
+            .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_folder_too_large_for_one_disc() {
+        let folders = vec![flac_album("a", 1.0), flac_album("huge", 12.0)];
+
+        let err = plan_discs(
+            &folders,
+            192,
+            MediaProfile::Cd80,
+            CompatProfile::Modern,
+            PlaylistFormat::None,
+            BitrateMode::Abr,
+        )
+        .unwrap_err();
+
+        assert!(err.contains("huge"));
+        assert!(err.contains("192"));
+    }
+
+    #[test]
+    fn test_compat_folder_limit_starts_a_new_disc() {
+        let folders: Vec<_> = (0..120)
+            .map(|i| flac_album(&format!("album{}", i), 0.01))
+            .collect();
+
+        let modern = plan_discs(
+            &folders,
+            192,
+            MediaProfile::Cd80,
+            CompatProfile::Modern,
+            PlaylistFormat::None,
+            BitrateMode::Abr,
+        )
+        .unwrap();
+        let generic = plan_discs(
+            &folders,
+            192,
+            MediaProfile::Cd80,
+            CompatProfile::GenericHeadUnit,
+            PlaylistFormat::None,
+            BitrateMode::Abr,
+        )
+        .unwrap();
+
+        assert_eq!(modern.len(), 1);
+        assert_eq!(generic.len(), 2);
+        assert_eq!(generic[0].folders.len(), 99);
+        assert_eq!(generic[1].folders.len(), 21);
+    }
+
+    #[test]
+    fn test_empty_list() {
+        assert!(
+            plan_discs(
+                &[],
+                192,
+                MediaProfile::Cd80,
+                CompatProfile::Modern,
+                PlaylistFormat::None,
+                BitrateMode::Abr
+            )
+            .unwrap()
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_disc_volume_label() {
+        assert_eq!(disc_volume_label("MIX", 1, 3), "MIX 1/3");
+        assert_eq!(disc_volume_label("MIX", 1, 1), "MIX");
+        assert_eq!(disc_volume_label("", 2, 2), "DISC 2/2");
+        // Long labels are shortened so the disc number survives
+        let label = disc_volume_label("Summer Road Trip Mix", 2, 3);
+        assert_eq!(label, "Summer Road 2/3");
+        assert!(label.chars().count() <= MAX_LABEL_CHARS);
+    }
+}
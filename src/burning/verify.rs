@@ -0,0 +1,451 @@
+//! Post-burn verification
+//!
+//! Reads the burned disc back and compares it with the ISO it was burned
+//! from. Raw devices and image files are compared sector by sector; mounted
+//! discs (macOS) are compared file by file. Either way, differences are
+//! reported as the files they belong to.
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::iso9660::{IsoFileEntry, SECTOR_SIZE, read_file_table};
+
+/// Sectors read per chunk when comparing images
+const CHUNK_SECTORS: u64 = 32;
+
+/// Where a burned disc can be read back from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReadBackSource {
+    /// A raw device node or image file holding the disc's sectors
+    Image(PathBuf),
+    /// The root of the mounted disc filesystem
+    Mounted(PathBuf),
+}
+
+/// Outcome of comparing a burned disc with its ISO
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerificationResult {
+    /// Bytes read back and compared
+    pub bytes_checked: u64,
+    /// Files whose contents differ on the disc (paths relative to the disc root)
+    pub mismatched_files: Vec<String>,
+    /// Sectors that differ (image comparison only)
+    pub mismatched_sectors: u64,
+    /// Read error that stopped verification early
+    pub error: Option<String>,
+}
+
+impl VerificationResult {
+    /// Whether the disc matched the ISO completely
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && self.mismatched_sectors == 0 && self.mismatched_files.is_empty()
+    }
+
+    /// One-line summary for logs and dialogs
+    pub fn summary(&self) -> String {
+        if let Some(error) = &self.error {
+            format!("Verification could not finish: {}", error)
+        } else if self.passed() {
+            format!("Verified {} MB", self.bytes_checked / (1024 * 1024))
+        } else if self.mismatched_files.is_empty() {
+            format!("{} sectors differ from the ISO", self.mismatched_sectors)
+        } else {
+            format!(
+                "{} file(s) differ from the ISO",
+                self.mismatched_files.len()
+            )
+        }
+    }
+}
+
+/// Compare a burned disc with the ISO it was burned from
+///
+/// # Arguments
+/// * `iso_path` - The ISO that was burned
+/// * `source` - Where to read the disc back from
+/// * `on_progress` - Called with 0-100 as the comparison advances
+/// * `cancel_token` - Aborts the comparison when set
+///
+/// # Returns
+/// * `Ok(VerificationResult)` - comparison finished or stopped on a disc read error
+/// * `Err(String)` - the ISO could not be read, or verification was cancelled
+pub fn verify_disc(
+    iso_path: &Path,
+    source: &ReadBackSource,
+    on_progress: &dyn Fn(i32),
+    cancel_token: &AtomicBool,
+) -> Result<VerificationResult, String> {
+    let files = read_file_table(iso_path)?;
+    match source {
+        ReadBackSource::Image(path) => {
+            verify_image(iso_path, path, &files, on_progress, cancel_token)
+        }
+        ReadBackSource::Mounted(root) => {
+            verify_mounted(iso_path, root, &files, on_progress, cancel_token)
+        }
+    }
+}
+
+/// Sector-by-sector comparison against a device node or image file
+fn verify_image(
+    iso_path: &Path,
+    disc_path: &Path,
+    files: &[IsoFileEntry],
+    on_progress: &dyn Fn(i32),
+    cancel_token: &AtomicBool,
+) -> Result<VerificationResult, String> {
+    let mut iso = File::open(iso_path).map_err(|e| format!("Failed to open ISO: {}", e))?;
+    let total = iso
+        .metadata()
+        .map_err(|e| format!("Failed to read ISO size: {}", e))?
+        .len();
+
+    let mut result = VerificationResult::default();
+    let mut disc = match File::open(disc_path) {
+        Ok(disc) => disc,
+        Err(e) => {
+            result.error = Some(format!("Failed to open {}: {}", disc_path.display(), e));
+            return Ok(result);
+        }
+    };
+
+    let chunk_len = (CHUNK_SECTORS * SECTOR_SIZE) as usize;
+    let mut iso_buf = vec![0u8; chunk_len];
+    let mut disc_buf = vec![0u8; chunk_len];
+    let mut bad_sectors = Vec::new();
+    let mut reporter = ProgressReporter::new(total, on_progress);
+
+    while result.bytes_checked < total {
+        if cancel_token.load(Ordering::SeqCst) {
+            return Err("Verification cancelled by user".to_string());
+        }
+
+        let len = chunk_len.min((total - result.bytes_checked) as usize);
+        iso.read_exact(&mut iso_buf[..len])
+            .map_err(|e| format!("Failed to read ISO: {}", e))?;
+        let read = match read_fully(&mut disc, &mut disc_buf[..len]) {
+            Ok(read) => read,
+            Err(e) => {
+                result.error = Some(format!(
+                    "Read error at byte {}: {}",
+                    result.bytes_checked, e
+                ));
+                break;
+            }
+        };
+        if read < len {
+            result.error = Some(format!(
+                "Disc is shorter than the image ({} of {} bytes)",
+                result.bytes_checked + read as u64,
+                total
+            ));
+            break;
+        }
+
+        let first_sector = result.bytes_checked / SECTOR_SIZE;
+        for (i, (a, b)) in iso_buf[..len]
+            .chunks(SECTOR_SIZE as usize)
+            .zip(disc_buf[..len].chunks(SECTOR_SIZE as usize))
+            .enumerate()
+        {
+            if a != b {
+                bad_sectors.push(first_sector + i as u64);
+            }
+        }
+
+        result.bytes_checked += len as u64;
+        reporter.update(result.bytes_checked);
+    }
+
+    result.mismatched_sectors = bad_sectors.len() as u64;
+    result.mismatched_files = files_touching(files, &bad_sectors);
+    log_result(&result);
+    Ok(result)
+}
+
+/// File-by-file comparison against a mounted disc
+fn verify_mounted(
+    iso_path: &Path,
+    root: &Path,
+    files: &[IsoFileEntry],
+    on_progress: &dyn Fn(i32),
+    cancel_token: &AtomicBool,
+) -> Result<VerificationResult, String> {
+    let mut iso = File::open(iso_path).map_err(|e| format!("Failed to open ISO: {}", e))?;
+    let total = files.iter().map(|f| f.size).sum();
+
+    let mut result = VerificationResult::default();
+    let mut reporter = ProgressReporter::new(total, on_progress);
+
+    for entry in files {
+        if cancel_token.load(Ordering::SeqCst) {
+            return Err("Verification cancelled by user".to_string());
+        }
+
+        iso.seek(SeekFrom::Start(entry.sector as u64 * SECTOR_SIZE))
+            .map_err(|e| format!("Failed to read ISO: {}", e))?;
+        let matches = match File::open(root.join(&entry.path)) {
+            Ok(mut disc_file) => same_contents(&mut (&mut iso).take(entry.size), &mut disc_file)
+                .map_err(|e| format!("Failed to compare {}: {}", entry.path, e))?,
+            Err(_) => false,
+        };
+        if !matches {
+            log::warn!("Verification mismatch: {}", entry.path);
+            result.mismatched_files.push(entry.path.clone());
+        }
+
+        result.bytes_checked += entry.size;
+        reporter.update(result.bytes_checked);
+    }
+
+    log_result(&result);
+    Ok(result)
+}
+
+/// Paths of the files whose data overlaps any of the (sorted) bad sectors
+fn files_touching(files: &[IsoFileEntry], bad_sectors: &[u64]) -> Vec<String> {
+    files
+        .iter()
+        .filter(|file| {
+            let start = file.sector as u64;
+            let end = start + file.sector_count() as u64;
+            let i = bad_sectors.partition_point(|&s| s < start);
+            i < bad_sectors.len() && bad_sectors[i] < end
+        })
+        .map(|file| file.path.clone())
+        .collect()
+}
+
+/// Compare two streams to the end
+fn same_contents<A: Read, B: Read>(a: &mut A, b: &mut B) -> io::Result<bool> {
+    let mut buf_a = vec![0u8; 64 * 1024];
+    let mut buf_b = vec![0u8; 64 * 1024];
+    loop {
+        let read = read_fully(a, &mut buf_a)?;
+        if read_fully(b, &mut buf_b[..read])? != read || buf_a[..read] != buf_b[..read] {
+            return Ok(false);
+        }
+        if read < buf_a.len() {
+            // `a` is exhausted; `b` must be too
+            return Ok(b.read(&mut [0u8; 1])? == 0);
+        }
+    }
+}
+
+/// Fill `buf` as far as possible, returning the bytes read (short only at EOF)
+fn read_fully<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+fn log_result(result: &VerificationResult) {
+    if result.passed() {
+        log::info!("{}", result.summary());
+    } else {
+        log::warn!("{}", result.summary());
+    }
+}
+
+/// Reports whole-percent progress, skipping repeats
+struct ProgressReporter<'a> {
+    total: u64,
+    last: i32,
+    on_progress: &'a dyn Fn(i32),
+}
+
+impl<'a> ProgressReporter<'a> {
+    fn new(total: u64, on_progress: &'a dyn Fn(i32)) -> Self {
+        on_progress(0);
+        Self {
+            total,
+            last: 0,
+            on_progress,
+        }
+    }
+
+    fn update(&mut self, done: u64) {
+        let percent = (done * 100).checked_div(self.total).unwrap_or(100) as i32;
+        if percent != self.last {
+            self.last = percent;
+            (self.on_progress)(percent);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burning::iso9660::{IsoOptions, write_iso_image};
+    use std::fs;
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    /// Build a small ISO with two files, returning (temp dir, iso path)
+    fn build_iso() -> (TempDir, PathBuf) {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("source");
+        fs::create_dir_all(source.join("Album")).unwrap();
+        fs::write(source.join("Album/01 First.mp3"), vec![1u8; 5000]).unwrap();
+        fs::write(source.join("Album/02 Second.mp3"), vec![2u8; 3000]).unwrap();
+
+        let iso_path = temp_dir.path().join("test.iso");
+        write_iso_image(&source, &iso_path, &IsoOptions::new("Test")).unwrap();
+        (temp_dir, iso_path)
+    }
+
+    fn no_progress(_: i32) {}
+
+    #[test]
+    fn test_identical_image_passes() {
+        let (temp_dir, iso_path) = build_iso();
+        let disc = temp_dir.path().join("disc.img");
+        // Discs may read back with trailing padding
+        let mut data = fs::read(&iso_path).unwrap();
+        data.extend_from_slice(&[0u8; 4096]);
+        fs::write(&disc, data).unwrap();
+
+        let seen = Mutex::new(Vec::new());
+        let result = verify_disc(
+            &iso_path,
+            &ReadBackSource::Image(disc),
+            &|p| seen.lock().unwrap().push(p),
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+
+        assert!(result.passed(), "{:?}", result);
+        assert_eq!(result.bytes_checked, fs::metadata(&iso_path).unwrap().len());
+        let seen = seen.into_inner().unwrap();
+        assert_eq!(seen.first(), Some(&0));
+        assert_eq!(seen.last(), Some(&100));
+    }
+
+    #[test]
+    fn test_corrupt_sector_reports_file() {
+        let (temp_dir, iso_path) = build_iso();
+        let entries = read_file_table(&iso_path).unwrap();
+        let second = entries
+            .iter()
+            .find(|e| e.path == "Album/02 Second.mp3")
+            .unwrap();
+
+        let mut data = fs::read(&iso_path).unwrap();
+        data[second.sector as usize * SECTOR_SIZE as usize + 10] ^= 0xFF;
+        let disc = temp_dir.path().join("disc.img");
+        fs::write(&disc, data).unwrap();
+
+        let result = verify_disc(
+            &iso_path,
+            &ReadBackSource::Image(disc),
+            &no_progress,
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+
+        assert!(!result.passed());
+        assert_eq!(result.mismatched_sectors, 1);
+        assert_eq!(result.mismatched_files, vec!["Album/02 Second.mp3"]);
+    }
+
+    #[test]
+    fn test_short_disc_is_an_error() {
+        let (temp_dir, iso_path) = build_iso();
+        let data = fs::read(&iso_path).unwrap();
+        let disc = temp_dir.path().join("disc.img");
+        fs::write(&disc, &data[..data.len() - 2048]).unwrap();
+
+        let result = verify_disc(
+            &iso_path,
+            &ReadBackSource::Image(disc),
+            &no_progress,
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+
+        assert!(!result.passed());
+        assert!(result.error.unwrap().contains("shorter"));
+    }
+
+    #[test]
+    fn test_missing_device_is_an_error() {
+        let (temp_dir, iso_path) = build_iso();
+
+        let result = verify_disc(
+            &iso_path,
+            &ReadBackSource::Image(temp_dir.path().join("nope")),
+            &no_progress,
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+
+        assert!(result.error.is_some());
+    }
+
+    #[test]
+    fn test_mounted_disc_comparison() {
+        let (temp_dir, iso_path) = build_iso();
+        let mount = temp_dir.path().join("mount");
+        fs::create_dir_all(mount.join("Album")).unwrap();
+        fs::write(mount.join("Album/01 First.mp3"), vec![1u8; 5000]).unwrap();
+        fs::write(mount.join("Album/02 Second.mp3"), vec![2u8; 2999]).unwrap();
+
+        let result = verify_disc(
+            &iso_path,
+            &ReadBackSource::Mounted(mount.clone()),
+            &no_progress,
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+        assert_eq!(result.mismatched_files, vec!["Album/02 Second.mp3"]);
+
+        fs::write(mount.join("Album/02 Second.mp3"), vec![2u8; 3000]).unwrap();
+        let result = verify_disc(
+            &iso_path,
+            &ReadBackSource::Mounted(mount),
+            &no_progress,
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+        assert!(result.passed());
+        assert_eq!(result.bytes_checked, 8000);
+    }
+
+    #[test]
+    fn test_verification_cancelled() {
+        let (_temp_dir, iso_path) = build_iso();
+
+        let err = verify_disc(
+            &iso_path,
+            &ReadBackSource::Image(iso_path.clone()),
+            &no_progress,
+            &AtomicBool::new(true),
+        )
+        .unwrap_err();
+        assert!(err.contains("cancelled"));
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut result = VerificationResult {
+            bytes_checked: 3 * 1024 * 1024,
+            ..Default::default()
+        };
+        assert_eq!(result.summary(), "Verified 3 MB");
+
+        result.mismatched_files = vec!["a.mp3".to_string()];
+        assert!(result.summary().contains("1 file(s)"));
+
+        result.error = Some("boom".to_string());
+        assert!(result.summary().contains("boom"));
+    }
+}
@@ -6,10 +6,24 @@
 use std::path::{Path, PathBuf};
 use std::sync::atomic::Ordering;
 
-use super::coordinator::{BurnConfig, coordinate_burn};
-use super::iso::create_iso;
+use super::audio_cd::{AudioCdOptions, remove_audio_cd_image, write_audio_cd_image};
+use super::burner::EraseMode;
+use super::catalog::{DiscCatalog, DiscRecord};
+use super::cd_text::cd_text_for_folders;
+use super::coordinator::{
+    BurnConfig, BurnCoordinationResult, coordinate_audio_burn, coordinate_burn,
+};
+use super::copies::burn_copies;
+use super::disc_index::{DiscIndexInfo, write_disc_index};
+use super::erase::{EraseResult, coordinate_erase};
+use super::error::BurnError;
+use super::export::{ExportResult, export_staging};
+use super::iso::create_iso_from_staging;
+use super::iso_names::{IsoNaming, NameChange, apply_iso_naming};
+use super::playlist::{PlaylistFormat, rename_playlist_entries};
+use super::spanning::disc_volume_label;
 use crate::conversion::{EncodingPhase, OutputManager, SimpleEncoderHandle};
-use crate::core::{BurnStage, ConversionState, MusicFolder};
+use crate::core::{BurnStage, CompatProfile, ConversionState, MusicFolder};
 
 /// Execute a full burn workflow: wait for conversion, create ISO, burn
 ///
@@ -20,27 +34,77 @@ use crate::core::{BurnStage, ConversionState, MusicFolder};
 /// 3. Create an ISO from the staging directory
 /// 4. Coordinate the burn process (wait for CD, burn, etc.)
 ///
-/// Progress is reported via the ConversionState.
+/// Progress is reported via the ConversionState. A successful burn is added
+/// to the disc catalog as `record`, filled in with the disc's tracks.
 pub fn execute_full_burn(
     state: ConversionState,
     encoder_handle: SimpleEncoderHandle,
     output_manager: OutputManager,
     folders: Vec<MusicFolder>,
-    simulate_burn: bool,
+    config: BurnConfig,
     volume_label: String,
+    record: DiscRecord,
 ) {
-    // Wait for all folders to be converted
+    if !wait_for_conversion(&state, &encoder_handle, &output_manager, &folders, None) {
+        return;
+    }
+
+    // Create ISO staging directory with symlinks to converted folders
+    state.set_stage(BurnStage::CreatingIso);
+    log::info!("\n=== Creating ISO image ===");
+
+    let index = DiscIndexInfo::for_burn(&volume_label, &config);
+    let staging = output_manager
+        .create_iso_staging(&folders, config.compat, config.playlists)
+        .and_then(|dir| add_disc_index(&output_manager, &dir, &folders, &index).map(|_| dir))
+        .and_then(|dir| apply_naming(&state, &dir, config.iso_naming).map(|c| (dir, c)));
+    let (staging_dir, record) = match staging {
+        Ok((dir, changes)) => {
+            log::info!("ISO staging directory: {:?}", dir);
+            let staged = output_manager.staged_track_paths(&folders, config.compat);
+            (dir, record.with_tracks(&folders, &staged, &changes))
+        }
+        Err(e) => {
+            log::error!("Failed to create ISO staging: {}", e);
+            *state.burn_error.lock().unwrap() = Some(e.into());
+            state.set_stage(BurnStage::Complete);
+            state.finish();
+            return;
+        }
+    };
+
+    // Create ISO and burn
+    execute_iso_and_burn(state, staging_dir, config, volume_label, record);
+}
+
+/// Wait for the background encoder to convert all `folders`
+///
+/// With `lossless_bitrate` set, output encoded at any other bitrate doesn't
+/// count: the encoder must have picked up the new bitrate first.
+///
+/// Returns false (with the state already marked cancelled and finished) if
+/// the burn was cancelled while waiting.
+fn wait_for_conversion(
+    state: &ConversionState,
+    encoder_handle: &SimpleEncoderHandle,
+    output_manager: &OutputManager,
+    folders: &[MusicFolder],
+    lossless_bitrate: Option<u32>,
+) -> bool {
     loop {
         if state.is_cancelled() {
             log::info!("Burn cancelled while waiting for conversion");
             state.set_stage(BurnStage::Cancelled);
             state.finish();
-            return;
+            return false;
         }
 
         // Check if encoder is idle/complete (no active work)
-        let phase = encoder_handle.get_state().get_phase();
-        let is_done = matches!(phase, EncodingPhase::Complete | EncodingPhase::Idle);
+        let encoder_state = encoder_handle.get_state();
+        let phase = encoder_state.get_phase();
+        let is_done = matches!(phase, EncodingPhase::Complete | EncodingPhase::Idle)
+            && lossless_bitrate
+                .is_none_or(|br| encoder_state.lossless_bitrate.load(Ordering::SeqCst) == br);
 
         // Count folders that have output (converted)
         let completed_count = folders
@@ -53,31 +117,11 @@ pub fn execute_full_burn(
 
         if is_done && completed_count == folders.len() {
             log::info!("All folders converted ({} total)", completed_count);
-            break;
+            return true;
         }
 
         std::thread::sleep(std::time::Duration::from_millis(200));
     }
-
-    // Create ISO staging directory with symlinks to converted folders
-    state.set_stage(BurnStage::CreatingIso);
-    log::info!("\n=== Creating ISO image ===");
-
-    let staging_dir = match output_manager.create_iso_staging(&folders) {
-        Ok(dir) => {
-            log::info!("ISO staging directory: {:?}", dir);
-            dir
-        }
-        Err(e) => {
-            log::error!("Failed to create ISO staging: {}", e);
-            state.set_stage(BurnStage::Complete);
-            state.finish();
-            return;
-        }
-    };
-
-    // Create ISO and burn
-    execute_iso_and_burn(state, staging_dir, simulate_burn, volume_label);
 }
 
 /// Execute ISO creation and burn
@@ -88,22 +132,30 @@ pub fn execute_full_burn(
 fn execute_iso_and_burn(
     state: ConversionState,
     staging_dir: PathBuf,
-    simulate_burn: bool,
+    config: BurnConfig,
     volume_label: String,
+    record: DiscRecord,
 ) {
     state.set_stage(BurnStage::CreatingIso);
     log::info!("\n=== Creating ISO image ===");
 
-    match create_iso(&staging_dir, &volume_label) {
+    let iso_path = staging_dir.with_file_name("mp3cd.iso");
+    match create_iso_from_staging(&staging_dir, &iso_path, &volume_label, &state) {
         Ok(result) => {
             log::info!("ISO created at: {}", result.iso_path.display());
             *state.iso_path.lock().unwrap() = Some(result.iso_path.clone());
 
             // Coordinate the burn process
-            execute_burn(&result.iso_path, &state, simulate_burn);
+            execute_burn(&result.iso_path, &state, &config, record);
+        }
+        Err(e) if state.is_cancelled() => {
+            log::info!("{}", e);
+            state.set_stage(BurnStage::Cancelled);
+            state.finish();
         }
         Err(e) => {
             log::error!("ISO creation failed: {}", e);
+            *state.burn_error.lock().unwrap() = Some(e);
             state.set_stage(BurnStage::Complete);
             state.finish();
         }
@@ -113,26 +165,369 @@ fn execute_iso_and_burn(
 /// Execute burn of an existing ISO
 ///
 /// This is a blocking function that should be run in a background thread.
-/// It coordinates the burn process for an existing ISO file.
-pub fn execute_burn_existing(state: ConversionState, iso_path: PathBuf, simulate_burn: bool) {
-    execute_burn(&iso_path, &state, simulate_burn);
+/// It coordinates the burn process for an existing ISO file, cataloguing
+/// the disc as `record` if it burns.
+pub fn execute_burn_existing(
+    state: ConversionState,
+    iso_path: PathBuf,
+    config: BurnConfig,
+    record: DiscRecord,
+) {
+    execute_burn(&iso_path, &state, &config, record);
 }
 
-/// Execute the burn coordination
-fn execute_burn(iso_path: &Path, state: &ConversionState, simulate_burn: bool) {
-    let config = BurnConfig {
-        simulate: simulate_burn,
+/// Execute a multi-copy burn of an existing ISO
+///
+/// This is a blocking function that should be run in a background thread.
+/// It burns `copies` discs from the same ISO, ejecting each one and waiting
+/// for the next blank disc in between. Each copy's outcome is recorded in
+/// `state.copy_results` for the final summary, and every good copy is added
+/// to the disc catalog as `record`.
+pub fn execute_burn_copies(
+    state: ConversionState,
+    iso_path: PathBuf,
+    copies: usize,
+    config: BurnConfig,
+    record: DiscRecord,
+) {
+    let outcomes = burn_copies(&iso_path, &state, &config, copies);
+    let good = outcomes.iter().filter(|o| o.succeeded()).count();
+    log::info!(
+        "Multi-copy burn finished: {} of {} copies good",
+        good,
+        copies
+    );
+    if good > 0 && !config.simulate {
+        catalog_discs(&iso_path, record, good);
+    }
+    state.finish();
+}
+
+/// Execute a standalone erase of a CD-RW
+///
+/// This is a blocking function that should be run in a background thread.
+/// It waits for a rewritable disc, asks before erasing it like a burn does,
+/// erases it with `mode` and ejects it. The outcome is stored in
+/// `state.erase` unless the erase was cancelled.
+pub fn execute_erase(state: ConversionState, mode: EraseMode, config: BurnConfig) {
+    let error = match coordinate_erase(&state, &config, mode) {
+        BurnCoordinationResult::Cancelled => {
+            state.finish();
+            return;
+        }
+        BurnCoordinationResult::NoCdTimeout => Some(BurnError::Other(
+            "No CD-RW was inserted in time.".to_string(),
+        )),
+        BurnCoordinationResult::Error(e) => Some(e),
+        _ => None,
+    };
+    *state.erase.lock().unwrap() = Some(EraseResult { mode, error });
+    state.finish();
+}
+
+/// Execute a multi-disc burn
+///
+/// This is a blocking function that should be run in a background thread.
+/// It waits for every folder to be converted at `lossless_bitrate` (which the
+/// caller has already sent to the encoder), then for each disc in turn
+/// stages its folders, writes its ISO (labelled e.g. "MIX 1/3") and burns it,
+/// prompting for the next blank disc in between. A disc that fails or is
+/// cancelled stops the remaining discs. Each disc that burns is catalogued
+/// as a copy of `record` with its own label and tracks.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_spanned_burn(
+    state: ConversionState,
+    encoder_handle: SimpleEncoderHandle,
+    output_manager: OutputManager,
+    discs: Vec<Vec<MusicFolder>>,
+    lossless_bitrate: u32,
+    config: BurnConfig,
+    volume_label: String,
+    record: DiscRecord,
+) {
+    let all_folders: Vec<MusicFolder> = discs.iter().flatten().cloned().collect();
+    if !wait_for_conversion(
+        &state,
+        &encoder_handle,
+        &output_manager,
+        &all_folders,
+        Some(lossless_bitrate),
+    ) {
+        return;
+    }
+
+    burn_discs(
+        &state,
+        &output_manager,
+        &discs,
+        &config,
+        &volume_label,
+        &record,
+    );
+    state.finish();
+}
+
+/// Stage, write and burn each disc of a spanned burn in turn
+fn burn_discs(
+    state: &ConversionState,
+    output_manager: &OutputManager,
+    discs: &[Vec<MusicFolder>],
+    config: &BurnConfig,
+    volume_label: &str,
+    record: &DiscRecord,
+) {
+    let count = discs.len();
+    for (index, folders) in discs.iter().enumerate() {
+        let disc = index + 1;
+        state.set_disc(disc, count);
+        // Every disc needs its own approval before a CD-RW is erased
+        state.erase_approved.store(false, Ordering::SeqCst);
+
+        state.set_stage(BurnStage::CreatingIso);
+        log::info!("\n=== Creating ISO for disc {} of {} ===", disc, count);
+
+        let label = disc_volume_label(volume_label, disc, count);
+        let mut disc_record = record.clone().with_volume_label(&label);
+        let iso = output_manager
+            .create_disc_staging(folders, disc, config.compat, config.playlists)
+            .map_err(BurnError::from)
+            .and_then(|staging| {
+                let index = DiscIndexInfo::for_burn(&label, config);
+                add_disc_index(output_manager, &staging, folders, &index)?;
+                let changes = apply_naming(state, &staging, config.iso_naming)?;
+                let staged = output_manager.staged_track_paths(folders, config.compat);
+                disc_record = disc_record.clone().with_tracks(folders, &staged, &changes);
+                let iso_path = staging.with_file_name(format!("mp3cd-disc{}.iso", disc));
+                create_iso_from_staging(&staging, &iso_path, &label, state)
+            });
+        let iso = match iso {
+            Ok(iso) => iso,
+            Err(e) if state.is_cancelled() => {
+                log::info!("{}", e);
+                state.set_stage(BurnStage::Cancelled);
+                return;
+            }
+            Err(e) => {
+                log::error!("ISO creation failed for disc {}: {}", disc, e);
+                *state.burn_error.lock().unwrap() = Some(e);
+                state.set_stage(BurnStage::Complete);
+                return;
+            }
+        };
+        *state.iso_path.lock().unwrap() = Some(iso.iso_path.clone());
+
+        let result = run_burn(&iso.iso_path, state, config, disc_record);
+        if !matches!(
+            result,
+            BurnCoordinationResult::Success
+                | BurnCoordinationResult::Simulated
+                | BurnCoordinationResult::Verified(_)
+        ) {
+            log::info!("Stopping multi-disc burn after disc {} of {}", disc, count);
+            return;
+        }
+        state.disc_burned();
+    }
+}
+
+/// Execute an audio CD burn
+///
+/// This is a blocking function that should be run in a background thread.
+/// It decodes the active tracks of `folders` into a BIN/CUE image in
+/// `output_dir` (the caller has already checked the playing time), then
+/// burns it disc-at-once and deletes the image. CD-TEXT comes from the
+/// folders' tags and edits, with `disc_title` (if any) as the disc title.
+/// Progress through the tracks is reported as completed/total on the
+/// ConversionState.
+pub fn execute_audio_cd_burn(
+    state: ConversionState,
+    folders: Vec<MusicFolder>,
+    output_dir: PathBuf,
+    options: AudioCdOptions,
+    disc_title: Option<String>,
+    config: BurnConfig,
+) {
+    state.set_stage(BurnStage::DecodingAudio);
+    log::info!("\n=== Decoding audio CD image ===");
+
+    let cd_text = cd_text_for_folders(&folders, disc_title.as_deref());
+    let image = write_audio_cd_image(
+        &folders,
+        &output_dir,
+        options,
+        Some(&cd_text),
+        &|done, total| {
+            state.completed.store(done, Ordering::SeqCst);
+            state.total.store(total, Ordering::SeqCst);
+        },
+        &state.cancel_requested,
+    );
+
+    match image {
+        Ok(image) => {
+            let result = coordinate_audio_burn(&image.cue_path, &state, &config);
+            log::info!("Burn coordination result: {:?}", result);
+        }
+        Err(e) if state.is_cancelled() => {
+            log::info!("{}", e);
+            state.set_stage(BurnStage::Cancelled);
+        }
+        Err(e) => {
+            log::error!("Audio CD image creation failed: {}", e);
+            *state.burn_error.lock().unwrap() = Some(e.into());
+            state.set_stage(BurnStage::Complete);
+        }
+    }
+    remove_audio_cd_image(&output_dir);
+    state.finish();
+}
+
+/// Execute an export to a folder or USB drive
+///
+/// This is a blocking function that should be run in a background thread.
+/// It waits for all folders to be converted, stages them the same way a
+/// burn would (with `playlists`), then copies the staged files into
+/// `dest_dir` with names made safe for FAT32 (and for `compat`'s players).
+/// Progress is reported as a percentage through `burn_progress`, and the
+/// outcome is stored in `state.export`.
+pub fn execute_export(
+    state: ConversionState,
+    encoder_handle: SimpleEncoderHandle,
+    output_manager: OutputManager,
+    folders: Vec<MusicFolder>,
+    dest_dir: PathBuf,
+    compat: CompatProfile,
+    playlists: PlaylistFormat,
+) {
+    if !wait_for_conversion(&state, &encoder_handle, &output_manager, &folders, None) {
+        return;
+    }
+
+    let index = DiscIndexInfo {
+        compat,
+        playlists,
         ..Default::default()
     };
+    let result = output_manager
+        .create_iso_staging(&folders, compat, playlists)
+        .and_then(|dir| add_disc_index(&output_manager, &dir, &folders, &index).map(|_| dir))
+        .and_then(|staging_dir| export_to(&state, &staging_dir, &dest_dir));
+    match result {
+        Ok(export) => {
+            log::info!("{}", export.summary());
+            *state.export.lock().unwrap() = Some(export);
+            state.set_stage(BurnStage::Complete);
+        }
+        Err(e) if state.is_cancelled() => {
+            log::info!("{}", e);
+            state.set_stage(BurnStage::Cancelled);
+        }
+        Err(e) => {
+            log::error!("Export failed: {}", e);
+            *state.export.lock().unwrap() = Some(ExportResult {
+                dest_dir,
+                error: Some(e),
+                ..Default::default()
+            });
+            state.set_stage(BurnStage::Complete);
+        }
+    }
+    state.finish();
+}
 
-    let result = coordinate_burn(iso_path, state, &config);
-    log::info!("Burn coordination result: {:?}", result);
+/// Copy a staging directory into `dest_dir`, reporting progress on `state`
+fn export_to(
+    state: &ConversionState,
+    staging_dir: &Path,
+    dest_dir: &Path,
+) -> Result<ExportResult, String> {
+    state.set_stage(BurnStage::Exporting);
+    log::info!("\n=== Exporting to {} ===", dest_dir.display());
+
+    export_staging(
+        staging_dir,
+        dest_dir,
+        &|percent| state.burn_progress.store(percent, Ordering::SeqCst),
+        &state.cancel_requested,
+    )
+}
+
+/// Write the disc index into a staging directory, before any renames
+fn add_disc_index(
+    output_manager: &OutputManager,
+    staging_dir: &Path,
+    folders: &[MusicFolder],
+    index: &DiscIndexInfo,
+) -> Result<(), String> {
+    let staged = output_manager.staged_track_paths(folders, index.compat);
+    write_disc_index(staging_dir, folders, &staged, index)?;
+    Ok(())
+}
+
+/// Make the staged names fit `naming`, recording the renames on `state`
+///
+/// Playlists are updated to the new names. Also returns this staging
+/// directory's renames, for the disc catalog.
+fn apply_naming(
+    state: &ConversionState,
+    staging_dir: &Path,
+    naming: IsoNaming,
+) -> Result<Vec<NameChange>, String> {
+    let changes = apply_iso_naming(staging_dir, naming)?;
+    rename_playlist_entries(staging_dir, &changes)?;
+    state
+        .name_changes
+        .lock()
+        .unwrap()
+        .extend(changes.iter().cloned());
+    Ok(changes)
+}
+
+/// Execute the burn coordination
+fn execute_burn(iso_path: &Path, state: &ConversionState, config: &BurnConfig, record: DiscRecord) {
+    run_burn(iso_path, state, config, record);
     state.finish();
 }
 
+/// Coordinate a burn, cataloguing the disc as `record` if it burned
+///
+/// Simulated burns aren't catalogued.
+fn run_burn(
+    iso_path: &Path,
+    state: &ConversionState,
+    config: &BurnConfig,
+    record: DiscRecord,
+) -> BurnCoordinationResult {
+    let result = coordinate_burn(iso_path, state, config);
+    log::info!("Burn coordination result: {:?}", result);
+    if !config.simulate
+        && matches!(
+            result,
+            BurnCoordinationResult::Success | BurnCoordinationResult::Verified(_)
+        )
+    {
+        catalog_discs(iso_path, record, 1);
+    }
+    result
+}
+
+/// Add `count` discs burned from `iso_path` to the disc catalog
+///
+/// The burn has already succeeded, so a catalog failure is only logged.
+fn catalog_discs(iso_path: &Path, mut record: DiscRecord, count: usize) {
+    if let Err(e) = record.finish(iso_path) {
+        log::warn!("Not adding disc to the catalog: {}", e);
+        return;
+    }
+    if let Err(e) = DiscCatalog::record(&vec![record; count]) {
+        log::warn!("Failed to update the disc catalog: {}", e);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::burning::iso::create_iso;
 
     #[test]
     fn test_burn_config_default() {
@@ -140,4 +535,133 @@ mod tests {
         assert!(!config.simulate);
         assert_eq!(config.cd_wait_timeout_secs, 120);
     }
+
+    #[test]
+    fn test_execute_burn_existing_simulated_with_verify() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let staging = temp_dir.path().join("_iso_staging");
+        std::fs::create_dir_all(&staging).unwrap();
+        std::fs::write(staging.join("track.mp3"), b"mp3 data").unwrap();
+        let iso = create_iso(&staging, "Test").unwrap();
+
+        let state = ConversionState::new();
+        state.reset(0);
+        let config = BurnConfig {
+            simulate: true,
+            verify: true,
+            ..Default::default()
+        };
+        execute_burn_existing(
+            state.clone(),
+            iso.iso_path,
+            config,
+            DiscRecord::new("Test", None, None),
+        );
+
+        assert!(!state.is_converting());
+        assert_eq!(state.get_stage(), BurnStage::Complete);
+        assert!(
+            state
+                .verification
+                .lock()
+                .unwrap()
+                .as_ref()
+                .unwrap()
+                .passed()
+        );
+    }
+
+    #[test]
+    fn test_execute_burn_copies_simulated() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let staging = temp_dir.path().join("_iso_staging");
+        std::fs::create_dir_all(&staging).unwrap();
+        std::fs::write(staging.join("track.mp3"), b"mp3 data").unwrap();
+        let iso = create_iso(&staging, "Test").unwrap();
+
+        let state = ConversionState::new();
+        state.reset(0);
+        let config = BurnConfig {
+            simulate: true,
+            ..Default::default()
+        };
+        execute_burn_copies(
+            state.clone(),
+            iso.iso_path,
+            2,
+            config,
+            DiscRecord::new("Test", None, None),
+        );
+
+        assert!(!state.is_converting());
+        assert_eq!(state.get_stage(), BurnStage::Complete);
+        assert_eq!(state.copy_progress(), Some((2, 2)));
+        assert_eq!(state.copy_results.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_export_to() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let staging = temp_dir.path().join("_iso_staging");
+        std::fs::create_dir_all(staging.join("01-Album")).unwrap();
+        std::fs::write(staging.join("01-Album/track.mp3"), b"mp3 data").unwrap();
+        let dest = temp_dir.path().join("usb");
+        std::fs::create_dir_all(&dest).unwrap();
+
+        let state = ConversionState::new();
+        state.reset(0);
+        let export = export_to(&state, &staging, &dest).unwrap();
+
+        assert_eq!(state.get_stage(), BurnStage::Exporting);
+        assert_eq!(state.get_burn_progress(), 100);
+        assert_eq!(export.files_copied, 1);
+        assert_eq!(
+            std::fs::read(dest.join("01-Album/track.mp3")).unwrap(),
+            b"mp3 data"
+        );
+    }
+
+    #[test]
+    fn test_burn_discs_simulated() {
+        let manager = OutputManager::new().unwrap();
+        let mut discs = Vec::new();
+        for name in ["first", "second"] {
+            let folder = MusicFolder::new_for_test_with_id(name);
+            let output = manager.session_dir().join(name);
+            std::fs::create_dir_all(&output).unwrap();
+            std::fs::write(output.join("track.mp3"), name.as_bytes()).unwrap();
+            discs.push(vec![folder]);
+        }
+
+        let state = ConversionState::new();
+        state.reset(2);
+        let config = BurnConfig {
+            simulate: true,
+            ..Default::default()
+        };
+        let record = DiscRecord::new("MIX", None, None);
+        burn_discs(&state, &manager, &discs, &config, "MIX", &record);
+
+        assert_eq!(state.disc_progress(), Some((2, 2)));
+        assert_eq!(state.discs_burned(), Some((2, 2)));
+        assert_eq!(state.get_stage(), BurnStage::Complete);
+        for disc in 1..=2 {
+            let iso = manager
+                .session_dir()
+                .join(format!("mp3cd-disc{}.iso", disc));
+            let image = std::fs::read(&iso).unwrap();
+            // Primary volume identifier ("/" isn't a d-character)
+            let label = format!("MIX_{}_2", disc);
+            assert!(image[16 * 2048 + 40..].starts_with(label.as_bytes()));
+            // Staging is cleaned up once the image is written
+            assert!(!manager.disc_staging_dir(disc).exists());
+        }
+        assert_eq!(
+            state.iso_path.lock().unwrap().as_deref(),
+            Some(manager.session_dir().join("mp3cd-disc2.iso").as_path())
+        );
+
+        // Cleanup
+        let _ = manager.cleanup();
+    }
 }
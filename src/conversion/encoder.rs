@@ -0,0 +1,136 @@
+//! Pluggable MP3 encoder backends
+//!
+//! The background encoder hands every transcode and remux to an [`Encoder`],
+//! so it doesn't care how the MP3 gets made. Two backends exist: ffmpeg,
+//! when its binary is bundled or installed, and a built-in one that decodes
+//! with symphonia and encodes with LAME in-process. [`select_encoder`] picks
+//! one at startup.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::ffmpeg::FfmpegEncoder;
+use super::lame::LameEncoder;
+use super::verify_ffmpeg;
+use crate::core::{CompatProfile, EncoderSettings};
+
+/// How to encode a track
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodeOptions {
+    /// Bitrate in kbps, already fitted to the mode (see
+    /// [`fit_bitrate`](super::optimizer::fit_bitrate))
+    pub bitrate: u32,
+    /// Cover art to embed, if any
+    pub album_art: Option<PathBuf>,
+    /// Profile deciding which tags are written
+    pub compat: CompatProfile,
+    /// Bitrate mode and LAME tuning
+    pub settings: EncoderSettings,
+}
+
+impl EncodeOptions {
+    pub fn new(bitrate: u32, compat: CompatProfile) -> Self {
+        Self {
+            bitrate,
+            album_art: None,
+            compat,
+            settings: EncoderSettings::default(),
+        }
+    }
+
+    pub fn with_album_art(mut self, album_art: Option<PathBuf>) -> Self {
+        self.album_art = album_art;
+        self
+    }
+
+    pub fn with_settings(mut self, settings: EncoderSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+}
+
+/// Lets whoever started an encode follow it and stop it
+pub trait EncodeControl: Sync {
+    /// Whether the encode should stop as soon as it can
+    fn is_cancelled(&self) -> bool;
+
+    /// How far through the track the encode is, from 0.0 to 1.0
+    fn progress(&self, _fraction: f64) {}
+
+    /// A helper process was started, which cancelling should kill
+    fn process_started(&self, _pid: u32) {}
+
+    /// A helper process from [`EncodeControl::process_started`] exited
+    fn process_finished(&self, _pid: u32) {}
+}
+
+/// An MP3 encoder backend
+pub trait Encoder: Send + Sync {
+    /// Name for logs
+    fn name(&self) -> &'static str;
+
+    /// Encode `input`, an audio file in any format the backend can decode,
+    /// to an MP3 at `output`
+    ///
+    /// Tags are carried over from the input, in the versions
+    /// `options.compat` asks for. On failure or cancellation `output` may
+    /// be left partly written.
+    fn encode(
+        &self,
+        input: &Path,
+        output: &Path,
+        options: &EncodeOptions,
+        control: &dyn EncodeControl,
+    ) -> Result<(), String>;
+
+    /// Copy an MP3's audio as-is, dropping its cover art and rewriting its
+    /// tags for `compat`
+    fn copy_without_art(
+        &self,
+        input: &Path,
+        output: &Path,
+        compat: CompatProfile,
+        control: &dyn EncodeControl,
+    ) -> Result<(), String>;
+}
+
+/// Pick the encoder backend for this run
+///
+/// ffmpeg is preferred when it's available, as it reads more formats and
+/// writes ID3v2.3 tags. Without it the built-in LAME encoder is used.
+pub fn select_encoder() -> Arc<dyn Encoder> {
+    let encoder: Arc<dyn Encoder> = match verify_ffmpeg() {
+        Ok(path) => Arc::new(FfmpegEncoder::new(path)),
+        Err(e) => {
+            log::info!("{}", e);
+            Arc::new(LameEncoder)
+        }
+    };
+    log::info!("Encoding with {}", encoder.name());
+    encoder
+}
+
+/// How much of `total` is `done`, as a progress fraction
+pub(crate) fn fraction(done: f64, total: f64) -> f64 {
+    if total > 0.0 {
+        (done / total).clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_options() {
+        let options = EncodeOptions::new(192, CompatProfile::Modern)
+            .with_album_art(Some(PathBuf::from("/tmp/cover.jpg")));
+        assert_eq!(options.bitrate, 192);
+        assert_eq!(options.album_art, Some(PathBuf::from("/tmp/cover.jpg")));
+        assert_eq!(options.settings, EncoderSettings::default());
+        assert_eq!(fraction(30.0, 120.0), 0.25);
+        assert_eq!(fraction(5.0, 0.0), 0.0);
+    }
+}
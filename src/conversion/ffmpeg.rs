@@ -1,6 +1,176 @@
 //! FFmpeg subprocess handling for audio conversion
 
-use std::path::PathBuf;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::encoder::{EncodeControl, EncodeOptions, Encoder, fraction};
+use super::optimizer::{snap_to_valid_bitrate, vbr_level};
+use crate::core::{BitrateMode, CompatProfile, StereoMode};
+
+/// Encoder backend that runs an ffmpeg binary for each track
+pub struct FfmpegEncoder {
+    path: PathBuf,
+}
+
+impl FfmpegEncoder {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Run an ffmpeg command, reporting its progress through `duration` seconds
+    fn run(
+        &self,
+        mut cmd: Command,
+        duration: f64,
+        control: &dyn EncodeControl,
+    ) -> Result<std::process::ExitStatus, String> {
+        cmd.stdin(Stdio::null());
+        cmd.stdout(Stdio::piped());
+        cmd.stderr(Stdio::null());
+
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| format!("Failed to spawn ffmpeg: {}", e))?;
+        let pid = child.id();
+        control.process_started(pid);
+
+        if let Some(stdout) = child.stdout.take() {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if let Some(micros) = line
+                    .strip_prefix("out_time_us=")
+                    .and_then(|value| value.parse::<f64>().ok())
+                {
+                    control.progress(fraction(micros / 1_000_000.0, duration));
+                }
+            }
+        }
+        let status = child
+            .wait()
+            .map_err(|e| format!("Failed to wait for ffmpeg: {}", e))?;
+        control.process_finished(pid);
+        Ok(status)
+    }
+}
+
+impl Encoder for FfmpegEncoder {
+    fn name(&self) -> &'static str {
+        "ffmpeg"
+    }
+
+    fn encode(
+        &self,
+        input: &Path,
+        output: &Path,
+        options: &EncodeOptions,
+        control: &dyn EncodeControl,
+    ) -> Result<(), String> {
+        let mut cmd = Command::new(&self.path);
+        cmd.arg("-y")
+            .args(["-nostats", "-progress", "pipe:1"])
+            .arg("-i")
+            .arg(input)
+            .arg("-vn")
+            .arg("-codec:a")
+            .arg("libmp3lame")
+            .args(lame_args(options))
+            .arg("-map_metadata")
+            .arg("0")
+            .args(options.compat.id3_args());
+
+        if let Some(art_path) = &options.album_art
+            && art_path.exists()
+        {
+            cmd.arg("-i")
+                .arg(art_path)
+                .arg("-map")
+                .arg("0:a")
+                .arg("-map")
+                .arg("1:v")
+                .arg("-c:v")
+                .arg("copy")
+                .arg("-metadata:s:v")
+                .arg("title=Album cover")
+                .arg("-metadata:s:v")
+                .arg("comment=Cover (front)");
+        }
+        cmd.arg(output);
+
+        let status = self.run(cmd, file_duration(input), control)?;
+        if status.success() {
+            control.progress(1.0);
+            Ok(())
+        } else {
+            Err(format!("ffmpeg failed with status: {}", status))
+        }
+    }
+
+    fn copy_without_art(
+        &self,
+        input: &Path,
+        output: &Path,
+        compat: CompatProfile,
+        control: &dyn EncodeControl,
+    ) -> Result<(), String> {
+        let mut cmd = Command::new(&self.path);
+        cmd.arg("-y")
+            .args(["-nostats", "-progress", "pipe:1"])
+            .arg("-i")
+            .arg(input)
+            .arg("-vn") // Strip video/album art
+            .arg("-codec:a")
+            .arg("copy") // Copy audio stream as-is
+            .arg("-map_metadata")
+            .arg("0")
+            .args(compat.id3_args())
+            .arg(output);
+
+        let status = self.run(cmd, file_duration(input), control)?;
+        if status.success() {
+            control.progress(1.0);
+            Ok(())
+        } else {
+            Err(format!("ffmpeg copy failed with status: {}", status))
+        }
+    }
+}
+
+/// libmp3lame options for the bitrate mode and tuning in `options`
+fn lame_args(options: &EncodeOptions) -> Vec<String> {
+    let settings = &options.settings;
+    let mut args: Vec<String> = match settings.mode {
+        // ABR mode: targets average bitrate precisely (not limited to CBR presets)
+        BitrateMode::Abr => vec!["-abr".into(), "1".into(), "-b:a".into()],
+        BitrateMode::Cbr => vec!["-b:a".into()],
+        BitrateMode::Vbr => vec!["-q:a".into()],
+    };
+    args.push(match settings.mode {
+        BitrateMode::Abr => format!("{}k", options.bitrate),
+        BitrateMode::Cbr => format!("{}k", snap_to_valid_bitrate(options.bitrate)),
+        BitrateMode::Vbr => vbr_level(options.bitrate).to_string(),
+    });
+
+    if let Some(quality) = settings.quality() {
+        args.extend(["-compression_level".into(), quality.to_string()]);
+    }
+    if let Some(hz) = settings.lowpass_hz {
+        args.extend(["-cutoff".into(), hz.to_string()]);
+    }
+    if settings.stereo == StereoMode::Stereo {
+        args.extend(["-joint_stereo".into(), "0".into()]);
+    }
+    if let Some(rate) = settings.sample_rate() {
+        args.extend(["-ar".into(), rate.to_string()]);
+    }
+    args
+}
+
+/// Length of an audio file in seconds, or 0.0 if it can't be read
+fn file_duration(path: &Path) -> f64 {
+    crate::audio::get_audio_metadata(path)
+        .map(|(duration, ..)| duration)
+        .unwrap_or(0.0)
+}
 
 /// Result of a file conversion
 #[derive(Debug, Clone)]
@@ -34,4 +204,85 @@ mod tests {
         assert!(result.success);
         assert!(result.error.is_none());
     }
+
+    #[test]
+    fn test_lame_args() {
+        use crate::core::EncoderSettings;
+
+        let abr = EncodeOptions::new(187, CompatProfile::Modern);
+        assert_eq!(lame_args(&abr), ["-abr", "1", "-b:a", "187k"]);
+
+        let cbr = abr.clone().with_settings(EncoderSettings {
+            mode: BitrateMode::Cbr,
+            ..Default::default()
+        });
+        assert_eq!(lame_args(&cbr), ["-b:a", "160k"]);
+
+        let vbr = EncodeOptions::new(209, CompatProfile::Modern).with_settings(EncoderSettings {
+            mode: BitrateMode::Vbr,
+            quality: Some(2),
+            lowpass_hz: Some(19_000),
+            stereo: StereoMode::Stereo,
+            sample_rate: Some(44_100),
+        });
+        assert_eq!(
+            lame_args(&vbr),
+            [
+                "-q:a",
+                "2",
+                "-compression_level",
+                "2",
+                "-cutoff",
+                "19000",
+                "-joint_stereo",
+                "0",
+                "-ar",
+                "44100"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ffmpeg_encoder_reports_progress() {
+        use std::sync::Mutex;
+
+        #[derive(Default)]
+        struct Recorder {
+            progress: Mutex<Vec<f64>>,
+            pids: Mutex<Vec<u32>>,
+        }
+
+        impl EncodeControl for Recorder {
+            fn is_cancelled(&self) -> bool {
+                false
+            }
+            fn progress(&self, fraction: f64) {
+                self.progress.lock().unwrap().push(fraction);
+            }
+            fn process_started(&self, pid: u32) {
+                self.pids.lock().unwrap().push(pid);
+            }
+        }
+
+        let input = crate::test_fixtures::generate_audio_file("ffmpeg_encoder", "flac", 3, None);
+        let dir = tempfile::TempDir::new().unwrap();
+        let output = dir.path().join("out.mp3");
+        let encoder = FfmpegEncoder::new(crate::conversion::get_ffmpeg_path().unwrap());
+        let recorder = Recorder::default();
+
+        encoder
+            .encode(
+                &input,
+                &output,
+                &EncodeOptions::new(128, CompatProfile::Modern),
+                &recorder,
+            )
+            .unwrap();
+
+        assert!(output.exists());
+        assert_eq!(recorder.pids.lock().unwrap().len(), 1);
+        let progress = recorder.progress.lock().unwrap();
+        assert_eq!(progress.last(), Some(&1.0));
+        assert!(progress.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
 }
@@ -0,0 +1,409 @@
+//! Built-in MP3 encoding with LAME
+//!
+//! Decodes with symphonia and encodes with LAME in-process, so tracks can be
+//! converted without an ffmpeg binary. Tags and cover art are copied with
+//! lofty, which writes ID3v2.4 rather than the ID3v2.3 some head units need,
+//! so ffmpeg is still preferred when it's there.
+
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::os::raw::c_int;
+use std::path::Path;
+
+use lofty::{Picture, PictureType, Tag, TagExt, TagType, TaggedFileExt};
+use mp3lame_encoder::{Builder, FlushNoGap, InterleavedPcm, Mode, VbrMode};
+
+use super::encoder::{EncodeControl, EncodeOptions, Encoder};
+use super::optimizer::{snap_to_valid_bitrate, vbr_level};
+use crate::audio::{decode_file, to_stereo};
+use crate::core::{BitrateMode, CompatProfile, StereoMode};
+
+/// Bytes LAME may write when flushing its last frames
+const FLUSH_BUFFER_BYTES: usize = 7200;
+
+/// Largest VBR tag frame LAME writes (a 320 kbps frame at 32 kHz)
+const VBR_TAG_BYTES: usize = 1441;
+
+/// Lowest and highest average bitrates LAME accepts, in kbps
+const MIN_BITRATE: u32 = 8;
+const MAX_BITRATE: u32 = 320;
+
+/// Encoder backend that runs LAME in-process
+pub struct LameEncoder;
+
+impl Encoder for LameEncoder {
+    fn name(&self) -> &'static str {
+        "LAME (built-in)"
+    }
+
+    fn encode(
+        &self,
+        input: &Path,
+        output: &Path,
+        options: &EncodeOptions,
+        control: &dyn EncodeControl,
+    ) -> Result<(), String> {
+        encode_file(input, output, options, control)?;
+        write_tags(
+            input,
+            output,
+            options.compat,
+            options.album_art.as_deref().filter(|art| art.exists()),
+        )?;
+        control.progress(1.0);
+        Ok(())
+    }
+
+    fn copy_without_art(
+        &self,
+        input: &Path,
+        output: &Path,
+        compat: CompatProfile,
+        control: &dyn EncodeControl,
+    ) -> Result<(), String> {
+        if control.is_cancelled() {
+            return Err("Encoding cancelled".to_string());
+        }
+        std::fs::copy(input, output).map_err(|e| format!("Failed to copy file: {}", e))?;
+        for tag_type in [TagType::Id3v2, TagType::Id3v1, TagType::Ape] {
+            tag_type
+                .remove_from_path(output)
+                .map_err(|e| format!("Failed to remove tags: {}", e))?;
+        }
+        write_tags(input, output, compat, None)?;
+        control.progress(1.0);
+        Ok(())
+    }
+}
+
+/// An MP3 file being written by LAME
+struct Mp3Writer {
+    lame: mp3lame_encoder::Encoder,
+    file: BufWriter<File>,
+    buffer: Vec<u8>,
+}
+
+impl Mp3Writer {
+    /// Start a stereo MP3 at `path`, in the bitrate mode and tuning `options` ask for
+    fn create(path: &Path, sample_rate: u32, options: &EncodeOptions) -> Result<Self, String> {
+        let setup_error = |e: mp3lame_encoder::BuildError| format!("Failed to set up LAME: {}", e);
+        let settings = &options.settings;
+        let bitrate = options.bitrate.clamp(MIN_BITRATE, MAX_BITRATE);
+
+        let mut builder = Builder::new().ok_or_else(|| "Failed to start LAME".to_string())?;
+        builder.set_num_channels(2).map_err(setup_error)?;
+        builder.set_sample_rate(sample_rate).map_err(setup_error)?;
+        builder
+            .set_mode(match settings.stereo {
+                StereoMode::Joint => Mode::JointStereo,
+                StereoMode::Stereo => Mode::Stereo,
+            })
+            .map_err(setup_error)?;
+        builder
+            .set_vbr_mode(match settings.mode {
+                BitrateMode::Abr => VbrMode::Abr,
+                BitrateMode::Cbr => VbrMode::Off,
+                BitrateMode::Vbr => VbrMode::Mtrh,
+            })
+            .map_err(setup_error)?;
+        // Players read an ABR or VBR file's length from the Xing/LAME tag
+        // and seek with its table. ffmpeg writes it in every mode, so this does too.
+        builder.set_to_write_vbr_tag(true).map_err(setup_error)?;
+
+        // The builder only offers the standard bitrates and a few presets,
+        // so the rest is set on its flags directly. The flags pointer
+        // belongs to the builder and lives until it's built.
+        let flags = builder.as_ptr();
+        match settings.mode {
+            // An ABR target can be any average, like ffmpeg's `-abr 1`
+            BitrateMode::Abr => lame_setting("bitrate", bitrate, unsafe {
+                mp3lame_encoder::ffi::lame_set_VBR_mean_bitrate_kbps(flags, bitrate as c_int)
+            })?,
+            BitrateMode::Cbr => {
+                let bitrate = snap_to_valid_bitrate(bitrate);
+                lame_setting("bitrate", bitrate, unsafe {
+                    mp3lame_encoder::ffi::lame_set_brate(flags, bitrate as c_int)
+                })?
+            }
+            BitrateMode::Vbr => {
+                let level = vbr_level(bitrate);
+                lame_setting("VBR preset", level, unsafe {
+                    mp3lame_encoder::ffi::lame_set_VBR_q(flags, level as c_int)
+                })?
+            }
+        }
+        if let Some(quality) = settings.quality() {
+            lame_setting("quality", quality, unsafe {
+                mp3lame_encoder::ffi::lame_set_quality(flags, quality as c_int)
+            })?;
+        }
+        if let Some(hz) = settings.lowpass_hz {
+            lame_setting("lowpass", hz, unsafe {
+                mp3lame_encoder::ffi::lame_set_lowpassfreq(flags, hz as c_int)
+            })?;
+        }
+        if let Some(rate) = settings.sample_rate() {
+            lame_setting("sample rate", rate, unsafe {
+                mp3lame_encoder::ffi::lame_set_out_samplerate(flags, rate as c_int)
+            })?;
+        }
+
+        let lame = builder
+            .build()
+            .map_err(|e| format!("Failed to start LAME: {}", e))?;
+
+        let file =
+            File::create(path).map_err(|e| format!("Failed to create output file: {}", e))?;
+        Ok(Self {
+            lame,
+            file: BufWriter::new(file),
+            buffer: Vec::new(),
+        })
+    }
+
+    /// Encode interleaved stereo samples
+    fn write(&mut self, stereo: &[i16]) -> Result<(), String> {
+        self.buffer.clear();
+        self.buffer
+            .reserve(mp3lame_encoder::max_required_buffer_size(stereo.len() / 2));
+        self.lame
+            .encode_to_vec(InterleavedPcm(stereo), &mut self.buffer)
+            .map_err(|e| format!("Failed to encode audio: {}", e))?;
+        self.file
+            .write_all(&self.buffer)
+            .map_err(|e| format!("Failed to write MP3: {}", e))
+    }
+
+    /// Write the last frames and close the file
+    fn finish(mut self) -> Result<(), String> {
+        self.buffer.clear();
+        self.buffer.reserve(FLUSH_BUFFER_BYTES);
+        self.lame
+            .flush_to_vec::<FlushNoGap>(&mut self.buffer)
+            .map_err(|e| format!("Failed to encode audio: {}", e))?;
+        self.file
+            .write_all(&self.buffer)
+            .map_err(|e| format!("Failed to write MP3: {}", e))?;
+
+        // LAME left an empty frame at the start for the tag, which it can
+        // only fill in now that it's seen every frame
+        let mut frame = vec![0u8; VBR_TAG_BYTES];
+        let size = unsafe {
+            mp3lame_encoder::ffi::lame_get_lametag_frame(
+                self.lame.as_ptr(),
+                frame.as_mut_ptr(),
+                frame.len(),
+            )
+        };
+        if size > 0 && size <= frame.len() {
+            self.file
+                .seek(SeekFrom::Start(0))
+                .and_then(|_| self.file.write_all(&frame[..size]))
+                .map_err(|e| format!("Failed to write MP3: {}", e))?;
+        }
+        self.file
+            .flush()
+            .map_err(|e| format!("Failed to write MP3: {}", e))
+    }
+}
+
+/// Check the result of setting one of LAME's flags
+fn lame_setting(name: &str, value: impl std::fmt::Display, result: c_int) -> Result<(), String> {
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(format!("Failed to set up LAME: {} {} refused", name, value))
+    }
+}
+
+/// Decode `input` with symphonia and encode it as it's decoded
+fn encode_file(
+    input: &Path,
+    output: &Path,
+    options: &EncodeOptions,
+    control: &dyn EncodeControl,
+) -> Result<(), String> {
+    let mut writer: Option<Mp3Writer> = None;
+    decode_file(input, &mut |block| {
+        if control.is_cancelled() {
+            return Err("Encoding cancelled".to_string());
+        }
+        // The output's sample rate is only known once audio is decoded
+        let writer = match &mut writer {
+            Some(writer) => writer,
+            None => writer.insert(Mp3Writer::create(output, block.sample_rate, options)?),
+        };
+        writer.write(&to_stereo(block.samples, block.channels))?;
+        control.progress(block.progress);
+        Ok(())
+    })?;
+
+    writer
+        .ok_or_else(|| "No audio could be decoded".to_string())?
+        .finish()
+}
+
+/// Tag `output` the way `compat` asks, with the tags from `source`
+///
+/// Cover art from the source is dropped; `album_art` is embedded instead.
+fn write_tags(
+    source: &Path,
+    output: &Path,
+    compat: CompatProfile,
+    album_art: Option<&Path>,
+) -> Result<(), String> {
+    let mut tag = read_tag(source).unwrap_or_else(|| Tag::new(TagType::Id3v2));
+    tag.re_tag(TagType::Id3v2);
+
+    let picture_types: Vec<PictureType> = tag.pictures().iter().map(|p| p.pic_type()).collect();
+    for picture_type in picture_types {
+        tag.remove_picture_type(picture_type);
+    }
+    if let Some(art_path) = album_art {
+        let mut art =
+            File::open(art_path).map_err(|e| format!("Failed to open cover art: {}", e))?;
+        let mut picture = Picture::from_reader(&mut art)
+            .map_err(|e| format!("Failed to read cover art: {}", e))?;
+        picture.set_pic_type(PictureType::CoverFront);
+        picture.set_description(Some("Album cover".to_string()));
+        tag.push_picture(picture);
+    }
+
+    if compat.writes_id3v2() {
+        tag.save_to_path(output)
+            .map_err(|e| format!("Failed to write tags: {}", e))?;
+    }
+    if compat.writes_id3v1() {
+        tag.re_tag(TagType::Id3v1);
+        tag.save_to_path(output)
+            .map_err(|e| format!("Failed to write tags: {}", e))?;
+    }
+    Ok(())
+}
+
+/// The main tag of an audio file, if it has one
+fn read_tag(path: &Path) -> Option<Tag> {
+    let tagged_file = lofty::read_from_path(path).ok()?;
+    tagged_file
+        .primary_tag()
+        .or_else(|| tagged_file.first_tag())
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    struct NoControl;
+
+    impl EncodeControl for NoControl {
+        fn is_cancelled(&self) -> bool {
+            false
+        }
+    }
+
+    /// A WAV file in `dir` holding two seconds of a 440 Hz tone
+    fn tone(dir: &TempDir, channels: u16) -> PathBuf {
+        let sample_rate: u32 = 44_100;
+        let data: Vec<u8> = (0..sample_rate * 2)
+            .flat_map(|i| {
+                let t = i as f64 / sample_rate as f64;
+                let s = ((t * 440.0 * std::f64::consts::TAU).sin() * 8000.0) as i16;
+                std::iter::repeat_n(s, channels as usize)
+            })
+            .flat_map(i16::to_le_bytes)
+            .collect();
+        let block_align = channels * 2;
+
+        let mut wav = Vec::with_capacity(44 + data.len());
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        wav.extend_from_slice(&channels.to_le_bytes());
+        wav.extend_from_slice(&sample_rate.to_le_bytes());
+        wav.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+        wav.extend_from_slice(&block_align.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        wav.extend_from_slice(&data);
+
+        let path = dir.path().join(format!("tone{}.wav", channels));
+        std::fs::write(&path, wav).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_encode_wav_and_reencode() {
+        let dir = TempDir::new().unwrap();
+        let first = dir.path().join("tone.mp3");
+        let options = EncodeOptions::new(128, CompatProfile::GenericHeadUnit);
+
+        LameEncoder
+            .encode(&tone(&dir, 1), &first, &options, &NoControl)
+            .unwrap();
+        let (_, _, codec, _) = crate::audio::get_audio_metadata(&first).unwrap();
+        assert_eq!(codec, "mp3");
+
+        // Decoding an MP3 back through symphonia and encoding it again
+        let second = dir.path().join("again.mp3");
+        LameEncoder
+            .encode(&first, &second, &options, &NoControl)
+            .unwrap();
+        let size = std::fs::metadata(&second).unwrap().len();
+        // About 2 seconds at 128 kbps
+        assert!((20_000..45_000).contains(&size), "{} bytes", size);
+    }
+
+    #[test]
+    fn test_encode_each_mode() {
+        use crate::core::EncoderSettings;
+
+        let dir = TempDir::new().unwrap();
+        let input = tone(&dir, 2);
+        let encode = |name: &str, bitrate: u32, mode: BitrateMode| {
+            let output = dir.path().join(name);
+            let options =
+                EncodeOptions::new(bitrate, CompatProfile::Modern).with_settings(EncoderSettings {
+                    mode,
+                    quality: Some(5),
+                    lowpass_hz: Some(16_000),
+                    ..Default::default()
+                });
+            LameEncoder
+                .encode(&input, &output, &options, &NoControl)
+                .unwrap();
+            std::fs::read(&output).unwrap()
+        };
+
+        // 150 kbps is rounded down to 128 kbps CBR: about 2 seconds' worth
+        let cbr = encode("cbr.mp3", 150, BitrateMode::Cbr);
+        assert!((28_000..40_000).contains(&cbr.len()), "{} bytes", cbr.len());
+
+        // ABR and VBR files start with the tag players read their length from
+        let abr = encode("abr.mp3", 150, BitrateMode::Abr);
+        assert!(abr.windows(4).any(|w| w == b"Xing"));
+        let vbr = encode("vbr.mp3", 209, BitrateMode::Vbr);
+        assert!(vbr.windows(4).any(|w| w == b"Xing"));
+    }
+
+    #[test]
+    fn test_encode_cancelled() {
+        struct Cancelled;
+        impl EncodeControl for Cancelled {
+            fn is_cancelled(&self) -> bool {
+                true
+            }
+        }
+
+        let dir = TempDir::new().unwrap();
+        let output = dir.path().join("tone.mp3");
+        let options = EncodeOptions::new(128, CompatProfile::Modern);
+        let result = LameEncoder.encode(&tone(&dir, 2), &output, &options, &Cancelled);
+        assert_eq!(result, Err("Encoding cancelled".to_string()));
+    }
+}
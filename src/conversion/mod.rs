@@ -1,16 +1,24 @@
 //! Audio conversion module
 //!
-//! Handles transcoding audio files to MP3 using ffmpeg.
+//! Handles transcoding audio files to MP3, using ffmpeg when it's available
+//! and the built-in LAME encoder otherwise.
 
 mod background;
+mod encoder;
 mod ffmpeg;
+mod lame;
 mod optimizer;
 mod output_manager;
 mod simple_encoder;
 
 pub use background::{EncoderEvent, EncodingPhase};
-pub use optimizer::{MultipassEstimate, calculate_multipass_bitrate};
-pub use output_manager::{OutputManager, calculate_dir_size};
+pub use encoder::{EncodeControl, EncodeOptions, Encoder, select_encoder};
+pub use ffmpeg::FfmpegEncoder;
+pub use lame::LameEncoder;
+pub use optimizer::{
+    MultipassEstimate, bitrate_label, calculate_multipass_bitrate, fit_bitrate,
+};
+pub use output_manager::{OutputManager, StagingEntry, calculate_dir_size, disc_names};
 pub use simple_encoder::{SimpleEncoderHandle, start_simple_encoder};
 
 use std::path::PathBuf;
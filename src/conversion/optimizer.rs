@@ -1,21 +1,24 @@
 //! Bitrate optimization through dry-run estimation
 //!
 //! Estimates output size without running ffmpeg, then iteratively
-//! adjusts target bitrate to maximize quality while staying under CD capacity.
+//! adjusts target bitrate to maximize quality while staying under the target
+//! media capacity (see [`MediaProfile`](crate::core::MediaProfile)).
 //!
 //! The multi-pass-aware estimate accounts for:
 //! - MP3s being copied (exact size)
 //! - Lossy files transcoded at source bitrate
 //! - Lossless files using remaining space
+//!
+//! Each [`BitrateMode`] has its own size model: ABR lands on the bitrate
+//! it's given, CBR on the valid bitrate below it, and VBR on the typical
+//! bitrate of its preset plus a margin. The planned bitrate is always one
+//! the mode can hit (see [`fit_bitrate`]), so a VBR preset is still chosen
+//! to fit the disc.
 
 #![allow(dead_code)]
 
 use crate::audio::{EncodingStrategy, determine_encoding_strategy};
-use crate::core::AudioFileInfo;
-
-/// CD capacity target in bytes (700 MB decimal)
-/// CD-Rs are labeled 700 MB using decimal (not binary) megabytes
-const CD_CAPACITY_BYTES: u64 = 700 * 1000 * 1000;
+use crate::core::{AudioFileInfo, BitrateMode};
 
 /// Safety margin for estimation errors (5%)
 /// Accounts for: VBR encoding unpredictability at higher bitrates,
@@ -52,6 +55,72 @@ fn next_higher_bitrate(bitrate: u32) -> Option<u32> {
     VALID_CBR_BITRATES.iter().find(|&&b| b > bitrate).copied()
 }
 
+/// Typical average bitrate of LAME's VBR presets, V0 first (kbps)
+const VBR_PRESET_KBPS: [u32; 10] = [245, 225, 190, 175, 165, 130, 115, 100, 85, 65];
+
+/// How far a VBR track may run over its preset's typical bitrate, in percent
+/// Dense material (distorted guitars, applause) takes well above average.
+const VBR_SIZE_MARGIN_PERCENT: u32 = 10;
+
+/// Bitrate a VBR preset is planned at: its typical bitrate plus the margin
+fn vbr_planned_bitrate(level: u8) -> u32 {
+    (VBR_PRESET_KBPS[level as usize] * (100 + VBR_SIZE_MARGIN_PERCENT)).div_ceil(100)
+}
+
+/// Best VBR preset (0 = V0) expected to fit in `bitrate` kbps
+///
+/// Falls back to V9, the smallest, when none does.
+pub fn vbr_level(bitrate: u32) -> u8 {
+    (0..VBR_PRESET_KBPS.len() as u8)
+        .find(|&level| vbr_planned_bitrate(level) <= bitrate)
+        .unwrap_or(VBR_PRESET_KBPS.len() as u8 - 1)
+}
+
+/// Lowest bitrate `mode` is planned at (kbps)
+///
+/// VBR can't go below V9, which is planned a little above [`MIN_BITRATE`].
+fn min_bitrate(mode: BitrateMode) -> u32 {
+    match mode {
+        BitrateMode::Abr | BitrateMode::Cbr => MIN_BITRATE,
+        BitrateMode::Vbr => vbr_planned_bitrate(VBR_PRESET_KBPS.len() as u8 - 1),
+    }
+}
+
+/// Bitrate an encode in `mode` is planned at, given `bitrate` kbps to spend
+///
+/// ABR hits any average, CBR rounds down to a valid bitrate and VBR to the
+/// best preset that fits. Nothing is planned below the mode's lowest
+/// bitrate, so less than that is raised to it. Fitting the result again
+/// returns it unchanged, so it's the bitrate handed to the encoder and shown
+/// to the user.
+pub fn fit_bitrate(mode: BitrateMode, bitrate: u32) -> u32 {
+    let bitrate = bitrate.max(min_bitrate(mode));
+    match mode {
+        BitrateMode::Abr => bitrate,
+        BitrateMode::Cbr => snap_to_valid_bitrate(bitrate),
+        BitrateMode::Vbr => vbr_planned_bitrate(vbr_level(bitrate)),
+    }
+}
+
+/// Planned size of a `duration_secs` transcode in `mode` at `bitrate` kbps
+pub fn estimate_encoded_size(mode: BitrateMode, bitrate: u32, duration_secs: f64) -> u64 {
+    let kbps = fit_bitrate(mode, bitrate);
+    let audio_bytes = (duration_secs * kbps as f64 * 1000.0 / 8.0) as u64;
+    audio_bytes + 10_000 // ~10KB overhead for headers/padding
+}
+
+/// A planned bitrate as shown to the user, e.g. "192 kbps" or "V2 (~190 kbps)"
+pub fn bitrate_label(mode: BitrateMode, bitrate: u32) -> String {
+    match mode {
+        BitrateMode::Abr => format!("{} kbps", bitrate),
+        BitrateMode::Cbr => format!("{} kbps CBR", snap_to_valid_bitrate(bitrate)),
+        BitrateMode::Vbr => {
+            let level = vbr_level(bitrate);
+            format!("V{} (~{} kbps)", level, VBR_PRESET_KBPS[level as usize])
+        }
+    }
+}
+
 /// Calculate estimated size at a given bitrate for lossless content
 /// Note: No safety margin here - the raw bitrate calculation doesn't include it either
 fn estimate_lossless_size(duration_secs: f64, bitrate: u32) -> u64 {
@@ -128,7 +197,13 @@ pub fn estimate_file_size(file: &AudioFileInfo, target_bitrate: u32) -> FileEsti
 }
 
 /// Estimate total output size for all files at a given target bitrate
-pub fn estimate_conversion(files: &[AudioFileInfo], target_bitrate: u32) -> ConversionEstimate {
+///
+/// Headroom is measured against `capacity_bytes`.
+pub fn estimate_conversion(
+    files: &[AudioFileInfo],
+    target_bitrate: u32,
+    capacity_bytes: u64,
+) -> ConversionEstimate {
     let mut total_bytes = 0u64;
     let mut copy_count = 0usize;
     let mut transcode_count = 0usize;
@@ -145,7 +220,7 @@ pub fn estimate_conversion(files: &[AudioFileInfo], target_bitrate: u32) -> Conv
 
     // Apply safety margin to account for estimation errors
     let adjusted_bytes = (total_bytes as f64 * (1.0 + SAFETY_MARGIN)) as u64;
-    let headroom_bytes = CD_CAPACITY_BYTES as i64 - adjusted_bytes as i64;
+    let headroom_bytes = capacity_bytes as i64 - adjusted_bytes as i64;
 
     ConversionEstimate {
         target_bitrate,
@@ -156,7 +231,7 @@ pub fn estimate_conversion(files: &[AudioFileInfo], target_bitrate: u32) -> Conv
     }
 }
 
-/// Optimize bitrate to maximize quality while staying under CD capacity
+/// Optimize bitrate to maximize quality while staying under `capacity_bytes`
 ///
 /// Returns the optimal bitrate and the estimate at that bitrate.
 /// Starts from the initial bitrate and increases until we exceed capacity,
@@ -164,15 +239,16 @@ pub fn estimate_conversion(files: &[AudioFileInfo], target_bitrate: u32) -> Conv
 pub fn optimize_bitrate(
     files: &[AudioFileInfo],
     initial_bitrate: u32,
+    capacity_bytes: u64,
 ) -> (u32, ConversionEstimate) {
     let mut best_bitrate = initial_bitrate.clamp(MIN_BITRATE, MAX_BITRATE);
-    let mut best_estimate = estimate_conversion(files, best_bitrate);
+    let mut best_estimate = estimate_conversion(files, best_bitrate, capacity_bytes);
 
     // If initial estimate is already over capacity, decrease bitrate
     if best_estimate.headroom_bytes < 0 {
         while best_bitrate > MIN_BITRATE && best_estimate.headroom_bytes < 0 {
             best_bitrate = best_bitrate.saturating_sub(BITRATE_STEP);
-            best_estimate = estimate_conversion(files, best_bitrate);
+            best_estimate = estimate_conversion(files, best_bitrate, capacity_bytes);
         }
         return (best_bitrate, best_estimate);
     }
@@ -182,7 +258,7 @@ pub fn optimize_bitrate(
 
     while current_bitrate < MAX_BITRATE {
         let next_bitrate = (current_bitrate + BITRATE_STEP).min(MAX_BITRATE);
-        let next_estimate = estimate_conversion(files, next_bitrate);
+        let next_estimate = estimate_conversion(files, next_bitrate, capacity_bytes);
 
         if next_estimate.headroom_bytes >= 0 {
             // Still fits - this becomes our new best
@@ -218,7 +294,8 @@ pub struct MultipassEstimate {
     pub lossless_count: usize,
     /// Maximum source bitrate among lossy files (for UI display logic)
     pub max_lossy_bitrate: u32,
-    /// Whether the estimated output would exceed CD capacity (before capping)
+    /// Whether the estimated output would exceed CD capacity (before capping).
+    /// With lossless files this means they don't fit even at the minimum bitrate.
     pub would_exceed_capacity: bool,
 }
 
@@ -236,10 +313,21 @@ impl MultipassEstimate {
 /// Multi-pass-aware bitrate calculation
 ///
 /// Calculates the lossless bitrate by accounting for:
-/// - MP3s being copied (exact size known)
+/// - MP3s being copied (size estimated from duration and bitrate)
 /// - Lossy files transcoded at source bitrate (estimated size)
-/// - Remaining space goes to lossless files
-pub fn calculate_multipass_bitrate(files: &[AudioFileInfo]) -> MultipassEstimate {
+/// - Remaining space (of `capacity_bytes`) goes to lossless files
+///
+/// The estimates leave out tags and art, so they get the safety margin.
+///
+/// `capacity_bytes` is the space left for the tracks' own bytes, with the
+/// disc's playlists, index files and filesystem already taken off (see
+/// [`disc_overhead`](crate::burning::disc_overhead)). Transcodes are sized
+/// with `mode`'s model, and the target bitrate is one `mode` can hit.
+pub fn calculate_multipass_bitrate(
+    files: &[AudioFileInfo],
+    capacity_bytes: u64,
+    mode: BitrateMode,
+) -> MultipassEstimate {
     let mut copy_size = 0u64;
     let mut lossy_size = 0u64;
     let mut lossless_duration = 0.0f64;
@@ -275,8 +363,7 @@ pub fn calculate_multipass_bitrate(files: &[AudioFileInfo]) -> MultipassEstimate
             }
             EncodingStrategy::ConvertAtSourceBitrate(br) => {
                 // Lossy transcode at source bitrate
-                let audio_bytes = (file.duration * *br as f64 * 1000.0 / 8.0) as u64;
-                lossy_size += audio_bytes + 10_000; // 10KB overhead
+                lossy_size += estimate_encoded_size(mode, *br, file.duration);
                 lossy_count += 1;
                 max_lossy_bitrate = max_lossy_bitrate.max(*br);
             }
@@ -288,14 +375,17 @@ pub fn calculate_multipass_bitrate(files: &[AudioFileInfo]) -> MultipassEstimate
         }
     }
 
-    // Apply safety margin to copy and lossy estimates
+    // Apply safety margin to copy and lossy estimates: both come from
+    // duration and bitrate, leaving out tags and art. The filesystem is
+    // already taken off the capacity.
     let fixed_size = ((copy_size + lossy_size) as f64 * (1.0 + SAFETY_MARGIN)) as u64;
 
-    // Check if lossy-only output would exceed capacity
-    let would_exceed_capacity = lossless_count == 0 && fixed_size > CD_CAPACITY_BYTES;
+    // Check if the output would exceed capacity even with lossless at the minimum bitrate
+    let lossless_min_size = (lossless_duration * min_bitrate(mode) as f64 * 1000.0 / 8.0) as u64;
+    let would_exceed_capacity = fixed_size + lossless_min_size > capacity_bytes;
 
     // Calculate remaining space for lossless
-    let remaining_bytes = CD_CAPACITY_BYTES.saturating_sub(fixed_size);
+    let remaining_bytes = capacity_bytes.saturating_sub(fixed_size);
 
     // Calculate optimal lossless bitrate from remaining space
     let raw_bitrate = if lossless_duration > 0.0 && remaining_bytes > 0 {
@@ -306,7 +396,7 @@ pub fn calculate_multipass_bitrate(files: &[AudioFileInfo]) -> MultipassEstimate
         // No lossless files - calculate what cap would be needed if we exceed capacity
         let total_duration: f64 = files.iter().map(|f| f.duration).sum();
         if total_duration > 0.0 {
-            let raw = (CD_CAPACITY_BYTES as f64 * 8.0 / total_duration / 1000.0) as u32;
+            let raw = (capacity_bytes as f64 * 8.0 / total_duration / 1000.0) as u32;
             raw.clamp(MIN_BITRATE, MAX_BITRATE)
         } else {
             MAX_BITRATE
@@ -316,9 +406,8 @@ pub fn calculate_multipass_bitrate(files: &[AudioFileInfo]) -> MultipassEstimate
         MIN_BITRATE
     };
 
-    // With ABR mode, we can use any bitrate - no need to snap to CBR values
-    // ABR targets the specified average bitrate with predictable file sizes
-    let target_bitrate = raw_bitrate;
+    // ABR keeps the raw bitrate; CBR and VBR round down to one they can hit
+    let target_bitrate = fit_bitrate(mode, raw_bitrate);
 
     MultipassEstimate {
         target_bitrate,
@@ -335,6 +424,8 @@ mod tests {
     use super::*;
     use std::path::PathBuf;
 
+    const CD_CAPACITY_BYTES: u64 = 700 * 1000 * 1000;
+
     fn make_test_file(
         codec: &str,
         bitrate: u32,
@@ -389,7 +480,7 @@ mod tests {
             make_test_file("flac", 0, 180.0, 30_000_000, false), // Transcode
         ];
 
-        let estimate = estimate_conversion(&files, 256);
+        let estimate = estimate_conversion(&files, 256, CD_CAPACITY_BYTES);
 
         assert_eq!(estimate.copy_count, 1);
         assert_eq!(estimate.transcode_count, 1);
@@ -404,7 +495,7 @@ mod tests {
             make_test_file("flac", 0, 60.0, 10_000_000, false),
         ];
 
-        let (optimized, estimate) = optimize_bitrate(&files, 128);
+        let (optimized, estimate) = optimize_bitrate(&files, 128, CD_CAPACITY_BYTES);
 
         // Should increase from 128 since files are small
         assert!(optimized > 128);
@@ -422,7 +513,7 @@ mod tests {
             .map(|_| make_test_file("flac", 0, 2160.0, 50_000_000, false)) // 36 min each
             .collect();
 
-        let (optimized, estimate) = optimize_bitrate(&files, 320);
+        let (optimized, estimate) = optimize_bitrate(&files, 320, CD_CAPACITY_BYTES);
 
         // Should decrease since total duration exceeds 5 hours
         assert!(optimized < 320, "Expected bitrate < 320, got {}", optimized);
@@ -512,7 +603,7 @@ mod tests {
             make_test_file("mp3", 192, 240.0, 5_760_000, true),
         ];
 
-        let result = calculate_multipass_bitrate(&files);
+        let result = calculate_multipass_bitrate(&files, CD_CAPACITY_BYTES, BitrateMode::Abr);
 
         assert_eq!(result.copy_count, 2);
         assert_eq!(result.lossy_count, 0);
@@ -528,7 +619,7 @@ mod tests {
             make_test_file("flac", 1411, 240.0, 40_000_000, false),
         ];
 
-        let result = calculate_multipass_bitrate(&files);
+        let result = calculate_multipass_bitrate(&files, CD_CAPACITY_BYTES, BitrateMode::Abr);
 
         assert_eq!(result.copy_count, 0);
         assert_eq!(result.lossy_count, 0);
@@ -546,7 +637,7 @@ mod tests {
             make_test_file("flac", 1411, 240.0, 40_000_000, false),
         ];
 
-        let result = calculate_multipass_bitrate(&files);
+        let result = calculate_multipass_bitrate(&files, CD_CAPACITY_BYTES, BitrateMode::Abr);
 
         assert_eq!(result.copy_count, 1);
         assert_eq!(result.lossy_count, 0);
@@ -561,7 +652,7 @@ mod tests {
             make_test_file("aac", 320, 240.0, 9_600_000, true),
         ];
 
-        let result = calculate_multipass_bitrate(&files);
+        let result = calculate_multipass_bitrate(&files, CD_CAPACITY_BYTES, BitrateMode::Abr);
 
         assert_eq!(result.lossy_count, 2);
         assert_eq!(result.max_lossy_bitrate, 320);
@@ -576,12 +667,126 @@ mod tests {
             .map(|_| make_test_file("aac", 320, 1800.0, 72_000_000, true)) // 30 min each
             .collect();
 
-        let result = calculate_multipass_bitrate(&files);
+        let result = calculate_multipass_bitrate(&files, CD_CAPACITY_BYTES, BitrateMode::Abr);
 
         assert!(result.would_exceed_capacity);
         assert!(result.should_show_bitrate()); // Should show because it exceeds
     }
 
+    #[test]
+    fn test_calculate_multipass_bitrate_lossless_exceeds_capacity() {
+        // 30 hours of FLAC doesn't fit even at the minimum bitrate
+        let files: Vec<_> = (0..30)
+            .map(|_| make_test_file("flac", 0, 3600.0, 400_000_000, false))
+            .collect();
+
+        let result = calculate_multipass_bitrate(&files, CD_CAPACITY_BYTES, BitrateMode::Abr);
+
+        assert_eq!(result.target_bitrate, MIN_BITRATE);
+        assert!(result.would_exceed_capacity);
+    }
+
+    #[test]
+    fn test_calculate_multipass_bitrate_larger_media() {
+        // 30 hours of FLAC: too much for a CD, comfortable on a DVD
+        let files: Vec<_> = (0..30)
+            .map(|_| make_test_file("flac", 0, 3600.0, 400_000_000, false))
+            .collect();
+
+        let cd = calculate_multipass_bitrate(&files, CD_CAPACITY_BYTES, BitrateMode::Abr);
+        let dvd = calculate_multipass_bitrate(&files, 4_700 * 1000 * 1000, BitrateMode::Abr);
+
+        assert!(cd.would_exceed_capacity);
+        assert!(!dvd.would_exceed_capacity);
+        assert!(dvd.target_bitrate > 256);
+    }
+
+    #[test]
+    fn test_fit_bitrate_per_mode() {
+        assert_eq!(fit_bitrate(BitrateMode::Abr, 250), 250);
+        assert_eq!(fit_bitrate(BitrateMode::Cbr, 250), 224);
+        // V0 is planned at 270 kbps, so 250 gets V1
+        assert_eq!(vbr_level(250), 1);
+        assert_eq!(fit_bitrate(BitrateMode::Vbr, 250), 248);
+        assert_eq!(vbr_level(320), 0);
+        assert_eq!(vbr_level(MIN_BITRATE), 9);
+
+        // Below a mode's lowest bitrate, fitting raises it
+        assert_eq!(fit_bitrate(BitrateMode::Abr, 32), MIN_BITRATE);
+        assert_eq!(min_bitrate(BitrateMode::Vbr), 72);
+        assert_eq!(fit_bitrate(BitrateMode::Vbr, MIN_BITRATE), 72);
+
+        for mode in [BitrateMode::Abr, BitrateMode::Cbr, BitrateMode::Vbr] {
+            let lowest = min_bitrate(mode);
+            for bitrate in MIN_BITRATE..=MAX_BITRATE {
+                let fitted = fit_bitrate(mode, bitrate);
+                assert!(fitted >= lowest, "{:?} at {}", mode, bitrate);
+                assert!(fitted <= bitrate.max(lowest), "{:?} at {}", mode, bitrate);
+                assert_eq!(
+                    fit_bitrate(mode, fitted),
+                    fitted,
+                    "{:?} at {}",
+                    mode,
+                    bitrate
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_bitrate_label() {
+        assert_eq!(bitrate_label(BitrateMode::Abr, 187), "187 kbps");
+        assert_eq!(bitrate_label(BitrateMode::Cbr, 187), "160 kbps CBR");
+        assert_eq!(bitrate_label(BitrateMode::Vbr, 209), "V2 (~190 kbps)");
+    }
+
+    #[test]
+    fn test_calculate_multipass_bitrate_vbr_fits() {
+        // 10 hours of FLAC on a CD: about 155 kbps to spend
+        let files: Vec<_> = (0..10)
+            .map(|_| make_test_file("flac", 0, 3600.0, 400_000_000, false))
+            .collect();
+
+        let abr = calculate_multipass_bitrate(&files, CD_CAPACITY_BYTES, BitrateMode::Abr);
+        let cbr = calculate_multipass_bitrate(&files, CD_CAPACITY_BYTES, BitrateMode::Cbr);
+        let vbr = calculate_multipass_bitrate(&files, CD_CAPACITY_BYTES, BitrateMode::Vbr);
+
+        assert_eq!(abr.target_bitrate, 155);
+        assert_eq!(cbr.target_bitrate, 128);
+        assert_eq!(vbr_level(vbr.target_bitrate), 5);
+        let vbr_size = estimate_encoded_size(BitrateMode::Vbr, vbr.target_bitrate, 36_000.0);
+        assert!(vbr_size <= CD_CAPACITY_BYTES);
+        assert!(!vbr.would_exceed_capacity);
+    }
+
+    #[test]
+    fn test_calculate_multipass_bitrate_margin_on_estimates() {
+        // An hour at 128 kbps is estimated at 57.6 MB, before tags and art
+        for codec in ["mp3", "aac"] {
+            let files = vec![make_test_file(codec, 128, 3600.0, 60_000_000, true)];
+            let tight = calculate_multipass_bitrate(&files, 58_000_000, BitrateMode::Abr);
+            assert!(tight.would_exceed_capacity, "{}", codec);
+            let roomy = calculate_multipass_bitrate(&files, 61_000_000, BitrateMode::Abr);
+            assert!(!roomy.would_exceed_capacity, "{}", codec);
+        }
+    }
+
+    #[test]
+    fn test_calculate_multipass_bitrate_vbr_lossy_sizes() {
+        // 256 kbps AAC re-encoded with VBR is planned at V1, not 256 kbps
+        // (117 MB with the margin, against 121 MB for ABR)
+        let files = vec![make_test_file("aac", 256, 3600.0, 115_200_000, true)];
+        let abr = calculate_multipass_bitrate(&files, 118_000_000, BitrateMode::Abr);
+        let vbr = calculate_multipass_bitrate(&files, 118_000_000, BitrateMode::Vbr);
+
+        assert!(abr.would_exceed_capacity);
+        assert!(!vbr.would_exceed_capacity);
+        assert_eq!(
+            estimate_encoded_size(BitrateMode::Vbr, 256, 3600.0),
+            3600 * 248 * 1000 / 8 + 10_000
+        );
+    }
+
     #[test]
     fn test_multipass_estimate_should_show_bitrate() {
         // Should show for lossless
@@ -630,7 +835,7 @@ mod tests {
     #[test]
     fn test_optimize_bitrate_empty_files() {
         let files: Vec<AudioFileInfo> = vec![];
-        let (bitrate, estimate) = optimize_bitrate(&files, 192);
+        let (bitrate, estimate) = optimize_bitrate(&files, 192, CD_CAPACITY_BYTES);
 
         // Empty files have 0 total bytes, so optimization pushes to max
         assert_eq!(bitrate, 320); // Maximizes since there's infinite headroom
@@ -643,7 +848,7 @@ mod tests {
         // Tiny file that could theoretically use infinite bitrate
         let files = vec![make_test_file("flac", 0, 10.0, 1_000_000, false)];
 
-        let (bitrate, _) = optimize_bitrate(&files, 128);
+        let (bitrate, _) = optimize_bitrate(&files, 128, CD_CAPACITY_BYTES);
 
         // Should cap at MAX_BITRATE (320)
         assert!(bitrate <= 320);
@@ -3,14 +3,45 @@
 //! This module manages the output directory structure for background encoding:
 //! - Session-based directories: `/tmp/mp3cd_output/{session_id}/{folder_id}/`
 //! - ISO staging with symlinks: Numbered symlinks for ISO creation
+//! - Staging dry-run: The planned disc layout, for previewing before a burn
 //! - Cleanup of old sessions
 
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::core::{FolderId, MusicFolder};
+use crate::audio::{EncodingStrategy, determine_encoding_strategy};
+use crate::burning::PlaylistFormat;
+use crate::burning::iso_names::{IsoNaming, plan_iso_naming, renamed_path};
+use crate::burning::playlist::{StagedFolder, write_playlists};
+use crate::core::{AudioFileInfo, CompatProfile, FolderId, MusicFolder};
+
+/// One track in a planned disc layout
+#[derive(Debug, Clone, PartialEq)]
+pub struct StagingEntry {
+    /// Path on the disc, relative to its root (e.g. `01-Album/02-Track.mp3`)
+    pub disc_path: PathBuf,
+    /// The source audio file
+    pub source: PathBuf,
+    /// How the encoder turns the source into the file on the disc
+    pub strategy: EncodingStrategy,
+    /// Size of the file on the disc in bytes (exact once it's encoded)
+    pub estimated_size: u64,
+}
+
+/// On-disc names for one folder's tracks
+struct FolderLayout<'a> {
+    /// Numbered folder name, e.g. `01-Album`
+    dir_name: String,
+    /// Directory holding the folder's encoded MP3s
+    source_dir: PathBuf,
+    /// Whether track names carry a numbered prefix
+    numbered: bool,
+    /// Active tracks in disc order, with their file names on the disc
+    tracks: Vec<(&'a AudioFileInfo, String)>,
+}
 
 /// Manages output directories for a conversion session
 ///
@@ -128,14 +159,7 @@ impl OutputManager {
     ///
     /// Creates the directory if it doesn't exist.
     pub fn get_folder_output_dir(&self, folder_id: &FolderId) -> Result<PathBuf, String> {
-        let bundle_path = self.get_bundle_path();
-        let folder_dir = if let Some(bundle) = bundle_path {
-            // Bundle mode: {bundle}/converted/{folder_id}/
-            bundle.join("converted").join(folder_id.as_str())
-        } else {
-            // Temp mode: {session_dir}/{folder_id}/
-            self.session_dir.join(folder_id.as_str())
-        };
+        let folder_dir = self.folder_output_path(folder_id);
 
         if !folder_dir.exists() {
             fs::create_dir_all(&folder_dir)
@@ -145,6 +169,17 @@ impl OutputManager {
         Ok(folder_dir)
     }
 
+    /// Where a folder's output goes, without creating it
+    fn folder_output_path(&self, folder_id: &FolderId) -> PathBuf {
+        if let Some(bundle) = self.get_bundle_path() {
+            // Bundle mode: {bundle}/converted/{folder_id}/
+            bundle.join("converted").join(folder_id.as_str())
+        } else {
+            // Temp mode: {session_dir}/{folder_id}/
+            self.session_dir.join(folder_id.as_str())
+        }
+    }
+
     /// Check if a folder's output directory exists (used in tests)
     #[allow(dead_code)]
     pub fn folder_output_exists(&self, folder_id: &FolderId) -> bool {
@@ -293,9 +328,42 @@ impl OutputManager {
     /// Note: Staging is always in the temp session directory (not in bundle),
     /// but symlinks point to converted files which may be in a bundle.
     ///
+    /// The layout is checked against `compat` first, and names are folded to
+    /// ASCII when its players need that. Playlists are written alongside the
+    /// tracks in the `playlists` format.
+    ///
     /// Returns the staging directory path.
-    pub fn create_iso_staging(&self, folders: &[MusicFolder]) -> Result<PathBuf, String> {
-        let staging_dir = self.session_dir.join("_iso_staging");
+    pub fn create_iso_staging(
+        &self,
+        folders: &[MusicFolder],
+        compat: CompatProfile,
+        playlists: PlaylistFormat,
+    ) -> Result<PathBuf, String> {
+        self.create_staging_in(folders, self.staging_dir(), compat, playlists)
+    }
+
+    /// Create the staging directory for one disc of a multi-disc burn
+    ///
+    /// Works like [`Self::create_iso_staging`], but each disc gets its own
+    /// directory and folder numbering starts at 01 on every disc.
+    pub fn create_disc_staging(
+        &self,
+        folders: &[MusicFolder],
+        disc: usize,
+        compat: CompatProfile,
+        playlists: PlaylistFormat,
+    ) -> Result<PathBuf, String> {
+        self.create_staging_in(folders, self.disc_staging_dir(disc), compat, playlists)
+    }
+
+    fn create_staging_in(
+        &self,
+        folders: &[MusicFolder],
+        staging_dir: PathBuf,
+        compat: CompatProfile,
+        playlists: PlaylistFormat,
+    ) -> Result<PathBuf, String> {
+        compat.check_layout(folders)?;
 
         // Clean up existing staging
         if staging_dir.exists() {
@@ -307,75 +375,39 @@ impl OutputManager {
             .map_err(|e| format!("Failed to create staging directory: {}", e))?;
 
         // Create numbered folders with symlinks to individual tracks
+        let mut layouts = Vec::with_capacity(folders.len());
         for (index, folder) in folders.iter().enumerate() {
-            // Get source directory from folder's conversion status if available,
-            // otherwise fall back to session directory.
-            let source_dir = match &folder.conversion_status {
-                crate::core::FolderConversionStatus::Converted { output_dir, .. } => {
-                    output_dir.clone()
-                }
-                _ => {
-                    // Fall back to session directory for folders not yet converted
-                    // (shouldn't happen during ISO staging, but just in case)
-                    self.session_dir.join(folder.id.as_str())
-                }
-            };
+            let layout = self.folder_layout(folder, index, compat);
 
-            if !source_dir.exists() {
+            if !layout.source_dir.exists() {
                 return Err(format!(
                     "Output directory not found for folder: {}",
                     folder.path.display()
                 ));
             }
 
-            // Create a numbered folder name with the album/mixtape name
-            let display_name = folder.display_name();
-            let safe_name = sanitize_filename(&display_name);
-            let numbered_name = format!("{:02}-{}", index + 1, safe_name);
-            let folder_staging_path = staging_dir.join(&numbered_name);
+            let folder_staging_path = staging_dir.join(&layout.dir_name);
 
             fs::create_dir_all(&folder_staging_path)
                 .map_err(|e| format!("Failed to create staging folder: {}", e))?;
 
-            // Determine if we need numbered prefixes for tracks:
-            // - Mixtapes: always numbered (user-curated playlist)
-            // - Albums: only if custom track order is set (user reordered)
-            let use_numbered_prefix = folder.is_mixtape() || folder.track_order.is_some();
-
-            // Get active tracks in order (respects exclusions and custom order)
-            let active_tracks = folder.active_tracks();
-
-            // Create symlinks for each track with optional numbered prefix
-            for (track_idx, track) in active_tracks.iter().enumerate() {
-                let stem = track
-                    .path
-                    .file_stem()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("unknown");
-
-                // Source file in output directory (encoded without number prefix)
-                let source_file = source_dir.join(format!("{}.mp3", stem));
-
-                // Destination filename with optional numbered prefix
-                let dest_filename = if use_numbered_prefix {
-                    format!("{:02}-{}.mp3", track_idx + 1, stem)
-                } else {
-                    format!("{}.mp3", stem)
-                };
-                let dest_path = folder_staging_path.join(&dest_filename);
+            // Create symlinks for each track
+            for (track, dest_filename) in &layout.tracks {
+                let source_file = encoded_path(&layout.source_dir, track);
+                let dest_path = folder_staging_path.join(dest_filename);
 
                 if source_file.exists() {
                     #[cfg(unix)]
                     {
                         std::os::unix::fs::symlink(&source_file, &dest_path).map_err(|e| {
-                            format!("Failed to create symlink for {}: {}", stem, e)
+                            format!("Failed to create symlink for {}: {}", dest_filename, e)
                         })?;
                     }
 
                     #[cfg(not(unix))]
                     {
                         fs::copy(&source_file, &dest_path).map_err(|e| {
-                            format!("Failed to copy file for {}: {}", stem, e)
+                            format!("Failed to copy file for {}: {}", dest_filename, e)
                         })?;
                     }
                 } else {
@@ -388,21 +420,138 @@ impl OutputManager {
 
             log::debug!(
                 "Staged: {} ({} tracks, numbered: {})",
-                numbered_name,
-                active_tracks.len(),
-                use_numbered_prefix
+                layout.dir_name,
+                layout.tracks.len(),
+                layout.numbered
             );
+            layouts.push(layout);
         }
 
+        let staged: Vec<StagedFolder> = layouts
+            .iter()
+            .map(|layout| StagedFolder {
+                dir_name: &layout.dir_name,
+                tracks: &layout.tracks,
+            })
+            .collect();
+        write_playlists(
+            &staging_dir,
+            &staged,
+            playlists,
+            compat.max_files_per_folder(),
+        )?;
+
         Ok(staging_dir)
     }
 
-    /// Get the ISO staging directory path (used in tests)
-    #[allow(dead_code)]
+    /// On-disc path and source file of every active track, in disc order
+    ///
+    /// Paths are the ones staging gives the tracks, before they're renamed
+    /// to fit the disc's naming level.
+    pub fn staged_track_paths(
+        &self,
+        folders: &[MusicFolder],
+        compat: CompatProfile,
+    ) -> Vec<(PathBuf, PathBuf)> {
+        let mut paths = Vec::new();
+        for (index, folder) in folders.iter().enumerate() {
+            let layout = self.folder_layout(folder, index, compat);
+            for (track, dest_filename) in layout.tracks {
+                paths.push((
+                    Path::new(&layout.dir_name).join(dest_filename),
+                    track.path.clone(),
+                ));
+            }
+        }
+        paths
+    }
+
+    /// Plan the ISO staging tree without touching the filesystem
+    ///
+    /// Returns one entry per track, in disc order, with the name it gets on
+    /// the disc: the one `create_iso_staging` gives it, fitted to `naming`.
+    /// Folders that haven't been encoded yet are planned with the strategy
+    /// the encoder would pick, using `lossless_bitrate` for lossless sources.
+    pub fn plan_iso_staging(
+        &self,
+        folders: &[MusicFolder],
+        compat: CompatProfile,
+        naming: IsoNaming,
+        lossless_bitrate: u32,
+        embed_album_art: bool,
+    ) -> Result<Vec<StagingEntry>, String> {
+        compat.check_layout(folders)?;
+
+        let mut entries = Vec::new();
+        for (index, folder) in folders.iter().enumerate() {
+            let layout = self.folder_layout(folder, index, compat);
+            for (track, dest_filename) in layout.tracks {
+                let strategy = if track.is_lossy {
+                    // Same decision the encoder's lossy pass makes
+                    determine_encoding_strategy(
+                        &track.codec,
+                        track.bitrate,
+                        320,
+                        true,
+                        false,
+                        embed_album_art && compat.keeps_source_tags(),
+                    )
+                } else {
+                    EncodingStrategy::ConvertAtTargetBitrate(lossless_bitrate)
+                };
+
+                // Already-encoded tracks report their real size
+                let estimated_size = match fs::metadata(encoded_path(&layout.source_dir, track)) {
+                    Ok(metadata) => metadata.len(),
+                    Err(_) => estimate_output_size(track, &strategy),
+                };
+
+                entries.push(StagingEntry {
+                    disc_path: Path::new(&layout.dir_name).join(dest_filename),
+                    source: track.path.clone(),
+                    strategy,
+                    estimated_size,
+                });
+            }
+        }
+
+        // Same renames the burn makes to the staged tree
+        let staged: Vec<PathBuf> = entries.iter().map(|e| e.disc_path.clone()).collect();
+        let changes = plan_iso_naming(&staged, naming);
+        for entry in &mut entries {
+            entry.disc_path = renamed_path(&entry.disc_path, &changes);
+        }
+        Ok(entries)
+    }
+
+    /// Work out the on-disc names for one folder
+    ///
+    /// `index` is the folder's position on the disc, starting at 0.
+    fn folder_layout<'a>(
+        &self,
+        folder: &'a MusicFolder,
+        index: usize,
+        compat: CompatProfile,
+    ) -> FolderLayout<'a> {
+        // Get source directory from folder's conversion status if available,
+        // otherwise fall back to the folder's output directory.
+        let source_dir = match &folder.conversion_status {
+            crate::core::FolderConversionStatus::Converted { output_dir, .. } => output_dir.clone(),
+            _ => self.folder_output_path(&folder.id),
+        };
+        layout_folder(folder, index, compat, source_dir)
+    }
+
+    /// Get the ISO staging directory path
     pub fn staging_dir(&self) -> PathBuf {
         self.session_dir.join("_iso_staging")
     }
 
+    /// Get the staging directory path for one disc of a multi-disc burn
+    pub fn disc_staging_dir(&self, disc: usize) -> PathBuf {
+        self.session_dir.join(format!("_iso_staging_disc{}", disc))
+    }
+
     /// Get all converted MP3 files for a folder
     ///
     /// Returns a list of paths to all MP3 files in the folder's output directory.
@@ -454,6 +603,80 @@ impl Default for OutputManager {
     }
 }
 
+/// Each folder's name on the disc, with its tracks' file names in disc order
+///
+/// The names staging gives them, worked out without a session, so what a
+/// disc holds can be measured before anything is encoded.
+pub fn disc_names(folders: &[MusicFolder], compat: CompatProfile) -> Vec<(String, Vec<String>)> {
+    folders
+        .iter()
+        .enumerate()
+        .map(|(index, folder)| {
+            let layout = layout_folder(folder, index, compat, PathBuf::new());
+            let tracks = layout.tracks.into_iter().map(|(_, name)| name).collect();
+            (layout.dir_name, tracks)
+        })
+        .collect()
+}
+
+/// On-disc names for one folder, with its encoded MP3s in `source_dir`
+///
+/// `index` is the folder's position on the disc, starting at 0.
+fn layout_folder<'a>(
+    folder: &'a MusicFolder,
+    index: usize,
+    compat: CompatProfile,
+    source_dir: PathBuf,
+) -> FolderLayout<'a> {
+    // Create a numbered folder name with the album/mixtape name
+    let display_name = folder.display_name();
+    let safe_name = compat.fold_name(&sanitize_filename(&display_name));
+    let dir_name = format!("{:02}-{}", index + 1, safe_name);
+
+    // Determine if we need numbered prefixes for tracks:
+    // - Mixtapes: always numbered (user-curated playlist)
+    // - Albums: only if custom track order is set (user reordered)
+    let numbered = folder.is_mixtape() || folder.track_order.is_some();
+
+    // Active tracks in order (respects exclusions and custom order)
+    let mut taken = HashSet::new();
+    let tracks = folder
+        .active_tracks()
+        .into_iter()
+        .enumerate()
+        .map(|(track_idx, track)| {
+            let stem = compat.fold_name(
+                track
+                    .path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("unknown"),
+            );
+            let dest_stem = if numbered {
+                format!("{:02}-{}", track_idx + 1, stem)
+            } else {
+                stem
+            };
+            // Folding can make two names the same, and the disc may be
+            // read case-insensitively
+            let mut dest_filename = format!("{}.mp3", dest_stem);
+            let mut n = 1;
+            while !taken.insert(dest_filename.to_lowercase()) {
+                dest_filename = format!("{}~{}.mp3", dest_stem, n);
+                n += 1;
+            }
+            (track, dest_filename)
+        })
+        .collect();
+
+    FolderLayout {
+        dir_name,
+        source_dir,
+        numbered,
+        tracks,
+    }
+}
+
 /// Generate a unique session ID based on timestamp and random component
 fn generate_session_id() -> String {
     let timestamp = SystemTime::now()
@@ -490,6 +713,29 @@ pub fn calculate_dir_size(path: &Path) -> Result<u64, String> {
     Ok(total)
 }
 
+/// Where the encoder writes `track` inside `output_dir`
+///
+/// Encoded files keep the source's stem, without any numbered prefix.
+fn encoded_path(output_dir: &Path, track: &AudioFileInfo) -> PathBuf {
+    let stem = track
+        .path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unknown");
+    output_dir.join(format!("{}.mp3", stem))
+}
+
+/// Expected size of `track` once encoded with `strategy`
+fn estimate_output_size(track: &AudioFileInfo, strategy: &EncodingStrategy) -> u64 {
+    match strategy {
+        EncodingStrategy::Copy | EncodingStrategy::CopyWithoutArt => track.size,
+        EncodingStrategy::ConvertAtSourceBitrate(kbps)
+        | EncodingStrategy::ConvertAtTargetBitrate(kbps) => {
+            (track.duration * *kbps as f64 * 1000.0 / 8.0) as u64
+        }
+    }
+}
+
 /// Sanitize a filename for safe filesystem use
 fn sanitize_filename(name: &str) -> String {
     name.chars()
@@ -670,6 +916,164 @@ mod tests {
         let _ = manager.cleanup();
     }
 
+    #[test]
+    fn test_create_disc_staging() {
+        let manager = OutputManager::new().unwrap();
+
+        let disc1 = manager
+            .create_disc_staging(&[], 1, CompatProfile::Modern, PlaylistFormat::None)
+            .unwrap();
+        let disc2 = manager
+            .create_disc_staging(&[], 2, CompatProfile::Modern, PlaylistFormat::None)
+            .unwrap();
+
+        assert!(disc1.ends_with("_iso_staging_disc1"));
+        assert_eq!(disc2, manager.disc_staging_dir(2));
+        assert!(disc1.is_dir() && disc2.is_dir());
+        assert_ne!(disc1, manager.staging_dir());
+
+        // Cleanup
+        let _ = manager.cleanup();
+    }
+
+    #[test]
+    fn test_create_iso_staging_folds_names_for_compat() {
+        let manager = OutputManager::new().unwrap();
+        let output = TempDir::new().unwrap();
+        fs::write(output.path().join("Café.mp3"), b"a").unwrap();
+        fs::write(output.path().join("Cafe.mp3"), b"b").unwrap();
+
+        let mut folder = MusicFolder::new_for_test_with_id("Déjà Vu");
+        folder.audio_files = ["Café", "Cafe"]
+            .iter()
+            .map(|stem| crate::core::AudioFileInfo {
+                path: PathBuf::from(format!("/src/{}.flac", stem)),
+                duration: 60.0,
+                bitrate: 1411,
+                size: 1000,
+                codec: "flac".to_string(),
+                is_lossy: false,
+            })
+            .collect();
+        folder.conversion_status = crate::core::FolderConversionStatus::Converted {
+            output_dir: output.path().to_path_buf(),
+            lossless_bitrate: Some(320),
+            output_size: 2,
+            completed_at: 0,
+        };
+
+        let staging = manager
+            .create_iso_staging(
+                &[folder],
+                CompatProfile::GenericHeadUnit,
+                PlaylistFormat::M3u8,
+            )
+            .unwrap();
+        let album = staging.join("01-Deja Vu");
+        assert!(album.join("Cafe.mp3").exists());
+        assert!(album.join("Cafe~1.mp3").exists());
+
+        // Playlists follow the staged names
+        let playlist = fs::read_to_string(album.join("01-Deja Vu.m3u8")).unwrap();
+        assert!(playlist.contains("\nCafe.mp3\n") && playlist.contains("\nCafe~1.mp3\n"));
+        let master = fs::read_to_string(staging.join("00-All Tracks.m3u8")).unwrap();
+        assert!(master.contains("\n01-Deja Vu/Cafe~1.mp3\n"));
+
+        // Cleanup
+        let _ = manager.cleanup();
+    }
+
+    #[test]
+    fn test_plan_iso_staging() {
+        let manager = OutputManager::new().unwrap();
+        let output = TempDir::new().unwrap();
+        fs::write(output.path().join("Done.mp3"), vec![0u8; 1234]).unwrap();
+
+        let track = |stem: &str, codec: &str, bitrate, is_lossy| crate::core::AudioFileInfo {
+            path: PathBuf::from(format!("/src/{}.{}", stem, codec)),
+            duration: 100.0,
+            bitrate,
+            size: 5000,
+            codec: codec.to_string(),
+            is_lossy,
+        };
+        let mut folder = MusicFolder::new_for_test_with_id("Mix");
+        folder.kind = crate::core::FolderKind::Mixtape {
+            name: "Road Trip".to_string(),
+        };
+        folder.audio_files = vec![
+            track("Done", "flac", 1411, false),
+            track("Copied", "mp3", 192, true),
+            track("Skipped", "mp3", 192, true),
+            track("Lossless", "flac", 1411, false),
+        ];
+        folder.excluded_tracks = vec![PathBuf::from("/src/Skipped.mp3")];
+        folder.conversion_status = crate::core::FolderConversionStatus::Converted {
+            output_dir: output.path().to_path_buf(),
+            lossless_bitrate: Some(256),
+            output_size: 1234,
+            completed_at: 0,
+        };
+
+        let folders = [folder];
+        let entries = manager
+            .plan_iso_staging(
+                &folders,
+                CompatProfile::Modern,
+                IsoNaming::Joliet,
+                256,
+                true,
+            )
+            .unwrap();
+
+        let paths: Vec<_> = entries.iter().map(|e| e.disc_path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("01-Road Trip/01-Done.mp3"),
+                PathBuf::from("01-Road Trip/02-Copied.mp3"),
+                PathBuf::from("01-Road Trip/03-Lossless.mp3"),
+            ]
+        );
+        // Encoded output is measured, the rest estimated
+        assert_eq!(entries[0].estimated_size, 1234);
+        assert_eq!(entries[1].strategy, EncodingStrategy::Copy);
+        assert_eq!(entries[1].estimated_size, 5000);
+        assert_eq!(
+            entries[2].strategy,
+            EncodingStrategy::ConvertAtTargetBitrate(256)
+        );
+        assert_eq!(entries[2].estimated_size, 3_200_000);
+        assert_eq!(entries[2].source, PathBuf::from("/src/Lossless.flac"));
+
+        let staged = manager.staged_track_paths(&folders, CompatProfile::Modern);
+        assert_eq!(staged.len(), 3);
+        assert_eq!(
+            staged[1],
+            (
+                PathBuf::from("01-Road Trip/02-Copied.mp3"),
+                PathBuf::from("/src/Copied.mp3")
+            )
+        );
+        assert_eq!(
+            disc_names(&folders, CompatProfile::Modern),
+            vec![(
+                "01-Road Trip".to_string(),
+                vec![
+                    "01-Done.mp3".to_string(),
+                    "02-Copied.mp3".to_string(),
+                    "03-Lossless.mp3".to_string(),
+                ]
+            )]
+        );
+
+        // Nothing was staged
+        assert!(!manager.staging_dir().exists());
+
+        // Cleanup
+        let _ = manager.cleanup();
+    }
+
     // Note: create_iso_staging requires MusicFolder with valid conversion state,
     // which requires more integration testing. The symlink creation logic is
     // tested implicitly through the individual helper tests.
@@ -5,15 +5,19 @@
 
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
 use crate::audio::{determine_encoding_strategy, EncodingStrategy};
-use crate::core::{AudioFileInfo, FolderId, MusicFolder};
+use crate::burning::{disc_overhead, PlaylistFormat};
+use crate::core::{
+    AudioFileInfo, BitrateMode, CompatProfile, EncoderSettings, FolderId, MediaProfile, MusicFolder,
+};
 use super::background::EncoderEvent;
+use super::encoder::{EncodeControl, EncodeOptions, Encoder};
+use super::optimizer::fit_bitrate;
 use super::output_manager::OutputManager;
 
 // Re-export EncodingPhase from background module
@@ -31,12 +35,20 @@ pub struct SimpleEncoderState {
     paused: AtomicBool,
     /// Embed album art setting
     embed_album_art: AtomicBool,
+    /// Car stereo profile the tags and cover art must suit
+    compat: Mutex<CompatProfile>,
+    /// Bitrate mode and LAME tuning for transcodes
+    encoder_settings: Mutex<EncoderSettings>,
+    /// Playlists written onto the disc, which take up some of its space
+    playlists: Mutex<PlaylistFormat>,
     /// Currently encoding folder (if any)
     pub current_folder: Mutex<Option<FolderId>>,
     /// Progress within current folder
     pub current_progress: Mutex<(usize, usize)>, // (completed, total)
     /// Manual bitrate override (None = auto-calculate)
     pub manual_bitrate: Mutex<Option<u32>>,
+    /// Capacity of the target media in bytes
+    pub capacity_bytes: AtomicU64,
     /// PIDs of currently running ffmpeg processes (for instant termination)
     running_pids: Mutex<HashSet<u32>>,
 }
@@ -49,9 +61,13 @@ impl SimpleEncoderState {
             restart_requested: AtomicBool::new(false),
             paused: AtomicBool::new(false),
             embed_album_art: AtomicBool::new(false),
+            compat: Mutex::new(CompatProfile::default()),
+            encoder_settings: Mutex::new(EncoderSettings::default()),
+            playlists: Mutex::new(PlaylistFormat::default()),
             current_folder: Mutex::new(None),
             current_progress: Mutex::new((0, 0)),
             manual_bitrate: Mutex::new(None),
+            capacity_bytes: AtomicU64::new(MediaProfile::default().capacity_bytes()),
             running_pids: Mutex::new(HashSet::new()),
         }
     }
@@ -115,6 +131,32 @@ impl SimpleEncoderState {
     pub fn set_phase(&self, phase: EncodingPhase) {
         *self.phase.lock().unwrap() = phase;
     }
+
+    pub fn get_compat(&self) -> CompatProfile {
+        *self.compat.lock().unwrap()
+    }
+
+    pub fn get_encoder_settings(&self) -> EncoderSettings {
+        *self.encoder_settings.lock().unwrap()
+    }
+
+    pub fn get_playlists(&self) -> PlaylistFormat {
+        *self.playlists.lock().unwrap()
+    }
+}
+
+impl EncodeControl for SimpleEncoderState {
+    fn is_cancelled(&self) -> bool {
+        self.is_restart_requested()
+    }
+
+    fn process_started(&self, pid: u32) {
+        self.register_pid(pid);
+    }
+
+    fn process_finished(&self, pid: u32) {
+        self.unregister_pid(pid);
+    }
 }
 
 /// Handle for controlling the encoder from the UI
@@ -160,12 +202,68 @@ impl SimpleEncoderHandle {
         self.restart();
     }
 
+    /// Set the target media, restarting if its capacity changed
+    pub fn set_media(&self, media: MediaProfile) {
+        let capacity = media.capacity_bytes();
+        if self.state.capacity_bytes.swap(capacity, Ordering::SeqCst) != capacity {
+            log::debug!("Encoder: target media is now {}", media.name());
+            self.restart();
+        }
+    }
+
     /// Set embed album art setting
     pub fn set_embed_album_art(&self, embed: bool) {
         self.state.embed_album_art.store(embed, Ordering::SeqCst);
         // No restart needed - will apply to new encodings
     }
 
+    /// Set the playlist format
+    pub fn set_playlists(&self, playlists: PlaylistFormat) {
+        *self.state.playlists.lock().unwrap() = playlists;
+        // No restart needed - only the next bitrate calculation uses it
+    }
+
+    /// Set the car stereo profile, re-encoding everything if it changed
+    ///
+    /// Unlike album art, finished files already carry the old profile's tags,
+    /// so they're deleted rather than kept (except for folders whose sources
+    /// are gone, which couldn't be encoded again).
+    pub fn set_compat(&self, compat: CompatProfile) {
+        let previous = std::mem::replace(&mut *self.state.compat.lock().unwrap(), compat);
+        if previous == compat {
+            return;
+        }
+        log::debug!("Encoder: compatibility profile is now {}", compat.label());
+        self.reencode_available_folders();
+    }
+
+    /// Set the bitrate mode and LAME tuning, re-encoding everything if they changed
+    ///
+    /// Like the car stereo profile, finished files were made with the old
+    /// settings, so folders whose sources are still there are encoded again.
+    pub fn set_encoder_settings(&self, settings: EncoderSettings) {
+        let previous =
+            std::mem::replace(&mut *self.state.encoder_settings.lock().unwrap(), settings);
+        if previous == settings {
+            return;
+        }
+        log::debug!("Encoder: settings are now {}", settings.summary());
+        // The bitrate has to be planned again for the new mode
+        self.state.lossless_bitrate.store(0, Ordering::SeqCst);
+        self.reencode_available_folders();
+    }
+
+    /// Restart, deleting the outputs of folders whose sources are available
+    fn reencode_available_folders(&self) {
+        self.state.request_restart();
+        let folders = self.shared_folders.lock().unwrap().clone();
+        for folder in folders.iter().filter(|f| f.source_available) {
+            let _ = self
+                .output_manager
+                .delete_folder_output_from_session(&folder.id);
+        }
+    }
+
     /// Clear all state (for New profile)
     pub fn clear(&self) {
         // First, stop any running encoding processes
@@ -291,10 +389,10 @@ impl SimpleEncoderHandle {
     }
 }
 
-/// Start the simple encoder
+/// Start the simple encoder, converting with `encoder`
 pub fn start_simple_encoder(
     output_manager: Arc<OutputManager>,
-    ffmpeg_path: PathBuf,
+    encoder: Arc<dyn Encoder>,
 ) -> (SimpleEncoderHandle, mpsc::Receiver<EncoderEvent>) {
     let state = Arc::new(SimpleEncoderState::new());
     let shared_folders: Arc<Mutex<Vec<MusicFolder>>> = Arc::new(Mutex::new(Vec::new()));
@@ -317,7 +415,7 @@ pub fn start_simple_encoder(
             output_manager_clone,
             shared_folders_clone,
             progress_tx,
-            ffmpeg_path,
+            encoder,
         );
     });
 
@@ -330,9 +428,9 @@ fn encoding_loop(
     output_manager: Arc<OutputManager>,
     shared_folders: Arc<Mutex<Vec<MusicFolder>>>,
     progress_tx: mpsc::Sender<EncoderEvent>,
-    ffmpeg_path: PathBuf,
+    encoder: Arc<dyn Encoder>,
 ) {
-    log::debug!("Simple encoder loop started");
+    log::debug!("Simple encoder loop started, using {}", encoder.name());
 
     loop {
         // Wait until we have work to do
@@ -379,7 +477,7 @@ fn encoding_loop(
         let was_interrupted = encode_all_lossy_parallel(
             &folders,
             320, // Target bitrate (used for strategy decisions)
-            &ffmpeg_path,
+            &encoder,
             &output_manager,
             &state,
             embed_art,
@@ -401,12 +499,19 @@ fn encoding_loop(
             .map(|f| f.duration)
             .sum();
 
+        let mode = state.get_encoder_settings().mode;
         let lossless_bitrate = {
             let manual = *state.manual_bitrate.lock().unwrap();
             if let Some(br) = manual {
-                br
+                fit_bitrate(mode, br)
             } else {
-                calculate_optimal_bitrate(lossy_size, lossless_duration)
+                // Leave room for the playlists, index files and filesystem
+                let overhead = disc_overhead(&folders, state.get_compat(), state.get_playlists());
+                let capacity = state
+                    .capacity_bytes
+                    .load(Ordering::SeqCst)
+                    .saturating_sub(overhead);
+                calculate_optimal_bitrate(lossy_size, lossless_duration, capacity, mode)
             }
         };
 
@@ -453,7 +558,7 @@ fn encoding_loop(
         let was_interrupted = encode_all_lossless_parallel(
             &folders,
             lossless_bitrate,
-            &ffmpeg_path,
+            &encoder,
             &output_manager,
             &state,
             embed_art,
@@ -529,20 +634,26 @@ fn measure_total_lossy_size(output_manager: &OutputManager, folders: &[MusicFold
         .sum()
 }
 
-/// Calculate optimal bitrate for lossless files
-fn calculate_optimal_bitrate(lossy_size: u64, lossless_duration: f64) -> u32 {
-    const CD_CAPACITY: u64 = 700 * 1000 * 1000;
+/// Calculate optimal bitrate for lossless files to fill `capacity` bytes
+///
+/// The bitrate is one `mode` can hit, so with VBR it picks the preset.
+fn calculate_optimal_bitrate(
+    lossy_size: u64,
+    lossless_duration: f64,
+    capacity: u64,
+    mode: BitrateMode,
+) -> u32 {
     const SAFETY_MARGIN: f64 = 0.98;
 
     if lossless_duration <= 0.0 {
-        return 320;
+        return fit_bitrate(mode, 320);
     }
 
-    let usable_capacity = (CD_CAPACITY as f64 * SAFETY_MARGIN) as u64;
+    let usable_capacity = (capacity as f64 * SAFETY_MARGIN) as u64;
     let remaining_space = usable_capacity.saturating_sub(lossy_size);
     let bitrate = ((remaining_space * 8) as f64 / lossless_duration / 1000.0) as u32;
 
-    bitrate.clamp(64, 320)
+    fit_bitrate(mode, bitrate.clamp(64, 320))
 }
 
 /// Delete lossless-sourced outputs (when bitrate changes)
@@ -597,7 +708,7 @@ struct LossyEncodeJob {
 fn encode_all_lossless_parallel(
     folders: &[MusicFolder],
     bitrate: u32,
-    ffmpeg_path: &Path,
+    encoder: &Arc<dyn Encoder>,
     output_manager: &OutputManager,
     state: &Arc<SimpleEncoderState>,
     embed_album_art: bool,
@@ -607,6 +718,7 @@ fn encode_all_lossless_parallel(
     use std::sync::atomic::AtomicUsize;
 
     let worker_count = calculate_worker_count();
+    let compat = state.get_compat();
 
     // Build folder contexts and collect all jobs
     let mut folder_contexts: HashMap<FolderId, FolderContext> = HashMap::new();
@@ -641,7 +753,7 @@ fn encode_all_lossless_parallel(
         };
 
         let album_art = if embed_album_art {
-            folder.album_art.clone()
+            compat.album_art(folder.album_art.as_deref())
         } else {
             None
         };
@@ -731,12 +843,11 @@ fn encode_all_lossless_parallel(
 
     // Spawn worker threads
     let mut handles = Vec::new();
-    let ffmpeg_path = ffmpeg_path.to_path_buf();
 
     for _worker_id in 0..worker_count {
         let job_rx = job_rx.clone();
         let state = state.clone();
-        let ffmpeg_path = ffmpeg_path.clone();
+        let encoder = encoder.clone();
         let folder_completed = folder_completed.clone();
         let folder_contexts = folder_contexts.clone();
         let folders_finished = folders_finished.clone();
@@ -772,7 +883,7 @@ fn encode_all_lossless_parallel(
 
                 // Encode the file
                 let result = transcode_file_internal(
-                    &ffmpeg_path,
+                    encoder.as_ref(),
                     &job.input_path,
                     &job.output_path,
                     job.bitrate,
@@ -860,7 +971,7 @@ fn encode_all_lossless_parallel(
 fn encode_all_lossy_parallel(
     folders: &[MusicFolder],
     target_bitrate: u32,
-    ffmpeg_path: &Path,
+    encoder: &Arc<dyn Encoder>,
     output_manager: &OutputManager,
     state: &Arc<SimpleEncoderState>,
     embed_album_art: bool,
@@ -870,6 +981,7 @@ fn encode_all_lossy_parallel(
     use std::sync::atomic::AtomicUsize;
 
     let worker_count = calculate_worker_count();
+    let compat = state.get_compat();
 
     // Build folder contexts and collect all jobs
     let mut folder_contexts: HashMap<FolderId, FolderContext> = HashMap::new();
@@ -904,7 +1016,7 @@ fn encode_all_lossy_parallel(
         };
 
         let album_art = if embed_album_art {
-            folder.album_art.clone()
+            compat.album_art(folder.album_art.as_deref())
         } else {
             None
         };
@@ -942,7 +1054,9 @@ fn encode_all_lossy_parallel(
                 target_bitrate,
                 file.is_lossy,
                 false, // no_lossy_mode - we're not implementing this yet
-                embed_album_art,
+                // Copies keep the source's tags, so only profiles that accept
+                // those can copy as-is
+                embed_album_art && compat.keeps_source_tags(),
             );
 
             all_jobs.push(LossyEncodeJob {
@@ -1009,12 +1123,11 @@ fn encode_all_lossy_parallel(
 
     // Spawn worker threads
     let mut handles = Vec::new();
-    let ffmpeg_path = ffmpeg_path.to_path_buf();
 
     for _worker_id in 0..worker_count {
         let job_rx = job_rx.clone();
         let state = state.clone();
-        let ffmpeg_path = ffmpeg_path.clone();
+        let encoder = encoder.clone();
         let folder_completed = folder_completed.clone();
         let folder_contexts = folder_contexts.clone();
         let folders_finished = folders_finished.clone();
@@ -1049,7 +1162,7 @@ fn encode_all_lossy_parallel(
 
                 // Execute the encoding strategy
                 let result = execute_encoding_strategy(
-                    &ffmpeg_path,
+                    encoder.as_ref(),
                     &job.input_path,
                     &job.output_path,
                     &job.strategy,
@@ -1129,7 +1242,7 @@ fn encode_all_lossy_parallel(
 
 /// Execute an encoding strategy (Copy, CopyWithoutArt, or Transcode)
 fn execute_encoding_strategy(
-    ffmpeg_path: &Path,
+    encoder: &dyn Encoder,
     input_path: &Path,
     output_path: &Path,
     strategy: &EncodingStrategy,
@@ -1152,49 +1265,21 @@ fn execute_encoding_strategy(
             Ok(())
         }
         EncodingStrategy::CopyWithoutArt => {
-            // Use ffmpeg to copy audio stream without album art
-            let mut cmd = Command::new(ffmpeg_path);
-            cmd.arg("-y")
-                .arg("-i")
-                .arg(input_path)
-                .arg("-vn") // Strip video/album art
-                .arg("-codec:a")
-                .arg("copy") // Copy audio stream as-is
-                .arg("-map_metadata")
-                .arg("0")
-                .arg(output_path);
-
-            cmd.stdout(Stdio::null());
-            cmd.stderr(Stdio::piped());
-
-            let mut child = cmd.spawn().map_err(|e| format!("Failed to spawn ffmpeg: {}", e))?;
-            let pid = child.id();
-
-            state.register_pid(pid);
-            let status = child.wait().map_err(|e| format!("Failed to wait for ffmpeg: {}", e))?;
-            state.unregister_pid(pid);
-
-            if status.success() {
-                Ok(())
-            } else {
-                let _ = std::fs::remove_file(output_path);
-                if state.is_restart_requested() {
-                    Err("Process terminated due to restart".to_string())
-                } else {
-                    Err(format!("ffmpeg copy failed with status: {}", status))
-                }
-            }
+            // Copy audio stream as-is, without album art
+            let result =
+                encoder.copy_without_art(input_path, output_path, state.get_compat(), state);
+            finish_encode(result, output_path, state)
         }
         EncodingStrategy::ConvertAtSourceBitrate(bitrate) | EncodingStrategy::ConvertAtTargetBitrate(bitrate) => {
             // Transcode using the internal function
-            transcode_file_internal(ffmpeg_path, input_path, output_path, *bitrate, album_art_path, state)
+            transcode_file_internal(encoder, input_path, output_path, *bitrate, album_art_path, state)
         }
     }
 }
 
 /// Internal transcode function that takes state by reference (for parallel use)
 fn transcode_file_internal(
-    ffmpeg_path: &Path,
+    encoder: &dyn Encoder,
     input_path: &Path,
     output_path: &Path,
     bitrate: u32,
@@ -1209,62 +1294,30 @@ fn transcode_file_internal(
         }
     }
 
-    let bitrate_str = format!("{}k", bitrate);
-
-    let mut cmd = Command::new(ffmpeg_path);
-    cmd.arg("-y")
-        .arg("-i")
-        .arg(input_path)
-        .arg("-vn")
-        .arg("-codec:a")
-        .arg("libmp3lame")
-        .arg("-abr")
-        .arg("1") // ABR mode: targets average bitrate precisely (not limited to CBR presets)
-        .arg("-b:a")
-        .arg(&bitrate_str)
-        .arg("-map_metadata")
-        .arg("0")
-        .arg("-id3v2_version")
-        .arg("3");
-
-    if let Some(art_path) = album_art_path {
-        if art_path.exists() {
-            cmd.arg("-i")
-                .arg(art_path)
-                .arg("-map")
-                .arg("0:a")
-                .arg("-map")
-                .arg("1:v")
-                .arg("-c:v")
-                .arg("copy")
-                .arg("-metadata:s:v")
-                .arg("title=Album cover")
-                .arg("-metadata:s:v")
-                .arg("comment=Cover (front)");
-        }
-    }
-
-    cmd.arg(output_path);
-    cmd.stdout(Stdio::null());
-    cmd.stderr(Stdio::piped());
-
-    let mut child = cmd.spawn().map_err(|e| format!("Failed to spawn ffmpeg: {}", e))?;
-    let pid = child.id();
-
-    state.register_pid(pid);
-    let status = child.wait().map_err(|e| format!("Failed to wait for ffmpeg: {}", e))?;
-    state.unregister_pid(pid);
+    let options = EncodeOptions::new(bitrate, state.get_compat())
+        .with_album_art(album_art_path.map(Path::to_path_buf))
+        .with_settings(state.get_encoder_settings());
+    let result = encoder.encode(input_path, output_path, &options, state);
+    finish_encode(result, output_path, state)
+}
 
-    if status.success() {
-        Ok(())
-    } else if state.is_restart_requested() {
-        // Process was killed - delete partial output file to avoid corruption
-        let _ = std::fs::remove_file(output_path);
-        Err("Process terminated due to restart".to_string())
-    } else {
-        // Process failed normally - also delete partial output
-        let _ = std::fs::remove_file(output_path);
-        Err(format!("ffmpeg failed with status: {}", status))
+/// Delete the partial output of a failed encode, so it's redone next pass
+fn finish_encode(
+    result: Result<(), String>,
+    output_path: &Path,
+    state: &SimpleEncoderState,
+) -> Result<(), String> {
+    match result {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            let _ = std::fs::remove_file(output_path);
+            if state.is_restart_requested() {
+                // Process was killed or the encode stopped for the restart
+                Err("Process terminated due to restart".to_string())
+            } else {
+                Err(e)
+            }
+        }
     }
 }
 
@@ -1401,6 +1454,32 @@ mod tests {
         assert_eq!(state.lossless_bitrate.load(Ordering::SeqCst), 320);
     }
 
+    #[test]
+    fn test_calculate_optimal_bitrate_uses_capacity() {
+        let cd = MediaProfile::Cd80.capacity_bytes();
+        let dvd = MediaProfile::Dvd5.capacity_bytes();
+
+        // 10 hours of lossless after 100 MB of lossy output
+        let on_cd = calculate_optimal_bitrate(100_000_000, 36_000.0, cd, BitrateMode::Abr);
+        let on_dvd = calculate_optimal_bitrate(100_000_000, 36_000.0, dvd, BitrateMode::Abr);
+
+        assert_eq!(on_cd, 130);
+        assert_eq!(on_dvd, 320);
+        assert_eq!(calculate_optimal_bitrate(0, 0.0, cd, BitrateMode::Abr), 320);
+    }
+
+    #[test]
+    fn test_calculate_optimal_bitrate_per_mode() {
+        let cd = MediaProfile::Cd80.capacity_bytes();
+
+        // 130 kbps to spend: CBR rounds down to 128, VBR picks V6
+        let cbr = calculate_optimal_bitrate(100_000_000, 36_000.0, cd, BitrateMode::Cbr);
+        let vbr = calculate_optimal_bitrate(100_000_000, 36_000.0, cd, BitrateMode::Vbr);
+
+        assert_eq!(cbr, 128);
+        assert_eq!(crate::conversion::optimizer::vbr_level(vbr), 6);
+    }
+
     #[test]
     fn test_simple_encoder_state_embed_album_art() {
         let state = SimpleEncoderState::new();
@@ -1413,6 +1492,17 @@ mod tests {
         assert!(state.embed_album_art.load(Ordering::SeqCst));
     }
 
+    #[test]
+    fn test_simple_encoder_state_compat() {
+        let state = SimpleEncoderState::new();
+        assert_eq!(state.get_compat(), CompatProfile::Modern);
+
+        *state.compat.lock().unwrap() = CompatProfile::Strict83;
+        assert_eq!(state.get_compat(), CompatProfile::Strict83);
+
+        assert_eq!(state.get_encoder_settings(), EncoderSettings::default());
+    }
+
     #[test]
     fn test_encoding_phase_variants() {
         assert_eq!(EncodingPhase::Idle, EncodingPhase::Idle);
@@ -1,17 +1,13 @@
 //! Bitrate calculation for CD-fitting optimization
 //!
 //! This module implements smart bitrate calculation to fit audio content
-//! onto a 700MB CD. It uses an iterative approach to find the optimal
+//! onto the target media. It uses an iterative approach to find the optimal
 //! encoding bitrate while minimizing quality loss.
 //! (Future: Stage 6)
 #![allow(dead_code)]
 
 use super::AudioFileInfo;
 
-/// Target CD size in bytes (700 MB decimal)
-/// CD-Rs are labeled 700 MB using decimal (not binary) megabytes
-pub const TARGET_SIZE_BYTES: u64 = 700 * 1000 * 1000;
-
 /// Maximum MP3 bitrate (kbps)
 pub const MAX_BITRATE: u32 = 320;
 
@@ -51,7 +47,7 @@ pub enum EncodingDecision {
     ConvertAt(u32),
 }
 
-/// Calculate optimal bitrate to fit all files on a 700MB CD
+/// Calculate optimal bitrate to fit all files in `capacity_bytes`
 ///
 /// This uses an iterative approach:
 /// 1. Start with an initial bitrate estimate based on total duration
@@ -62,12 +58,14 @@ pub enum EncodingDecision {
 /// # Arguments
 /// * `files` - List of audio files with metadata
 /// * `no_lossy_conversions` - If true, never re-encode lossy files (only convert lossless)
+/// * `capacity_bytes` - Capacity of the target media
 ///
 /// # Returns
 /// A `BitrateCalculation` with the recommended bitrate and file decisions
 pub fn calculate_optimal_bitrate(
     files: &[AudioFileInfo],
     no_lossy_conversions: bool,
+    capacity_bytes: u64,
 ) -> BitrateCalculation {
     if files.is_empty() {
         return BitrateCalculation {
@@ -80,7 +78,7 @@ pub fn calculate_optimal_bitrate(
     }
 
     let total_duration: f64 = files.iter().map(|f| f.duration).sum();
-    let target_size = (TARGET_SIZE_BYTES as f64 * OVERHEAD_COMPENSATION) as u64;
+    let target_size = (capacity_bytes as f64 * OVERHEAD_COMPENSATION) as u64;
 
     // Initial bitrate estimate based on total duration
     // bitrate (kbps) = (size_bytes * 8) / duration_seconds / 1000
@@ -123,7 +121,7 @@ pub fn calculate_optimal_bitrate(
         estimated_size,
         files_to_copy,
         files_to_convert,
-        fits_on_cd: estimated_size <= TARGET_SIZE_BYTES,
+        fits_on_cd: estimated_size <= capacity_bytes,
     }
 }
 
@@ -230,15 +228,16 @@ pub fn calculate_estimated_output_size(
     total_size as u64
 }
 
-/// Check if the files will fit on a CD at the given bitrate
+/// Check if the files will fit in `capacity_bytes` at the given bitrate
 pub fn will_fit_on_cd(
     files: &[AudioFileInfo],
     target_bitrate: u32,
     no_lossy_conversions: bool,
+    capacity_bytes: u64,
 ) -> bool {
     let estimated_size =
         calculate_estimated_output_size(files, target_bitrate, no_lossy_conversions);
-    estimated_size <= TARGET_SIZE_BYTES
+    estimated_size <= capacity_bytes
 }
 
 /// Format bitrate for display (e.g., "320 kbps")
@@ -251,6 +250,8 @@ mod tests {
     use super::*;
     use std::path::PathBuf;
 
+    const CD_CAPACITY_BYTES: u64 = 700 * 1000 * 1000;
+
     fn make_mp3(bitrate: u32, duration: f64) -> AudioFileInfo {
         let size = (bitrate as u64 * duration as u64 * 125) as u64;
         AudioFileInfo {
@@ -291,7 +292,7 @@ mod tests {
 
     #[test]
     fn test_empty_files() {
-        let result = calculate_optimal_bitrate(&[], false);
+        let result = calculate_optimal_bitrate(&[], false, CD_CAPACITY_BYTES);
         assert_eq!(result.target_bitrate, MAX_BITRATE);
         assert_eq!(result.estimated_size, 0);
         assert!(result.fits_on_cd);
@@ -353,7 +354,7 @@ mod tests {
         // 10 songs at 4 minutes each = 40 minutes total
         // At 320 kbps: 320 * 40 * 60 * 125 = ~96 MB
         let files: Vec<AudioFileInfo> = (0..10).map(|_| make_mp3(320, 240.0)).collect();
-        let result = calculate_optimal_bitrate(&files, false);
+        let result = calculate_optimal_bitrate(&files, false, CD_CAPACITY_BYTES);
 
         assert!(result.fits_on_cd);
         assert_eq!(result.target_bitrate, MAX_BITRATE);
@@ -364,7 +365,7 @@ mod tests {
         // 100 songs at 5 minutes each = 500 minutes = 30000 seconds
         // At 320 kbps: 320 * 30000 * 125 = ~1.2 GB (doesn't fit)
         let files: Vec<AudioFileInfo> = (0..100).map(|_| make_flac(300.0)).collect();
-        let result = calculate_optimal_bitrate(&files, false);
+        let result = calculate_optimal_bitrate(&files, false, CD_CAPACITY_BYTES);
 
         // Should calculate a lower bitrate to fit
         assert!(result.target_bitrate < MAX_BITRATE);
@@ -398,13 +399,13 @@ mod tests {
     fn test_will_fit_on_cd() {
         // Small file definitely fits
         let small_file = make_mp3(128, 180.0);
-        assert!(will_fit_on_cd(&[small_file], 320, false));
+        assert!(will_fit_on_cd(&[small_file], 320, false, CD_CAPACITY_BYTES));
 
         // Create files that definitely won't fit
         // 1000 songs at 10 minutes each at FLAC = way over 700MB
         let huge_files: Vec<AudioFileInfo> = (0..1000).map(|_| make_flac(600.0)).collect();
         // Even at min bitrate, might still not fit
-        let fits = will_fit_on_cd(&huge_files, MIN_BITRATE, false);
+        let fits = will_fit_on_cd(&huge_files, MIN_BITRATE, false, CD_CAPACITY_BYTES);
         // This is expected to not fit
         assert!(!fits);
     }
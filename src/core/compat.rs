@@ -0,0 +1,364 @@
+//! Car stereo compatibility profiles
+//!
+//! Many head units only read a narrow subset of what a data disc can hold:
+//! a capped number of folders and files, ASCII names, old ID3 tags and small
+//! (or no) embedded cover art. A profile bundles those limits so staging,
+//! the encoder and the multi-disc capacity check all keep to them. Staging
+//! always puts tracks one folder below the disc root, which is within every
+//! player's folder depth limit.
+
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+
+use super::MusicFolder;
+use crate::burning::IsoNaming;
+
+/// Largest cover art the generic head unit profile still embeds
+const GENERIC_MAX_ART_BYTES: u64 = 64 * 1024;
+
+/// Most folders a limited head unit lists on one disc
+const HEAD_UNIT_MAX_FOLDERS: usize = 99;
+
+/// Most files a limited head unit lists in one folder
+const HEAD_UNIT_MAX_FILES: usize = 255;
+
+/// Which players the disc has to work in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompatProfile {
+    /// Computers and recent stereos: no limits beyond the disc's file system
+    #[default]
+    Modern,
+    /// Typical MP3 head unit: 99 folders, 255 files per folder, ASCII names,
+    /// ID3v2.3 plus ID3v1 tags and small cover art
+    GenericHeadUnit,
+    /// Oldest MP3 head units: the generic limits, 8.3 names, ID3v1 tags only
+    /// and no cover art
+    Strict83,
+}
+
+impl CompatProfile {
+    /// Name shown in the Options menu
+    pub fn label(&self) -> &'static str {
+        match self {
+            CompatProfile::Modern => "Modern",
+            CompatProfile::GenericHeadUnit => "Generic Head Unit",
+            CompatProfile::Strict83 => "Strict 8.3",
+        }
+    }
+
+    /// Most album folders on one disc
+    pub fn max_folders(&self) -> Option<usize> {
+        match self {
+            CompatProfile::Modern => None,
+            CompatProfile::GenericHeadUnit | CompatProfile::Strict83 => Some(HEAD_UNIT_MAX_FOLDERS),
+        }
+    }
+
+    /// Most tracks in one folder
+    pub fn max_files_per_folder(&self) -> Option<usize> {
+        match self {
+            CompatProfile::Modern => None,
+            CompatProfile::GenericHeadUnit | CompatProfile::Strict83 => Some(HEAD_UNIT_MAX_FILES),
+        }
+    }
+
+    /// Whether folder and file names must be plain ASCII
+    pub fn ascii_names(&self) -> bool {
+        !matches!(self, CompatProfile::Modern)
+    }
+
+    /// Naming level the disc has to use, given the one chosen in Options
+    pub fn iso_naming(&self, chosen: IsoNaming) -> IsoNaming {
+        match self {
+            CompatProfile::Strict83 => IsoNaming::Level1,
+            CompatProfile::Modern | CompatProfile::GenericHeadUnit => chosen,
+        }
+    }
+
+    /// ffmpeg MP3 muxer options for the tags this profile's players read
+    pub fn id3_args(&self) -> &'static [&'static str] {
+        match self {
+            CompatProfile::Modern => &["-id3v2_version", "3"],
+            CompatProfile::GenericHeadUnit => &["-id3v2_version", "3", "-write_id3v1", "1"],
+            // Version 0 leaves the ID3v2 tag out entirely
+            CompatProfile::Strict83 => &["-id3v2_version", "0", "-write_id3v1", "1"],
+        }
+    }
+
+    /// Whether this profile's players read ID3v2 tags
+    pub fn writes_id3v2(&self) -> bool {
+        !matches!(self, CompatProfile::Strict83)
+    }
+
+    /// Whether an ID3v1 tag is added for players that only read those
+    pub fn writes_id3v1(&self) -> bool {
+        !matches!(self, CompatProfile::Modern)
+    }
+
+    /// Whether MP3s can be copied with their tags and embedded art untouched
+    ///
+    /// Otherwise they're remuxed without art so their tags are rewritten in a
+    /// version the player reads.
+    pub fn keeps_source_tags(&self) -> bool {
+        matches!(self, CompatProfile::Modern)
+    }
+
+    fn max_art_bytes(&self) -> Option<u64> {
+        match self {
+            CompatProfile::Modern => None,
+            CompatProfile::GenericHeadUnit => Some(GENERIC_MAX_ART_BYTES),
+            CompatProfile::Strict83 => Some(0),
+        }
+    }
+
+    /// The cover art to embed, or None if `art` is too large for this profile
+    pub fn album_art(&self, art: Option<&str>) -> Option<String> {
+        let art = art?;
+        match self.max_art_bytes() {
+            None => Some(art.to_string()),
+            Some(max) => fs::metadata(art)
+                .is_ok_and(|m| m.len() > 0 && m.len() <= max)
+                .then(|| art.to_string()),
+        }
+    }
+
+    /// Replace anything in `name` this profile's players can't display
+    ///
+    /// Accented Latin letters lose their accents; other non-ASCII characters
+    /// become "_".
+    pub fn fold_name(&self, name: &str) -> String {
+        if !self.ascii_names() || name.is_ascii() {
+            return name.to_string();
+        }
+        name.chars().fold(String::new(), |mut folded, c| {
+            if c.is_ascii() {
+                folded.push(c);
+            } else {
+                folded.push_str(ascii_equivalent(c));
+            }
+            folded
+        })
+    }
+
+    /// Folder and track counts beyond this profile's limits
+    fn limit_problems(&self, folders: &[MusicFolder]) -> Vec<String> {
+        let mut problems = Vec::new();
+        if let Some(max) = self.max_folders()
+            && folders.len() > max
+        {
+            problems.push(format!(
+                "{} folders on one disc (at most {})",
+                folders.len(),
+                max
+            ));
+        }
+        if let Some(max) = self.max_files_per_folder() {
+            for folder in folders {
+                let tracks = folder.active_tracks().len();
+                if tracks > max {
+                    problems.push(format!(
+                        "\"{}\" has {} tracks (at most {})",
+                        folder.display_name(),
+                        tracks,
+                        max
+                    ));
+                }
+            }
+        }
+        problems
+    }
+
+    /// Check `folders` can be staged as one disc under this profile
+    ///
+    /// # Returns
+    /// * `Ok(())` if the folder and track counts are within the limits
+    /// * `Err(String)` describing every limit that's exceeded
+    pub fn check_layout(&self, folders: &[MusicFolder]) -> Result<(), String> {
+        let problems = self.limit_problems(folders);
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Too much for a {} player: {}",
+                self.label(),
+                problems.join("; ")
+            ))
+        }
+    }
+
+    /// Everything about `folders` that breaks this profile, for warning
+    /// before encoding (empty when they comply)
+    ///
+    /// Besides the hard limits, this notes names that will lose characters.
+    pub fn check_folders(&self, folders: &[MusicFolder]) -> Vec<String> {
+        let mut problems = self.limit_problems(folders);
+        if self.ascii_names() {
+            let renamed = folders
+                .iter()
+                .map(|f| {
+                    let folder_name = usize::from(!f.display_name().is_ascii());
+                    let track_names = f
+                        .active_tracks()
+                        .iter()
+                        .filter(|t| !t.path.file_stem().is_some_and(|s| s.is_ascii()))
+                        .count();
+                    folder_name + track_names
+                })
+                .sum::<usize>();
+            if renamed > 0 {
+                problems.push(format!(
+                    "{} name{} with non-ASCII characters will be changed",
+                    renamed,
+                    if renamed == 1 { "" } else { "s" }
+                ));
+            }
+        }
+        problems
+    }
+}
+
+/// Closest ASCII spelling of a non-ASCII character
+fn ascii_equivalent(c: char) -> &'static str {
+    match c {
+        'À'..='Å' => "A",
+        'à'..='å' => "a",
+        'Æ' => "AE",
+        'æ' => "ae",
+        'Ç' => "C",
+        'ç' => "c",
+        'È'..='Ë' => "E",
+        'è'..='ë' => "e",
+        'Ì'..='Ï' => "I",
+        'ì'..='ï' => "i",
+        'Ð' => "D",
+        'ð' => "d",
+        'Ñ' => "N",
+        'ñ' => "n",
+        'Ò'..='Ö' | 'Ø' => "O",
+        'ò'..='ö' | 'ø' => "o",
+        'Ù'..='Ü' => "U",
+        'ù'..='ü' => "u",
+        'Ý' => "Y",
+        'ý' | 'ÿ' => "y",
+        'Þ' => "Th",
+        'þ' => "th",
+        'ß' => "ss",
+        'Œ' => "OE",
+        'œ' => "oe",
+        'Š' => "S",
+        'š' => "s",
+        'Ž' => "Z",
+        'ž' => "z",
+        '‘' | '’' | '“' | '”' => "'",
+        '–' | '—' => "-",
+        '…' => "...",
+        _ => "_",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::AudioFileInfo;
+    use std::path::PathBuf;
+
+    fn album(name: &str, tracks: usize) -> MusicFolder {
+        let mut folder = MusicFolder::new_for_test_with_id(name);
+        folder.audio_files = (0..tracks)
+            .map(|i| AudioFileInfo {
+                path: PathBuf::from(format!("/test/{}/{:03}.mp3", name, i)),
+                duration: 180.0,
+                bitrate: 192,
+                size: 4_000_000,
+                codec: "mp3".to_string(),
+                is_lossy: true,
+            })
+            .collect();
+        folder
+    }
+
+    #[test]
+    fn test_modern_has_no_limits() {
+        let folders: Vec<_> = (0..150).map(|i| album(&format!("a{}", i), 300)).collect();
+        assert!(CompatProfile::Modern.check_layout(&folders).is_ok());
+        assert!(CompatProfile::Modern.check_folders(&folders).is_empty());
+        assert_eq!(CompatProfile::Modern.fold_name("Café"), "Café");
+    }
+
+    #[test]
+    fn test_folder_and_track_limits() {
+        let mut folders: Vec<_> = (0..100).map(|i| album(&format!("a{}", i), 1)).collect();
+        let err = CompatProfile::GenericHeadUnit
+            .check_layout(&folders)
+            .unwrap_err();
+        assert!(err.contains("100 folders"));
+
+        folders.truncate(99);
+        assert!(
+            CompatProfile::GenericHeadUnit
+                .check_layout(&folders)
+                .is_ok()
+        );
+
+        folders.push(album("big", 256));
+        folders.remove(0);
+        let err = CompatProfile::Strict83.check_layout(&folders).unwrap_err();
+        assert!(err.contains("256 tracks"));
+    }
+
+    #[test]
+    fn test_fold_name() {
+        let generic = CompatProfile::GenericHeadUnit;
+        assert_eq!(generic.fold_name("Björk – Début"), "Bjork - Debut");
+        assert_eq!(generic.fold_name("Straße"), "Strasse");
+        assert_eq!(generic.fold_name("東京"), "__");
+        assert_eq!(generic.fold_name("Plain"), "Plain");
+    }
+
+    #[test]
+    fn test_check_folders_counts_non_ascii_names() {
+        let mut folder = album("Sigur Rós", 2);
+        folder.audio_files[0].path = PathBuf::from("/test/Sigur Rós/Svefn-g-englar.mp3");
+        folder.audio_files[1].path = PathBuf::from("/test/Sigur Rós/Ný batterí.mp3");
+        let problems = CompatProfile::GenericHeadUnit.check_folders(&[folder]);
+        assert_eq!(
+            problems,
+            vec!["2 names with non-ASCII characters will be changed"]
+        );
+    }
+
+    #[test]
+    fn test_album_art_size_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let small = dir.path().join("small.jpg");
+        let large = dir.path().join("large.jpg");
+        fs::write(&small, vec![0u8; 1024]).unwrap();
+        fs::write(&large, vec![0u8; 100 * 1024]).unwrap();
+        let small = small.to_str().unwrap();
+        let large = large.to_str().unwrap();
+
+        let generic = CompatProfile::GenericHeadUnit;
+        assert_eq!(generic.album_art(Some(small)).as_deref(), Some(small));
+        assert_eq!(generic.album_art(Some(large)), None);
+        assert_eq!(CompatProfile::Strict83.album_art(Some(small)), None);
+        assert_eq!(
+            CompatProfile::Modern.album_art(Some(large)).as_deref(),
+            Some(large)
+        );
+    }
+
+    #[test]
+    fn test_strict_forces_level_1_and_id3v1() {
+        let strict = CompatProfile::Strict83;
+        assert_eq!(strict.iso_naming(IsoNaming::Joliet), IsoNaming::Level1);
+        assert_eq!(
+            CompatProfile::GenericHeadUnit.iso_naming(IsoNaming::RockRidge),
+            IsoNaming::RockRidge
+        );
+        assert!(strict.id3_args().contains(&"-write_id3v1"));
+        assert!(strict.writes_id3v1() && !strict.writes_id3v2());
+        assert!(!strict.keeps_source_tags());
+    }
+}
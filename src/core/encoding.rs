@@ -0,0 +1,155 @@
+//! LAME encoder settings
+//!
+//! How lossless and lossy sources are turned into MP3s: the bitrate mode
+//! plus LAME's tuning options. Chosen per burn profile. The bitrate itself
+//! isn't part of it, as it's planned to fill the disc (see
+//! [`fit_bitrate`](crate::conversion::fit_bitrate)).
+
+use serde::{Deserialize, Serialize};
+
+/// Sample rates the encoder can resample to
+///
+/// Only the MPEG-1 rates, at which every CBR bitrate up to 320 kbps is valid.
+pub const RESAMPLE_RATES: [u32; 3] = [32_000, 44_100, 48_000];
+
+/// How the encoder spends bits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BitrateMode {
+    /// Average bitrate: hits the planned size closely
+    #[default]
+    Abr,
+    /// Constant bitrate, for players that can't seek in VBR files
+    Cbr,
+    /// A LAME VBR preset (V0-V9), picked to fit the disc
+    Vbr,
+}
+
+impl BitrateMode {
+    /// All modes, in the order the encoding options list them
+    pub const ALL: [BitrateMode; 3] = [BitrateMode::Abr, BitrateMode::Cbr, BitrateMode::Vbr];
+
+    /// Name shown in the encoding options
+    pub fn label(&self) -> &'static str {
+        match self {
+            BitrateMode::Abr => "Average Bitrate (ABR)",
+            BitrateMode::Cbr => "Constant Bitrate (CBR)",
+            BitrateMode::Vbr => "Variable Bitrate (VBR)",
+        }
+    }
+}
+
+/// How the two channels are coded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StereoMode {
+    /// Joint stereo: LAME codes mid/side where it saves bits
+    #[default]
+    Joint,
+    /// Forced stereo: left and right are always coded separately
+    Stereo,
+}
+
+impl StereoMode {
+    /// Name shown in the encoding options
+    pub fn label(&self) -> &'static str {
+        match self {
+            StereoMode::Joint => "Joint Stereo",
+            StereoMode::Stereo => "Forced Stereo",
+        }
+    }
+}
+
+/// Bitrate mode and LAME tuning options for transcodes
+///
+/// `None` leaves an option to LAME, which picks it from the bitrate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct EncoderSettings {
+    #[serde(default)]
+    pub mode: BitrateMode,
+    /// Algorithm quality (`-q`), 0 (best, slowest) to 9 (worst, fastest)
+    #[serde(default)]
+    pub quality: Option<u8>,
+    /// Lowpass filter cutoff in Hz
+    #[serde(default)]
+    pub lowpass_hz: Option<u32>,
+    #[serde(default)]
+    pub stereo: StereoMode,
+    /// Output sample rate in Hz, one of [`RESAMPLE_RATES`]
+    #[serde(default)]
+    pub sample_rate: Option<u32>,
+}
+
+impl EncoderSettings {
+    /// Algorithm quality to pass to LAME, if one was chosen
+    pub fn quality(&self) -> Option<u8> {
+        self.quality.map(|q| q.min(9))
+    }
+
+    /// Output sample rate to resample to, if a valid one was chosen
+    pub fn sample_rate(&self) -> Option<u32> {
+        self.sample_rate
+            .filter(|rate| RESAMPLE_RATES.contains(rate))
+    }
+
+    /// One-line summary for logs
+    pub fn summary(&self) -> String {
+        let mut parts = vec![self.mode.label().to_string()];
+        if let Some(quality) = self.quality() {
+            parts.push(format!("-q {}", quality));
+        }
+        if let Some(hz) = self.lowpass_hz {
+            parts.push(format!("lowpass {} Hz", hz));
+        }
+        parts.push(self.stereo.label().to_string());
+        if let Some(rate) = self.sample_rate() {
+            parts.push(format!("{} Hz", rate));
+        }
+        parts.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_settings() {
+        let settings = EncoderSettings::default();
+        assert_eq!(settings.mode, BitrateMode::Abr);
+        assert_eq!(settings.stereo, StereoMode::Joint);
+        assert_eq!(settings.summary(), "Average Bitrate (ABR), Joint Stereo");
+    }
+
+    #[test]
+    fn test_out_of_range_options_are_ignored() {
+        let settings = EncoderSettings {
+            quality: Some(12),
+            sample_rate: Some(22_050),
+            ..Default::default()
+        };
+        assert_eq!(settings.quality(), Some(9));
+        assert_eq!(settings.sample_rate(), None);
+    }
+
+    #[test]
+    fn test_settings_serde() {
+        let settings = EncoderSettings {
+            mode: BitrateMode::Vbr,
+            quality: Some(2),
+            lowpass_hz: Some(19_000),
+            stereo: StereoMode::Stereo,
+            sample_rate: Some(44_100),
+        };
+        let json = serde_json::to_string(&settings).unwrap();
+        assert!(json.contains("\"mode\":\"vbr\""));
+        assert_eq!(
+            serde_json::from_str::<EncoderSettings>(&json).unwrap(),
+            settings
+        );
+
+        // Older profiles have no encoder options at all
+        let empty: EncoderSettings = serde_json::from_str("{}").unwrap();
+        assert_eq!(empty, EncoderSettings::default());
+    }
+}
@@ -0,0 +1,183 @@
+//! Target media profiles
+//!
+//! The capacity everything is sized against: the bitrate optimizer, the
+//! background encoder and the ISO size check. Chosen per burn profile.
+
+use serde::{Deserialize, Serialize};
+
+/// Bytes per sector reported by drives for data discs, the same as the
+/// image's own sectors
+pub use crate::burning::iso9660::SECTOR_SIZE;
+
+/// Fraction by which a detected disc may exceed a preset and still be
+/// treated as that preset (blank media reports its raw sector count)
+const DETECTION_TOLERANCE: f64 = 0.10;
+
+/// The kind of media being written
+///
+/// Preset capacities use the decimal sizes printed on the media
+/// (a "700 MB" CD-R holds 700,000,000 bytes of data in practice).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaProfile {
+    /// 74-minute CD-R (650 MB)
+    Cd74,
+    /// 80-minute CD-R (700 MB)
+    #[default]
+    Cd80,
+    /// Single-layer DVD (4.7 GB)
+    Dvd5,
+    /// Dual-layer DVD (8.5 GB)
+    Dvd9,
+    /// Any other fixed budget (e.g. a USB stick)
+    Custom { bytes: u64 },
+}
+
+impl MediaProfile {
+    /// The preset profiles, smallest first
+    pub const PRESETS: [MediaProfile; 4] = [
+        MediaProfile::Cd74,
+        MediaProfile::Cd80,
+        MediaProfile::Dvd5,
+        MediaProfile::Dvd9,
+    ];
+
+    /// Usable capacity in bytes
+    pub const fn capacity_bytes(&self) -> u64 {
+        match self {
+            MediaProfile::Cd74 => 650 * 1000 * 1000,
+            MediaProfile::Cd80 => 700 * 1000 * 1000,
+            MediaProfile::Dvd5 => 4_700 * 1000 * 1000,
+            MediaProfile::Dvd9 => 8_500 * 1000 * 1000,
+            MediaProfile::Custom { bytes } => *bytes,
+        }
+    }
+
+    /// Audio CD playing time in minutes, or None for media that can't hold
+    /// a Red Book audio disc
+    pub const fn audio_minutes(&self) -> Option<u32> {
+        match self {
+            MediaProfile::Cd74 => Some(74),
+            MediaProfile::Cd80 => Some(80),
+            _ => None,
+        }
+    }
+
+    /// Create a custom profile from a size in megabytes (decimal)
+    pub fn custom_mb(mb: u64) -> Self {
+        MediaProfile::Custom {
+            bytes: mb * 1000 * 1000,
+        }
+    }
+
+    /// Pick the profile matching a blank disc's reported capacity
+    ///
+    /// Chooses the largest preset that fits; a capacity well above every
+    /// preset that fits (or below all of them) becomes a custom profile.
+    pub fn from_capacity(bytes: u64) -> Self {
+        let preset = Self::PRESETS
+            .iter()
+            .rev()
+            .find(|p| p.capacity_bytes() <= bytes)
+            .copied();
+
+        let limit = |p: &MediaProfile| p.capacity_bytes() as f64 * (1.0 + DETECTION_TOLERANCE);
+        match preset {
+            Some(p) if bytes as f64 <= limit(&p) => p,
+            _ => MediaProfile::Custom { bytes },
+        }
+    }
+
+    /// Capacity for display, e.g. "700 MB" or "4.7 GB"
+    pub fn capacity_label(&self) -> String {
+        let bytes = self.capacity_bytes();
+        if bytes >= 1000 * 1000 * 1000 {
+            format!("{:.1} GB", bytes as f64 / 1_000_000_000.0)
+        } else {
+            format!("{} MB", bytes / 1_000_000)
+        }
+    }
+
+    /// Name for menus and dialogs, e.g. "CD 80 min (700 MB)"
+    pub fn name(&self) -> String {
+        let kind = match self {
+            MediaProfile::Cd74 => "CD 74 min",
+            MediaProfile::Cd80 => "CD 80 min",
+            MediaProfile::Dvd5 => "DVD-5",
+            MediaProfile::Dvd9 => "DVD-9",
+            MediaProfile::Custom { .. } => "Custom",
+        };
+        format!("{} ({})", kind, self.capacity_label())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_is_80_minute_cd() {
+        assert_eq!(MediaProfile::default(), MediaProfile::Cd80);
+        assert_eq!(MediaProfile::default().capacity_bytes(), 700_000_000);
+    }
+
+    #[test]
+    fn test_capacity_labels() {
+        assert_eq!(MediaProfile::Cd74.name(), "CD 74 min (650 MB)");
+        assert_eq!(MediaProfile::Dvd5.capacity_label(), "4.7 GB");
+        assert_eq!(MediaProfile::custom_mb(256).name(), "Custom (256 MB)");
+    }
+
+    #[test]
+    fn test_audio_minutes() {
+        assert_eq!(MediaProfile::Cd74.audio_minutes(), Some(74));
+        assert_eq!(MediaProfile::Cd80.audio_minutes(), Some(80));
+        assert_eq!(MediaProfile::Dvd5.audio_minutes(), None);
+        assert_eq!(MediaProfile::custom_mb(700).audio_minutes(), None);
+    }
+
+    #[test]
+    fn test_from_capacity() {
+        // Raw sector counts reported for blank media
+        assert_eq!(
+            MediaProfile::from_capacity(333_000 * SECTOR_SIZE),
+            MediaProfile::Cd74
+        );
+        assert_eq!(
+            MediaProfile::from_capacity(359_849 * SECTOR_SIZE),
+            MediaProfile::Cd80
+        );
+        assert_eq!(
+            MediaProfile::from_capacity(2_298_496 * SECTOR_SIZE),
+            MediaProfile::Dvd5
+        );
+        assert_eq!(
+            MediaProfile::from_capacity(4_171_712 * SECTOR_SIZE),
+            MediaProfile::Dvd9
+        );
+
+        // Mini CD and something between CD and DVD sizes
+        assert_eq!(
+            MediaProfile::from_capacity(100_000_000),
+            MediaProfile::Custom { bytes: 100_000_000 }
+        );
+        assert_eq!(
+            MediaProfile::from_capacity(2_000_000_000),
+            MediaProfile::Custom {
+                bytes: 2_000_000_000
+            }
+        );
+    }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        for media in [MediaProfile::Dvd5, MediaProfile::custom_mb(1000)] {
+            let json = serde_json::to_string(&media).unwrap();
+            assert_eq!(serde_json::from_str::<MediaProfile>(&json).unwrap(), media);
+        }
+        assert_eq!(
+            serde_json::to_string(&MediaProfile::Cd74).unwrap(),
+            "\"cd74\""
+        );
+    }
+}
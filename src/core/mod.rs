@@ -5,16 +5,25 @@
 //! - Actions that can be triggered from menus or UI
 //! - Folder scanning and audio file discovery
 //! - Bitrate calculation for CD-fitting optimization
+//! - Target media profiles (CD, DVD, custom capacity)
+//! - Car stereo compatibility profiles
+//! - LAME encoder settings (bitrate mode and tuning)
 //! - Folder state tracking for background encoding
 
 use std::path::PathBuf;
 
 mod bitrate;
+mod compat;
+mod encoding;
 mod folder_state;
+mod media;
 mod scanning;
 mod state;
 
+pub use compat::CompatProfile;
+pub use encoding::{BitrateMode, EncoderSettings, RESAMPLE_RATES, StereoMode};
 pub use folder_state::{FolderConversionStatus, FolderId, calculate_folder_hash};
+pub use media::{MediaProfile, SECTOR_SIZE};
 pub use scanning::{
     AudioFileInfo, FolderKind, MusicFolder, SavedMixtapeTrackInfo, create_folder_from_metadata,
     create_mixtape_from_saved_state, find_album_folders, format_duration, format_size,
@@ -64,6 +73,26 @@ pub fn get_resource_path(relative_path: &str) -> Option<PathBuf> {
     None
 }
 
+/// Get the app data directory, creating it if needed
+///
+/// Settings, the recent profiles and the disc catalog are kept in
+/// "MP3 CD Burner" under the platform's data directory
+/// (~/Library/Application Support on macOS, ~/.local/share on Linux).
+pub fn get_app_data_dir() -> Result<PathBuf, String> {
+    let data_dir =
+        dirs::data_dir().ok_or_else(|| "Could not determine data directory".to_string())?;
+
+    let app_dir = data_dir.join("MP3 CD Burner");
+
+    // Create directory if it doesn't exist
+    if !app_dir.exists() {
+        std::fs::create_dir_all(&app_dir)
+            .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    }
+
+    Ok(app_dir)
+}
+
 /// Get the path to the default mixtape album art image
 pub fn get_mixtape_default_art() -> Option<PathBuf> {
     get_resource_path("images/mixtape.jpg")
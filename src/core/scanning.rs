@@ -174,6 +174,19 @@ impl MusicFolder {
             .collect()
     }
 
+    /// Playing time of the active tracks in seconds
+    ///
+    /// Same as `total_duration` unless tracks have been excluded.
+    pub fn active_duration(&self) -> f64 {
+        let excluded: f64 = self
+            .audio_files
+            .iter()
+            .filter(|f| self.excluded_tracks.contains(&f.path))
+            .map(|f| f.duration)
+            .sum();
+        (self.total_duration - excluded).max(0.0)
+    }
+
     /// Exclude a track from the burn
     #[allow(dead_code)]
     pub fn exclude_track(&mut self, path: &Path) {
@@ -1087,10 +1100,13 @@ mod tests {
                 is_lossy: true,
             },
         ];
+        folder.recalculate_totals();
+        assert_eq!(folder.active_duration(), 420.0);
         folder.exclude_track(Path::new("/test/1.mp3"));
         let tracks = folder.active_tracks();
         assert_eq!(tracks.len(), 1);
         assert_eq!(tracks[0].path, PathBuf::from("/test/2.mp3"));
+        assert_eq!(folder.active_duration(), 240.0);
     }
 
     #[test]
@@ -15,7 +15,15 @@ use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, AtomicI32, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 
-use super::MusicFolder;
+use super::{CompatProfile, MusicFolder, get_app_data_dir};
+use crate::burning::burner::{DriveSelection, EraseMode};
+use crate::burning::copies::CopyOutcome;
+use crate::burning::erase::EraseResult;
+use crate::burning::error::BurnError;
+use crate::burning::export::ExportResult;
+use crate::burning::iso_names::{IsoNaming, NameChange};
+use crate::burning::playlist::PlaylistFormat;
+use crate::burning::verify::VerificationResult;
 
 /// Application-wide settings
 ///
@@ -26,12 +34,30 @@ pub struct AppSettings {
     /// Whether to simulate burning (don't actually burn)
     #[serde(default)]
     pub simulate_burn: bool,
+    /// Whether to read the disc back and compare it with the ISO after burning
+    #[serde(default)]
+    pub verify_burn: bool,
     /// Whether to avoid lossy-to-lossy conversions
     #[serde(default)]
     pub no_lossy_conversions: bool,
     /// Whether to embed album art in MP3s
     #[serde(default)]
     pub embed_album_art: bool,
+    /// Whether audio CDs are burned without the 2-second gap between tracks
+    #[serde(default)]
+    pub gapless_audio_cd: bool,
+    /// Naming rules file names on data discs must follow
+    #[serde(default)]
+    pub iso_naming: IsoNaming,
+    /// Car stereo limits the disc layout and tags must keep to
+    #[serde(default)]
+    pub compat_profile: CompatProfile,
+    /// Playlists written onto data discs
+    #[serde(default)]
+    pub playlist_format: PlaylistFormat,
+    /// Drive and write speed burns use
+    #[serde(default)]
+    pub burn_drive: DriveSelection,
 }
 
 
@@ -40,22 +66,6 @@ impl Global for AppSettings {}
 impl AppSettings {
     const SETTINGS_FILE: &'static str = "app_settings.json";
 
-    /// Get the app data directory (~/Library/Application Support/MP3 CD Burner/)
-    fn get_app_data_dir() -> Result<PathBuf, String> {
-        let data_dir =
-            dirs::data_dir().ok_or_else(|| "Could not determine data directory".to_string())?;
-
-        let app_dir = data_dir.join("MP3 CD Burner");
-
-        // Create directory if it doesn't exist
-        if !app_dir.exists() {
-            std::fs::create_dir_all(&app_dir)
-                .map_err(|e| format!("Failed to create app data directory: {}", e))?;
-        }
-
-        Ok(app_dir)
-    }
-
     /// Load app settings from disk, or return defaults if not found
     pub fn load() -> Self {
         match Self::try_load() {
@@ -71,7 +81,7 @@ impl AppSettings {
     }
 
     fn try_load() -> Result<Self, String> {
-        let app_dir = Self::get_app_data_dir()?;
+        let app_dir = get_app_data_dir()?;
         let settings_path = app_dir.join(Self::SETTINGS_FILE);
 
         if !settings_path.exists() {
@@ -86,7 +96,7 @@ impl AppSettings {
 
     /// Save app settings to disk
     pub fn save(&self) -> Result<(), String> {
-        let app_dir = Self::get_app_data_dir()?;
+        let app_dir = get_app_data_dir()?;
         let settings_path = app_dir.join(Self::SETTINGS_FILE);
 
         let json = serde_json::to_string_pretty(self)
@@ -129,22 +139,6 @@ impl Default for WindowState {
 impl WindowState {
     const STATE_FILE: &'static str = "window_state.json";
 
-    /// Get the app data directory (~/Library/Application Support/MP3 CD Burner/)
-    fn get_app_data_dir() -> Result<PathBuf, String> {
-        let data_dir =
-            dirs::data_dir().ok_or_else(|| "Could not determine data directory".to_string())?;
-
-        let app_dir = data_dir.join("MP3 CD Burner");
-
-        // Create directory if it doesn't exist
-        if !app_dir.exists() {
-            std::fs::create_dir_all(&app_dir)
-                .map_err(|e| format!("Failed to create app data directory: {}", e))?;
-        }
-
-        Ok(app_dir)
-    }
-
     /// Load window state from disk, or return defaults if not found
     pub fn load() -> Self {
         match Self::try_load() {
@@ -163,7 +157,7 @@ impl WindowState {
     }
 
     fn try_load() -> Result<Self, String> {
-        let app_dir = Self::get_app_data_dir()?;
+        let app_dir = get_app_data_dir()?;
         let state_path = app_dir.join(Self::STATE_FILE);
 
         if !state_path.exists() {
@@ -178,7 +172,7 @@ impl WindowState {
 
     /// Save window state to disk
     pub fn save(&self) -> Result<(), String> {
-        let app_dir = Self::get_app_data_dir()?;
+        let app_dir = get_app_data_dir()?;
         let state_path = app_dir.join(Self::STATE_FILE);
 
         let json = serde_json::to_string_pretty(self)
@@ -229,22 +223,6 @@ impl Global for DisplaySettings {}
 impl DisplaySettings {
     const SETTINGS_FILE: &'static str = "display_settings.json";
 
-    /// Get the app data directory (~/Library/Application Support/MP3 CD Burner/)
-    fn get_app_data_dir() -> Result<PathBuf, String> {
-        let data_dir =
-            dirs::data_dir().ok_or_else(|| "Could not determine data directory".to_string())?;
-
-        let app_dir = data_dir.join("MP3 CD Burner");
-
-        // Create directory if it doesn't exist
-        if !app_dir.exists() {
-            std::fs::create_dir_all(&app_dir)
-                .map_err(|e| format!("Failed to create app data directory: {}", e))?;
-        }
-
-        Ok(app_dir)
-    }
-
     /// Load display settings from disk, or return defaults if not found
     pub fn load() -> Self {
         match Self::try_load() {
@@ -260,7 +238,7 @@ impl DisplaySettings {
     }
 
     fn try_load() -> Result<Self, String> {
-        let app_dir = Self::get_app_data_dir()?;
+        let app_dir = get_app_data_dir()?;
         let settings_path = app_dir.join(Self::SETTINGS_FILE);
 
         if !settings_path.exists() {
@@ -275,7 +253,7 @@ impl DisplaySettings {
 
     /// Save display settings to disk
     pub fn save(&self) -> Result<(), String> {
-        let app_dir = Self::get_app_data_dir()?;
+        let app_dir = get_app_data_dir()?;
         let settings_path = app_dir.join(Self::SETTINGS_FILE);
 
         let json = serde_json::to_string_pretty(self)
@@ -314,6 +292,8 @@ pub enum BurnStage {
     Converting,
     /// Creating ISO image
     CreatingIso,
+    /// Decoding tracks into an audio CD image
+    DecodingAudio,
     /// Waiting for user to insert a blank CD
     WaitingForCd,
     /// Detected an erasable disc (CD-RW) with data - waiting for user to confirm erase
@@ -322,8 +302,12 @@ pub enum BurnStage {
     Erasing,
     /// Burning ISO to CD
     Burning,
-    /// Finishing up (closing session, verifying)
+    /// Finishing up (closing session)
     Finishing,
+    /// Reading the disc back and comparing it with the ISO
+    Verifying,
+    /// Copying the staged files to a folder or USB drive
+    Exporting,
     /// Process complete (success or simulated)
     Complete,
     /// Process was cancelled
@@ -335,11 +319,14 @@ impl BurnStage {
         match self {
             BurnStage::Converting => "Converting...",
             BurnStage::CreatingIso => "Creating ISO...",
+            BurnStage::DecodingAudio => "Decoding audio...",
             BurnStage::WaitingForCd => "Insert blank CD",
             BurnStage::ErasableDiscDetected => "Erase disc?",
             BurnStage::Erasing => "Erasing...",
             BurnStage::Burning => "Burning...",
             BurnStage::Finishing => "Finishing...",
+            BurnStage::Verifying => "Verifying...",
+            BurnStage::Exporting => "Exporting...",
             BurnStage::Complete => "Complete!",
             BurnStage::Cancelled => "Cancelled",
         }
@@ -367,6 +354,30 @@ pub struct ConversionState {
     pub burn_progress: Arc<AtomicI32>,
     /// Path to the created ISO (for re-burning)
     pub iso_path: Arc<Mutex<Option<PathBuf>>>,
+    /// Result of the post-burn verification, if one ran
+    pub verification: Arc<Mutex<Option<VerificationResult>>>,
+    /// Why the last ISO write or burn failed, if one did
+    pub burn_error: Arc<Mutex<Option<BurnError>>>,
+    /// Disc currently being burned (1-based) in a multi-disc burn
+    pub disc_index: Arc<AtomicUsize>,
+    /// Number of discs in the burn (0 or 1 for a single disc)
+    pub disc_count: Arc<AtomicUsize>,
+    /// Discs of a multi-disc burn that burned (and verified, if asked) so far
+    pub discs_burned: Arc<AtomicUsize>,
+    /// Copy currently being burned (1-based) in a multi-copy burn
+    pub copy_index: Arc<AtomicUsize>,
+    /// Number of copies requested (0 unless this is a multi-copy burn)
+    pub copy_count: Arc<AtomicUsize>,
+    /// Outcome of each copy finished so far in a multi-copy burn
+    pub copy_results: Arc<Mutex<Vec<CopyOutcome>>>,
+    /// Result of an export to a folder or USB drive, if one ran
+    pub export: Arc<Mutex<Option<ExportResult>>>,
+    /// Mode of the standalone CD-RW erase running, if this is one
+    pub erase_mode: Arc<Mutex<Option<EraseMode>>>,
+    /// Result of a standalone CD-RW erase, once it's finished
+    pub erase: Arc<Mutex<Option<EraseResult>>>,
+    /// Staged names that were changed to fit the disc's naming rules
+    pub name_changes: Arc<Mutex<Vec<NameChange>>>,
 }
 
 impl Global for ConversionState {}
@@ -383,6 +394,18 @@ impl ConversionState {
             stage: Arc::new(Mutex::new(BurnStage::Converting)),
             burn_progress: Arc::new(AtomicI32::new(-1)),
             iso_path: Arc::new(Mutex::new(None)),
+            verification: Arc::new(Mutex::new(None)),
+            burn_error: Arc::new(Mutex::new(None)),
+            disc_index: Arc::new(AtomicUsize::new(0)),
+            disc_count: Arc::new(AtomicUsize::new(0)),
+            discs_burned: Arc::new(AtomicUsize::new(0)),
+            copy_index: Arc::new(AtomicUsize::new(0)),
+            copy_count: Arc::new(AtomicUsize::new(0)),
+            copy_results: Arc::new(Mutex::new(Vec::new())),
+            export: Arc::new(Mutex::new(None)),
+            erase_mode: Arc::new(Mutex::new(None)),
+            erase: Arc::new(Mutex::new(None)),
+            name_changes: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
@@ -396,6 +419,18 @@ impl ConversionState {
         *self.stage.lock().unwrap() = BurnStage::Converting;
         self.burn_progress.store(-1, Ordering::SeqCst);
         *self.iso_path.lock().unwrap() = None;
+        *self.verification.lock().unwrap() = None;
+        *self.burn_error.lock().unwrap() = None;
+        self.disc_index.store(0, Ordering::SeqCst);
+        self.disc_count.store(0, Ordering::SeqCst);
+        self.discs_burned.store(0, Ordering::SeqCst);
+        self.copy_index.store(0, Ordering::SeqCst);
+        self.copy_count.store(0, Ordering::SeqCst);
+        self.copy_results.lock().unwrap().clear();
+        *self.export.lock().unwrap() = None;
+        *self.erase_mode.lock().unwrap() = None;
+        *self.erase.lock().unwrap() = None;
+        self.name_changes.lock().unwrap().clear();
     }
 
     pub fn finish(&self) {
@@ -418,6 +453,41 @@ impl ConversionState {
         self.burn_progress.load(Ordering::SeqCst)
     }
 
+    /// Record which disc of a multi-disc burn is in progress
+    pub fn set_disc(&self, index: usize, count: usize) {
+        self.disc_index.store(index, Ordering::SeqCst);
+        self.disc_count.store(count, Ordering::SeqCst);
+    }
+
+    /// Current disc and disc count, if this is a multi-disc burn
+    pub fn disc_progress(&self) -> Option<(usize, usize)> {
+        let count = self.disc_count.load(Ordering::SeqCst);
+        (count > 1).then(|| (self.disc_index.load(Ordering::SeqCst), count))
+    }
+
+    /// Record that a disc of a multi-disc burn was burned
+    pub fn disc_burned(&self) {
+        self.discs_burned.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Discs burned so far and the disc count, if this is a multi-disc burn
+    pub fn discs_burned(&self) -> Option<(usize, usize)> {
+        let count = self.disc_count.load(Ordering::SeqCst);
+        (count > 1).then(|| (self.discs_burned.load(Ordering::SeqCst), count))
+    }
+
+    /// Record which copy of a multi-copy burn is in progress
+    pub fn set_copy(&self, index: usize, count: usize) {
+        self.copy_index.store(index, Ordering::SeqCst);
+        self.copy_count.store(count, Ordering::SeqCst);
+    }
+
+    /// Current copy and copy count, if this is a multi-copy burn
+    pub fn copy_progress(&self) -> Option<(usize, usize)> {
+        let count = self.copy_count.load(Ordering::SeqCst);
+        (count > 0).then(|| (self.copy_index.load(Ordering::SeqCst), count))
+    }
+
     /// Request cancellation of the current conversion
     pub fn request_cancel(&self) {
         self.cancel_requested.store(true, Ordering::SeqCst);
@@ -539,19 +609,39 @@ mod tests {
     fn test_app_settings_default() {
         let settings = AppSettings::default();
         assert!(!settings.simulate_burn);
+        assert!(!settings.verify_burn);
         assert!(!settings.no_lossy_conversions);
         assert!(!settings.embed_album_art);
+        assert!(!settings.gapless_audio_cd);
+        assert_eq!(settings.iso_naming, IsoNaming::Joliet);
+        assert_eq!(settings.compat_profile, CompatProfile::Modern);
+        assert_eq!(settings.playlist_format, PlaylistFormat::None);
+        assert_eq!(settings.burn_drive, DriveSelection::default());
     }
 
     #[test]
     fn test_app_settings_serialize() {
         let settings = AppSettings {
             simulate_burn: true,
+            verify_burn: true,
             no_lossy_conversions: true,
             embed_album_art: true,
+            gapless_audio_cd: true,
+            iso_naming: IsoNaming::Level1,
+            compat_profile: CompatProfile::GenericHeadUnit,
+            playlist_format: PlaylistFormat::M3u8,
+            burn_drive: DriveSelection {
+                drive: Some("/dev/sr1".to_string()),
+                speed: Some(8),
+            },
         };
         let json = serde_json::to_string(&settings).unwrap();
         assert!(json.contains("simulate_burn"));
+        assert!(json.contains("verify_burn"));
+        assert!(json.contains(r#""iso_naming":"level1""#));
+        assert!(json.contains(r#""compat_profile":"generic_head_unit""#));
+        assert!(json.contains(r#""playlist_format":"m3u8""#));
+        assert!(json.contains(r#""burn_drive":{"drive":"/dev/sr1","speed":8}"#));
         assert!(json.contains("true"));
     }
 
@@ -560,8 +650,12 @@ mod tests {
         let json = r#"{"simulate_burn":true,"no_lossy_conversions":false,"embed_album_art":true}"#;
         let settings: AppSettings = serde_json::from_str(json).unwrap();
         assert!(settings.simulate_burn);
+        assert!(!settings.verify_burn);
         assert!(!settings.no_lossy_conversions);
         assert!(settings.embed_album_art);
+        assert_eq!(settings.iso_naming, IsoNaming::Joliet);
+        assert_eq!(settings.compat_profile, CompatProfile::Modern);
+        assert_eq!(settings.playlist_format, PlaylistFormat::None);
     }
 
     #[test]
@@ -640,11 +734,14 @@ mod tests {
     fn test_burn_stage_display_text() {
         assert_eq!(BurnStage::Converting.display_text(), "Converting...");
         assert_eq!(BurnStage::CreatingIso.display_text(), "Creating ISO...");
+        assert_eq!(BurnStage::DecodingAudio.display_text(), "Decoding audio...");
         assert_eq!(BurnStage::WaitingForCd.display_text(), "Insert blank CD");
         assert_eq!(BurnStage::ErasableDiscDetected.display_text(), "Erase disc?");
         assert_eq!(BurnStage::Erasing.display_text(), "Erasing...");
         assert_eq!(BurnStage::Burning.display_text(), "Burning...");
         assert_eq!(BurnStage::Finishing.display_text(), "Finishing...");
+        assert_eq!(BurnStage::Verifying.display_text(), "Verifying...");
+        assert_eq!(BurnStage::Exporting.display_text(), "Exporting...");
         assert_eq!(BurnStage::Complete.display_text(), "Complete!");
         assert_eq!(BurnStage::Cancelled.display_text(), "Cancelled");
     }
@@ -680,6 +777,25 @@ mod tests {
         assert_eq!(state.get_burn_progress(), -1);
     }
 
+    #[test]
+    fn test_conversion_state_discs_burned() {
+        let state = ConversionState::new();
+        state.reset(10);
+        assert_eq!(state.discs_burned(), None);
+
+        // The last disc of three failed
+        state.set_disc(1, 3);
+        state.disc_burned();
+        state.set_disc(2, 3);
+        state.disc_burned();
+        state.set_disc(3, 3);
+        assert_eq!(state.disc_progress(), Some((3, 3)));
+        assert_eq!(state.discs_burned(), Some((2, 3)));
+
+        state.reset(10);
+        assert_eq!(state.discs_burned(), None);
+    }
+
     #[test]
     fn test_conversion_state_finish() {
         let state = ConversionState::new();
@@ -715,6 +831,32 @@ mod tests {
         assert_eq!(state.get_burn_progress(), 100);
     }
 
+    #[test]
+    fn test_conversion_state_disc_progress() {
+        let state = ConversionState::new();
+        assert_eq!(state.disc_progress(), None);
+        state.set_disc(1, 1);
+        assert_eq!(state.disc_progress(), None);
+        state.set_disc(2, 3);
+        assert_eq!(state.disc_progress(), Some((2, 3)));
+        state.reset(0);
+        assert_eq!(state.disc_progress(), None);
+    }
+
+    #[test]
+    fn test_conversion_state_copy_progress() {
+        let state = ConversionState::new();
+        assert_eq!(state.copy_progress(), None);
+        state.set_copy(1, 1);
+        assert_eq!(state.copy_progress(), Some((1, 1)));
+        state.set_copy(2, 5);
+        state.copy_results.lock().unwrap().push(CopyOutcome::Burned);
+        assert_eq!(state.copy_progress(), Some((2, 5)));
+        state.reset(0);
+        assert_eq!(state.copy_progress(), None);
+        assert!(state.copy_results.lock().unwrap().is_empty());
+    }
+
     #[test]
     fn test_conversion_state_progress_tracking() {
         let state = ConversionState::new();
@@ -924,8 +1066,14 @@ mod tests {
     fn test_app_settings_clone() {
         let settings = AppSettings {
             simulate_burn: true,
+            verify_burn: false,
             no_lossy_conversions: true,
             embed_album_art: false,
+            gapless_audio_cd: false,
+            iso_naming: IsoNaming::RockRidge,
+            compat_profile: CompatProfile::Strict83,
+            playlist_format: PlaylistFormat::Both,
+            burn_drive: DriveSelection::default(),
         };
         let cloned = settings.clone();
         assert!(cloned.simulate_burn);
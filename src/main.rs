@@ -15,10 +15,16 @@ mod test_fixtures;
 mod ui;
 
 use actions::{
-    About, NewMixtape, NewProfile, OpenDisplaySettings, OpenLogFolder, OpenOutputDir, OpenProfile,
-    Quit, SaveProfile, SetVolumeLabel, ToggleEmbedAlbumArt, ToggleSimulateBurn, push_pending_file,
+    About, BurnAcrossDiscs, BurnAudioCd, BurnCopies, ChooseBurnDrive, CompatGenericHeadUnit,
+    CompatModern, CompatStrict83, ExportToFolder, FullEraseDisc, IsoNamesJoliet, IsoNamesLevel1,
+    IsoNamesLevel2, IsoNamesRockRidge, NewMixtape, NewProfile, OpenDiscCatalog,
+    OpenDisplaySettings, OpenEncodingOptions, OpenLogFolder, OpenOutputDir, OpenProfile,
+    PlaylistsBoth, PlaylistsM3u8, PlaylistsNone, PlaylistsPls, PreviewDiscLayout, PrintInsert,
+    QuickEraseDisc, Quit, SaveProfile, SetVolumeLabel, ToggleEmbedAlbumArt, ToggleGaplessAudioCd,
+    ToggleSimulateBurn, ToggleVerifyBurn, push_pending_file,
 };
-use core::{AppSettings, DisplaySettings, WindowState};
+use burning::{IsoNaming, PlaylistFormat};
+use core::{AppSettings, CompatProfile, DisplaySettings, WindowState};
 use gpui::{
     App, Application, Bounds, KeyBinding, Menu, MenuItem, WindowBounds, WindowHandle,
     WindowOptions, point, prelude::*, px, size,
@@ -45,6 +51,75 @@ fn percent_decode_str(s: &str) -> String {
     result
 }
 
+/// Menu label for a disc naming level, checked if it's the current one
+fn iso_naming_label(settings: &AppSettings, naming: IsoNaming) -> String {
+    if settings.iso_naming == naming {
+        format!("✓ {}", naming.label())
+    } else {
+        naming.label().to_string()
+    }
+}
+
+/// Switch the disc naming level (the next ISO is staged with it)
+fn set_iso_naming(naming: IsoNaming, cx: &mut App) {
+    let settings = cx.global_mut::<AppSettings>();
+    settings.iso_naming = naming;
+    log::info!("Disc file names: {}", naming.label());
+
+    let menus = build_menus(settings);
+    cx.set_menus(menus);
+
+    if let Err(e) = cx.global::<AppSettings>().save() {
+        log::error!("Failed to save settings: {}", e);
+    }
+}
+
+/// Menu label for a car stereo profile, checked if it's the current one
+fn compat_label(settings: &AppSettings, compat: CompatProfile) -> String {
+    if settings.compat_profile == compat {
+        format!("✓ {}", compat.label())
+    } else {
+        compat.label().to_string()
+    }
+}
+
+/// Switch the car stereo profile (FolderList picks it up and re-encodes)
+fn set_compat_profile(compat: CompatProfile, cx: &mut App) {
+    let settings = cx.global_mut::<AppSettings>();
+    settings.compat_profile = compat;
+    log::info!("Car stereo compatibility: {}", compat.label());
+
+    let menus = build_menus(settings);
+    cx.set_menus(menus);
+
+    if let Err(e) = cx.global::<AppSettings>().save() {
+        log::error!("Failed to save settings: {}", e);
+    }
+}
+
+/// Menu label for a playlist format, checked if it's the current one
+fn playlist_label(settings: &AppSettings, format: PlaylistFormat) -> String {
+    if settings.playlist_format == format {
+        format!("✓ {}", format.label())
+    } else {
+        format.label().to_string()
+    }
+}
+
+/// Switch the playlists written onto data discs (the next ISO is staged with them)
+fn set_playlist_format(format: PlaylistFormat, cx: &mut App) {
+    let settings = cx.global_mut::<AppSettings>();
+    settings.playlist_format = format;
+    log::info!("Disc playlists: {}", format.label());
+
+    let menus = build_menus(settings);
+    cx.set_menus(menus);
+
+    if let Err(e) = cx.global::<AppSettings>().save() {
+        log::error!("Failed to save settings: {}", e);
+    }
+}
+
 /// Build the application menus with current settings state
 fn build_menus(settings: &AppSettings) -> Vec<Menu> {
     // Use checkmark prefix when enabled
@@ -54,12 +129,24 @@ fn build_menus(settings: &AppSettings) -> Vec<Menu> {
         "Simulate Burn"
     };
 
+    let verify_burn_label = if settings.verify_burn {
+        "✓ Verify After Burn"
+    } else {
+        "Verify After Burn"
+    };
+
     let embed_album_art_label = if settings.embed_album_art {
         "✓ Embed Album Art"
     } else {
         "Embed Album Art"
     };
 
+    let gapless_audio_cd_label = if settings.gapless_audio_cd {
+        "✓ Gapless Audio CDs"
+    } else {
+        "Gapless Audio CDs"
+    };
+
     vec![
         Menu {
             name: "MP3 CD Burner".into(),
@@ -77,14 +164,90 @@ fn build_menus(settings: &AppSettings) -> Vec<Menu> {
                 MenuItem::action("Open Burn Profile...", OpenProfile),
                 MenuItem::separator(),
                 MenuItem::action("Save Burn Profile...", SaveProfile),
+                MenuItem::separator(),
+                MenuItem::action("Preview Disc Layout...", PreviewDiscLayout),
+                MenuItem::action("Burn Copies...", BurnCopies),
+                MenuItem::action("Burn Across Multiple Discs...", BurnAcrossDiscs),
+                MenuItem::action("Burn Audio CD...", BurnAudioCd),
+                MenuItem::separator(),
+                MenuItem::action("Quick Erase CD-RW", QuickEraseDisc),
+                MenuItem::action("Full Erase CD-RW", FullEraseDisc),
+                MenuItem::separator(),
+                MenuItem::action("Export to Folder or USB Drive...", ExportToFolder),
+                MenuItem::separator(),
+                MenuItem::action("Print Insert and Tracklist...", PrintInsert),
+                MenuItem::action("Disc Catalog...", OpenDiscCatalog),
             ],
         },
         Menu {
             name: "Options".into(),
             items: vec![
                 MenuItem::action(simulate_burn_label, ToggleSimulateBurn),
+                MenuItem::action(verify_burn_label, ToggleVerifyBurn),
                 // TODO: MenuItem::action("No Lossy Conversions", ToggleNoLossyConversions),
                 MenuItem::action(embed_album_art_label, ToggleEmbedAlbumArt),
+                MenuItem::action(gapless_audio_cd_label, ToggleGaplessAudioCd),
+                MenuItem::submenu(Menu {
+                    name: "Disc File Names".into(),
+                    items: vec![
+                        MenuItem::action(
+                            iso_naming_label(settings, IsoNaming::Level1),
+                            IsoNamesLevel1,
+                        ),
+                        MenuItem::action(
+                            iso_naming_label(settings, IsoNaming::Level2),
+                            IsoNamesLevel2,
+                        ),
+                        MenuItem::action(
+                            iso_naming_label(settings, IsoNaming::Joliet),
+                            IsoNamesJoliet,
+                        ),
+                        MenuItem::action(
+                            iso_naming_label(settings, IsoNaming::RockRidge),
+                            IsoNamesRockRidge,
+                        ),
+                    ],
+                }),
+                MenuItem::submenu(Menu {
+                    name: "Car Stereo Compatibility".into(),
+                    items: vec![
+                        MenuItem::action(
+                            compat_label(settings, CompatProfile::Modern),
+                            CompatModern,
+                        ),
+                        MenuItem::action(
+                            compat_label(settings, CompatProfile::GenericHeadUnit),
+                            CompatGenericHeadUnit,
+                        ),
+                        MenuItem::action(
+                            compat_label(settings, CompatProfile::Strict83),
+                            CompatStrict83,
+                        ),
+                    ],
+                }),
+                MenuItem::submenu(Menu {
+                    name: "Disc Playlists".into(),
+                    items: vec![
+                        MenuItem::action(
+                            playlist_label(settings, PlaylistFormat::None),
+                            PlaylistsNone,
+                        ),
+                        MenuItem::action(
+                            playlist_label(settings, PlaylistFormat::M3u8),
+                            PlaylistsM3u8,
+                        ),
+                        MenuItem::action(
+                            playlist_label(settings, PlaylistFormat::Pls),
+                            PlaylistsPls,
+                        ),
+                        MenuItem::action(
+                            playlist_label(settings, PlaylistFormat::Both),
+                            PlaylistsBoth,
+                        ),
+                    ],
+                }),
+                MenuItem::action("Encoding Options...", OpenEncodingOptions),
+                MenuItem::action("Burner Drive and Speed...", ChooseBurnDrive),
                 MenuItem::separator(),
                 MenuItem::action("Set CD Volume Label...", SetVolumeLabel),
                 MenuItem::action("Display Settings...", OpenDisplaySettings),
@@ -161,6 +324,43 @@ fn main() {
                 log::error!("Failed to save settings: {}", e);
             }
         });
+        cx.on_action(|_: &ToggleVerifyBurn, cx| {
+            let settings = cx.global_mut::<AppSettings>();
+            settings.verify_burn = !settings.verify_burn;
+            log::info!("Verify after burn: {}", settings.verify_burn);
+
+            let menus = build_menus(settings);
+            cx.set_menus(menus);
+
+            if let Err(e) = cx.global::<AppSettings>().save() {
+                log::error!("Failed to save settings: {}", e);
+            }
+        });
+        cx.on_action(|_: &ToggleGaplessAudioCd, cx| {
+            let settings = cx.global_mut::<AppSettings>();
+            settings.gapless_audio_cd = !settings.gapless_audio_cd;
+            log::info!("Gapless audio CDs: {}", settings.gapless_audio_cd);
+
+            let menus = build_menus(settings);
+            cx.set_menus(menus);
+
+            if let Err(e) = cx.global::<AppSettings>().save() {
+                log::error!("Failed to save settings: {}", e);
+            }
+        });
+        cx.on_action(|_: &IsoNamesLevel1, cx| set_iso_naming(IsoNaming::Level1, cx));
+        cx.on_action(|_: &IsoNamesLevel2, cx| set_iso_naming(IsoNaming::Level2, cx));
+        cx.on_action(|_: &IsoNamesJoliet, cx| set_iso_naming(IsoNaming::Joliet, cx));
+        cx.on_action(|_: &IsoNamesRockRidge, cx| set_iso_naming(IsoNaming::RockRidge, cx));
+        cx.on_action(|_: &CompatModern, cx| set_compat_profile(CompatProfile::Modern, cx));
+        cx.on_action(|_: &CompatGenericHeadUnit, cx| {
+            set_compat_profile(CompatProfile::GenericHeadUnit, cx)
+        });
+        cx.on_action(|_: &CompatStrict83, cx| set_compat_profile(CompatProfile::Strict83, cx));
+        cx.on_action(|_: &PlaylistsNone, cx| set_playlist_format(PlaylistFormat::None, cx));
+        cx.on_action(|_: &PlaylistsM3u8, cx| set_playlist_format(PlaylistFormat::M3u8, cx));
+        cx.on_action(|_: &PlaylistsPls, cx| set_playlist_format(PlaylistFormat::Pls, cx));
+        cx.on_action(|_: &PlaylistsBoth, cx| set_playlist_format(PlaylistFormat::Both, cx));
         // Note: ToggleEmbedAlbumArt handler is registered after window creation
         // so it can access the window_handle to notify the encoder.
         cx.on_action(|_: &OpenDisplaySettings, cx| {
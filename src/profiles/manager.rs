@@ -14,7 +14,7 @@ use super::types::{
 };
 use crate::burning::IsoState;
 use crate::conversion::OutputManager;
-use crate::core::{FolderConversionStatus, FolderKind, MusicFolder};
+use crate::core::{EncoderSettings, FolderConversionStatus, FolderKind, MediaProfile, MusicFolder};
 
 /// Setup info for loading a profile asynchronously
 ///
@@ -40,6 +40,10 @@ pub struct ProfileLoadSetup {
     pub bundle_path: Option<PathBuf>,
     /// Manual bitrate override if saved in profile
     pub manual_bitrate_override: Option<u32>,
+    /// Target media saved in profile
+    pub media: MediaProfile,
+    /// Bitrate mode and LAME tuning saved in profile
+    pub encoder: EncoderSettings,
 }
 
 /// Prepare to load a profile (fast, does not scan folders)
@@ -90,6 +94,8 @@ pub fn prepare_profile_load(path: &Path) -> Result<ProfileLoadSetup, String> {
         volume_label: profile.volume_label,
         bundle_path,
         manual_bitrate_override: profile.manual_bitrate_override,
+        media: profile.media,
+        encoder: profile.settings.encoder,
     })
 }
 
@@ -100,6 +106,7 @@ pub fn prepare_profile_load(path: &Path) -> Result<ProfileLoadSetup, String> {
 ///
 /// If `for_bundle` is true, uses v2.0 format with relative paths for output_dir.
 /// If `for_bundle` is false, uses v1.x format with absolute paths (legacy).
+#[allow(clippy::too_many_arguments)]
 pub fn create_profile(
     profile_name: String,
     folders: &[MusicFolder],
@@ -107,12 +114,15 @@ pub fn create_profile(
     iso_state: Option<&IsoState>,
     volume_label: Option<String>,
     manual_bitrate_override: Option<u32>,
+    media: MediaProfile,
+    encoder: EncoderSettings,
     for_bundle: bool,
 ) -> BurnProfile {
     let settings = BurnSettings {
         target_bitrate: "auto".to_string(),
         no_lossy_conversions: false,
         embed_album_art: true,
+        encoder,
     };
 
     let folder_paths: Vec<String> = folders
@@ -123,6 +133,7 @@ pub fn create_profile(
     let mut profile = BurnProfile::new(profile_name, folder_paths, settings);
     profile.volume_label = volume_label;
     profile.manual_bitrate_override = manual_bitrate_override;
+    profile.media = media;
 
     // Add conversion state if we have it
     if let Some(output_manager) = output_manager {
@@ -245,6 +256,8 @@ pub fn save_profile_to_path(
     iso_state: Option<&IsoState>,
     volume_label: Option<String>,
     manual_bitrate_override: Option<u32>,
+    media: MediaProfile,
+    encoder: EncoderSettings,
     for_bundle: bool,
 ) -> Result<(), String> {
     let profile = create_profile(
@@ -254,6 +267,8 @@ pub fn save_profile_to_path(
         iso_state,
         volume_label,
         manual_bitrate_override,
+        media,
+        encoder,
         for_bundle,
     );
     save_profile(&profile, path)?;
@@ -269,7 +284,17 @@ mod tests {
 
     #[test]
     fn test_create_profile_empty_folders() {
-        let profile = create_profile("Test".to_string(), &[], None, None, None, None, false);
+        let profile = create_profile(
+            "Test".to_string(),
+            &[],
+            None,
+            None,
+            None,
+            None,
+            MediaProfile::default(),
+            EncoderSettings::default(),
+            false,
+        );
         assert_eq!(profile.profile_name, "Test");
         assert!(profile.folders.is_empty());
     }
@@ -285,6 +310,8 @@ mod tests {
             None,
             None,
             None,
+            MediaProfile::default(),
+            EncoderSettings::default(),
             false,
         );
         assert_eq!(profile.profile_name, "My Album");
@@ -303,6 +330,8 @@ mod tests {
             None,
             Some("My CD".to_string()),
             None,
+            MediaProfile::default(),
+            EncoderSettings::default(),
             false,
         );
         assert_eq!(profile.volume_label, Some("My CD".to_string()));
@@ -319,6 +348,8 @@ mod tests {
             None,
             None,
             None,
+            MediaProfile::default(),
+            EncoderSettings::default(),
             true,
         );
         assert_eq!(profile.version, "2.0");
@@ -335,6 +366,8 @@ mod tests {
             None,
             None,
             None,
+            MediaProfile::default(),
+            EncoderSettings::default(),
             false,
         );
         assert_eq!(profile.version, "1.0");
@@ -355,7 +388,9 @@ mod tests {
             None,
             None,
             Some("Test CD".to_string()),
-            None,  // no bitrate override
+            None, // no bitrate override
+            MediaProfile::default(),
+            EncoderSettings::default(),
             false, // legacy format
         );
         assert!(result.is_ok());
@@ -378,7 +413,9 @@ mod tests {
             None,
             Some("Test CD".to_string()),
             Some(285), // with bitrate override
-            true,      // bundle format
+            MediaProfile::default(),
+            EncoderSettings::default(),
+            true, // bundle format
         );
         assert!(result.is_ok());
         // Bundle creates a directory
@@ -387,6 +424,38 @@ mod tests {
         assert!(profile_path.join("converted").is_dir());
     }
 
+    #[test]
+    fn test_encoder_settings_saved_and_restored() {
+        use crate::core::{BitrateMode, StereoMode};
+
+        let temp_dir = TempDir::new().unwrap();
+        let profile_path = temp_dir.path().join("vbr.mp3cd");
+        let encoder = EncoderSettings {
+            mode: BitrateMode::Vbr,
+            quality: Some(0),
+            lowpass_hz: Some(18_000),
+            stereo: StereoMode::Stereo,
+            sample_rate: Some(48_000),
+        };
+
+        save_profile_to_path(
+            &profile_path,
+            "VBR".to_string(),
+            &[],
+            None,
+            None,
+            None,
+            None,
+            MediaProfile::default(),
+            encoder,
+            false,
+        )
+        .unwrap();
+
+        let setup = prepare_profile_load(&profile_path).unwrap();
+        assert_eq!(setup.encoder, encoder);
+    }
+
     #[test]
     fn test_create_profile_with_bitrate_override() {
         let folders = vec![MusicFolder::new_for_test("/test/album")];
@@ -398,6 +467,8 @@ mod tests {
             None,
             None,
             Some(256), // manual override
+            MediaProfile::default(),
+            EncoderSettings::default(),
             false,
         );
         assert_eq!(profile.manual_bitrate_override, Some(256));
@@ -418,6 +489,8 @@ mod tests {
             None,
             None,
             None,
+            MediaProfile::default(),
+            EncoderSettings::default(),
             false,
         );
         assert_eq!(profile.folders.len(), 3);
@@ -443,11 +516,14 @@ mod tests {
             volume_label: Some("Test CD".to_string()),
             bundle_path: None,
             manual_bitrate_override: Some(192),
+            media: MediaProfile::Dvd5,
+            encoder: EncoderSettings::default(),
         };
 
         assert_eq!(setup.folder_paths.len(), 1);
         assert!(setup.iso_path.is_some());
         assert_eq!(setup.manual_bitrate_override, Some(192));
+        assert_eq!(setup.media, MediaProfile::Dvd5);
     }
 
     #[test]
@@ -466,6 +542,8 @@ mod tests {
             volume_label: None,
             bundle_path: Some(PathBuf::from("/bundle")),
             manual_bitrate_override: None,
+            media: MediaProfile::default(),
+            encoder: EncoderSettings::default(),
         };
 
         let cloned = setup.clone();
@@ -476,7 +554,17 @@ mod tests {
 
     #[test]
     fn test_create_profile_settings_defaults() {
-        let profile = create_profile("Test".to_string(), &[], None, None, None, None, false);
+        let profile = create_profile(
+            "Test".to_string(),
+            &[],
+            None,
+            None,
+            None,
+            None,
+            MediaProfile::default(),
+            EncoderSettings::default(),
+            false,
+        );
 
         // Check default settings
         assert_eq!(profile.settings.target_bitrate, "auto");
@@ -6,6 +6,7 @@
 #![allow(dead_code)]
 
 use super::types::{BurnProfile, ConversionStateValidation};
+use crate::core::get_app_data_dir;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::UNIX_EPOCH;
@@ -204,24 +205,23 @@ pub fn validate_conversion_state(
     validation
 }
 
-/// Get the path to the app's data directory for storing recent profiles
-fn get_app_data_dir() -> Result<PathBuf, String> {
-    let home = dirs::home_dir().ok_or_else(|| "Could not determine home directory".to_string())?;
-
-    let app_data = home.join(".mp3cd-burner");
-
-    // Create directory if it doesn't exist
-    if !app_data.exists() {
-        fs::create_dir_all(&app_data)
-            .map_err(|e| format!("Failed to create app data directory: {}", e))?;
-    }
-
-    Ok(app_data)
-}
-
 /// Get the path to the recent profiles file
+///
+/// The list used to be kept in ~/.mp3cd-burner, and is moved from there
+/// the first time it's needed.
 fn get_recent_profiles_path() -> Result<PathBuf, String> {
-    Ok(get_app_data_dir()?.join(RECENT_PROFILES_FILE))
+    let path = get_app_data_dir()?.join(RECENT_PROFILES_FILE);
+    if !path.exists()
+        && let Some(home) = dirs::home_dir()
+    {
+        let old_path = home.join(".mp3cd-burner").join(RECENT_PROFILES_FILE);
+        if old_path.exists()
+            && let Err(e) = fs::rename(&old_path, &path)
+        {
+            log::warn!("Couldn't move the recent profiles list: {}", e);
+        }
+    }
+    Ok(path)
 }
 
 /// Load the list of recent profile paths
@@ -289,6 +289,7 @@ pub fn remove_from_recent_profiles(profile_path: &str) -> Result<(), String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::EncoderSettings;
     use crate::profiles::types::BurnSettings;
     use tempfile::TempDir;
 
@@ -297,6 +298,7 @@ mod tests {
             target_bitrate: "auto".to_string(),
             no_lossy_conversions: false,
             embed_album_art: true,
+            encoder: EncoderSettings::default(),
         }
     }
 
@@ -5,6 +5,8 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::core::{EncoderSettings, MediaProfile};
+
 /// Represents a burn profile - a saved configuration for burning a CD
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BurnProfile {
@@ -35,6 +37,11 @@ pub struct BurnProfile {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub manual_bitrate_override: Option<u32>,
 
+    /// Target media the folders are sized for
+    /// Profiles saved before this setting existed load as an 80-minute CD
+    #[serde(default)]
+    pub media: MediaProfile,
+
     // === Conversion State (v1.1+) ===
     /// Session ID for the output directory
     /// Output is stored in /tmp/mp3cd_output/{session_id}/
@@ -262,6 +269,10 @@ pub struct BurnSettings {
 
     /// Whether to embed album art in output files
     pub embed_album_art: bool,
+
+    /// Bitrate mode and LAME tuning for transcodes
+    #[serde(default)]
+    pub encoder: EncoderSettings,
 }
 
 impl BurnProfile {
@@ -278,6 +289,7 @@ impl BurnProfile {
             settings,
             volume_label: None,
             manual_bitrate_override: None,
+            media: MediaProfile::default(),
             // Conversion state (v1.1+) - None for new profiles
             session_id: None,
             folder_states: None,
@@ -332,6 +344,7 @@ mod tests {
             target_bitrate: "auto".to_string(),
             no_lossy_conversions: false,
             embed_album_art: true,
+            encoder: EncoderSettings::default(),
         };
 
         let profile = BurnProfile::new(
@@ -352,6 +365,7 @@ mod tests {
             target_bitrate: "320".to_string(),
             no_lossy_conversions: true,
             embed_album_art: false,
+            encoder: EncoderSettings::default(),
         };
 
         let profile = BurnProfile::new(
@@ -374,6 +388,7 @@ mod tests {
             target_bitrate: "auto".to_string(),
             no_lossy_conversions: false,
             embed_album_art: true,
+            encoder: EncoderSettings::default(),
         };
 
         let profile = BurnProfile::new(
@@ -394,6 +409,7 @@ mod tests {
             target_bitrate: "auto".to_string(),
             no_lossy_conversions: false,
             embed_album_art: true,
+            encoder: EncoderSettings::default(),
         };
 
         let mut profile = BurnProfile::new(
@@ -435,6 +451,7 @@ mod tests {
             target_bitrate: "auto".to_string(),
             no_lossy_conversions: false,
             embed_album_art: true,
+            encoder: EncoderSettings::default(),
         };
 
         let mut profile = BurnProfile::new(
@@ -486,6 +503,7 @@ mod tests {
         // New fields should default to None
         assert!(profile.session_id.is_none());
         assert!(profile.folder_states.is_none());
+        assert_eq!(profile.media, MediaProfile::Cd80);
     }
 
     #[test]
@@ -652,6 +670,7 @@ mod tests {
             target_bitrate: "256".to_string(),
             no_lossy_conversions: true,
             embed_album_art: false,
+            encoder: EncoderSettings::default(),
         };
 
         let cloned = settings.clone();
@@ -666,6 +685,7 @@ mod tests {
             target_bitrate: "auto".to_string(),
             no_lossy_conversions: false,
             embed_album_art: true,
+            encoder: EncoderSettings::default(),
         };
 
         let mut profile = BurnProfile::new("Test".to_string(), vec![], settings);
@@ -701,6 +721,7 @@ mod tests {
             target_bitrate: "auto".to_string(),
             no_lossy_conversions: false,
             embed_album_art: true,
+            encoder: EncoderSettings::default(),
         };
 
         let mut profile = BurnProfile::new("Test".to_string(), vec![], settings);
@@ -715,6 +736,7 @@ mod tests {
             target_bitrate: "auto".to_string(),
             no_lossy_conversions: false,
             embed_album_art: true,
+            encoder: EncoderSettings::default(),
         };
 
         let mut profile = BurnProfile::new("Test".to_string(), vec![], settings);
@@ -723,6 +745,49 @@ mod tests {
         assert_eq!(profile.manual_bitrate_override, Some(192));
     }
 
+    #[test]
+    fn test_burn_profile_media_roundtrip() {
+        let settings = BurnSettings {
+            target_bitrate: "auto".to_string(),
+            no_lossy_conversions: false,
+            embed_album_art: true,
+            encoder: EncoderSettings::default(),
+        };
+
+        let mut profile = BurnProfile::new("Test".to_string(), vec![], settings);
+        profile.media = MediaProfile::custom_mb(4000);
+
+        let json = serde_json::to_string(&profile).unwrap();
+        let deserialized: BurnProfile = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.media, MediaProfile::custom_mb(4000));
+    }
+
+    #[test]
+    fn test_burn_settings_encoder_roundtrip() {
+        use crate::core::BitrateMode;
+
+        let settings = BurnSettings {
+            target_bitrate: "auto".to_string(),
+            no_lossy_conversions: false,
+            embed_album_art: true,
+            encoder: EncoderSettings {
+                mode: BitrateMode::Vbr,
+                quality: Some(2),
+                ..Default::default()
+            },
+        };
+
+        let json = serde_json::to_string(&settings).unwrap();
+        let deserialized: BurnSettings = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.encoder, settings.encoder);
+
+        // Profiles saved before encoder settings existed load with the defaults
+        let old =
+            r#"{"target_bitrate":"auto","no_lossy_conversions":false,"embed_album_art":true}"#;
+        let deserialized: BurnSettings = serde_json::from_str(old).unwrap();
+        assert_eq!(deserialized.encoder, EncoderSettings::default());
+    }
+
     #[test]
     fn test_saved_folder_kind_serialization() {
         let kind = SavedFolderKind::Album {
@@ -0,0 +1,243 @@
+//! Burn Copies Dialog
+//!
+//! Modal dialog asking how many copies of the current ISO to burn.
+
+use gpui::{
+    Bounds, Context, FocusHandle, KeyDownEvent, Render, SharedString, Window, WindowBounds,
+    WindowOptions, div, prelude::*, px, size,
+};
+
+use crate::burning::MAX_COPIES;
+use crate::ui::Theme;
+
+/// Copy count the dialog starts with
+const DEFAULT_COPIES: usize = 2;
+
+/// The Burn Copies Dialog modal
+pub struct BurnCopiesDialog {
+    /// Current input text (numeric string)
+    text: String,
+    /// Focus handle for keyboard input
+    focus_handle: FocusHandle,
+    /// Callback with the number of copies when Burn is pressed
+    on_confirm: Option<Box<dyn Fn(usize) + 'static>>,
+}
+
+impl BurnCopiesDialog {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        Self {
+            text: DEFAULT_COPIES.to_string(),
+            focus_handle: cx.focus_handle(),
+            on_confirm: None,
+        }
+    }
+
+    /// Open the Burn Copies Dialog window
+    ///
+    /// The callback is only called when Burn is pressed.
+    pub fn open<F>(cx: &mut gpui::App, on_confirm: F) -> gpui::WindowHandle<Self>
+    where
+        F: Fn(usize) + 'static,
+    {
+        let bounds = Bounds::centered(None, size(px(320.), px(200.)), cx);
+
+        cx.open_window(
+            WindowOptions {
+                window_bounds: Some(WindowBounds::Windowed(bounds)),
+                window_min_size: Some(size(px(320.), px(200.))),
+                titlebar: Some(gpui::TitlebarOptions {
+                    title: Some("Burn Copies".into()),
+                    appears_transparent: false,
+                    traffic_light_position: None,
+                }),
+                ..Default::default()
+            },
+            |_window, cx| {
+                cx.new(|cx| {
+                    let mut dialog = BurnCopiesDialog::new(cx);
+                    dialog.on_confirm = Some(Box::new(on_confirm));
+                    dialog
+                })
+            },
+        )
+        .unwrap()
+    }
+
+    /// The number of copies entered, if it's in range
+    fn copies(&self) -> Option<usize> {
+        match self.text.parse::<usize>() {
+            Ok(copies) if (1..=MAX_COPIES).contains(&copies) => Some(copies),
+            _ => None,
+        }
+    }
+
+    /// Handle a key press - returns true if the event was handled
+    fn handle_key(
+        &mut self,
+        event: &KeyDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let keystroke = &event.keystroke;
+
+        if keystroke.key == "backspace" {
+            self.text.pop();
+            cx.notify();
+            return true;
+        }
+
+        if keystroke.key == "escape" {
+            self.cancel(window, cx);
+            return true;
+        }
+
+        if keystroke.key == "enter" {
+            if self.copies().is_some() {
+                self.confirm(window, cx);
+            }
+            return true;
+        }
+
+        // Digits only, at most 2 (MAX_COPIES is 99)
+        if let Some(ref key_char) = keystroke.key_char {
+            for c in key_char.chars() {
+                if c.is_ascii_digit() && self.text.len() < 2 {
+                    self.text.push(c);
+                }
+            }
+            cx.notify();
+            return true;
+        }
+
+        false
+    }
+
+    fn confirm(&mut self, window: &mut Window, _cx: &mut Context<Self>) {
+        if let Some(copies) = self.copies()
+            && let Some(ref on_confirm) = self.on_confirm
+        {
+            on_confirm(copies);
+        }
+        window.remove_window();
+    }
+
+    fn cancel(&mut self, window: &mut Window, _cx: &mut Context<Self>) {
+        window.remove_window();
+    }
+}
+
+impl Render for BurnCopiesDialog {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = Theme::from_appearance(window.appearance());
+        let text_display = self.text.clone();
+        let is_valid = self.copies().is_some();
+
+        // Focus the dialog on render
+        if !self.focus_handle.is_focused(window) {
+            self.focus_handle.focus(window);
+        }
+
+        div()
+            .key_context("BurnCopiesDialog")
+            .track_focus(&self.focus_handle)
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                this.handle_key(event, window, cx);
+            }))
+            .size_full()
+            .flex()
+            .flex_col()
+            .bg(theme.bg)
+            .p_4()
+            .gap_3()
+            // Input row
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(div().text_sm().text_color(theme.text).child("Copies:"))
+                    .child(
+                        div()
+                            .id(SharedString::from("copies-input"))
+                            .w(px(60.))
+                            .h(px(36.))
+                            .px_3()
+                            .flex()
+                            .items_center()
+                            .bg(theme.bg_card)
+                            .border_1()
+                            .border_color(if is_valid || self.text.is_empty() {
+                                theme.accent
+                            } else {
+                                theme.danger
+                            })
+                            .rounded_md()
+                            .child(div().text_base().text_color(theme.text).child(
+                                if text_display.is_empty() {
+                                    " ".to_string()
+                                } else {
+                                    text_display
+                                },
+                            ))
+                            // Cursor
+                            .child(div().w(px(2.)).h(px(20.)).bg(theme.accent).ml_px()),
+                    ),
+            )
+            // How the run works
+            .child(div().text_xs().text_color(theme.text_muted).child(
+                "Each disc is ejected when it's done. Insert the next blank disc to continue.",
+            ))
+            // Buttons
+            .child(
+                div()
+                    .flex()
+                    .justify_end()
+                    .gap_2()
+                    .mt_2()
+                    .child(
+                        div()
+                            .id(SharedString::from("cancel-btn"))
+                            .px_4()
+                            .py_2()
+                            .bg(theme.bg_card)
+                            .text_color(theme.text)
+                            .text_sm()
+                            .rounded_md()
+                            .border_1()
+                            .border_color(theme.text_muted)
+                            .cursor_pointer()
+                            .hover(|s| s.bg(theme.bg_card_hover))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.cancel(window, cx);
+                            }))
+                            .child("Cancel"),
+                    )
+                    .child(
+                        div()
+                            .id(SharedString::from("burn-btn"))
+                            .px_4()
+                            .py_2()
+                            .bg(if is_valid {
+                                theme.accent
+                            } else {
+                                theme.bg_card
+                            })
+                            .text_color(if is_valid {
+                                gpui::white()
+                            } else {
+                                theme.text_muted
+                            })
+                            .text_sm()
+                            .rounded_md()
+                            .when(is_valid, |el| el.cursor_pointer())
+                            .when(is_valid, |el| el.hover(|s| s.bg(theme.success)))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                if this.copies().is_some() {
+                                    this.confirm(window, cx);
+                                }
+                            }))
+                            .child("Burn"),
+                    ),
+            )
+    }
+}
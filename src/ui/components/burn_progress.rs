@@ -80,10 +80,13 @@ impl BurnProgressWindow {
         match self.conversion_state.get_stage() {
             BurnStage::Converting => "Converting files...",
             BurnStage::CreatingIso => "Creating ISO...",
+            BurnStage::DecodingAudio => "Decoding audio...",
             BurnStage::WaitingForCd => "Insert blank CD...",
             BurnStage::Erasing => "Erasing disc...",
             BurnStage::Burning => "Burning CD...",
             BurnStage::Finishing => "Finishing...",
+            BurnStage::Verifying => "Verifying disc...",
+            BurnStage::Exporting => "Copying files...",
             BurnStage::ErasableDiscDetected => "Erasable disc detected...",
             BurnStage::Complete => "Complete!",
             BurnStage::Cancelled => "Cancelled",
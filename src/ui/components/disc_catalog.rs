@@ -0,0 +1,329 @@
+//! Disc Catalog Window
+//!
+//! Lists every disc burned so far and searches their tracks by title,
+//! artist or album, showing which disc holds each match. A disc's burn
+//! profile can be re-opened from here.
+
+use std::path::PathBuf;
+
+use gpui::{
+    Bounds, Context, FocusHandle, KeyDownEvent, Render, SharedString, Window, WindowBounds,
+    WindowOptions, div, prelude::*, px, size,
+};
+
+use crate::burning::{DiscCatalog, DiscRecord};
+use crate::ui::Theme;
+
+/// Most search results shown at once
+const MAX_RESULTS: usize = 200;
+
+/// The Disc Catalog window
+pub struct DiscCatalogWindow {
+    /// Every burned disc, oldest first
+    catalog: DiscCatalog,
+    /// Search text typed so far
+    query: String,
+    /// Focus handle for keyboard input
+    focus_handle: FocusHandle,
+    /// Callback when Open Profile is pressed
+    on_open_profile: Option<Box<dyn Fn(PathBuf) + 'static>>,
+}
+
+impl DiscCatalogWindow {
+    pub fn new(catalog: DiscCatalog, cx: &mut Context<Self>) -> Self {
+        Self {
+            catalog,
+            query: String::new(),
+            focus_handle: cx.focus_handle(),
+            on_open_profile: None,
+        }
+    }
+
+    /// Open the Disc Catalog window
+    ///
+    /// The callback gets the profile path when Open Profile is pressed.
+    pub fn open<F>(
+        cx: &mut gpui::App,
+        catalog: DiscCatalog,
+        on_open_profile: F,
+    ) -> gpui::WindowHandle<Self>
+    where
+        F: Fn(PathBuf) + 'static,
+    {
+        let bounds = Bounds::centered(None, size(px(640.), px(560.)), cx);
+
+        cx.open_window(
+            WindowOptions {
+                window_bounds: Some(WindowBounds::Windowed(bounds)),
+                window_min_size: Some(size(px(480.), px(360.))),
+                titlebar: Some(gpui::TitlebarOptions {
+                    title: Some("Disc Catalog".into()),
+                    appears_transparent: false,
+                    traffic_light_position: None,
+                }),
+                ..Default::default()
+            },
+            |_window, cx| {
+                cx.new(|cx| {
+                    let mut window = DiscCatalogWindow::new(catalog, cx);
+                    window.on_open_profile = Some(Box::new(on_open_profile));
+                    window
+                })
+            },
+        )
+        .unwrap()
+    }
+
+    fn handle_key(&mut self, event: &KeyDownEvent, window: &mut Window, cx: &mut Context<Self>) {
+        let keystroke = &event.keystroke;
+        match keystroke.key.as_str() {
+            "escape" => self.close(window, cx),
+            "backspace" => {
+                self.query.pop();
+                cx.notify();
+            }
+            _ => {
+                if let Some(ref key_char) = keystroke.key_char
+                    && !keystroke.modifiers.platform
+                {
+                    self.query
+                        .extend(key_char.chars().filter(|c| !c.is_control()));
+                    cx.notify();
+                }
+            }
+        }
+    }
+
+    fn open_profile(&mut self, path: PathBuf, window: &mut Window, _cx: &mut Context<Self>) {
+        if let Some(ref on_open_profile) = self.on_open_profile {
+            on_open_profile(path);
+        }
+        window.remove_window();
+    }
+
+    fn close(&mut self, window: &mut Window, _cx: &mut Context<Self>) {
+        window.remove_window();
+    }
+
+    /// Render one result row, with an Open Profile button if the disc has one
+    fn render_row(
+        &self,
+        id: SharedString,
+        title: String,
+        detail: String,
+        disc: &DiscRecord,
+        theme: &Theme,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let profile = disc.profile_path.clone();
+
+        div()
+            .flex()
+            .items_center()
+            .gap_3()
+            .py_1()
+            .border_b_1()
+            .border_color(theme.bg_card)
+            .child(
+                div()
+                    .flex_1()
+                    .flex()
+                    .flex_col()
+                    .overflow_hidden()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(theme.text)
+                            .text_ellipsis()
+                            .child(title),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(theme.text_muted)
+                            .text_ellipsis()
+                            .child(detail),
+                    ),
+            )
+            .when_some(profile, |el, path| {
+                el.child(
+                    div()
+                        .id(id)
+                        .px_2()
+                        .py_1()
+                        .bg(theme.bg_card)
+                        .text_color(theme.text)
+                        .text_xs()
+                        .rounded_md()
+                        .border_1()
+                        .border_color(theme.text_muted)
+                        .cursor_pointer()
+                        .hover(|s| s.bg(theme.bg_card_hover))
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.open_profile(path.clone(), window, cx);
+                        }))
+                        .child("Open Profile"),
+                )
+            })
+    }
+}
+
+impl Render for DiscCatalogWindow {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = Theme::from_appearance(window.appearance());
+
+        // Focus the window on render
+        if !self.focus_handle.is_focused(window) {
+            self.focus_handle.focus(window);
+        }
+
+        // With no search, list the discs themselves, newest first
+        let (rows, summary): (Vec<_>, String) = if self.query.trim().is_empty() {
+            let rows = self
+                .catalog
+                .discs
+                .iter()
+                .enumerate()
+                .rev()
+                .map(|(index, disc)| {
+                    self.render_row(
+                        SharedString::from(format!("disc-{}", index)),
+                        disc.volume_label.clone(),
+                        format!(
+                            "{} · {} track{} · {}",
+                            disc.burned_at_display(),
+                            disc.tracks.len(),
+                            if disc.tracks.len() == 1 { "" } else { "s" },
+                            disc.folders.join(", ")
+                        ),
+                        disc,
+                        &theme,
+                        cx,
+                    )
+                    .into_any_element()
+                })
+                .collect();
+            let count = self.catalog.discs.len();
+            (
+                rows,
+                format!("{} disc{} burned", count, if count == 1 { "" } else { "s" }),
+            )
+        } else {
+            let matches = self.catalog.search(&self.query);
+            let rows = matches
+                .iter()
+                .take(MAX_RESULTS)
+                .enumerate()
+                .map(|(index, found)| {
+                    let track = found.track;
+                    self.render_row(
+                        SharedString::from(format!("match-{}", index)),
+                        format!("{} — {}", track.title, track.artist),
+                        format!(
+                            "{} · disc \"{}\" ({}) · {}",
+                            track.album,
+                            found.disc.volume_label,
+                            found.disc.burned_at_display(),
+                            track.disc_path
+                        ),
+                        found.disc,
+                        &theme,
+                        cx,
+                    )
+                    .into_any_element()
+                })
+                .collect();
+            let summary = if matches.len() > MAX_RESULTS {
+                format!("Showing {} of {} tracks", MAX_RESULTS, matches.len())
+            } else {
+                format!(
+                    "{} track{} found",
+                    matches.len(),
+                    if matches.len() == 1 { "" } else { "s" }
+                )
+            };
+            (rows, summary)
+        };
+        let is_empty = rows.is_empty();
+
+        div()
+            .key_context("DiscCatalogWindow")
+            .track_focus(&self.focus_handle)
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                this.handle_key(event, window, cx);
+            }))
+            .size_full()
+            .flex()
+            .flex_col()
+            .bg(theme.bg)
+            .p_4()
+            .gap_3()
+            // Search field
+            .child(
+                div()
+                    .id(SharedString::from("catalog-search"))
+                    .w_full()
+                    .h(px(36.))
+                    .px_3()
+                    .flex()
+                    .items_center()
+                    .bg(theme.bg_card)
+                    .border_1()
+                    .border_color(theme.accent)
+                    .rounded_md()
+                    .child(
+                        div()
+                            .text_base()
+                            .when(self.query.is_empty(), |el| {
+                                el.text_color(theme.text_muted)
+                                    .italic()
+                                    .child("Search by track, artist or album")
+                            })
+                            .when(!self.query.is_empty(), |el| {
+                                el.text_color(theme.text).child(self.query.clone())
+                            }),
+                    )
+                    // Cursor
+                    .child(div().w(px(2.)).h(px(20.)).bg(theme.accent).ml_px()),
+            )
+            // Results
+            .child(
+                div()
+                    .id(SharedString::from("catalog-list"))
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .children(rows)
+                    .when(is_empty, |el| {
+                        el.child(div().text_sm().text_color(theme.text_muted).child(
+                            if self.catalog.discs.is_empty() {
+                                "Discs appear here once they've been burned."
+                            } else {
+                                "No tracks match."
+                            },
+                        ))
+                    }),
+            )
+            .child(div().text_xs().text_color(theme.text_muted).child(summary))
+            // Buttons
+            .child(
+                div().flex().justify_end().gap_2().child(
+                    div()
+                        .id(SharedString::from("close-btn"))
+                        .px_4()
+                        .py_2()
+                        .bg(theme.bg_card)
+                        .text_color(theme.text)
+                        .text_sm()
+                        .rounded_md()
+                        .border_1()
+                        .border_color(theme.text_muted)
+                        .cursor_pointer()
+                        .hover(|s| s.bg(theme.bg_card_hover))
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.close(window, cx);
+                        }))
+                        .child("Close"),
+                ),
+            )
+    }
+}
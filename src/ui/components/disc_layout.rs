@@ -0,0 +1,227 @@
+//! Disc Layout Window
+//!
+//! Shows the file tree the ISO will be built from, before anything is
+//! staged, so folder numbering, mixtape names and excluded tracks can be
+//! checked ahead of a burn.
+
+use gpui::{
+    Bounds, Context, FocusHandle, KeyDownEvent, Render, SharedString, Window, WindowBounds,
+    WindowOptions, div, prelude::*, px, size,
+};
+
+use crate::audio::EncodingStrategy;
+use crate::conversion::StagingEntry;
+use crate::core::format_size;
+use crate::ui::Theme;
+
+/// Short description of how a track gets onto the disc
+fn strategy_label(strategy: &EncodingStrategy) -> String {
+    match strategy {
+        EncodingStrategy::Copy => "Copy".to_string(),
+        EncodingStrategy::CopyWithoutArt => "Copy (art removed)".to_string(),
+        EncodingStrategy::ConvertAtSourceBitrate(kbps)
+        | EncodingStrategy::ConvertAtTargetBitrate(kbps) => format!("Encode at {} kbps", kbps),
+    }
+}
+
+/// The Disc Layout preview window
+pub struct DiscLayoutWindow {
+    /// Planned files, in disc order
+    entries: Vec<StagingEntry>,
+    /// Predicted size of the ISO image, with playlists, index and filesystem
+    iso_size: u64,
+    /// Focus handle for keyboard input
+    focus_handle: FocusHandle,
+    /// Callback when Burn is pressed
+    on_burn: Option<Box<dyn Fn() + 'static>>,
+}
+
+impl DiscLayoutWindow {
+    pub fn new(entries: Vec<StagingEntry>, iso_size: u64, cx: &mut Context<Self>) -> Self {
+        Self {
+            entries,
+            iso_size,
+            focus_handle: cx.focus_handle(),
+            on_burn: None,
+        }
+    }
+
+    /// Open the Disc Layout window
+    ///
+    /// The callback is only called when Burn is pressed.
+    pub fn open<F>(
+        cx: &mut gpui::App,
+        entries: Vec<StagingEntry>,
+        iso_size: u64,
+        on_burn: F,
+    ) -> gpui::WindowHandle<Self>
+    where
+        F: Fn() + 'static,
+    {
+        let bounds = Bounds::centered(None, size(px(640.), px(520.)), cx);
+
+        cx.open_window(
+            WindowOptions {
+                window_bounds: Some(WindowBounds::Windowed(bounds)),
+                window_min_size: Some(size(px(480.), px(320.))),
+                titlebar: Some(gpui::TitlebarOptions {
+                    title: Some("Disc Layout".into()),
+                    appears_transparent: false,
+                    traffic_light_position: None,
+                }),
+                ..Default::default()
+            },
+            |_window, cx| {
+                cx.new(|cx| {
+                    let mut layout = DiscLayoutWindow::new(entries, iso_size, cx);
+                    layout.on_burn = Some(Box::new(on_burn));
+                    layout
+                })
+            },
+        )
+        .unwrap()
+    }
+
+    fn burn(&mut self, window: &mut Window, _cx: &mut Context<Self>) {
+        if let Some(ref on_burn) = self.on_burn {
+            on_burn();
+        }
+        window.remove_window();
+    }
+
+    fn close(&mut self, window: &mut Window, _cx: &mut Context<Self>) {
+        window.remove_window();
+    }
+}
+
+impl Render for DiscLayoutWindow {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = Theme::from_appearance(window.appearance());
+        let total_size: u64 = self.entries.iter().map(|e| e.estimated_size).sum();
+
+        // Focus the window on render
+        if !self.focus_handle.is_focused(window) {
+            self.focus_handle.focus(window);
+        }
+
+        let rows = self.entries.iter().map(|entry| {
+            let source_name = entry
+                .source
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            div()
+                .flex()
+                .items_center()
+                .gap_3()
+                .py_1()
+                .border_b_1()
+                .border_color(theme.bg_card)
+                .child(
+                    div()
+                        .flex_1()
+                        .flex()
+                        .flex_col()
+                        .overflow_hidden()
+                        .child(
+                            div()
+                                .text_sm()
+                                .text_color(theme.text)
+                                .text_ellipsis()
+                                .child(entry.disc_path.display().to_string()),
+                        )
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(theme.text_muted)
+                                .text_ellipsis()
+                                .child(format!(
+                                    "{} · {}",
+                                    source_name,
+                                    strategy_label(&entry.strategy)
+                                )),
+                        ),
+                )
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(theme.text_muted)
+                        .child(format_size(entry.estimated_size)),
+                )
+        });
+
+        div()
+            .key_context("DiscLayoutWindow")
+            .track_focus(&self.focus_handle)
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                if event.keystroke.key == "escape" {
+                    this.close(window, cx);
+                }
+            }))
+            .size_full()
+            .flex()
+            .flex_col()
+            .bg(theme.bg)
+            .p_4()
+            .gap_3()
+            // File tree
+            .child(
+                div()
+                    .id(SharedString::from("layout-list"))
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .children(rows),
+            )
+            // Totals
+            .child(div().text_xs().text_color(theme.text_muted).child(format!(
+                "{} file{}, about {}, for an ISO image of about {}. \
+                 Sizes of files not encoded yet are estimates.",
+                self.entries.len(),
+                if self.entries.len() == 1 { "" } else { "s" },
+                format_size(total_size),
+                format_size(self.iso_size)
+            )))
+            // Buttons
+            .child(
+                div()
+                    .flex()
+                    .justify_end()
+                    .gap_2()
+                    .child(
+                        div()
+                            .id(SharedString::from("close-btn"))
+                            .px_4()
+                            .py_2()
+                            .bg(theme.bg_card)
+                            .text_color(theme.text)
+                            .text_sm()
+                            .rounded_md()
+                            .border_1()
+                            .border_color(theme.text_muted)
+                            .cursor_pointer()
+                            .hover(|s| s.bg(theme.bg_card_hover))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.close(window, cx);
+                            }))
+                            .child("Close"),
+                    )
+                    .child(
+                        div()
+                            .id(SharedString::from("burn-btn"))
+                            .px_4()
+                            .py_2()
+                            .bg(theme.accent)
+                            .text_color(gpui::white())
+                            .text_sm()
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|s| s.bg(theme.success))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.burn(window, cx);
+                            }))
+                            .child("Burn"),
+                    ),
+            )
+    }
+}
@@ -0,0 +1,327 @@
+//! Burner Drive Dialog
+//!
+//! Modal dialog for choosing which drive burns use and how fast they write.
+//! Slower speeds burn cheap media more reliably.
+
+use gpui::{
+    Bounds, Context, FocusHandle, KeyDownEvent, Render, SharedString, Window, WindowBounds,
+    WindowOptions, div, prelude::*, px, size,
+};
+
+use crate::burning::{DriveInfo, DriveSelection};
+use crate::ui::Theme;
+
+/// Speeds offered when the drive doesn't report its own
+const COMMON_SPEEDS: [u32; 5] = [4, 8, 16, 24, 48];
+
+/// The Burner Drive Dialog modal
+pub struct DriveDialog {
+    /// Drives found on this machine
+    drives: Vec<DriveInfo>,
+    /// Chosen drive and speed
+    selection: DriveSelection,
+    /// Focus handle for keyboard input
+    focus_handle: FocusHandle,
+    /// Callback when Apply is pressed
+    on_confirm: Option<Box<dyn Fn(DriveSelection) + 'static>>,
+}
+
+impl DriveDialog {
+    pub fn new(cx: &mut Context<Self>, drives: Vec<DriveInfo>, current: DriveSelection) -> Self {
+        Self {
+            drives,
+            selection: current,
+            focus_handle: cx.focus_handle(),
+            on_confirm: None,
+        }
+    }
+
+    /// Open the Burner Drive Dialog window
+    ///
+    /// The callback is only called when Apply is pressed.
+    pub fn open<F>(
+        cx: &mut gpui::App,
+        drives: Vec<DriveInfo>,
+        current: DriveSelection,
+        on_confirm: F,
+    ) -> gpui::WindowHandle<Self>
+    where
+        F: Fn(DriveSelection) + 'static,
+    {
+        let bounds = Bounds::centered(None, size(px(400.), px(480.)), cx);
+
+        cx.open_window(
+            WindowOptions {
+                window_bounds: Some(WindowBounds::Windowed(bounds)),
+                window_min_size: Some(size(px(360.), px(360.))),
+                titlebar: Some(gpui::TitlebarOptions {
+                    title: Some("Burner Drive".into()),
+                    appears_transparent: false,
+                    traffic_light_position: None,
+                }),
+                ..Default::default()
+            },
+            |_window, cx| {
+                cx.new(|cx| {
+                    let mut dialog = DriveDialog::new(cx, drives, current);
+                    dialog.on_confirm = Some(Box::new(on_confirm));
+                    dialog
+                })
+            },
+        )
+        .unwrap()
+    }
+
+    /// Write speeds to offer for the selected drive
+    fn speeds(&self) -> Vec<u32> {
+        let reported = self
+            .selection
+            .drive
+            .as_ref()
+            .and_then(|id| self.drives.iter().find(|d| &d.id == id))
+            .map(|d| d.write_speeds.clone())
+            .unwrap_or_default();
+        if reported.is_empty() {
+            COMMON_SPEEDS.to_vec()
+        } else {
+            reported
+        }
+    }
+
+    fn select_drive(&mut self, drive: Option<String>) {
+        self.selection.drive = drive;
+        // A speed the new drive can't do would just be rounded down by it
+        if let Some(speed) = self.selection.speed
+            && !self.speeds().contains(&speed)
+        {
+            self.selection.speed = None;
+        }
+    }
+
+    fn confirm(&mut self, window: &mut Window, _cx: &mut Context<Self>) {
+        if let Some(ref on_confirm) = self.on_confirm {
+            on_confirm(self.selection.clone());
+        }
+        window.remove_window();
+    }
+
+    fn cancel(&mut self, window: &mut Window, _cx: &mut Context<Self>) {
+        window.remove_window();
+    }
+
+    /// Render one selectable row
+    fn render_option(
+        &self,
+        id: SharedString,
+        label: String,
+        detail: Option<String>,
+        is_selected: bool,
+        theme: &Theme,
+    ) -> gpui::Stateful<gpui::Div> {
+        div()
+            .id(id)
+            .w_full()
+            .px_3()
+            .py_2()
+            .flex()
+            .items_center()
+            .gap_2()
+            .rounded_md()
+            .border_1()
+            .border_color(if is_selected {
+                theme.accent
+            } else {
+                theme.border
+            })
+            .bg(theme.bg_card)
+            .cursor_pointer()
+            .hover(|s| s.bg(theme.bg_card_hover))
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(if is_selected {
+                        theme.accent
+                    } else {
+                        theme.text_muted
+                    })
+                    .child(if is_selected { "●" } else { "○" }),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .overflow_hidden()
+                    .child(div().text_sm().text_color(theme.text).child(label))
+                    .when_some(detail, |el, detail| {
+                        el.child(
+                            div()
+                                .text_xs()
+                                .text_color(theme.text_muted)
+                                .text_ellipsis()
+                                .child(detail),
+                        )
+                    }),
+            )
+    }
+}
+
+impl Render for DriveDialog {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = Theme::from_appearance(window.appearance());
+
+        // Focus the dialog on render
+        if !self.focus_handle.is_focused(window) {
+            self.focus_handle.focus(window);
+        }
+
+        let mut drive_rows = vec![
+            self.render_option(
+                "drive-default".into(),
+                "Default Drive".to_string(),
+                None,
+                self.selection.drive.is_none(),
+                &theme,
+            )
+            .on_click(cx.listener(|this, _, _window, cx| {
+                this.select_drive(None);
+                cx.notify();
+            })),
+        ];
+        for drive in &self.drives {
+            let id = drive.id.clone();
+            let detail = if drive.media.is_empty() {
+                drive.id.clone()
+            } else {
+                format!("{} · {}", drive.id, drive.media.join(", "))
+            };
+            drive_rows.push(
+                self.render_option(
+                    SharedString::from(format!("drive-{}", drive.id)),
+                    drive.model.clone(),
+                    Some(detail),
+                    self.selection.drive.as_ref() == Some(&drive.id),
+                    &theme,
+                )
+                .on_click(cx.listener(move |this, _, _window, cx| {
+                    this.select_drive(Some(id.clone()));
+                    cx.notify();
+                })),
+            );
+        }
+
+        let mut speed_rows = vec![
+            self.render_option(
+                "speed-max".into(),
+                "Maximum".to_string(),
+                None,
+                self.selection.speed.is_none(),
+                &theme,
+            )
+            .on_click(cx.listener(|this, _, _window, cx| {
+                this.selection.speed = None;
+                cx.notify();
+            })),
+        ];
+        for speed in self.speeds() {
+            speed_rows.push(
+                self.render_option(
+                    SharedString::from(format!("speed-{}", speed)),
+                    format!("{}x", speed),
+                    None,
+                    self.selection.speed == Some(speed),
+                    &theme,
+                )
+                .on_click(cx.listener(move |this, _, _window, cx| {
+                    this.selection.speed = Some(speed);
+                    cx.notify();
+                })),
+            );
+        }
+
+        div()
+            .key_context("DriveDialog")
+            .track_focus(&self.focus_handle)
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                match event.keystroke.key.as_str() {
+                    "escape" => this.cancel(window, cx),
+                    "enter" => this.confirm(window, cx),
+                    _ => {}
+                }
+            }))
+            .size_full()
+            .flex()
+            .flex_col()
+            .bg(theme.bg)
+            .p_4()
+            .gap_2()
+            .child(
+                div()
+                    .id(SharedString::from("drive-options"))
+                    .flex_1()
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .overflow_y_scroll()
+                    .child(div().text_sm().text_color(theme.text_muted).child("Drive"))
+                    .children(drive_rows)
+                    .when(self.drives.is_empty(), |el| {
+                        el.child(
+                            div()
+                                .text_xs()
+                                .text_color(theme.text_muted)
+                                .child("No other drives were found."),
+                        )
+                    })
+                    .child(
+                        div()
+                            .mt_2()
+                            .text_sm()
+                            .text_color(theme.text_muted)
+                            .child("Write Speed"),
+                    )
+                    .children(speed_rows),
+            )
+            // Buttons
+            .child(
+                div()
+                    .flex()
+                    .justify_end()
+                    .gap_2()
+                    .mt_2()
+                    .child(
+                        div()
+                            .id(SharedString::from("cancel-btn"))
+                            .px_4()
+                            .py_2()
+                            .bg(theme.bg_card)
+                            .text_color(theme.text)
+                            .text_sm()
+                            .rounded_md()
+                            .border_1()
+                            .border_color(theme.text_muted)
+                            .cursor_pointer()
+                            .hover(|s| s.bg(theme.bg_card_hover))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.cancel(window, cx);
+                            }))
+                            .child("Cancel"),
+                    )
+                    .child(
+                        div()
+                            .id(SharedString::from("apply-btn"))
+                            .px_4()
+                            .py_2()
+                            .bg(theme.accent)
+                            .text_color(gpui::white())
+                            .text_sm()
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|s| s.bg(theme.success))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.confirm(window, cx);
+                            }))
+                            .child("Apply"),
+                    ),
+            )
+    }
+}
@@ -0,0 +1,356 @@
+//! Encoding Options Dialog
+//!
+//! Modal dialog for the bitrate mode (ABR, CBR or a VBR preset) and LAME's
+//! tuning options. The bitrate itself is still planned to fill the disc.
+
+use gpui::{
+    Bounds, Context, FocusHandle, KeyDownEvent, Render, SharedString, Window, WindowBounds,
+    WindowOptions, div, prelude::*, px, size,
+};
+
+use crate::core::{BitrateMode, EncoderSettings, RESAMPLE_RATES, StereoMode};
+use crate::ui::Theme;
+
+/// Algorithm qualities offered, with what they trade
+const QUALITIES: [(u8, &str); 4] = [
+    (0, "Best, slowest"),
+    (2, "High"),
+    (5, "Standard"),
+    (7, "Fast"),
+];
+
+/// Lowpass cutoffs offered (Hz)
+const LOWPASSES: [u32; 4] = [16_000, 18_000, 19_000, 20_000];
+
+/// The Encoding Options Dialog modal
+pub struct EncodingOptionsDialog {
+    /// Settings being edited
+    settings: EncoderSettings,
+    /// Focus handle for keyboard input
+    focus_handle: FocusHandle,
+    /// Callback when Apply is pressed
+    on_confirm: Option<Box<dyn Fn(EncoderSettings) + 'static>>,
+}
+
+impl EncodingOptionsDialog {
+    pub fn new(cx: &mut Context<Self>, current: EncoderSettings) -> Self {
+        Self {
+            settings: current,
+            focus_handle: cx.focus_handle(),
+            on_confirm: None,
+        }
+    }
+
+    /// Open the Encoding Options Dialog window
+    ///
+    /// The callback is only called when Apply is pressed.
+    pub fn open<F>(
+        cx: &mut gpui::App,
+        current: EncoderSettings,
+        on_confirm: F,
+    ) -> gpui::WindowHandle<Self>
+    where
+        F: Fn(EncoderSettings) + 'static,
+    {
+        let bounds = Bounds::centered(None, size(px(400.), px(560.)), cx);
+
+        cx.open_window(
+            WindowOptions {
+                window_bounds: Some(WindowBounds::Windowed(bounds)),
+                window_min_size: Some(size(px(360.), px(400.))),
+                titlebar: Some(gpui::TitlebarOptions {
+                    title: Some("Encoding Options".into()),
+                    appears_transparent: false,
+                    traffic_light_position: None,
+                }),
+                ..Default::default()
+            },
+            |_window, cx| {
+                cx.new(|cx| {
+                    let mut dialog = EncodingOptionsDialog::new(cx, current);
+                    dialog.on_confirm = Some(Box::new(on_confirm));
+                    dialog
+                })
+            },
+        )
+        .unwrap()
+    }
+
+    fn confirm(&mut self, window: &mut Window, _cx: &mut Context<Self>) {
+        if let Some(ref on_confirm) = self.on_confirm {
+            on_confirm(self.settings);
+        }
+        window.remove_window();
+    }
+
+    fn cancel(&mut self, window: &mut Window, _cx: &mut Context<Self>) {
+        window.remove_window();
+    }
+
+    /// Render one selectable row, applying `select` to the settings when clicked
+    fn render_option(
+        id: String,
+        label: String,
+        detail: Option<&'static str>,
+        is_selected: bool,
+        theme: &Theme,
+        select: impl Fn(&mut EncoderSettings) + 'static,
+        cx: &mut Context<Self>,
+    ) -> gpui::Stateful<gpui::Div> {
+        div()
+            .id(SharedString::from(id))
+            .w_full()
+            .px_3()
+            .py_2()
+            .flex()
+            .items_center()
+            .gap_2()
+            .rounded_md()
+            .border_1()
+            .border_color(if is_selected {
+                theme.accent
+            } else {
+                theme.border
+            })
+            .bg(theme.bg_card)
+            .cursor_pointer()
+            .hover(|s| s.bg(theme.bg_card_hover))
+            .on_click(cx.listener(move |this, _, _window, cx| {
+                select(&mut this.settings);
+                cx.notify();
+            }))
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(if is_selected {
+                        theme.accent
+                    } else {
+                        theme.text_muted
+                    })
+                    .child(if is_selected { "●" } else { "○" }),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .overflow_hidden()
+                    .child(div().text_sm().text_color(theme.text).child(label))
+                    .when_some(detail, |el, detail| {
+                        el.child(
+                            div()
+                                .text_xs()
+                                .text_color(theme.text_muted)
+                                .text_ellipsis()
+                                .child(detail),
+                        )
+                    }),
+            )
+    }
+
+    /// Render a section heading
+    fn render_heading(title: &'static str, theme: &Theme) -> gpui::Div {
+        div()
+            .mt_2()
+            .text_sm()
+            .text_color(theme.text_muted)
+            .child(title)
+    }
+}
+
+impl Render for EncodingOptionsDialog {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = Theme::from_appearance(window.appearance());
+        let settings = self.settings;
+
+        // Focus the dialog on render
+        if !self.focus_handle.is_focused(window) {
+            self.focus_handle.focus(window);
+        }
+
+        let mode_rows: Vec<_> = BitrateMode::ALL
+            .into_iter()
+            .map(|mode| {
+                let detail = match mode {
+                    BitrateMode::Abr => "Fills the disc most closely",
+                    BitrateMode::Cbr => "For players that can't seek in VBR files",
+                    BitrateMode::Vbr => "The best preset (V0-V9) that fits the disc",
+                };
+                Self::render_option(
+                    format!("mode-{:?}", mode),
+                    mode.label().to_string(),
+                    Some(detail),
+                    settings.mode == mode,
+                    &theme,
+                    move |s| s.mode = mode,
+                    cx,
+                )
+            })
+            .collect();
+
+        let mut quality_rows = vec![Self::render_option(
+            "quality-default".to_string(),
+            "LAME Default".to_string(),
+            None,
+            settings.quality.is_none(),
+            &theme,
+            |s| s.quality = None,
+            cx,
+        )];
+        for (quality, detail) in QUALITIES {
+            quality_rows.push(Self::render_option(
+                format!("quality-{}", quality),
+                format!("-q {}", quality),
+                Some(detail),
+                settings.quality == Some(quality),
+                &theme,
+                move |s| s.quality = Some(quality),
+                cx,
+            ));
+        }
+
+        let mut lowpass_rows = vec![Self::render_option(
+            "lowpass-default".to_string(),
+            "Chosen by LAME".to_string(),
+            Some("Depends on the bitrate"),
+            settings.lowpass_hz.is_none(),
+            &theme,
+            |s| s.lowpass_hz = None,
+            cx,
+        )];
+        for hz in LOWPASSES {
+            lowpass_rows.push(Self::render_option(
+                format!("lowpass-{}", hz),
+                format!("{} kHz", hz / 1000),
+                None,
+                settings.lowpass_hz == Some(hz),
+                &theme,
+                move |s| s.lowpass_hz = Some(hz),
+                cx,
+            ));
+        }
+
+        let stereo_rows: Vec<_> = [StereoMode::Joint, StereoMode::Stereo]
+            .into_iter()
+            .map(|stereo| {
+                let detail = match stereo {
+                    StereoMode::Joint => "Saves bits where the channels are alike",
+                    StereoMode::Stereo => "Left and right always coded separately",
+                };
+                Self::render_option(
+                    format!("stereo-{:?}", stereo),
+                    stereo.label().to_string(),
+                    Some(detail),
+                    settings.stereo == stereo,
+                    &theme,
+                    move |s| s.stereo = stereo,
+                    cx,
+                )
+            })
+            .collect();
+
+        let mut rate_rows = vec![Self::render_option(
+            "rate-source".to_string(),
+            "Same as Source".to_string(),
+            None,
+            settings.sample_rate().is_none(),
+            &theme,
+            |s| s.sample_rate = None,
+            cx,
+        )];
+        for rate in RESAMPLE_RATES {
+            rate_rows.push(Self::render_option(
+                format!("rate-{}", rate),
+                format!("{} kHz", rate as f64 / 1000.0),
+                None,
+                settings.sample_rate() == Some(rate),
+                &theme,
+                move |s| s.sample_rate = Some(rate),
+                cx,
+            ));
+        }
+
+        div()
+            .key_context("EncodingOptionsDialog")
+            .track_focus(&self.focus_handle)
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                match event.keystroke.key.as_str() {
+                    "escape" => this.cancel(window, cx),
+                    "enter" => this.confirm(window, cx),
+                    _ => {}
+                }
+            }))
+            .size_full()
+            .flex()
+            .flex_col()
+            .bg(theme.bg)
+            .p_4()
+            .gap_2()
+            .child(
+                div()
+                    .id(SharedString::from("encoding-options"))
+                    .flex_1()
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .overflow_y_scroll()
+                    .child(Self::render_heading("Bitrate Mode", &theme))
+                    .children(mode_rows)
+                    .child(Self::render_heading("Quality", &theme))
+                    .children(quality_rows)
+                    .child(Self::render_heading("Lowpass Filter", &theme))
+                    .children(lowpass_rows)
+                    .child(Self::render_heading("Stereo", &theme))
+                    .children(stereo_rows)
+                    .child(Self::render_heading("Sample Rate", &theme))
+                    .children(rate_rows),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(theme.text_muted)
+                    .child("Changing these re-encodes every folder."),
+            )
+            // Buttons
+            .child(
+                div()
+                    .flex()
+                    .justify_end()
+                    .gap_2()
+                    .mt_2()
+                    .child(
+                        div()
+                            .id(SharedString::from("cancel-btn"))
+                            .px_4()
+                            .py_2()
+                            .bg(theme.bg_card)
+                            .text_color(theme.text)
+                            .text_sm()
+                            .rounded_md()
+                            .border_1()
+                            .border_color(theme.text_muted)
+                            .cursor_pointer()
+                            .hover(|s| s.bg(theme.bg_card_hover))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.cancel(window, cx);
+                            }))
+                            .child("Cancel"),
+                    )
+                    .child(
+                        div()
+                            .id(SharedString::from("apply-btn"))
+                            .px_4()
+                            .py_2()
+                            .bg(theme.accent)
+                            .text_color(gpui::white())
+                            .text_sm()
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|s| s.bg(theme.success))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.confirm(window, cx);
+                            }))
+                            .child("Apply"),
+                    ),
+            )
+    }
+}
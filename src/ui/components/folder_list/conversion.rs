@@ -2,17 +2,31 @@
 //!
 //! Handles bitrate calculation, burn workflows, and progress polling.
 
+use std::sync::atomic::Ordering;
 use std::time::Duration;
 
-use gpui::{AnyWindowHandle, AsyncApp, Context, PromptLevel, Timer, WeakEntity, Window};
-
-use crate::burning::IsoState;
-use crate::conversion::{MultipassEstimate, calculate_multipass_bitrate};
-use crate::core::{AppSettings, BurnStage, ConversionState};
-use crate::ui::components::BitrateOverrideDialog;
+use gpui::{
+    AnyWindowHandle, AsyncApp, Context, PathPromptOptions, PromptLevel, Timer, WeakEntity, Window,
+};
+
+use crate::burning::burner::burner_for;
+use crate::burning::{
+    AudioCdOptions, BurnConfig, DEFAULT_MIN_BITRATE, DiscRecord, DriveEvent, DriveMonitor,
+    DriveSelection, EraseMode, IsoState, check_audio_cd_fits, copies_summary, disc_overhead,
+    plan_discs, predict_image_size,
+};
+use crate::conversion::{MultipassEstimate, calculate_multipass_bitrate, fit_bitrate};
+use crate::core::{AppSettings, BurnStage, ConversionState, EncoderSettings, MediaProfile};
+use crate::ui::components::{
+    BitrateOverrideDialog, BurnCopiesDialog, DiscLayoutWindow, DriveDialog, EncodingOptionsDialog,
+    MediaProfileDialog,
+};
 
 use super::{FolderList, PendingBurnAction};
 
+/// How often the drive monitor checks the burn drive
+const DRIVE_MONITOR_INTERVAL: Duration = Duration::from_secs(2);
+
 impl FolderList {
     /// Check and execute any pending burn action
     ///
@@ -53,6 +67,21 @@ impl FolderList {
                 log::debug!("Triggering convert & burn after volume label dialog");
                 self.run_conversion(window, cx);
             }
+            PendingBurnAction::SpanDiscs => {
+                self.pending_burn_action = None;
+                log::debug!("Triggering multi-disc burn after volume label dialog");
+                self.run_spanned_burn(window, cx);
+            }
+            PendingBurnAction::BurnCopies(copies) => {
+                self.pending_burn_action = None;
+                log::debug!("Triggering burn of {} copies", copies);
+                self.burn_iso_copies(copies, window, cx);
+            }
+            PendingBurnAction::Export => {
+                self.pending_burn_action = None;
+                log::debug!("Triggering export after folder picker");
+                self.run_export(window, cx);
+            }
         }
         true
     }
@@ -60,7 +89,7 @@ impl FolderList {
     /// Calculate the optimal bitrate to fit on a 700MB CD
     ///
     /// Uses multi-pass-aware calculation:
-    /// - MP3s are copied (exact size)
+    /// - MP3s are copied (estimated size)
     /// - Lossy files transcoded at source bitrate
     /// - Lossless files get remaining space
     ///
@@ -81,8 +110,18 @@ impl FolderList {
             return None;
         }
 
-        // Use multi-pass-aware calculation
-        let mut estimate = calculate_multipass_bitrate(&all_files);
+        // Use multi-pass-aware calculation, leaving room for the playlists,
+        // index files and filesystem
+        let capacity = self
+            .media_profile
+            .capacity_bytes()
+            .saturating_sub(disc_overhead(
+                &self.folders,
+                self.compat_profile,
+                self.playlist_format,
+            ));
+        let mut estimate =
+            calculate_multipass_bitrate(&all_files, capacity, self.encoder_settings.mode);
 
         // If we have an optimized bitrate from pass 2 (stored in last_calculated_bitrate),
         // use that instead of the preliminary estimate. This happens after the phase
@@ -118,7 +157,15 @@ impl FolderList {
             return 320;
         }
 
-        calculate_multipass_bitrate(&all_files).target_bitrate
+        let capacity = self
+            .media_profile
+            .capacity_bytes()
+            .saturating_sub(disc_overhead(
+                &self.folders,
+                self.compat_profile,
+                self.playlist_format,
+            ));
+        calculate_multipass_bitrate(&all_files, capacity, self.encoder_settings.mode).target_bitrate
     }
 
     /// Get the target bitrate for encoding
@@ -185,6 +232,8 @@ impl FolderList {
                 // Determine the target bitrate
                 let new_bitrate = match bitrate_option {
                     Some(br) => {
+                        // Round down to a bitrate the mode can hit, as the encoder would
+                        let br = fit_bitrate(self.encoder_settings.mode, br);
                         log::debug!("Manual bitrate override: {} kbps", br);
                         self.manual_bitrate_override = Some(br);
                         br
@@ -201,37 +250,328 @@ impl FolderList {
                 // Mark as having unsaved changes when bitrate is modified
                 self.has_unsaved_changes = true;
 
-                // Set flag to prevent ISO generation until recalculation completes
-                self.bitrate_recalc_pending = true;
+                self.reencode_at_bitrate(new_bitrate);
 
-                // Trigger re-encoding at new bitrate
-                // This handles all folders in the encoder's completed map (including bundle folders)
-                if let Some(ref encoder) = self.simple_encoder {
-                    encoder.recalculate_bitrate(new_bitrate);
-                }
+                return true;
+            }
+        false
+    }
 
-                // Reset lossless folder statuses immediately to prevent ISO race condition
-                // (The BitrateRecalculated event will also do this, but it comes later)
-                for folder in &mut self.folders {
-                    if let crate::core::FolderConversionStatus::Converted {
-                        lossless_bitrate: Some(br),
-                        ..
-                    } = folder.conversion_status
-                        && br != new_bitrate {
-                            folder.conversion_status =
-                                crate::core::FolderConversionStatus::NotConverted;
-                        }
+    /// Send a new lossless bitrate to the encoder and invalidate stale output
+    fn reencode_at_bitrate(&mut self, new_bitrate: u32) {
+        // Set flag to prevent ISO generation until recalculation completes
+        self.bitrate_recalc_pending = true;
+
+        // Trigger re-encoding at new bitrate
+        // This handles all folders in the encoder's completed map (including bundle folders)
+        if let Some(ref encoder) = self.simple_encoder {
+            encoder.recalculate_bitrate(new_bitrate);
+        }
+
+        // Reset lossless folder statuses immediately to prevent ISO race condition
+        // (The BitrateRecalculated event will also do this, but it comes later)
+        for folder in &mut self.folders {
+            if let crate::core::FolderConversionStatus::Converted {
+                lossless_bitrate: Some(br),
+                ..
+            } = folder.conversion_status
+                && br != new_bitrate
+            {
+                folder.conversion_status = crate::core::FolderConversionStatus::NotConverted;
+            }
+        }
+
+        // Invalidate ISO state - output files are being regenerated
+        self.iso_state = None;
+        self.iso_generation_attempted = false;
+    }
+
+    /// Show the target media dialog
+    pub fn show_media_profile_dialog(&mut self, cx: &mut Context<Self>) {
+        if self.conversion_state.is_converting() {
+            return;
+        }
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.pending_media_rx = Some(rx);
+
+        MediaProfileDialog::open(cx, self.media_profile, move |media| {
+            let _ = tx.send(media);
+        });
+    }
+
+    /// Poll for target media dialog result and blank disc detection
+    ///
+    /// Returns true if the target media changed or detection finished.
+    pub(super) fn poll_media_profile(&mut self, cx: &mut Context<Self>) -> bool {
+        if let Some(ref rx) = self.pending_media_rx
+            && let Ok(media_option) = rx.try_recv()
+        {
+            self.pending_media_rx = None;
+            match media_option {
+                Some(media) => self.set_media_profile(media),
+                None => self.start_media_detection(cx.global::<AppSettings>().burn_drive.clone()),
+            }
+            return true;
+        }
+
+        if let Some(ref rx) = self.pending_media_detect_rx
+            && let Ok(result) = rx.try_recv()
+        {
+            self.pending_media_detect_rx = None;
+            match result {
+                Ok(Some(capacity)) => {
+                    let media = MediaProfile::from_capacity(capacity);
+                    log::info!("Detected {} byte blank disc: {}", capacity, media.name());
+                    self.set_media_profile(media);
                 }
+                Ok(None) => {
+                    self.pending_error_message = Some((
+                        "No Blank Disc".to_string(),
+                        "Insert a blank disc to detect its capacity.".to_string(),
+                    ));
+                }
+                Err(e) => {
+                    self.pending_error_message = Some(("Couldn't Detect Disc".to_string(), e));
+                }
+            }
+            return true;
+        }
+        false
+    }
 
-                // Invalidate ISO state - output files are being regenerated
-                self.iso_state = None;
-                self.iso_generation_attempted = false;
+    /// Read the blank disc's capacity in the chosen drive on a background thread
+    fn start_media_detection(&mut self, drive: DriveSelection) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.pending_media_detect_rx = Some(rx);
+        std::thread::spawn(move || {
+            let _ = tx.send(crate::burning::cd::detect_media_capacity(&drive));
+        });
+    }
 
-                return true;
+    /// Look for drives on a background thread, then show the Burner Drive dialog
+    pub(super) fn show_drive_dialog(&mut self) {
+        if self.pending_drives_rx.is_some() {
+            return;
+        }
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.pending_drives_rx = Some(rx);
+        std::thread::spawn(move || {
+            let _ = tx.send(crate::burning::cd::list_drives());
+        });
+    }
+
+    /// Poll for the drive list and the Burner Drive dialog result
+    ///
+    /// The chosen drive and speed are saved in the app settings, so every
+    /// later burn uses them. Returns true if either arrived.
+    pub(super) fn poll_drive_selection(&mut self, cx: &mut Context<Self>) -> bool {
+        if let Some(ref rx) = self.pending_drives_rx
+            && let Ok(result) = rx.try_recv()
+        {
+            self.pending_drives_rx = None;
+            let drives = result.unwrap_or_else(|e| {
+                log::warn!("Couldn't list drives: {}", e);
+                Vec::new()
+            });
+            let current = cx.global::<AppSettings>().burn_drive.clone();
+
+            let (tx, rx) = std::sync::mpsc::channel();
+            self.pending_drive_rx = Some(rx);
+            DriveDialog::open(cx, drives, current, move |selection| {
+                let _ = tx.send(selection);
+            });
+            return true;
+        }
+
+        if let Some(ref rx) = self.pending_drive_rx
+            && let Ok(selection) = rx.try_recv()
+        {
+            self.pending_drive_rx = None;
+            log::info!("Burning with {}", selection.describe());
+            let settings = cx.global_mut::<AppSettings>();
+            settings.burn_drive = selection;
+            if let Err(e) = settings.save() {
+                log::error!("Failed to save settings: {}", e);
             }
+            return true;
+        }
+        false
+    }
+
+    /// Keep the drive monitor watching the chosen drive and pick up its events
+    ///
+    /// Returns true if what's in the drive changed.
+    pub(super) fn poll_drive_monitor(&mut self, cx: &mut Context<Self>) -> bool {
+        let selection = &cx.global::<AppSettings>().burn_drive;
+        let mut changed = false;
+        if self.drive_monitor.is_none() || self.monitored_drive.as_ref() != Some(selection) {
+            log::debug!("Watching {}", selection.describe());
+            // Paused while converting or burning, when the drive is busy
+            self.drive_monitor = Some(DriveMonitor::start(
+                burner_for(selection),
+                DRIVE_MONITOR_INTERVAL,
+                self.conversion_state.is_converting.clone(),
+            ));
+            self.monitored_drive = Some(selection.clone());
+            changed = self.disc_info.take().is_some();
+        }
+
+        let Some(monitor) = &self.drive_monitor else {
+            return changed;
+        };
+        while let Some(event) = monitor.try_recv() {
+            match event {
+                DriveEvent::DiscInserted(disc) | DriveEvent::MediaChanged(disc) => {
+                    log::debug!("Drive: {}", disc.describe());
+                    self.disc_info = Some(disc);
+                }
+                DriveEvent::DiscEjected => {
+                    log::debug!("Drive: no disc");
+                    self.disc_info = None;
+                }
+                DriveEvent::Error(e) => {
+                    log::debug!("Couldn't check the drive: {}", e);
+                    self.disc_info = None;
+                }
+            }
+            changed = true;
+        }
+        changed
+    }
+
+    /// Switch the target media and re-size the encode for it
+    fn set_media_profile(&mut self, media: MediaProfile) {
+        if media == self.media_profile {
+            return;
+        }
+        log::debug!("Target media: {}", media.name());
+        self.media_profile = media;
+
+        // Mark as having unsaved changes - the media is saved with the profile
+        self.has_unsaved_changes = true;
+
+        if let Some(ref encoder) = self.simple_encoder {
+            encoder.set_media(media);
+        }
+
+        // Clear cached bitrate and let the debounced recalculation pick the
+        // bitrate for the new capacity
+        self.last_calculated_bitrate = None;
+        self.last_folder_change = Some(std::time::Instant::now());
+    }
+
+    /// Show the encoding options dialog
+    pub fn show_encoding_options_dialog(&mut self, cx: &mut Context<Self>) {
+        if self.conversion_state.is_converting() {
+            return;
+        }
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.pending_encoder_settings_rx = Some(rx);
+
+        EncodingOptionsDialog::open(cx, self.encoder_settings, move |settings| {
+            let _ = tx.send(settings);
+        });
+    }
+
+    /// Poll for encoding options dialog result
+    ///
+    /// Returns true if new settings were received.
+    pub(super) fn poll_encoder_settings(&mut self) -> bool {
+        if let Some(ref rx) = self.pending_encoder_settings_rx
+            && let Ok(settings) = rx.try_recv()
+        {
+            self.pending_encoder_settings_rx = None;
+            self.set_encoder_settings(settings);
+            return true;
+        }
         false
     }
 
+    /// Switch the bitrate mode and LAME tuning, re-encoding with them
+    fn set_encoder_settings(&mut self, settings: EncoderSettings) {
+        if settings == self.encoder_settings {
+            return;
+        }
+        log::info!("Encoder settings: {}", settings.summary());
+        self.encoder_settings = settings;
+
+        // Mark as having unsaved changes - the settings are saved with the profile
+        self.has_unsaved_changes = true;
+
+        // A manual bitrate is rounded to the new mode (the encoder does the same)
+        self.manual_bitrate_override = self
+            .manual_bitrate_override
+            .map(|br| fit_bitrate(settings.mode, br));
+
+        // Finished files were made with the old settings, so the encoder
+        // starts over
+        if let Some(ref encoder) = self.simple_encoder {
+            encoder.set_encoder_settings(settings);
+        }
+        for folder in self.folders.iter_mut().filter(|f| f.source_available) {
+            folder.conversion_status = crate::core::FolderConversionStatus::NotConverted;
+        }
+        self.iso_state = None;
+        self.iso_generation_attempted = false;
+
+        // Clear cached bitrate and let the debounced recalculation plan it for the new mode
+        self.last_calculated_bitrate = None;
+        self.last_folder_change = Some(std::time::Instant::now());
+    }
+
+    /// Pick up a car stereo profile change and warn when the folders break it
+    ///
+    /// The profile is an app setting changed from the Options menu, so it's
+    /// compared against the last one seen on every poll. Runs as folders are
+    /// added, before their encoding gets far.
+    ///
+    /// Returns true if the profile changed or a warning was queued.
+    pub(super) fn poll_compat_profile(&mut self, cx: &mut Context<Self>) -> bool {
+        let compat = cx.global::<AppSettings>().compat_profile;
+        let mut changed = false;
+
+        if compat != self.compat_profile && !self.conversion_state.is_converting() {
+            log::debug!("Compatibility profile: {}", compat.label());
+            self.compat_profile = compat;
+
+            // Finished files carry the old profile's tags and cover art, so
+            // the encoder starts over
+            if let Some(ref encoder) = self.simple_encoder {
+                encoder.set_compat(compat);
+            }
+            for folder in self.folders.iter_mut().filter(|f| f.source_available) {
+                folder.conversion_status = crate::core::FolderConversionStatus::NotConverted;
+            }
+            self.iso_state = None;
+            self.iso_generation_attempted = false;
+            changed = true;
+        }
+
+        let problems = self.compat_profile.check_folders(&self.folders);
+        if problems != self.compat_problems {
+            if !problems.is_empty() {
+                let mut message = format!(
+                    "These folders don't suit a {} player:",
+                    self.compat_profile.label()
+                );
+                for problem in &problems {
+                    message.push_str(&format!("\n• {}", problem));
+                }
+                if self.compat_profile.check_layout(&self.folders).is_err() {
+                    message.push_str(
+                        "\n\nRemove some folders or tracks, or use Burn Across Multiple Discs.",
+                    );
+                }
+                self.pending_info_message = Some(("Car Stereo Compatibility".to_string(), message));
+            }
+            self.compat_problems = problems;
+            changed = true;
+        }
+        changed
+    }
+
     /// Check if debounce period has passed and trigger bitrate recalculation
     ///
     /// This is called from the encoder polling loop. When folder list changes:
@@ -329,6 +669,12 @@ impl FolderList {
             return;
         }
 
+        if let Err(e) = self.compat_profile.check_layout(&self.folders) {
+            self.pending_error_message = Some(("Can't Burn This Disc".to_string(), e));
+            cx.notify();
+            return;
+        }
+
         // Check if background encoder is available
         let encoder_handle = match &self.simple_encoder {
             Some(handle) => handle.clone(),
@@ -365,9 +711,10 @@ impl FolderList {
         }
 
         let state = self.conversion_state.clone();
-        let simulate_burn = cx.global::<AppSettings>().simulate_burn;
+        let burn_config = burn_config(cx.global::<AppSettings>());
         let folders: Vec<_> = self.folders.to_vec();
         let volume_label = self.volume_label.clone();
+        let record = self.disc_record(self.calculated_bitrate());
 
         // Spawn background thread to execute the full burn workflow
         std::thread::spawn(move || {
@@ -376,8 +723,9 @@ impl FolderList {
                 encoder_handle,
                 output_manager,
                 folders,
-                simulate_burn,
+                burn_config,
                 volume_label,
+                record,
             );
         });
 
@@ -389,6 +737,243 @@ impl FolderList {
         cx.notify();
     }
 
+    /// Check the folder list can be split across discs, then ask for the volume label
+    ///
+    /// Called from File > Burn Across Multiple Discs. The burn itself starts in
+    /// [`Self::run_spanned_burn`] once the volume label dialog closes.
+    pub(super) fn start_spanned_burn(&mut self, cx: &mut Context<Self>) {
+        if self.conversion_state.is_converting() || self.folders.is_empty() {
+            return;
+        }
+
+        match plan_discs(
+            &self.folders,
+            self.spanning_min_bitrate(),
+            self.media_profile,
+            self.compat_profile,
+            cx.global::<AppSettings>().playlist_format,
+            self.encoder_settings.mode,
+        ) {
+            Ok(_) => {
+                self.show_volume_label_dialog(Some(PendingBurnAction::SpanDiscs), cx);
+            }
+            Err(e) => {
+                self.pending_error_message = Some(("Can't Split Across Discs".to_string(), e));
+                cx.notify();
+            }
+        }
+    }
+
+    /// Minimum lossless bitrate for a multi-disc burn
+    ///
+    /// A manual bitrate override doubles as the minimum; otherwise
+    /// [`DEFAULT_MIN_BITRATE`] is used.
+    fn spanning_min_bitrate(&self) -> u32 {
+        self.manual_bitrate_override.unwrap_or(DEFAULT_MIN_BITRATE)
+    }
+
+    /// Run a multi-disc burn - splits the folders, re-encodes, then burns each disc
+    ///
+    /// Every disc is encoded at the same lossless bitrate: the lowest of the
+    /// per-disc estimates, so the fullest disc still fits.
+    pub(super) fn run_spanned_burn(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.conversion_state.is_converting() {
+            log::debug!("Already in progress");
+            return;
+        }
+
+        let discs = match plan_discs(
+            &self.folders,
+            self.spanning_min_bitrate(),
+            self.media_profile,
+            self.compat_profile,
+            cx.global::<AppSettings>().playlist_format,
+            self.encoder_settings.mode,
+        ) {
+            Ok(discs) => discs,
+            Err(e) => {
+                self.pending_error_message = Some(("Can't Split Across Discs".to_string(), e));
+                cx.notify();
+                return;
+            }
+        };
+
+        // Nothing to split - this is just a normal burn
+        if discs.len() <= 1 {
+            self.run_conversion(window, cx);
+            return;
+        }
+
+        let encoder_handle = match &self.simple_encoder {
+            Some(handle) => handle.clone(),
+            None => {
+                log::error!("Background encoder not available - cannot burn");
+                return;
+            }
+        };
+        let output_manager = match &self.output_manager {
+            Some(om) => om.clone(),
+            None => {
+                log::error!("No output manager available");
+                return;
+            }
+        };
+
+        let bitrate = discs
+            .iter()
+            .filter(|d| d.estimate.lossless_count > 0)
+            .map(|d| d.estimate.target_bitrate)
+            .min()
+            .unwrap_or_else(|| self.calculated_bitrate());
+        log::debug!(
+            "Starting multi-disc burn: {} discs at {} kbps",
+            discs.len(),
+            bitrate
+        );
+
+        // Keep the debounced recalculation from undoing the spanning bitrate
+        self.last_calculated_bitrate = Some(bitrate);
+        if encoder_handle.get_state().lossless_bitrate.load(Ordering::SeqCst) != bitrate {
+            self.reencode_at_bitrate(bitrate);
+        }
+
+        self.conversion_state.reset(self.folders.len());
+        self.conversion_state.set_disc(1, discs.len());
+        self.conversion_state.set_stage(BurnStage::Converting);
+
+        let state = self.conversion_state.clone();
+        let burn_config = burn_config(cx.global::<AppSettings>());
+        let discs: Vec<_> = discs.into_iter().map(|d| d.folders).collect();
+        let volume_label = self.volume_label.clone();
+        let record = self.disc_record(bitrate);
+
+        std::thread::spawn(move || {
+            crate::burning::execute_spanned_burn(
+                state,
+                encoder_handle,
+                output_manager,
+                discs,
+                bitrate,
+                burn_config,
+                volume_label,
+                record,
+            );
+        });
+
+        let window_handle = window.window_handle();
+        Self::start_progress_polling(self.conversion_state.clone(), window_handle, cx);
+
+        cx.notify();
+    }
+
+    /// Burn the folder list as a Red Book audio CD
+    ///
+    /// Called from File > Burn Audio CD. Tracks are decoded straight from the
+    /// source files (the MP3 encoder isn't involved), so every folder's
+    /// sources must still be available.
+    pub(super) fn run_audio_cd_burn(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.conversion_state.is_converting() || self.folders.is_empty() {
+            return;
+        }
+
+        let settings = cx.global::<AppSettings>();
+        let options = if settings.gapless_audio_cd {
+            AudioCdOptions::gapless()
+        } else {
+            AudioCdOptions::default()
+        };
+        let burn_config = burn_config(settings);
+
+        let fit = match check_audio_cd_fits(&self.folders, self.media_profile, options) {
+            Ok(fit) => fit,
+            Err(e) => {
+                self.pending_error_message = Some(("Can't Burn Audio CD".to_string(), e));
+                cx.notify();
+                return;
+            }
+        };
+        if let Some(folder) = self.folders.iter().find(|f| !f.source_available) {
+            self.pending_error_message = Some((
+                "Can't Burn Audio CD".to_string(),
+                format!(
+                    "The original files for \"{}\" aren't available",
+                    folder.display_name()
+                ),
+            ));
+            cx.notify();
+            return;
+        }
+
+        let output_dir = match &self.output_manager {
+            Some(om) => om.session_dir().join("audio_cd"),
+            None => crate::conversion::get_output_dir().join("audio_cd"),
+        };
+        log::debug!(
+            "Starting audio CD burn: {} tracks, {:.0}s of {:.0}s",
+            fit.track_count,
+            fit.duration_secs,
+            fit.limit_secs
+        );
+
+        self.conversion_state.reset(fit.track_count);
+        self.conversion_state.set_stage(BurnStage::DecodingAudio);
+
+        // A label the user actually set doubles as the CD-TEXT disc title
+        let disc_title = Some(self.volume_label.trim())
+            .filter(|label| !label.is_empty() && *label != "Untitled MP3CD")
+            .map(str::to_string);
+        let state = self.conversion_state.clone();
+        let folders = self.folders.clone();
+
+        std::thread::spawn(move || {
+            crate::burning::execute_audio_cd_burn(
+                state,
+                folders,
+                output_dir,
+                options,
+                disc_title,
+                burn_config,
+            );
+        });
+
+        let window_handle = window.window_handle();
+        Self::start_progress_polling(self.conversion_state.clone(), window_handle, cx);
+
+        cx.notify();
+    }
+
+    /// Erase the CD-RW in the drive (File > Quick Erase / Full Erase CD-RW)
+    ///
+    /// Doesn't need any folders. The disc waits in the drive for the same
+    /// Erase button a burn shows before it erases a CD-RW.
+    pub(super) fn run_erase(
+        &mut self,
+        mode: EraseMode,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.conversion_state.is_converting() {
+            log::debug!("Already in progress");
+            return;
+        }
+
+        log::debug!("Starting {}", mode.label().to_lowercase());
+        self.conversion_state.reset(0);
+        *self.conversion_state.erase_mode.lock().unwrap() = Some(mode);
+
+        let state = self.conversion_state.clone();
+        let burn_config = burn_config(cx.global::<AppSettings>());
+
+        std::thread::spawn(move || {
+            crate::burning::execute_erase(state, mode, burn_config);
+        });
+
+        let window_handle = window.window_handle();
+        Self::start_progress_polling(self.conversion_state.clone(), window_handle, cx);
+
+        cx.notify();
+    }
+
     /// Burn an existing ISO (for "Burn Another" functionality)
     ///
     /// This skips the conversion step and directly burns the existing ISO.
@@ -414,11 +999,12 @@ impl FolderList {
         self.conversion_state.reset(0);
 
         let state = self.conversion_state.clone();
-        let simulate_burn = cx.global::<AppSettings>().simulate_burn;
+        let burn_config = burn_config(cx.global::<AppSettings>());
+        let record = self.existing_iso_record(&burn_config);
 
         // Spawn background thread for burn execution
         std::thread::spawn(move || {
-            crate::burning::execute_burn_existing(state, iso_path, simulate_burn);
+            crate::burning::execute_burn_existing(state, iso_path, burn_config, record());
         });
 
         // Start polling for progress updates
@@ -429,6 +1015,275 @@ impl FolderList {
         cx.notify();
     }
 
+    /// Show the Burn Copies dialog
+    ///
+    /// Copies are burned from the ISO that's already been built, so this
+    /// needs a ready ISO that matches the current folders.
+    pub(super) fn show_burn_copies_dialog(&mut self, cx: &mut Context<Self>) {
+        if self.conversion_state.is_converting() {
+            return;
+        }
+        if !self.can_burn_another() {
+            self.pending_error_message = Some((
+                "Can't Burn Copies".to_string(),
+                "The ISO isn't ready yet. Wait for encoding to finish, then try again.".to_string(),
+            ));
+            cx.notify();
+            return;
+        }
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.pending_copies_rx = Some(rx);
+
+        BurnCopiesDialog::open(cx, move |copies| {
+            let _ = tx.send(copies);
+        });
+    }
+
+    /// Poll for the Burn Copies dialog result
+    ///
+    /// Returns true if a copy count was received (the burn itself starts
+    /// from the render loop, which has window access).
+    pub(super) fn poll_burn_copies(&mut self) -> bool {
+        if let Some(ref rx) = self.pending_copies_rx
+            && let Ok(copies) = rx.try_recv()
+        {
+            self.pending_copies_rx = None;
+            self.pending_burn_action = Some(PendingBurnAction::BurnCopies(copies));
+            return true;
+        }
+        false
+    }
+
+    /// Show the Disc Layout window
+    ///
+    /// The layout is planned from the current folders without staging
+    /// anything, so it can be checked before encoding finishes.
+    pub(super) fn show_disc_layout(&mut self, cx: &mut Context<Self>) {
+        if self.folders.is_empty() || self.conversion_state.is_converting() {
+            return;
+        }
+        let Some(output_manager) = &self.output_manager else {
+            log::error!("No output manager available");
+            return;
+        };
+
+        let embed_album_art = cx.global::<AppSettings>().embed_album_art;
+        let entries = match output_manager.plan_iso_staging(
+            &self.folders,
+            self.compat_profile,
+            self.iso_naming,
+            self.calculated_bitrate(),
+            embed_album_art,
+        ) {
+            Ok(entries) => entries,
+            Err(e) => {
+                self.pending_error_message = Some(("Can't Preview Disc Layout".to_string(), e));
+                cx.notify();
+                return;
+            }
+        };
+
+        let iso_size = predict_image_size(
+            &entries,
+            &self.folders,
+            self.compat_profile,
+            cx.global::<AppSettings>().playlist_format,
+            self.iso_naming,
+        );
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.pending_layout_burn_rx = Some(rx);
+
+        DiscLayoutWindow::open(cx, entries, iso_size, move || {
+            let _ = tx.send(());
+        });
+    }
+
+    /// Poll for Burn being pressed in the Disc Layout window
+    ///
+    /// Burns the existing ISO if it's ready, otherwise converts first - the
+    /// same as the main Burn button. Returns true if a burn was requested.
+    pub(super) fn poll_disc_layout(&mut self, cx: &mut Context<Self>) -> bool {
+        if let Some(ref rx) = self.pending_layout_burn_rx
+            && rx.try_recv().is_ok()
+        {
+            self.pending_layout_burn_rx = None;
+            let action = if self.can_burn_another() {
+                PendingBurnAction::BurnExisting
+            } else {
+                PendingBurnAction::ConvertAndBurn
+            };
+            self.show_volume_label_dialog(Some(action), cx);
+            return true;
+        }
+        false
+    }
+
+    /// Start the disc catalog record for a burn of the current folder list
+    fn disc_record(&self, bitrate: u32) -> DiscRecord {
+        DiscRecord::new(
+            &self.volume_label,
+            self.current_profile_path.clone(),
+            Some(bitrate),
+        )
+    }
+
+    /// Catalog record for burning the ISO that's already been built
+    ///
+    /// The ISO matches the current folder list, so its tracks are the ones
+    /// staging would give the folders now, renamed the way the ISO's were.
+    /// Filling them in reads every track's tags, so the burn thread does it
+    /// by calling the returned closure.
+    fn existing_iso_record(
+        &self,
+        config: &BurnConfig,
+    ) -> impl FnOnce() -> DiscRecord + Send + 'static {
+        let record = self.disc_record(self.calculated_bitrate());
+        let staged = self
+            .output_manager
+            .as_ref()
+            .map(|om| om.staged_track_paths(&self.folders, config.compat));
+        let folders = self.folders.clone();
+        let changes = self.name_changes.clone();
+        move || match staged {
+            Some(staged) => record.with_tracks(&folders, &staged, &changes),
+            None => record,
+        }
+    }
+
+    /// Burn several copies of the existing ISO, one disc after another
+    pub(super) fn burn_iso_copies(
+        &mut self,
+        copies: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let iso_path = match &self.iso_state {
+            Some(iso) if iso.file_exists() => iso.path.clone(),
+            _ => {
+                log::error!("No valid ISO available for burning copies");
+                return;
+            }
+        };
+        if self.conversion_state.is_converting() {
+            log::debug!("Already burning");
+            return;
+        }
+
+        log::debug!("Burning {} copies of {:?}", copies, iso_path);
+        self.conversion_state.reset(0);
+        self.conversion_state.set_copy(1, copies);
+
+        let state = self.conversion_state.clone();
+        let burn_config = burn_config(cx.global::<AppSettings>());
+        let record = self.existing_iso_record(&burn_config);
+
+        std::thread::spawn(move || {
+            crate::burning::execute_burn_copies(state, iso_path, copies, burn_config, record());
+        });
+
+        let window_handle = window.window_handle();
+        Self::start_progress_polling(self.conversion_state.clone(), window_handle, cx);
+
+        cx.notify();
+    }
+
+    /// Ask where to export the folders (File > Export to Folder or USB Drive)
+    ///
+    /// The export itself starts from the render loop once a folder has been
+    /// picked, since it needs window access for progress polling.
+    pub(super) fn show_export_picker(&mut self, cx: &mut Context<Self>) {
+        if self.folders.is_empty() || self.conversion_state.is_converting() {
+            return;
+        }
+
+        let options = PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+            prompt: Some("Export".into()),
+        };
+        let receiver = cx.prompt_for_paths(options);
+        cx.spawn(|this_handle: WeakEntity<Self>, cx: &mut AsyncApp| {
+            let mut async_cx = cx.clone();
+            async move {
+                if let Ok(Ok(Some(paths))) = receiver.await
+                    && let Some(path) = paths.first()
+                {
+                    let path = path.clone();
+                    let _ = this_handle.update(&mut async_cx, |this, cx| {
+                        this.pending_export_dir = Some(path);
+                        this.pending_burn_action = Some(PendingBurnAction::Export);
+                        cx.notify();
+                    });
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// Copy the converted folders to the folder picked for export
+    ///
+    /// Waits for the background encoder like a burn does, then copies the
+    /// same layout the ISO would get, with names made safe for FAT32.
+    pub(super) fn run_export(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(dest_dir) = self.pending_export_dir.take() else {
+            return;
+        };
+        if self.conversion_state.is_converting() {
+            log::debug!("Already in progress");
+            return;
+        }
+
+        let encoder_handle = match &self.simple_encoder {
+            Some(handle) => handle.clone(),
+            None => {
+                log::error!("Background encoder not available - cannot export");
+                return;
+            }
+        };
+        let output_manager = match &self.output_manager {
+            Some(om) => om.clone(),
+            None => {
+                log::error!("No output manager available");
+                return;
+            }
+        };
+
+        if let Err(e) = self.compat_profile.check_layout(&self.folders) {
+            self.pending_error_message = Some(("Can't Export".to_string(), e));
+            cx.notify();
+            return;
+        }
+
+        log::debug!("Exporting {} folders to {:?}", self.folders.len(), dest_dir);
+        self.conversion_state.reset(self.folders.len());
+        self.conversion_state.set_stage(BurnStage::Converting);
+
+        let state = self.conversion_state.clone();
+        let folders = self.folders.clone();
+        let compat = self.compat_profile;
+        let playlists = cx.global::<AppSettings>().playlist_format;
+
+        std::thread::spawn(move || {
+            crate::burning::execute_export(
+                state,
+                encoder_handle,
+                output_manager,
+                folders,
+                dest_dir,
+                compat,
+                playlists,
+            );
+        });
+
+        let window_handle = window.window_handle();
+        Self::start_progress_polling(self.conversion_state.clone(), window_handle, cx);
+
+        cx.notify();
+    }
+
     /// Start a polling loop that updates the UI periodically during conversion
     pub(super) fn start_progress_polling(
         state: ConversionState,
@@ -482,27 +1337,113 @@ impl FolderList {
 
                 // Show success dialog if completed (not cancelled)
                 let final_stage = state.get_stage();
-                if final_stage == BurnStage::Complete {
-                    // Mark that the ISO has been burned (for "Burn Another" button text)
-                    let _ = this.update(&mut async_cx, |folder_list, cx| {
-                        folder_list.iso_has_been_burned = true;
-                        cx.notify();
+                let export = state.export.lock().unwrap().clone();
+                let erase = state.erase.lock().unwrap().clone();
+                // Erasing or exporting burns nothing, so the ISO keeps its
+                // Burn/Burn Another state
+                let finished = match (erase, export) {
+                    (Some(erase), _) if erase.succeeded() => {
+                        Some((PromptLevel::Info, "Disc Erased", erase.summary()))
+                    }
+                    (Some(erase), _) => {
+                        Some((PromptLevel::Warning, "Erase Failed", erase.summary()))
+                    }
+                    (None, Some(export)) if export.succeeded() => {
+                        Some((PromptLevel::Info, "Export Complete", export.summary()))
+                    }
+                    (None, Some(export)) => {
+                        Some((PromptLevel::Warning, "Export Failed", export.summary()))
+                    }
+                    (None, None) => None,
+                };
+                if let Some((level, title, message)) = finished {
+                    use gpui::AppContext;
+                    if let Ok(prompt_future) =
+                        async_cx.update_window(window_handle, |_, window, cx| {
+                            window.prompt(level, title, Some(message.as_str()), &["OK"], cx)
+                        })
+                    {
+                        let _ = prompt_future.await;
+                    }
+                } else if final_stage == BurnStage::Complete {
+                    let error = state.burn_error.lock().unwrap().clone();
+                    let copies = state.copy_progress().map(|(_, count)| {
+                        copies_summary(&state.copy_results.lock().unwrap(), count)
                     });
 
+                    // Mark that the ISO has been burned (for "Burn Another" button text)
+                    if error.is_none() || copies.is_some() {
+                        let _ = this.update(&mut async_cx, |folder_list, cx| {
+                            folder_list.iso_has_been_burned = true;
+                            cx.notify();
+                        });
+                    }
+
                     // Show completion prompt - await the future so it displays
+                    let verification = state.verification.lock().unwrap().clone();
+                    let burned = match state.discs_burned() {
+                        Some((burned, count)) if burned == count => {
+                            format!("All {} discs have been burned", count)
+                        }
+                        Some((burned, count)) => {
+                            format!("Only {} of {} discs have been burned", burned, count)
+                        }
+                        None => "The CD has been burned".to_string(),
+                    };
+                    let (level, title, message) = match (copies, error, verification) {
+                        (Some((true, text)), _, _) => (PromptLevel::Info, "Burn Complete", text),
+                        (Some((false, text)), _, _) => {
+                            (PromptLevel::Warning, "Some Copies Weren't Burned", text)
+                        }
+                        // The failure, what to do about it, and how far a
+                        // multi-disc burn got
+                        (None, Some(error), _) => {
+                            let mut message = error.with_hint();
+                            if state.disc_progress().is_some() {
+                                message.push_str(&format!("\n\n{}.", burned));
+                            }
+                            (PromptLevel::Warning, error.title(), message)
+                        }
+                        (None, None, Some(v)) if !v.passed() => {
+                            let mut message = v.summary();
+                            for path in v.mismatched_files.iter().take(10) {
+                                message.push_str(&format!("\n• {}", path));
+                            }
+                            if v.mismatched_files.len() > 10 {
+                                message.push_str(&format!(
+                                    "\n…and {} more",
+                                    v.mismatched_files.len() - 10
+                                ));
+                            }
+                            (PromptLevel::Warning, "Verification Failed", message)
+                        }
+                        (None, None, Some(_)) => (
+                            PromptLevel::Info,
+                            "Burn Complete",
+                            format!("{} and verified successfully.", burned),
+                        ),
+                        (None, None, None) => (
+                            PromptLevel::Info,
+                            "Burn Complete",
+                            format!("{} successfully.", burned),
+                        ),
+                    };
+                    // A good burn can go straight on to printing its insert
+                    let buttons: &[&str] = if level == PromptLevel::Info {
+                        &["OK", "Print Insert..."]
+                    } else {
+                        &["OK"]
+                    };
                     use gpui::AppContext;
                     if let Ok(prompt_future) =
                         async_cx.update_window(window_handle, |_, window, cx| {
-                            window.prompt(
-                                PromptLevel::Info,
-                                "Burn Complete",
-                                Some("The CD has been burned successfully."),
-                                &["OK"],
-                                cx,
-                            )
+                            window.prompt(level, title, Some(message.as_str()), buttons, cx)
                         })
+                        && let Ok(1) = prompt_future.await
                     {
-                        let _ = prompt_future.await;
+                        let _ = this.update(&mut async_cx, |folder_list, cx| {
+                            folder_list.show_insert_picker(cx);
+                        });
                     }
                 }
             }
@@ -510,3 +1451,16 @@ impl FolderList {
         .detach();
     }
 }
+
+/// Burn options from the app settings
+fn burn_config(settings: &AppSettings) -> BurnConfig {
+    BurnConfig {
+        simulate: settings.simulate_burn,
+        verify: settings.verify_burn,
+        iso_naming: settings.compat_profile.iso_naming(settings.iso_naming),
+        compat: settings.compat_profile,
+        playlists: settings.playlist_format,
+        drive: settings.burn_drive.clone(),
+        ..Default::default()
+    }
+}
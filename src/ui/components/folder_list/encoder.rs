@@ -8,7 +8,7 @@ use gpui::{AsyncApp, Context, Timer, WeakEntity};
 
 use crate::conversion::{
     EncoderEvent, EncodingPhase, OutputManager,
-    SimpleEncoderHandle, start_simple_encoder, verify_ffmpeg,
+    SimpleEncoderHandle, select_encoder, start_simple_encoder,
 };
 use std::sync::Arc;
 use crate::core::{FolderConversionStatus, FolderId};
@@ -28,11 +28,11 @@ impl FolderList {
         // Clean up old sessions from previous runs
         output_manager.cleanup_old_sessions()?;
 
-        // Get ffmpeg path
-        let ffmpeg_path = verify_ffmpeg()?;
+        // Use ffmpeg if it's there, else the built-in encoder
+        let encoder = select_encoder();
 
         // Create the simple encoder
-        let (handle, event_rx) = start_simple_encoder(output_manager.clone(), ffmpeg_path);
+        let (handle, event_rx) = start_simple_encoder(output_manager.clone(), encoder);
 
         // Store the handle, event receiver, and output manager
         let handle_clone = handle.clone();
@@ -333,6 +333,51 @@ impl FolderList {
                                 had_changes = true;
                             }
 
+                            // Poll for target media dialog result / disc detection
+                            if this.poll_media_profile(cx) {
+                                had_changes = true;
+                            }
+
+                            // Poll for encoding options dialog result
+                            if this.poll_encoder_settings() {
+                                had_changes = true;
+                            }
+
+                            // Poll for the drive list / Burner Drive dialog result
+                            if this.poll_drive_selection(cx) {
+                                had_changes = true;
+                            }
+
+                            // Pick up discs going in and out of the burn drive
+                            if this.poll_drive_monitor(cx) {
+                                had_changes = true;
+                            }
+
+                            // Re-open a profile picked in the Disc Catalog window
+                            if this.poll_catalog_profile(cx) {
+                                had_changes = true;
+                            }
+
+                            // Report a finished jewel-case insert
+                            if this.poll_insert() {
+                                had_changes = true;
+                            }
+
+                            // Pick up car stereo profile changes and warnings
+                            if this.poll_compat_profile(cx) {
+                                had_changes = true;
+                            }
+
+                            // Poll for the Burn Copies dialog result
+                            if this.poll_burn_copies() {
+                                had_changes = true;
+                            }
+
+                            // Poll for Burn from the Disc Layout window
+                            if this.poll_disc_layout(cx) {
+                                had_changes = true;
+                            }
+
                             // Check for debounced bitrate recalculation
                             if this.check_debounced_bitrate_recalculation() {
                                 had_changes = true;
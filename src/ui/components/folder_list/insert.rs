@@ -0,0 +1,95 @@
+//! Jewel-case insert for FolderList
+//!
+//! Handles File > Print Insert and Tracklist: picking where to save, writing
+//! the insert and tracklists in the background, and reporting the result.
+
+use gpui::{AsyncApp, Context, PathPromptOptions, WeakEntity};
+
+use crate::burning::{Tracklist, write_insert};
+
+use super::FolderList;
+
+impl FolderList {
+    /// Ask where to save the insert and tracklists
+    ///
+    /// Called from File > Print Insert and Tracklist and from the post-burn
+    /// summary. The files are written once a folder has been picked.
+    pub(super) fn show_insert_picker(&mut self, cx: &mut Context<Self>) {
+        if self.folders.is_empty() || self.pending_insert_rx.is_some() {
+            return;
+        }
+
+        let options = PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+            prompt: Some("Save Insert".into()),
+        };
+        let receiver = cx.prompt_for_paths(options);
+        cx.spawn(|this_handle: WeakEntity<Self>, cx: &mut AsyncApp| {
+            let mut async_cx = cx.clone();
+            async move {
+                if let Ok(Ok(Some(paths))) = receiver.await
+                    && let Some(path) = paths.first()
+                {
+                    let path = path.clone();
+                    let _ = this_handle.update(&mut async_cx, |this, cx| {
+                        this.save_insert(path, cx);
+                    });
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// Write the insert SVG and tracklists into `dir` in the background
+    ///
+    /// Reading every track's tags can take a while, so this runs on its own
+    /// thread and the result is picked up by [`Self::poll_insert`].
+    fn save_insert(&mut self, dir: std::path::PathBuf, cx: &mut Context<Self>) {
+        let folders = self.folders.clone();
+        let title = self.volume_label.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.pending_insert_rx = Some(rx);
+        std::thread::spawn(move || {
+            let tracklist = Tracklist::for_folders(&folders, &title);
+            let _ = tx.send(write_insert(&tracklist, &dir));
+        });
+        cx.notify();
+    }
+
+    /// Poll for the written insert, open it for printing and report the files
+    ///
+    /// Returns true if the insert finished (or failed).
+    pub(super) fn poll_insert(&mut self) -> bool {
+        let Some(result) = self
+            .pending_insert_rx
+            .as_ref()
+            .and_then(|rx| rx.try_recv().ok())
+        else {
+            return false;
+        };
+        self.pending_insert_rx = None;
+
+        match result {
+            Ok(files) => {
+                // Opens in the default SVG viewer, ready to print at 100% scale
+                let _ = std::process::Command::new("open").arg(&files.svg).spawn();
+                self.pending_info_message = Some((
+                    "Insert Saved".to_string(),
+                    format!(
+                        "{}\n{}\n{}\n\nPrint the insert at 100% scale (not \"fit to page\") so it matches a jewel case.",
+                        files.svg.display(),
+                        files.html.display(),
+                        files.text.display()
+                    ),
+                ));
+            }
+            Err(e) => {
+                log::error!("Failed to save insert: {}", e);
+                self.pending_error_message = Some(("Couldn't Save Insert".to_string(), e));
+            }
+        }
+        true
+    }
+}
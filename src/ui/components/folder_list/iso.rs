@@ -6,8 +6,10 @@ use std::time::Duration;
 
 use gpui::{AsyncApp, Context, Timer, WeakEntity};
 
-use crate::burning::{IsoAction, IsoGenerationCheck, IsoState, determine_iso_action};
-use crate::core::{ConversionState, MusicFolder};
+use crate::burning::{
+    IsoAction, IsoGenerationCheck, IsoNaming, IsoState, determine_iso_action, name_changes_summary,
+};
+use crate::core::{AppSettings, ConversionState, MusicFolder};
 
 use super::FolderList;
 
@@ -40,12 +42,12 @@ impl FolderList {
         }
     }
 
-    /// Check if the current ISO exceeds the CD size limit
+    /// Check if the current ISO exceeds the target media's capacity
     ///
-    /// Returns true if we have an ISO but it's too large for a CD.
+    /// Returns true if we have an ISO but it's too large for the disc.
     pub fn iso_exceeds_limit(&self) -> bool {
         match &self.iso_state {
-            Some(iso) => iso.exceeds_cd_limit(),
+            Some(iso) => iso.exceeds_capacity(self.media_profile),
             None => false,
         }
     }
@@ -69,6 +71,25 @@ impl FolderList {
     /// This should be called periodically to auto-generate ISO when all folders are encoded.
     /// Returns true if ISO generation was triggered.
     pub(super) fn maybe_generate_iso(&mut self, cx: &mut Context<Self>) -> bool {
+        // A different naming level or playlist format puts different files on the disc
+        let settings = cx.global::<AppSettings>();
+        let naming = settings.compat_profile.iso_naming(settings.iso_naming);
+        let playlists = settings.playlist_format;
+        if (naming != self.iso_naming || playlists != self.playlist_format)
+            && !self.conversion_state.is_converting()
+        {
+            if self.iso_state.is_some() {
+                log::debug!("Disc naming or playlists changed - invalidating existing ISO");
+                self.iso_state = None;
+            }
+            self.iso_generation_attempted = false;
+            self.iso_naming = naming;
+            self.playlist_format = playlists;
+            if let Some(ref encoder) = self.simple_encoder {
+                encoder.set_playlists(playlists);
+            }
+        }
+
         // Don't generate ISO if a bitrate recalculation is pending
         // (command sent but not yet processed by encoder)
         if self.bitrate_recalc_pending {
@@ -123,10 +144,13 @@ impl FolderList {
             folders.clone(),
             self.conversion_state.clone(),
             self.volume_label.clone(),
+            naming,
+            self.compat_profile,
+            playlists,
         );
 
         // Start polling for ISO creation progress
-        Self::start_iso_creation_polling(self.conversion_state.clone(), folders, cx);
+        Self::start_iso_creation_polling(self.conversion_state.clone(), folders, naming, cx);
 
         cx.notify();
         true
@@ -136,6 +160,7 @@ impl FolderList {
     pub(super) fn start_iso_creation_polling(
         state: ConversionState,
         folders: Vec<MusicFolder>,
+        naming: IsoNaming,
         cx: &mut Context<Self>,
     ) {
         cx.spawn(|this: WeakEntity<Self>, cx: &mut AsyncApp| {
@@ -168,6 +193,16 @@ impl FolderList {
                             log::debug!("ISO state saved - ready for Burn");
                             cx.notify(); // Ensure UI updates with new ISO size
                         }
+
+                        // Show the renames, unless they're the ones already shown
+                        let changes = state.name_changes.lock().unwrap().clone();
+                        if !changes.is_empty() && changes != folder_list.name_changes {
+                            folder_list.pending_info_message = Some((
+                                "File Names Changed for the Disc".to_string(),
+                                name_changes_summary(&changes, naming),
+                            ));
+                        }
+                        folder_list.name_changes = changes;
                     });
                 }
                 // Note: cx.notify() inside update() is sufficient - no refresh needed
@@ -8,6 +8,7 @@
 mod conversion;
 mod encoder;
 mod folders;
+mod insert;
 mod iso;
 mod profiles;
 mod render;
@@ -17,9 +18,15 @@ mod tests;
 use gpui::{Context, FocusHandle, ScrollHandle};
 use std::path::PathBuf;
 
-use crate::burning::IsoState;
+use crate::burning::{
+    DiscInfo, DriveInfo, DriveMonitor, DriveSelection, InsertFiles, IsoNaming, IsoState,
+    NameChange, PlaylistFormat,
+};
 use crate::conversion::OutputManager;
-use crate::core::{ConversionState, FolderId, FolderKind, ImportState, MusicFolder};
+use crate::core::{
+    CompatProfile, ConversionState, EncoderSettings, FolderId, FolderKind, ImportState,
+    MediaProfile, MusicFolder,
+};
 use crate::profiles::ProfileLoadSetup;
 use crate::ui::components::{TrackEditorUpdate, TrackEntry};
 
@@ -61,6 +68,16 @@ pub struct FolderList {
     pub(crate) iso_generation_attempted: bool,
     /// Whether the current ISO has been burned at least once (for "Burn Another" vs "Burn")
     pub(crate) iso_has_been_burned: bool,
+    /// Naming rules the current ISO's names were made to follow
+    pub(crate) iso_naming: IsoNaming,
+    /// Names changed for the disc when the current ISO was staged
+    pub(crate) name_changes: Vec<NameChange>,
+    /// Playlists the current ISO was staged with
+    pub(crate) playlist_format: PlaylistFormat,
+    /// Car stereo profile the encoder and staging were last told about
+    pub(crate) compat_profile: CompatProfile,
+    /// Ways the folder list breaks the car stereo profile (last warned about)
+    pub(crate) compat_problems: Vec<String>,
     /// Timestamp of last folder list change (for debounced bitrate recalculation)
     pub(crate) last_folder_change: Option<std::time::Instant>,
     /// Last calculated bitrate (to detect changes that require re-encoding)
@@ -89,6 +106,37 @@ pub struct FolderList {
     pub(crate) manual_bitrate_override: Option<u32>,
     /// Receiver for bitrate override dialog result (None = use automatic)
     pub(crate) pending_bitrate_rx: Option<std::sync::mpsc::Receiver<Option<u32>>>,
+    /// Target media the folders are sized for
+    pub(crate) media_profile: MediaProfile,
+    /// Receiver for target media dialog result (None = detect from disc)
+    pub(crate) pending_media_rx: Option<std::sync::mpsc::Receiver<Option<MediaProfile>>>,
+    /// Bitrate mode and LAME tuning for transcodes
+    pub(crate) encoder_settings: EncoderSettings,
+    /// Receiver for encoding options dialog result
+    pub(crate) pending_encoder_settings_rx: Option<std::sync::mpsc::Receiver<EncoderSettings>>,
+    /// Receiver for the number of copies chosen in the Burn Copies dialog
+    pub(crate) pending_copies_rx: Option<std::sync::mpsc::Receiver<usize>>,
+    /// Receiver for Burn being pressed in the Disc Layout window
+    pub(crate) pending_layout_burn_rx: Option<std::sync::mpsc::Receiver<()>>,
+    /// Destination chosen for Export to Folder (picked up by the Export action)
+    pub(crate) pending_export_dir: Option<PathBuf>,
+    /// Receiver for a running blank disc capacity check
+    pub(crate) pending_media_detect_rx:
+        Option<std::sync::mpsc::Receiver<Result<Option<u64>, String>>>,
+    /// Receiver for the drives found for the Burner Drive dialog
+    pub(crate) pending_drives_rx: Option<std::sync::mpsc::Receiver<Result<Vec<DriveInfo>, String>>>,
+    /// Receiver for the drive and speed chosen in the Burner Drive dialog
+    pub(crate) pending_drive_rx: Option<std::sync::mpsc::Receiver<DriveSelection>>,
+    /// Background watcher of the burn drive (started by the polling loop)
+    pub(crate) drive_monitor: Option<DriveMonitor>,
+    /// Drive the monitor is watching, to restart it when another is chosen
+    pub(crate) monitored_drive: Option<DriveSelection>,
+    /// What the monitor last saw in the drive (None while it's empty)
+    pub(crate) disc_info: Option<DiscInfo>,
+    /// Receiver for a profile to re-open from the Disc Catalog window
+    pub(crate) pending_catalog_profile_rx: Option<std::sync::mpsc::Receiver<PathBuf>>,
+    /// Receiver for the files written by Print Insert and Tracklist
+    pub(crate) pending_insert_rx: Option<std::sync::mpsc::Receiver<Result<InsertFiles, String>>>,
     /// Flag to track when a bitrate recalculation is pending (waiting for encoder to re-encode)
     /// This prevents ISO generation until the recalculation is complete
     pub(crate) bitrate_recalc_pending: bool,
@@ -113,6 +161,12 @@ pub(crate) enum PendingBurnAction {
     BurnExisting,
     /// Run conversion then burn
     ConvertAndBurn,
+    /// Split the folders across several discs, then convert and burn each
+    SpanDiscs,
+    /// Burn this many copies of the existing ISO
+    BurnCopies(usize),
+    /// Copy the converted folders to `pending_export_dir`
+    Export,
 }
 
 /// Data for opening a track editor window (deferred until render loop)
@@ -144,6 +198,11 @@ impl FolderList {
             iso_state: None,
             iso_generation_attempted: false,
             iso_has_been_burned: false,
+            iso_naming: IsoNaming::default(),
+            name_changes: Vec::new(),
+            playlist_format: PlaylistFormat::default(),
+            compat_profile: CompatProfile::default(),
+            compat_problems: Vec::new(),
             last_folder_change: None,
             last_calculated_bitrate: None,
             needs_initial_focus: true,
@@ -158,6 +217,21 @@ impl FolderList {
             has_unsaved_changes: false,
             manual_bitrate_override: None,
             pending_bitrate_rx: None,
+            media_profile: MediaProfile::default(),
+            pending_media_rx: None,
+            encoder_settings: EncoderSettings::default(),
+            pending_encoder_settings_rx: None,
+            pending_copies_rx: None,
+            pending_layout_burn_rx: None,
+            pending_export_dir: None,
+            pending_media_detect_rx: None,
+            pending_drives_rx: None,
+            pending_drive_rx: None,
+            drive_monitor: None,
+            monitored_drive: None,
+            disc_info: None,
+            pending_catalog_profile_rx: None,
+            pending_insert_rx: None,
             bitrate_recalc_pending: false,
             pending_error_message: None,
             pending_info_message: None,
@@ -186,6 +260,11 @@ impl FolderList {
             iso_state: None,
             iso_generation_attempted: false,
             iso_has_been_burned: false,
+            iso_naming: IsoNaming::default(),
+            name_changes: Vec::new(),
+            playlist_format: PlaylistFormat::default(),
+            compat_profile: CompatProfile::default(),
+            compat_problems: Vec::new(),
             last_folder_change: None,
             last_calculated_bitrate: None,
             needs_initial_focus: false,
@@ -200,6 +279,21 @@ impl FolderList {
             has_unsaved_changes: false,
             manual_bitrate_override: None,
             pending_bitrate_rx: None,
+            media_profile: MediaProfile::default(),
+            pending_media_rx: None,
+            encoder_settings: EncoderSettings::default(),
+            pending_encoder_settings_rx: None,
+            pending_copies_rx: None,
+            pending_layout_burn_rx: None,
+            pending_export_dir: None,
+            pending_media_detect_rx: None,
+            pending_drives_rx: None,
+            pending_drive_rx: None,
+            drive_monitor: None,
+            monitored_drive: None,
+            disc_info: None,
+            pending_catalog_profile_rx: None,
+            pending_insert_rx: None,
             bitrate_recalc_pending: false,
             pending_error_message: None,
             pending_info_message: None,
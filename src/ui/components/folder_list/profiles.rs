@@ -10,8 +10,13 @@ use gpui::{
 };
 
 use crate::actions::take_pending_files;
-use crate::core::{FolderConversionStatus, FolderKind, ImportState, scan_music_folder};
+use crate::burning::DiscCatalog;
+use crate::core::{
+    EncoderSettings, FolderConversionStatus, FolderKind, ImportState, MediaProfile,
+    scan_music_folder,
+};
 use crate::profiles::types::SavedFolderKind;
+use crate::ui::components::DiscCatalogWindow;
 
 use super::{FolderList, PendingBurnAction, VolumeLabelDialog};
 
@@ -120,6 +125,8 @@ impl FolderList {
             self.iso_state.as_ref(),
             volume_label,
             self.manual_bitrate_override,
+            self.media_profile,
+            self.encoder_settings,
             for_bundle,
         )?;
 
@@ -206,6 +213,18 @@ impl FolderList {
         // Restore manual bitrate override from profile (or reset to auto-calculate)
         self.manual_bitrate_override = setup.manual_bitrate_override;
 
+        // Restore target media (profiles saved before it existed load as a CD)
+        self.media_profile = setup.media;
+        if let Some(encoder) = &self.simple_encoder {
+            encoder.set_media(setup.media);
+        }
+
+        // Restore encoder settings (older profiles load with ABR and LAME's defaults)
+        self.encoder_settings = setup.encoder;
+        if let Some(encoder) = &self.simple_encoder {
+            encoder.set_encoder_settings(setup.encoder);
+        }
+
         // DON'T set bundle_path when loading - new encodes should always go to temp.
         // The bundle is a read-only snapshot until the user explicitly saves.
         // Bundle files will be copied to temp during import, so we always clean first.
@@ -448,6 +467,8 @@ impl FolderList {
         self.last_folder_change = None;
         self.last_calculated_bitrate = None;
         self.manual_bitrate_override = None; // Reset to auto-calculate
+        self.media_profile = MediaProfile::default();
+        self.encoder_settings = EncoderSettings::default();
         self.volume_label = DEFAULT_LABEL.to_string();
         self.current_profile_path = None;
         self.has_unsaved_changes = false;
@@ -456,6 +477,8 @@ impl FolderList {
         self.pending_track_editor_open = None;
         // Clear the encoder state and delete converted files
         if let Some(encoder) = &self.simple_encoder {
+            encoder.set_media(self.media_profile);
+            encoder.set_encoder_settings(self.encoder_settings);
             encoder.clear_all();
         }
         // Clear bundle path so new encodes go to temp directory, not the old bundle
@@ -491,6 +514,38 @@ impl FolderList {
         });
     }
 
+    /// Show the Disc Catalog window (File > Disc Catalog)
+    pub(super) fn show_disc_catalog(&mut self, cx: &mut Context<Self>) {
+        let catalog = DiscCatalog::load();
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.pending_catalog_profile_rx = Some(rx);
+        DiscCatalogWindow::open(cx, catalog, move |path| {
+            let _ = tx.send(path);
+        });
+    }
+
+    /// Poll for a profile picked in the Disc Catalog window and load it
+    ///
+    /// Returns true if a profile was picked.
+    pub(super) fn poll_catalog_profile(&mut self, cx: &mut Context<Self>) -> bool {
+        let Some(path) = self
+            .pending_catalog_profile_rx
+            .as_ref()
+            .and_then(|rx| rx.try_recv().ok())
+        else {
+            return false;
+        };
+        log::debug!("Loading profile from the disc catalog: {:?}", path);
+        if let Err(e) = self.load_profile(&path, cx) {
+            log::error!("Failed to load profile: {}", e);
+            self.pending_error_message = Some((
+                "Couldn't Open Profile".to_string(),
+                format!("{}\n\n{}", path.display(), e),
+            ));
+        }
+        true
+    }
+
     /// Show file picker to open a profile (called from File > Open menu)
     ///
     /// If there are unsaved changes, shows a confirmation dialog first.
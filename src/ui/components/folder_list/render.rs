@@ -8,7 +8,12 @@ use gpui::{
     Context, ExternalPaths, IntoElement, Render, SharedString, Window, div, prelude::*, rgb,
 };
 
-use crate::actions::{NewMixtape, NewProfile, OpenProfile, SaveProfile, SetVolumeLabel};
+use crate::actions::{
+    BurnAcrossDiscs, BurnAudioCd, BurnCopies, ChooseBurnDrive, ExportToFolder, FullEraseDisc,
+    NewMixtape, NewProfile, OpenDiscCatalog, OpenEncodingOptions, OpenProfile, PreviewDiscLayout,
+    PrintInsert, QuickEraseDisc, SaveProfile, SetVolumeLabel,
+};
+use crate::burning::{DiscInfo, EraseMode};
 use crate::core::{BurnStage, DisplaySettings, FolderConversionStatus, WindowState};
 use crate::ui::Theme;
 
@@ -18,8 +23,8 @@ use super::{FolderList, PendingBurnAction};
 use crate::ui::components::folder_item::{DraggedFolder, FolderItemProps, render_folder_item};
 use crate::ui::components::status_bar::{
     StatusBarState, is_stage_cancelable, render_burn_button_base, render_clickable_bitrate,
-    render_convert_burn_button_base, render_erase_burn_button_base, render_import_progress,
-    render_iso_too_large, render_progress_box, render_stats_panel,
+    render_clickable_target, render_convert_burn_button_base, render_erase_burn_button_base,
+    render_import_progress, render_iso_too_large, render_progress_box, render_stats_panel,
 };
 
 impl FolderList {
@@ -113,7 +118,13 @@ impl FolderList {
             iso_has_been_burned: self.iso_has_been_burned,
             is_manual_override: self.manual_bitrate_override.is_some(),
             effective_bitrate: self.calculated_bitrate(), // Respects manual override
+            bitrate_mode: self.encoder_settings.mode,
             is_bitrate_preliminary: self.is_bitrate_preliminary(),
+            disc_progress: self.conversion_state.disc_progress(),
+            copy_progress: self.conversion_state.copy_progress(),
+            erase_mode: *self.conversion_state.erase_mode.lock().unwrap(),
+            drive_status: self.disc_info.as_ref().map(DiscInfo::describe),
+            target_label: self.media_profile.capacity_label(),
         }
     }
 
@@ -138,7 +149,14 @@ impl FolderList {
             }));
         }
 
-        // Build row 3: Bitrate, ISO, CD-RW
+        // Build clickable target media element
+        let target_el = render_clickable_target(&state, theme).on_click(cx.listener(
+            |this, _event, _window, cx| {
+                this.show_media_profile_dialog(cx);
+            },
+        ));
+
+        // Build row 3: Bitrate, ISO, drive contents / CD-RW
         let bitrate_row = div()
             .flex()
             .gap_4()
@@ -157,6 +175,11 @@ impl FolderList {
                     ),
                 )
             })
+            // What's in the drive, until a burn takes it over
+            .when_some(
+                state.drive_status.clone().filter(|_| !state.is_converting),
+                |el, status| el.child(div().text_color(text_color).child(status)),
+            )
             // CD-RW indicator (only show when erasable disc detected)
             .when(
                 state.is_converting && state.burn_stage == BurnStage::ErasableDiscDetected,
@@ -175,7 +198,7 @@ impl FolderList {
             .flex()
             .flex_col()
             .gap_1()
-            .child(render_stats_panel(&state, theme))
+            .child(render_stats_panel(&state, theme, target_el))
             .child(bitrate_row);
 
         div()
@@ -262,17 +285,16 @@ impl FolderList {
                 }
                 el.child(progress_box)
             })
-            // Erase & Burn button (only show when erasable disc detected)
+            // Erase & Burn (or just Erase) button, only when erasable disc detected
             .when(state.burn_stage == BurnStage::ErasableDiscDetected, |el| {
                 el.child(
-                    render_erase_burn_button_base(success_color, success_hover).on_click(
-                        cx.listener(|this, _event, _window, _cx| {
-                            log::debug!("Erase & Burn clicked");
+                    render_erase_burn_button_base(state.erase_mode, success_color, success_hover)
+                        .on_click(cx.listener(|this, _event, _window, _cx| {
+                            log::debug!("Erase approved");
                             this.conversion_state
                                 .erase_approved
                                 .store(true, Ordering::SeqCst);
-                        }),
-                    ),
+                        })),
                 )
             })
     }
@@ -466,6 +488,39 @@ impl Render for FolderList {
         let on_set_volume_label = cx.listener(|this, _: &SetVolumeLabel, _window, cx| {
             this.show_volume_label_dialog(None, cx);
         });
+        let on_burn_across_discs = cx.listener(|this, _: &BurnAcrossDiscs, _window, cx| {
+            this.start_spanned_burn(cx);
+        });
+        let on_burn_audio_cd = cx.listener(|this, _: &BurnAudioCd, window, cx| {
+            this.run_audio_cd_burn(window, cx);
+        });
+        let on_burn_copies = cx.listener(|this, _: &BurnCopies, _window, cx| {
+            this.show_burn_copies_dialog(cx);
+        });
+        let on_quick_erase = cx.listener(|this, _: &QuickEraseDisc, window, cx| {
+            this.run_erase(EraseMode::Quick, window, cx);
+        });
+        let on_full_erase = cx.listener(|this, _: &FullEraseDisc, window, cx| {
+            this.run_erase(EraseMode::Full, window, cx);
+        });
+        let on_export_to_folder = cx.listener(|this, _: &ExportToFolder, _window, cx| {
+            this.show_export_picker(cx);
+        });
+        let on_preview_disc_layout = cx.listener(|this, _: &PreviewDiscLayout, _window, cx| {
+            this.show_disc_layout(cx);
+        });
+        let on_choose_burn_drive = cx.listener(|this, _: &ChooseBurnDrive, _window, _cx| {
+            this.show_drive_dialog();
+        });
+        let on_open_encoding_options = cx.listener(|this, _: &OpenEncodingOptions, _window, cx| {
+            this.show_encoding_options_dialog(cx);
+        });
+        let on_open_disc_catalog = cx.listener(|this, _: &OpenDiscCatalog, _window, cx| {
+            this.show_disc_catalog(cx);
+        });
+        let on_print_insert = cx.listener(|this, _: &PrintInsert, _window, cx| {
+            this.show_insert_picker(cx);
+        });
 
         // Build status bar after listeners
         let status_bar = self.render_status_bar(&theme, cx);
@@ -484,6 +539,17 @@ impl Render for FolderList {
             .on_action(on_open_profile)
             .on_action(on_save_profile)
             .on_action(on_set_volume_label)
+            .on_action(on_burn_across_discs)
+            .on_action(on_burn_audio_cd)
+            .on_action(on_burn_copies)
+            .on_action(on_quick_erase)
+            .on_action(on_full_erase)
+            .on_action(on_export_to_folder)
+            .on_action(on_preview_disc_layout)
+            .on_action(on_choose_burn_drive)
+            .on_action(on_open_encoding_options)
+            .on_action(on_open_disc_catalog)
+            .on_action(on_print_insert)
             // Handle external file drops on the entire window
             .on_drop(on_external_drop)
             // Style when dragging external files over window
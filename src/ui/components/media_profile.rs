@@ -0,0 +1,354 @@
+//! Target Media Dialog
+//!
+//! Modal dialog for choosing the capacity everything is sized against:
+//! one of the CD/DVD presets, a custom size, or whatever blank disc is
+//! in the drive.
+
+use gpui::{
+    Bounds, Context, FocusHandle, KeyDownEvent, Render, SharedString, Window, WindowBounds,
+    WindowOptions, div, prelude::*, px, size,
+};
+
+use crate::core::MediaProfile;
+use crate::ui::Theme;
+
+/// Smallest custom capacity accepted (MB)
+const MIN_CUSTOM_MB: u64 = 10;
+/// Largest custom capacity accepted (MB)
+const MAX_CUSTOM_MB: u64 = 1_000_000;
+
+/// The Target Media Dialog modal
+pub struct MediaProfileDialog {
+    /// Currently selected profile
+    selected: MediaProfile,
+    /// Custom size input text (MB, numeric string)
+    custom_text: String,
+    /// Focus handle for keyboard input
+    focus_handle: FocusHandle,
+    /// Callback when Apply/Detect from Disc is pressed
+    /// Some(media) = use that media, None = detect from the disc in the drive
+    on_confirm: Option<Box<dyn Fn(Option<MediaProfile>) + 'static>>,
+}
+
+impl MediaProfileDialog {
+    pub fn new(cx: &mut Context<Self>, current: MediaProfile) -> Self {
+        Self {
+            selected: current,
+            custom_text: (current.capacity_bytes() / 1_000_000).to_string(),
+            focus_handle: cx.focus_handle(),
+            on_confirm: None,
+        }
+    }
+
+    /// Open the Target Media Dialog window
+    ///
+    /// The callback will be called with:
+    /// - Some(media) when Apply is pressed
+    /// - None when "Detect from Disc" is pressed
+    pub fn open<F>(
+        cx: &mut gpui::App,
+        current: MediaProfile,
+        on_confirm: F,
+    ) -> gpui::WindowHandle<Self>
+    where
+        F: Fn(Option<MediaProfile>) + 'static,
+    {
+        let bounds = Bounds::centered(None, size(px(320.), px(380.)), cx);
+
+        cx.open_window(
+            WindowOptions {
+                window_bounds: Some(WindowBounds::Windowed(bounds)),
+                window_min_size: Some(size(px(320.), px(380.))),
+                titlebar: Some(gpui::TitlebarOptions {
+                    title: Some("Target Media".into()),
+                    appears_transparent: false,
+                    traffic_light_position: None,
+                }),
+                ..Default::default()
+            },
+            |_window, cx| {
+                cx.new(|cx| {
+                    let mut dialog = MediaProfileDialog::new(cx, current);
+                    dialog.on_confirm = Some(Box::new(on_confirm));
+                    dialog
+                })
+            },
+        )
+        .unwrap()
+    }
+
+    fn is_custom(&self) -> bool {
+        matches!(self.selected, MediaProfile::Custom { .. })
+    }
+
+    /// The profile Apply would use, if the input is valid
+    fn chosen(&self) -> Option<MediaProfile> {
+        if !self.is_custom() {
+            return Some(self.selected);
+        }
+        match self.custom_text.parse::<u64>() {
+            Ok(mb) if (MIN_CUSTOM_MB..=MAX_CUSTOM_MB).contains(&mb) => {
+                Some(MediaProfile::custom_mb(mb))
+            }
+            _ => None,
+        }
+    }
+
+    fn select_custom(&mut self) {
+        self.selected = MediaProfile::Custom { bytes: 0 };
+    }
+
+    /// Handle a key press - returns true if the event was handled
+    fn handle_key(
+        &mut self,
+        event: &KeyDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let keystroke = &event.keystroke;
+
+        if keystroke.key == "backspace" {
+            self.custom_text.pop();
+            self.select_custom();
+            cx.notify();
+            return true;
+        }
+
+        if keystroke.key == "escape" {
+            self.cancel(window, cx);
+            return true;
+        }
+
+        if keystroke.key == "enter" {
+            if self.chosen().is_some() {
+                self.confirm(window, cx);
+            }
+            return true;
+        }
+
+        // Typing digits edits the custom size
+        if let Some(ref key_char) = keystroke.key_char {
+            for c in key_char.chars() {
+                if !c.is_ascii_digit() || self.custom_text.len() >= 7 {
+                    continue;
+                }
+                self.custom_text.push(c);
+                self.select_custom();
+            }
+            cx.notify();
+            return true;
+        }
+
+        false
+    }
+
+    fn confirm(&mut self, window: &mut Window, _cx: &mut Context<Self>) {
+        if let Some(media) = self.chosen()
+            && let Some(ref on_confirm) = self.on_confirm
+        {
+            on_confirm(Some(media));
+        }
+        window.remove_window();
+    }
+
+    fn detect(&mut self, window: &mut Window, _cx: &mut Context<Self>) {
+        if let Some(ref on_confirm) = self.on_confirm {
+            on_confirm(None);
+        }
+        window.remove_window();
+    }
+
+    fn cancel(&mut self, window: &mut Window, _cx: &mut Context<Self>) {
+        window.remove_window();
+    }
+
+    /// Render one selectable row
+    fn render_option(
+        &self,
+        id: &'static str,
+        label: String,
+        is_selected: bool,
+        theme: &Theme,
+    ) -> gpui::Stateful<gpui::Div> {
+        div()
+            .id(SharedString::from(id))
+            .w_full()
+            .px_3()
+            .py_2()
+            .flex()
+            .items_center()
+            .gap_2()
+            .rounded_md()
+            .border_1()
+            .border_color(if is_selected {
+                theme.accent
+            } else {
+                theme.border
+            })
+            .bg(theme.bg_card)
+            .cursor_pointer()
+            .hover(|s| s.bg(theme.bg_card_hover))
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(if is_selected {
+                        theme.accent
+                    } else {
+                        theme.text_muted
+                    })
+                    .child(if is_selected { "●" } else { "○" }),
+            )
+            .child(div().text_sm().text_color(theme.text).child(label))
+    }
+}
+
+impl Render for MediaProfileDialog {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = Theme::from_appearance(window.appearance());
+        let is_valid = self.chosen().is_some();
+        let is_custom = self.is_custom();
+        let custom_display = self.custom_text.clone();
+
+        // Focus the dialog on render
+        if !self.focus_handle.is_focused(window) {
+            self.focus_handle.focus(window);
+        }
+
+        let ids = ["media-cd74", "media-cd80", "media-dvd5", "media-dvd9"];
+        let presets: Vec<_> = MediaProfile::PRESETS
+            .iter()
+            .zip(ids)
+            .map(|(&preset, id)| {
+                self.render_option(id, preset.name(), self.selected == preset, &theme)
+                    .on_click(cx.listener(move |this, _, _window, cx| {
+                        this.selected = preset;
+                        cx.notify();
+                    }))
+            })
+            .collect();
+
+        div()
+            .key_context("MediaProfileDialog")
+            .track_focus(&self.focus_handle)
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                this.handle_key(event, window, cx);
+            }))
+            .size_full()
+            .flex()
+            .flex_col()
+            .bg(theme.bg)
+            .p_4()
+            .gap_2()
+            .children(presets)
+            // Custom size row
+            .child(
+                self.render_option("media-custom", "Custom:".to_string(), is_custom, &theme)
+                    .on_click(cx.listener(|this, _, _window, cx| {
+                        this.select_custom();
+                        cx.notify();
+                    }))
+                    .child(
+                        div()
+                            .w(px(90.))
+                            .h(px(28.))
+                            .px_2()
+                            .flex()
+                            .items_center()
+                            .bg(theme.bg)
+                            .border_1()
+                            .border_color(if is_valid || !is_custom {
+                                theme.accent
+                            } else {
+                                theme.danger
+                            })
+                            .rounded_md()
+                            .child(div().text_sm().text_color(theme.text).child(
+                                if custom_display.is_empty() {
+                                    " ".to_string()
+                                } else {
+                                    custom_display
+                                },
+                            ))
+                            // Cursor
+                            .when(is_custom, |el| {
+                                el.child(div().w(px(2.)).h(px(16.)).bg(theme.accent).ml_px())
+                            }),
+                    )
+                    .child(div().text_sm().text_color(theme.text).child("MB")),
+            )
+            // "Detect from Disc" button
+            .child(
+                div()
+                    .id(SharedString::from("detect-media-btn"))
+                    .w_full()
+                    .px_4()
+                    .py_2()
+                    .mt_1()
+                    .bg(theme.bg_card)
+                    .text_color(theme.accent)
+                    .text_sm()
+                    .text_center()
+                    .rounded_md()
+                    .border_1()
+                    .border_color(theme.accent)
+                    .cursor_pointer()
+                    .hover(|s| s.bg(theme.bg_card_hover))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.detect(window, cx);
+                    }))
+                    .child("Detect from Blank Disc"),
+            )
+            // Buttons
+            .child(
+                div()
+                    .flex()
+                    .justify_end()
+                    .gap_2()
+                    .mt_2()
+                    .child(
+                        div()
+                            .id(SharedString::from("cancel-btn"))
+                            .px_4()
+                            .py_2()
+                            .bg(theme.bg_card)
+                            .text_color(theme.text)
+                            .text_sm()
+                            .rounded_md()
+                            .border_1()
+                            .border_color(theme.text_muted)
+                            .cursor_pointer()
+                            .hover(|s| s.bg(theme.bg_card_hover))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.cancel(window, cx);
+                            }))
+                            .child("Cancel"),
+                    )
+                    .child(
+                        div()
+                            .id(SharedString::from("apply-btn"))
+                            .px_4()
+                            .py_2()
+                            .bg(if is_valid {
+                                theme.accent
+                            } else {
+                                theme.bg_card
+                            })
+                            .text_color(if is_valid {
+                                gpui::white()
+                            } else {
+                                theme.text_muted
+                            })
+                            .text_sm()
+                            .rounded_md()
+                            .when(is_valid, |el| el.cursor_pointer())
+                            .when(is_valid, |el| el.hover(|s| s.bg(theme.success)))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                if this.chosen().is_some() {
+                                    this.confirm(window, cx);
+                                }
+                            }))
+                            .child("Apply"),
+                    ),
+            )
+    }
+}
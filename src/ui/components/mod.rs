@@ -2,18 +2,30 @@
 
 mod about;
 mod bitrate_override;
+mod burn_copies;
 mod burn_progress;
+mod disc_catalog;
+mod disc_layout;
 mod display_settings;
+mod drive_select;
+mod encoding_options;
 mod folder_item;
 mod folder_list;
+mod media_profile;
 mod status_bar;
 mod track_editor;
 mod volume_label;
 
 pub use about::AboutBox;
 pub use bitrate_override::BitrateOverrideDialog;
+pub use burn_copies::BurnCopiesDialog;
 pub use burn_progress::BurnProgressWindow;
+pub use disc_catalog::DiscCatalogWindow;
+pub use disc_layout::DiscLayoutWindow;
 pub use display_settings::DisplaySettingsModal;
+pub use drive_select::DriveDialog;
+pub use encoding_options::EncodingOptionsDialog;
 pub use folder_list::FolderList;
+pub use media_profile::MediaProfileDialog;
 pub use track_editor::{TrackEditorUpdate, TrackEditorWindow, TrackEntry};
 pub use volume_label::VolumeLabelDialog;
@@ -8,8 +8,9 @@
 
 use gpui::{SharedString, div, prelude::*};
 
-use crate::conversion::MultipassEstimate;
-use crate::core::{BurnStage, format_duration};
+use crate::burning::EraseMode;
+use crate::conversion::{MultipassEstimate, bitrate_label};
+use crate::core::{BitrateMode, BurnStage, format_duration};
 use crate::ui::Theme;
 
 /// State needed to render the status bar
@@ -41,7 +42,7 @@ pub struct StatusBarState {
     pub is_cancelled: bool,
     /// Whether a valid ISO exists that can be burned
     pub can_burn_another: bool,
-    /// Whether the ISO exceeds CD size limit
+    /// Whether the ISO exceeds the target media's capacity
     pub iso_exceeds_limit: bool,
     /// ISO size in MB (if available)
     pub iso_size_mb: Option<f64>,
@@ -51,8 +52,20 @@ pub struct StatusBarState {
     pub is_manual_override: bool,
     /// The effective bitrate (either calculated or manual override)
     pub effective_bitrate: u32,
+    /// Bitrate mode the encoder uses, for showing VBR as its preset
+    pub bitrate_mode: BitrateMode,
     /// Whether the bitrate is preliminary (will be recalculated after lossy encoding)
     pub is_bitrate_preliminary: bool,
+    /// Current disc of a multi-disc burn (disc, count)
+    pub disc_progress: Option<(usize, usize)>,
+    /// Current copy of a multi-copy burn (copy, count)
+    pub copy_progress: Option<(usize, usize)>,
+    /// Mode of the standalone CD-RW erase running, if this is one
+    pub erase_mode: Option<EraseMode>,
+    /// What's in the burn drive, e.g. "Blank 700 MB CD-R ready" (None when empty)
+    pub drive_status: Option<String>,
+    /// Target media capacity for display (e.g. "700 MB")
+    pub target_label: String,
 }
 
 impl StatusBarState {
@@ -69,9 +82,12 @@ impl StatusBarState {
 
                 if self.is_manual_override {
                     // Show the effective (overridden) bitrate with asterisk
-                    format!("{} kbps*", self.effective_bitrate)
+                    format!(
+                        "{}*",
+                        bitrate_label(self.bitrate_mode, self.effective_bitrate)
+                    )
                 } else {
-                    format!("{} kbps", e.target_bitrate)
+                    bitrate_label(self.bitrate_mode, e.target_bitrate)
                 }
             }
             _ => "--".to_string(),
@@ -93,8 +109,13 @@ impl StatusBarState {
 }
 
 /// Render the left stats panel (Files, Duration, Size, Target)
-/// Note: Bitrate row is rendered separately in render.rs to support click handlers
-pub fn render_stats_panel(state: &StatusBarState, theme: &Theme) -> impl IntoElement {
+/// Note: Bitrate row is rendered separately in render.rs to support click handlers,
+/// and the Target element is passed in for the same reason
+pub fn render_stats_panel(
+    state: &StatusBarState,
+    theme: &Theme,
+    target_el: impl IntoElement,
+) -> impl IntoElement {
     let size_mb = state.size_mb();
     let text_color = theme.text;
     let text_muted = theme.text_muted;
@@ -139,17 +160,32 @@ pub fn render_stats_panel(state: &StatusBarState, theme: &Theme) -> impl IntoEle
                             .child(format!("{:.2} MB", size_mb)),
                     ),
                 )
-                .child(
-                    div().flex().gap_1().child("Target:").child(
-                        div()
-                            .text_color(text_color)
-                            .font_weight(gpui::FontWeight::BOLD)
-                            .child("700 MB"),
-                    ),
-                ),
+                .child(target_el),
         )
 }
 
+/// Render just the clickable target media element (for wrapping with on_click in render.rs)
+pub fn render_clickable_target(
+    state: &StatusBarState,
+    theme: &Theme,
+) -> gpui::Stateful<gpui::Div> {
+    div()
+        .id(SharedString::from("target-display"))
+        .flex()
+        .gap_1()
+        .child("Target:")
+        .child(
+            div()
+                .text_color(theme.text)
+                .font_weight(gpui::FontWeight::BOLD)
+                .child(state.target_label.clone()),
+        )
+        .cursor_pointer()
+        .hover(|s| s.bg(theme.bg_card_hover).rounded_sm())
+        .px_1()
+        .mx(gpui::px(-4.0))
+}
+
 /// Render just the clickable bitrate element (for wrapping with on_click in render.rs)
 pub fn render_clickable_bitrate(
     state: &StatusBarState,
@@ -245,7 +281,7 @@ impl ProgressDisplay {
     pub fn from_state(state: &StatusBarState) -> Self {
         let (completed, failed, total) = state.conversion_progress;
 
-        match state.burn_stage {
+        let mut display = match state.burn_stage {
             BurnStage::Converting => {
                 let frac = if total > 0 {
                     (completed + failed) as f32 / total as f32
@@ -258,15 +294,42 @@ impl ProgressDisplay {
                     stage_text: "Converting...",
                 }
             }
-            BurnStage::CreatingIso => Self {
-                fraction: 1.0,
-                text: "".to_string(),
-                stage_text: "Creating ISO...",
-            },
+            BurnStage::CreatingIso => {
+                // Staging comes first and reports no progress
+                if state.burn_progress >= 0 {
+                    Self {
+                        fraction: state.burn_progress as f32 / 100.0,
+                        text: format!("{}%", state.burn_progress),
+                        stage_text: "Creating ISO...",
+                    }
+                } else {
+                    Self {
+                        fraction: 1.0,
+                        text: "".to_string(),
+                        stage_text: "Creating ISO...",
+                    }
+                }
+            }
+            BurnStage::DecodingAudio => {
+                let frac = if total > 0 {
+                    completed as f32 / total as f32
+                } else {
+                    0.0
+                };
+                Self {
+                    fraction: frac,
+                    text: format!("{}/{}", completed, total),
+                    stage_text: "Decoding audio...",
+                }
+            }
             BurnStage::WaitingForCd => Self {
                 fraction: 1.0,
                 text: "".to_string(),
-                stage_text: "Insert blank CD",
+                stage_text: if state.erase_mode.is_some() {
+                    "Insert CD-RW"
+                } else {
+                    "Insert blank CD"
+                },
             },
             BurnStage::ErasableDiscDetected => Self {
                 fraction: 1.0,
@@ -312,6 +375,22 @@ impl ProgressDisplay {
                 text: "".to_string(),
                 stage_text: "Finishing...",
             },
+            BurnStage::Verifying => {
+                let progress = state.burn_progress.max(0);
+                Self {
+                    fraction: progress as f32 / 100.0,
+                    text: format!("{}%", progress),
+                    stage_text: "Verifying...",
+                }
+            }
+            BurnStage::Exporting => {
+                let progress = state.burn_progress.max(0);
+                Self {
+                    fraction: progress as f32 / 100.0,
+                    text: format!("{}%", progress),
+                    stage_text: "Exporting...",
+                }
+            }
             BurnStage::Complete => Self {
                 fraction: 1.0,
                 text: "✓".to_string(),
@@ -322,7 +401,20 @@ impl ProgressDisplay {
                 text: "".to_string(),
                 stage_text: "Cancelled",
             },
+        };
+
+        // Show which disc of a multi-disc burn we're on when there's no other count
+        if let Some((disc, count)) = state.disc_progress
+            && display.text.is_empty()
+        {
+            display.text = format!("Disc {}/{}", disc, count);
+        }
+        if let Some((copy, count)) = state.copy_progress
+            && display.text.is_empty()
+        {
+            display.text = format!("Copy {}/{}", copy, count);
         }
+        display
     }
 }
 
@@ -430,10 +522,20 @@ pub fn render_progress_box(state: &StatusBarState, theme: &Theme) -> gpui::State
 /// Render the Erase & Burn button visual structure (without click handler)
 ///
 /// Returns a Stateful<Div> that can be wrapped with a click handler by the caller.
+///
+/// A standalone erase (`erase_mode`) gets a plain "Quick Erase" / "Full
+/// Erase" label instead.
 pub fn render_erase_burn_button_base(
+    erase_mode: Option<EraseMode>,
     success_color: gpui::Hsla,
     success_hover: gpui::Hsla,
 ) -> gpui::Stateful<gpui::Div> {
+    let button_text = match erase_mode {
+        Some(EraseMode::Quick) => "Quick\nErase",
+        Some(EraseMode::Full) => "Full\nErase",
+        None => "Erase\n& Burn",
+    };
+
     div()
         .id(SharedString::from("erase-burn-btn"))
         .w(gpui::px(150.0))
@@ -449,7 +551,7 @@ pub fn render_erase_burn_button_base(
         .text_center()
         .cursor_pointer()
         .hover(move |s| s.bg(success_hover))
-        .child("Erase\n& Burn")
+        .child(button_text)
 }
 
 /// Render the Burn/Burn Another button visual structure (without click handler)